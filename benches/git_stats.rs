@@ -10,8 +10,8 @@
 use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
 use kodo::cli::args::Period;
 use kodo::config::{default_config_path, expand_tilde, load_config};
-use kodo::git::Repository;
-use kodo::stats::{DateRange, Days, TimeZoneMode, collect_stats};
+use kodo::git::{CollectOptions, Repository};
+use kodo::stats::{DateRange, Days, FileFilter, TimeZoneMode, collect_stats};
 use std::env;
 use std::path::PathBuf;
 
@@ -62,8 +62,8 @@ impl BenchmarkConfig {
     }
 }
 
-/// Benchmark `Repository::commits_in_range` with different day ranges
-fn bench_commits_in_range(c: &mut Criterion) {
+/// Benchmark `Repository::collect_commits` with different day ranges
+fn bench_collect_commits(c: &mut Criterion) {
     let config = BenchmarkConfig::load();
 
     let repo = Repository::open(&config.repo_path, &config.repo_name).unwrap_or_else(|e| {
@@ -80,20 +80,23 @@ fn bench_commits_in_range(c: &mut Criterion) {
         config.repo_path.display()
     );
 
-    let mut group = c.benchmark_group("commits_in_range");
+    let mut group = c.benchmark_group("collect_commits");
 
     for days in [7, 30, 90] {
-        group.bench_with_input(BenchmarkId::new("days", days), &days, |b, &days| {
-            let range = DateRange::last_n_days(Days::new(days));
-            b.iter(|| {
-                repo.commits_in_range(
-                    black_box(range.from),
-                    black_box(range.to),
-                    None,
-                    true, // exclude merges
-                )
-            });
+        // HEAD only, without merges; totals only or with per-file details
+        let range = DateRange::last_n_days(Days::new(days));
+        let totals = CollectOptions::new(range.from, range.to).with_exclude_merges(true);
+        let details = totals.clone().with_file_details(true);
+        group.bench_with_input(BenchmarkId::new("days", days), &totals, |b, totals| {
+            b.iter(|| repo.collect_commits(black_box(totals)));
         });
+        group.bench_with_input(
+            BenchmarkId::new("days_with_file_details", days),
+            &details,
+            |b, details| {
+                b.iter(|| repo.collect_commits(black_box(details)));
+            },
+        );
     }
 
     group.finish();
@@ -114,13 +117,18 @@ fn bench_collect_stats(c: &mut Criterion) {
     // Pre-fetch commits for 30 days
     let range = DateRange::last_n_days(Days::new(30));
     let commits = repo
-        .commits_in_range(range.from, range.to, None, true)
+        .collect_commits(
+            &CollectOptions::new(range.from, range.to)
+                .with_exclude_merges(true)
+                .with_file_details(true),
+        )
         .expect("Failed to fetch commits");
 
     println!("Benchmarking collect_stats with {} commits", commits.len());
 
     let mut group = c.benchmark_group("collect_stats");
     let timezone = TimeZoneMode::Local;
+    let filter = FileFilter::default();
 
     group.bench_function("daily", |b| {
         b.iter(|| {
@@ -129,7 +137,7 @@ fn bench_collect_stats(c: &mut Criterion) {
                 black_box(commits.clone()),
                 black_box(range),
                 black_box(Period::Daily),
-                None,
+                &filter,
                 black_box(&timezone),
             )
         });
@@ -142,7 +150,7 @@ fn bench_collect_stats(c: &mut Criterion) {
                 black_box(commits.clone()),
                 black_box(range),
                 black_box(Period::Weekly),
-                None,
+                &filter,
                 black_box(&timezone),
             )
         });
@@ -151,5 +159,5 @@ fn bench_collect_stats(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_commits_in_range, bench_collect_stats);
+criterion_group!(benches, bench_collect_commits, bench_collect_stats);
 criterion_main!(benches);
@@ -1,12 +1,30 @@
 //! Command-line argument definitions
 
+use crate::cli::dates::DateExpr;
+use crate::output::columns::{Column, SortKey};
+use crate::stats::WorkHours;
 use clap::{Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::num::{NonZeroU16, NonZeroU64, NonZeroUsize};
+use std::path::{Path, PathBuf};
+
+/// Exit status documentation shown at the end of `--help`
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  Success
+  1  Other errors (I/O, JSON, failed doctor checks)
+  2  Invalid arguments
+  3  Configuration errors
+  4  Repository errors
+  5  No commits in range (with --fail-if-empty)";
 
 /// Analyze Git commit statistics across repositories
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Default)]
 #[command(name = "kodo")]
 #[command(version, about, long_about = None)]
+#[command(after_help = EXIT_CODES)]
+// CLI flags map naturally to independent bools
+#[allow(clippy::struct_excessive_bools)]
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Command>,
@@ -15,45 +33,247 @@ pub struct Args {
     #[arg(short, long, env = "KODO_CONFIG", global = true)]
     pub config: Option<PathBuf>,
 
-    /// Repository path (overrides config)
+    /// Repository paths to analyze (overrides config; same as --repo)
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+
+    /// Repository path (overrides config; repeatable)
+    #[arg(short, long)]
+    pub repo: Vec<PathBuf>,
+
+    /// Number of days to analyze [default: config `defaults.days`, or 7]
     #[arg(short, long)]
-    pub repo: Option<PathBuf>,
+    pub days: Option<u32>,
 
-    /// Number of days to analyze
-    #[arg(short, long, default_value = "7")]
-    pub days: u32,
+    /// Start date, inclusive (YYYY-MM-DD, yesterday, "2 weeks ago", "last friday"); overrides --days
+    #[arg(long, visible_alias = "since", value_name = "DATE")]
+    pub from: Option<DateExpr>,
 
-    /// Include merge commits
+    /// End date, inclusive, in the same forms as --from [default: today]
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<DateExpr>,
+
+    /// Include merge commits [default: config `defaults.exclude_merges`, or excluded]
     #[arg(long)]
     pub include_merges: bool,
 
-    /// Output format
-    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
-    pub output: OutputFormat,
+    /// Count the lines of merge commits, diffed against their first parent; they repeat
+    /// the merged commits' lines, so by default merges add none
+    #[arg(long)]
+    pub merge_diffs: bool,
+
+    /// Output format [default: config `defaults.output`, or table]
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Append weekday and hour activity sections to CSV output
+    #[arg(long)]
+    pub csv_sections: bool,
+
+    /// Columns of the table and CSV output, in order (comma-separated)
+    /// [default: every column but merges; CSV also leaves out unique]
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "COLUMNS")]
+    pub columns: Option<Vec<Column>>,
+
+    /// Sort table and CSV rows by a column, as `<column>[:asc|desc]` [default: by date]
+    #[arg(long, value_name = "COLUMN[:ORDER]")]
+    pub sort: Option<SortKey>,
+
+    /// Working hours for the business-hours/evenings/weekends split, as the half-open
+    /// hour range START-END [default: 9-18]
+    #[arg(long, value_name = "START-END")]
+    pub work_hours: Option<WorkHours>,
+
+    /// Add a Trend column to the table with a sparkline of commits per period on the TOTAL row
+    #[arg(long)]
+    pub sparkline: bool,
+
+    /// Leave periods without any commits, lines, or changed files out of table, CSV, and JSON
+    /// rows; totals still cover the whole range (the TUI keeps every period)
+    #[arg(long)]
+    pub skip_empty: bool,
+
+    /// Write output to a file instead of stdout (not used in TUI mode)
+    #[arg(long, value_name = "PATH")]
+    pub out: Option<PathBuf>,
 
-    /// Aggregation period
-    #[arg(short, long, value_enum, default_value = "daily")]
-    pub period: Period,
+    /// Render a JSON file written by `--output json` instead of analyzing repositories
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "repo", "paths", "repo_name", "group", "days", "from", "to", "period", "watch",
+            "complete_periods"
+        ]
+    )]
+    pub input: Option<PathBuf>,
+
+    /// Aggregation period [default: config `defaults.period`, or daily]
+    #[arg(short, long, value_enum)]
+    pub period: Option<Period>,
+
+    /// Trim the range to whole periods, dropping the partial weeks/months/years at its edges
+    #[arg(long)]
+    pub complete_periods: bool,
 
     /// Branch to analyze
     #[arg(short, long)]
     pub branch: Option<String>,
 
+    /// Analyze commits from every local branch
+    #[arg(long, conflicts_with = "branch")]
+    pub all_branches: bool,
+
+    /// Follow only the first parent of merges (mainline history); implies --include-merges
+    #[arg(long)]
+    pub first_parent: bool,
+
     /// File extensions to include (comma-separated)
     #[arg(long, value_delimiter = ',')]
     pub ext: Option<Vec<String>>,
 
+    /// Only count files whose repository-relative path matches this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub path: Vec<String>,
+
+    /// Skip files whose repository-relative path matches this glob (repeatable, wins over --path)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude_path: Vec<String>,
+
+    /// Keep counting commits whose files are all filtered out by --ext/--path/--exclude-path
+    #[arg(long)]
+    pub count_all_commits: bool,
+
+    /// Include a per-extension breakdown (JSON `extensions` array, extra table section)
+    #[arg(long)]
+    pub by_extension: bool,
+
+    /// Number of extensions tracked per period with --by-extension; the rest are summed as `other` [default: 10]
+    #[arg(long, value_name = "N", requires = "by_extension")]
+    pub period_extensions: Option<usize>,
+
+    /// Smooth per-period series with a trailing N-period moving average (TUI overlay, JSON `moving_average`)
+    #[arg(long, value_name = "N")]
+    pub moving_average: Option<NonZeroUsize>,
+
+    /// Report stretches without commits longer than N days (JSON `gaps`, table and TUI footer)
+    /// [default: 3 for daily, else one period]
+    #[arg(long, value_name = "N")]
+    pub min_gap: Option<u32>,
+
+    /// Maximum number of worker threads [default: available CPUs]
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Keep only the newest N commits in range per repository (the result is marked truncated)
+    #[arg(long, value_name = "N")]
+    pub max_commits: Option<NonZeroUsize>,
+
+    /// Stop collecting after SECONDS, keeping the commits collected so far (the result is marked truncated)
+    #[arg(long, value_name = "SECONDS")]
+    pub max_seconds: Option<NonZeroU64>,
+
+    /// Abort when any repository fails instead of skipping it
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Exit with an error (code 5) when no commits are counted after filtering
+    #[arg(long, conflicts_with = "watch")]
+    pub fail_if_empty: bool,
+
+    /// Do not read or write the diff cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Show the N largest commits by lines changed (JSON `top_commits` array, extra table section)
+    #[arg(long, value_name = "N")]
+    pub top_commits: Option<usize>,
+
+    /// Count renamed files as a full delete + add instead of detecting renames
+    #[arg(long)]
+    pub no_renames: bool,
+
+    /// Similarity (percent) at which a deleted + added file pair counts as a rename [default: 50]
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        conflicts_with = "no_renames",
+        value_parser = clap::value_parser!(u16).range(0..=100)
+    )]
+    pub rename_threshold: Option<u16>,
+
+    /// Ignore all whitespace when counting changed lines, like `git diff -w`
+    /// [default: config `defaults.ignore_whitespace`, or off]
+    #[arg(long, conflicts_with = "ignore_whitespace_eol")]
+    pub ignore_whitespace: bool,
+
+    /// Ignore whitespace at the end of lines when counting changed lines
+    #[arg(long)]
+    pub ignore_whitespace_eol: bool,
+
+    /// Show the N files with the most lines changed (JSON `top_files` array, extra table section)
+    #[arg(long, value_name = "N")]
+    pub top_files: Option<usize>,
+
+    /// Show the N directories with the most lines changed (JSON `top_dirs` array, extra table section)
+    #[arg(long, value_name = "N")]
+    pub top_dirs: Option<usize>,
+
+    /// Add a detail level: `files` lists every (commit, file) pair (CSV rows, JSON `file_details`)
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub detail: Option<Detail>,
+
+    /// Compare totals against the preceding range of equal length (JSON `comparison`, extra table section)
+    #[arg(long)]
+    pub compare: bool,
+
+    /// Compare each period against the same period a year earlier; needs --period weekly or monthly
+    /// (JSON `year_over_year`, extra table section)
+    #[arg(long, conflicts_with = "compare")]
+    pub yoy: bool,
+
+    /// Re-run the analysis every SECONDS until interrupted (TUI refreshes in place)
+    #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "300")]
+    pub watch: Option<NonZeroU64>,
+
+    /// Milliseconds between TUI ticks, which expire status messages and move the loading gauge
+    /// [default: config `defaults.tick_ms`, or 250]
+    #[arg(long, value_name = "MS")]
+    pub tick_ms: Option<NonZeroU64>,
+
     /// Show single metric instead of all metrics (TUI mode)
     #[arg(long)]
     pub single_metric: bool,
 
+    /// Draw the TUI in ROWS rows below the prompt instead of the alternate screen,
+    /// leaving the last frame in the scrollback on quit
+    #[arg(long, value_name = "ROWS", num_args = 0..=1, default_missing_value = "20")]
+    pub inline: Option<NonZeroU16>,
+
+    /// TUI color theme [default: config `defaults.theme`, or default]
+    #[arg(long)]
+    pub theme: Option<ThemeName>,
+
     /// Timezone for date/activity aggregation: local, utc, or IANA tz (e.g. Asia/Tokyo)
-    #[arg(long, default_value = "local")]
-    pub timezone: String,
+    /// [default: config `defaults.timezone`, or local]
+    #[arg(long)]
+    pub timezone: Option<String>,
 
     /// Filter repositories by name (comma-separated, from config)
     #[arg(long, value_delimiter = ',')]
     pub repo_name: Option<Vec<String>>,
+
+    /// Filter repositories by config group (comma-separated or repeated, any may match)
+    #[arg(long, value_delimiter = ',')]
+    pub group: Option<Vec<String>>,
+
+    /// Suppress the spinner and informational messages; results and errors are still printed
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Draw tables with ASCII and disable colors (also enabled by a non-empty `NO_COLOR`)
+    #[arg(long, global = true)]
+    pub no_color: bool,
 }
 
 /// Available subcommands
@@ -63,8 +283,18 @@ pub enum Command {
     Add(AddArgs),
     /// Remove a repository from the configuration
     Remove(RemoveArgs),
+    /// Remove repositories whose path is gone or registered twice
+    Prune(PruneArgs),
     /// List registered repositories
     List(ListArgs),
+    /// Manage the diff cache
+    Cache(CacheArgs),
+    /// Manage the configuration file
+    Config(ConfigArgs),
+    /// Check the configuration and registered repositories for problems
+    Doctor(DoctorArgs),
+    /// Compare statistics of two repositories or branches side by side
+    Compare(CompareArgs),
 }
 
 /// Arguments for the `add` subcommand
@@ -80,13 +310,106 @@ pub struct AddArgs {
     /// Default branch to analyze
     #[arg(short, long)]
     pub branch: Option<String>,
+
+    /// Group to put the repository in (repeatable)
+    #[arg(short, long)]
+    pub group: Vec<String>,
+
+    /// Scan the directory for git repositories and add each one
+    #[arg(long, conflicts_with = "name")]
+    pub scan: bool,
+
+    /// Maximum directory depth to scan (with --scan)
+    #[arg(long, default_value = "2", requires = "scan")]
+    pub depth: usize,
+
+    /// Preview what would be added without writing the config (with --scan)
+    #[arg(long, requires = "scan")]
+    pub dry_run: bool,
+
+    /// Update the name or branch of an already registered repository without asking
+    #[arg(short, long, conflicts_with = "scan")]
+    pub yes: bool,
 }
 
 /// Arguments for the `remove` subcommand
 #[derive(Parser, Debug)]
 pub struct RemoveArgs {
-    /// Repository path or name to remove
-    pub identifier: String,
+    /// Repository paths or names to remove
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    pub identifier: Vec<String>,
+
+    /// Remove every registered repository
+    #[arg(long)]
+    pub all: bool,
+
+    /// Remove every repository with --all without asking
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Arguments for the `prune` subcommand
+#[derive(Parser, Debug)]
+pub struct PruneArgs {
+    /// Report what would be removed without writing the config
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Only remove repositories that no longer exist, keeping duplicates
+    #[arg(long)]
+    pub missing_only: bool,
+}
+
+/// Arguments for the `cache` subcommand
+#[derive(Parser, Debug)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub action: CacheCommand,
+}
+
+/// Actions for the `cache` subcommand
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheCommand {
+    /// Delete all cached diff statistics
+    Clear,
+}
+
+/// Arguments for the `config` subcommand
+#[derive(Parser, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigCommand,
+}
+
+/// Actions for the `config` subcommand
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Create the config file with default settings
+    Init(InitArgs),
+}
+
+/// Arguments for `config init`
+#[derive(Parser, Debug)]
+pub struct InitArgs {
+    /// Overwrite an existing config file
+    #[arg(long)]
+    pub force: bool,
+
+    /// Pre-populate repositories found under DIR [default DIR: parent of the current directory]
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = "..")]
+    pub scan: Option<PathBuf>,
+
+    /// Maximum directory depth to scan (with --scan)
+    #[arg(long, default_value = "2", requires = "scan")]
+    pub depth: usize,
+
+    /// Add every scanned repository without asking (with --scan)
+    #[arg(short, long, requires = "scan")]
+    pub yes: bool,
+
+    /// File format [default: from the --config extension, otherwise json]
+    #[arg(long, value_enum)]
+    pub format: Option<ConfigFormat>,
 }
 
 /// Arguments for the `list` subcommand
@@ -95,10 +418,113 @@ pub struct ListArgs {
     /// Output in JSON format
     #[arg(long)]
     pub json: bool,
+
+    /// Only list repositories in these groups (comma-separated or repeated)
+    #[arg(long, value_delimiter = ',')]
+    pub group: Option<Vec<String>>,
+
+    /// Open each repository to show its HEAD branch, last commit, and commit count
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+/// Arguments for the `doctor` subcommand
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for the `compare` subcommand
+#[derive(Parser, Debug)]
+// CLI flags map naturally to independent bools
+#[allow(clippy::struct_excessive_bools)]
+pub struct CompareArgs {
+    /// First target: repository path, configured repository name, or `repo@branch`
+    #[arg(value_parser = parse_target)]
+    pub left: RepoTarget,
+
+    /// Second target, in the same forms as the first
+    #[arg(value_parser = parse_target)]
+    pub right: RepoTarget,
+
+    /// Number of days to analyze [default: config `defaults.days`, or 7]
+    #[arg(short, long)]
+    pub days: Option<u32>,
+
+    /// Start date, inclusive (YYYY-MM-DD, yesterday, "2 weeks ago", "last friday"); overrides --days
+    #[arg(long, visible_alias = "since", value_name = "DATE")]
+    pub from: Option<DateExpr>,
+
+    /// End date, inclusive, in the same forms as --from [default: today]
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<DateExpr>,
+
+    /// Aggregation period [default: config `defaults.period`, or daily]
+    #[arg(short, long, value_enum)]
+    pub period: Option<Period>,
+
+    /// Timezone for date aggregation: local, utc, or IANA tz (e.g. Asia/Tokyo)
+    /// [default: config `defaults.timezone`, or local]
+    #[arg(long)]
+    pub timezone: Option<String>,
+
+    /// Include merge commits [default: config `defaults.exclude_merges`, or excluded]
+    #[arg(long)]
+    pub include_merges: bool,
+
+    /// File extensions to include (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub ext: Option<Vec<String>>,
+
+    /// Do not read or write the diff cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// A repository (path or configured name) with an optional branch, as in `repo@branch`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoTarget {
+    /// Repository path or configured name; empty for the current directory
+    pub repo: String,
+    /// Branch to analyze instead of the repository's default
+    pub branch: Option<String>,
+}
+
+impl std::fmt::Display for RepoTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.branch {
+            Some(branch) => write!(f, "{}@{branch}", self.repo),
+            None => write!(f, "{}", self.repo),
+        }
+    }
+}
+
+/// Parse a `repo`, `repo@branch`, or `@branch` compare target
+///
+/// The branch is split off at the last `@`, so repository paths containing
+/// `@` need an explicit branch.
+fn parse_target(input: &str) -> Result<RepoTarget, String> {
+    let (repo, branch) = match input.rsplit_once('@') {
+        Some((_, "")) => return Err(format!("missing branch name after '@' in '{input}'")),
+        Some((repo, branch)) => (repo, Some(branch.to_string())),
+        None if input.is_empty() => return Err("target must not be empty".to_string()),
+        None => (input, None),
+    };
+    Ok(RepoTarget {
+        repo: repo.to_string(),
+        branch,
+    })
 }
 
 /// Output format options
-#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     /// Terminal UI with charts
     Tui,
@@ -107,8 +533,16 @@ pub enum OutputFormat {
     Table,
     /// JSON output
     Json,
+    /// Newline-delimited JSON (one object per period, then totals)
+    Ndjson,
     /// CSV output
     Csv,
+    /// Standalone HTML report
+    Html,
+    /// GitHub-style contribution graph of commits per day
+    Graph,
+    /// Prometheus text exposition format for the node exporter textfile collector
+    Prometheus,
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -117,13 +551,68 @@ impl std::fmt::Display for OutputFormat {
             Self::Tui => write!(f, "tui"),
             Self::Table => write!(f, "table"),
             Self::Json => write!(f, "json"),
+            Self::Ndjson => write!(f, "ndjson"),
             Self::Csv => write!(f, "csv"),
+            Self::Html => write!(f, "html"),
+            Self::Graph => write!(f, "graph"),
+            Self::Prometheus => write!(f, "prometheus"),
         }
     }
 }
 
-/// Time period for aggregation
+/// TUI color theme presets
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    /// Colored charts with green additions and red deletions
+    #[default]
+    Default,
+    /// Grayscale only
+    Monochrome,
+    /// Blue/orange palette that avoids red/green pairs
+    Colorblind,
+}
+
+/// Config file formats
 #[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// JSON, validated by the published schema
+    #[default]
+    Json,
+    /// TOML, which allows comments
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Format of a config file, from its extension (`.toml`, otherwise JSON)
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+
+    /// File extension for this format
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// Extra detail levels for `--detail`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detail {
+    /// One entry per file changed by each commit
+    Files,
+}
+
+/// Time period for aggregation
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Period {
     /// Aggregate by day
     #[default]
@@ -150,6 +639,7 @@ impl std::fmt::Display for Period {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
     use clap::CommandFactory;
 
     #[test]
@@ -158,6 +648,10 @@ mod tests {
         assert_eq!(OutputFormat::Table.to_string(), "table");
         assert_eq!(OutputFormat::Json.to_string(), "json");
         assert_eq!(OutputFormat::Csv.to_string(), "csv");
+        assert_eq!(OutputFormat::Html.to_string(), "html");
+        assert_eq!(OutputFormat::Ndjson.to_string(), "ndjson");
+        assert_eq!(OutputFormat::Graph.to_string(), "graph");
+        assert_eq!(OutputFormat::Prometheus.to_string(), "prometheus");
     }
 
     #[test]
@@ -171,47 +665,212 @@ mod tests {
     #[test]
     fn test_args_defaults() {
         let args = Args::parse_from(["kodo"]);
-        assert_eq!(args.days, 7);
+        // Unset values fall back to config defaults, then built-in defaults
+        assert_eq!(args.days, None);
         assert!(!args.include_merges);
-        assert_eq!(args.output, OutputFormat::Table);
-        assert_eq!(args.period, Period::Daily);
+        assert_eq!(args.output, None);
+        assert_eq!(args.period, None);
+        assert_eq!(args.timezone, None);
         assert!(args.command.is_none());
+        assert!(!args.by_extension);
+        assert_eq!(args.top_commits, None);
     }
 
     #[test]
     fn test_args_with_repo() {
         let args = Args::parse_from(["kodo", "--repo", "/tmp/repo"]);
-        assert_eq!(args.repo, Some(PathBuf::from("/tmp/repo")));
+        assert_eq!(args.repo, [PathBuf::from("/tmp/repo")]);
+    }
+
+    #[test]
+    fn test_args_multiple_repos_and_paths() {
+        let args = Args::parse_from([
+            "kodo", "-r", "/a", "--repo", "/b", "/c", "/d", "--days", "30",
+        ]);
+        assert_eq!(args.repo, [PathBuf::from("/a"), PathBuf::from("/b")]);
+        assert_eq!(args.paths, [PathBuf::from("/c"), PathBuf::from("/d")]);
+        assert_eq!(args.days, Some(30));
+        assert!(args.command.is_none());
+    }
+
+    #[test]
+    fn test_args_subcommand_wins_over_path() {
+        let args = Args::parse_from(["kodo", "-c", "config.json", "add", "."]);
+        assert!(matches!(args.command, Some(Command::Add(_))));
+        assert!(args.paths.is_empty());
+    }
+
+    #[test]
+    fn test_args_quiet_and_no_color_are_global() {
+        let args = Args::parse_from(["kodo", "-q", "--no-color"]);
+        assert!(args.quiet && args.no_color);
+
+        let args = Args::parse_from(["kodo", "list", "--quiet", "--no-color"]);
+        assert!(matches!(args.command, Some(Command::List(_))));
+        assert!(args.quiet && args.no_color);
     }
 
     #[test]
     fn test_args_with_days() {
         let args = Args::parse_from(["kodo", "--days", "30"]);
-        assert_eq!(args.days, 30);
+        assert_eq!(args.days, Some(30));
+    }
+
+    #[test]
+    fn test_args_with_from_and_to() {
+        let args = Args::parse_from(["kodo", "--from", "2024-03-01", "--to", "2024-03-14"]);
+        assert_eq!(
+            args.from,
+            NaiveDate::from_ymd_opt(2024, 3, 1).map(DateExpr::Date)
+        );
+        assert_eq!(
+            args.to,
+            NaiveDate::from_ymd_opt(2024, 3, 14).map(DateExpr::Date)
+        );
+
+        let args = Args::parse_from(["kodo", "--since", "2 weeks ago", "--to", "yesterday"]);
+        assert!(matches!(args.from, Some(DateExpr::Ago(2, _))));
+        assert!(matches!(args.to, Some(DateExpr::Ago(1, _))));
+    }
+
+    #[test]
+    fn test_args_invalid_date_is_error() {
+        let err = Args::try_parse_from(["kodo", "--from", "2024-13-01"]).unwrap_err();
+        assert!(err.to_string().contains("expected YYYY-MM-DD"));
+
+        let err = Args::try_parse_from(["kodo", "--to", "yesterday-ish"]).unwrap_err();
+        assert!(err.to_string().contains("expected YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn test_args_unknown_column_lists_valid_names() {
+        let args = Args::parse_from(["kodo", "--columns", "net,period"]);
+        assert_eq!(args.columns, Some(vec![Column::Net, Column::Period]));
+
+        let err = Args::try_parse_from(["kodo", "--columns", "period,lines"]).unwrap_err();
+        assert!(err.to_string().contains("commits"), "{err}");
+
+        let err = Args::try_parse_from(["kodo", "--sort", "lines:desc"]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("valid columns: period, commits, additions, deletions, net, files"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_remove_command_identifiers_or_all() {
+        let args = Args::parse_from(["kodo", "remove", "api", "web"]);
+        let Some(Command::Remove(remove)) = args.command else {
+            panic!("expected remove command");
+        };
+        assert_eq!(remove.identifier, ["api", "web"]);
+
+        assert!(Args::try_parse_from(["kodo", "remove"]).is_err());
+        assert!(Args::try_parse_from(["kodo", "remove", "api", "--all"]).is_err());
+        assert!(Args::try_parse_from(["kodo", "remove", "--all", "-y"]).is_ok());
+    }
+
+    #[test]
+    fn test_args_all_branches_conflicts_with_branch() {
+        let args = Args::parse_from(["kodo", "--all-branches"]);
+        assert!(args.all_branches);
+
+        let result = Args::try_parse_from(["kodo", "--all-branches", "--branch", "main"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_args_watch_interval() {
+        let args = Args::parse_from(["kodo"]);
+        assert_eq!(args.watch, None);
+
+        let args = Args::parse_from(["kodo", "--watch"]);
+        assert_eq!(args.watch.map(NonZeroU64::get), Some(300));
+
+        let args = Args::parse_from(["kodo", "--watch", "60", "--days", "7"]);
+        assert_eq!(args.watch.map(NonZeroU64::get), Some(60));
+        assert_eq!(args.days, Some(7));
+
+        assert!(Args::try_parse_from(["kodo", "--watch", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_args_fail_if_empty_conflicts_with_watch() {
+        let args = Args::parse_from(["kodo", "--fail-if-empty"]);
+        assert!(args.fail_if_empty);
+
+        // Watch mode keeps running, so an empty cycle has nothing to exit
+        let result = Args::try_parse_from(["kodo", "--fail-if-empty", "--watch"]);
+        assert!(result.is_err());
+        let result = Args::try_parse_from(["kodo", "--watch", "60", "--fail-if-empty"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_args_inline_height() {
+        let args = Args::parse_from(["kodo", "--inline"]);
+        assert_eq!(args.inline.map(NonZeroU16::get), Some(20));
+
+        let args = Args::parse_from(["kodo", "--inline", "12"]);
+        assert_eq!(args.inline.map(NonZeroU16::get), Some(12));
+
+        assert!(Args::try_parse_from(["kodo", "--inline", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_args_tick_ms() {
+        let args = Args::parse_from(["kodo", "--tick-ms", "1000"]);
+        assert_eq!(args.tick_ms.map(NonZeroU64::get), Some(1000));
+        assert!(Args::try_parse_from(["kodo", "--tick-ms", "0"]).is_err());
     }
 
     #[test]
     fn test_args_output_tui_explicit() {
         let args = Args::parse_from(["kodo", "--output", "tui"]);
-        assert_eq!(args.output, OutputFormat::Tui);
+        assert_eq!(args.output, Some(OutputFormat::Tui));
     }
 
     #[test]
     fn test_args_output_tui_short() {
         let args = Args::parse_from(["kodo", "-o", "tui"]);
-        assert_eq!(args.output, OutputFormat::Tui);
+        assert_eq!(args.output, Some(OutputFormat::Tui));
     }
 
     #[test]
     fn test_args_output_json_explicit() {
         let args = Args::parse_from(["kodo", "--output", "json"]);
-        assert_eq!(args.output, OutputFormat::Json);
+        assert_eq!(args.output, Some(OutputFormat::Json));
     }
 
     #[test]
     fn test_args_output_csv_explicit() {
         let args = Args::parse_from(["kodo", "--output", "csv"]);
-        assert_eq!(args.output, OutputFormat::Csv);
+        assert_eq!(args.output, Some(OutputFormat::Csv));
+    }
+
+    #[test]
+    fn test_args_output_html_with_out_file() {
+        let args = Args::parse_from(["kodo", "-o", "html", "--out", "report.html"]);
+        assert_eq!(args.output, Some(OutputFormat::Html));
+        assert_eq!(args.out, Some(PathBuf::from("report.html")));
+    }
+
+    #[test]
+    fn test_args_input_conflicts_with_repo_selection() {
+        let args = Args::parse_from(["kodo", "--input", "out.json", "-o", "tui"]);
+        assert_eq!(args.input, Some(PathBuf::from("out.json")));
+
+        assert!(Args::try_parse_from(["kodo", "--input", "out.json", "--repo", "."]).is_err());
+        assert!(Args::try_parse_from(["kodo", "--input", "out.json", "--days", "7"]).is_err());
+        assert!(Args::try_parse_from(["kodo", "--input", "out.json", "--watch"]).is_err());
+    }
+
+    #[test]
+    fn test_args_detail_files() {
+        let args = Args::parse_from(["kodo", "-o", "csv", "--detail", "files"]);
+        assert_eq!(args.detail, Some(Detail::Files));
+        assert!(Args::try_parse_from(["kodo", "--detail", "lines"]).is_err());
     }
 
     #[test]
@@ -231,9 +890,34 @@ mod tests {
             assert_eq!(add_args.path, PathBuf::from("."));
             assert!(add_args.name.is_none());
             assert!(add_args.branch.is_none());
+            assert!(!add_args.scan);
+            assert_eq!(add_args.depth, 2);
         }
     }
 
+    #[test]
+    fn test_add_command_with_scan() {
+        let args = Args::parse_from([
+            "kodo",
+            "add",
+            "~/code",
+            "--scan",
+            "--depth",
+            "3",
+            "--dry-run",
+        ]);
+        if let Some(Command::Add(add_args)) = args.command {
+            assert!(add_args.scan);
+            assert_eq!(add_args.depth, 3);
+            assert!(add_args.dry_run);
+        } else {
+            panic!("expected add command");
+        }
+
+        assert!(Args::try_parse_from(["kodo", "add", ".", "--dry-run"]).is_err());
+        assert!(Args::try_parse_from(["kodo", "add", ".", "--scan", "--name", "x"]).is_err());
+    }
+
     #[test]
     fn test_add_command_with_options() {
         let args = Args::parse_from([
@@ -258,6 +942,39 @@ mod tests {
         assert!(matches!(args.command, Some(Command::List(_))));
         if let Some(Command::List(list_args)) = args.command {
             assert!(!list_args.json);
+            assert!(!list_args.verbose);
+        }
+
+        let args = Args::parse_from(["kodo", "list", "-v", "--json"]);
+        if let Some(Command::List(list_args)) = args.command {
+            assert!(list_args.verbose && list_args.json);
+        }
+    }
+
+    #[test]
+    fn test_group_flags() {
+        let args = Args::parse_from(["kodo", "--group", "work,oss", "--group", "infra"]);
+        assert_eq!(
+            args.group,
+            Some(vec![
+                "work".to_string(),
+                "oss".to_string(),
+                "infra".to_string()
+            ])
+        );
+
+        let args = Args::parse_from(["kodo", "add", ".", "-g", "work", "--group", "backend"]);
+        if let Some(Command::Add(add_args)) = args.command {
+            assert_eq!(add_args.group, ["work", "backend"]);
+        } else {
+            panic!("expected add command");
+        }
+
+        let args = Args::parse_from(["kodo", "list", "--group", "work"]);
+        if let Some(Command::List(list_args)) = args.command {
+            assert_eq!(list_args.group, Some(vec!["work".to_string()]));
+        } else {
+            panic!("expected list command");
         }
     }
 
@@ -270,6 +987,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_prune_command() {
+        let args = Args::parse_from(["kodo", "prune", "--dry-run", "--missing-only"]);
+        let Some(Command::Prune(prune)) = args.command else {
+            panic!("expected prune command");
+        };
+        assert!(prune.dry_run);
+        assert!(prune.missing_only);
+    }
+
+    #[test]
+    fn test_cache_clear_command() {
+        let args = Args::parse_from(["kodo", "cache", "clear"]);
+        if let Some(Command::Cache(cache_args)) = args.command {
+            assert_eq!(cache_args.action, CacheCommand::Clear);
+        } else {
+            panic!("expected cache command");
+        }
+    }
+
+    #[test]
+    fn test_config_init_command() {
+        let args = Args::parse_from(["kodo", "config", "init"]);
+        let Some(Command::Config(ConfigArgs {
+            action: ConfigCommand::Init(init),
+        })) = args.command
+        else {
+            panic!("expected config init command");
+        };
+        assert!(!init.force);
+        assert_eq!(init.scan, None);
+
+        let args = Args::parse_from(["kodo", "config", "init", "--scan", "--yes"]);
+        let Some(Command::Config(ConfigArgs {
+            action: ConfigCommand::Init(init),
+        })) = args.command
+        else {
+            panic!("expected config init command");
+        };
+        assert_eq!(init.scan, Some(PathBuf::from("..")));
+        assert!(init.yes);
+
+        assert!(Args::try_parse_from(["kodo", "config", "init", "--yes"]).is_err());
+    }
+
+    #[test]
+    fn test_compare_command_targets() {
+        let args = Args::parse_from(["kodo", "compare", "kodo@main", "~/src/other", "-d", "30"]);
+        let Some(Command::Compare(compare)) = args.command else {
+            panic!("expected compare command");
+        };
+        assert_eq!(
+            compare.left,
+            RepoTarget {
+                repo: "kodo".to_string(),
+                branch: Some("main".to_string()),
+            }
+        );
+        assert_eq!(compare.right.repo, "~/src/other");
+        assert_eq!(compare.right.branch, None);
+        assert_eq!(compare.days, Some(30));
+    }
+
+    #[test]
+    fn test_parse_target_forms() {
+        let target = parse_target("@feature/login").unwrap();
+        assert_eq!(target.repo, "");
+        assert_eq!(target.branch.as_deref(), Some("feature/login"));
+        assert_eq!(target.to_string(), "@feature/login");
+
+        assert!(parse_target("kodo@").is_err());
+        assert!(parse_target("").is_err());
+    }
+
     #[test]
     fn test_help_includes_output_short() {
         let help = Args::command().render_help().to_string();
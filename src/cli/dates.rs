@@ -0,0 +1,224 @@
+//! Date expressions accepted by `--from` and `--to`
+//!
+//! Besides `YYYY-MM-DD`, dates can be given relative to today: `today`,
+//! `yesterday`, `N days|weeks|months|years ago`, and `last <weekday>`.
+//! Expressions are parsed up front and resolved later against the current
+//! date in the selected timezone, so the parser never reads the clock.
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::str::FromStr;
+
+/// Accepted forms, listed in parse errors
+const ACCEPTED: &str = "expected YYYY-MM-DD, today, yesterday, N days/weeks/months/years ago, \
+                        or last <weekday> (e.g. 2024-03-01, \"2 weeks ago\", \"last friday\")";
+
+/// Largest amount accepted in `N <unit> ago`
+const MAX_AMOUNT: u32 = 100_000;
+
+/// Unit of a relative `N <unit> ago` expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Unit {
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "d" | "day" | "days" => Some(Self::Day),
+            "w" | "week" | "weeks" => Some(Self::Week),
+            "month" | "months" => Some(Self::Month),
+            "y" | "year" | "years" => Some(Self::Year),
+            _ => None,
+        }
+    }
+}
+
+/// A date given on the command line, absolute or relative to today
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateExpr {
+    /// A fixed calendar date
+    Date(NaiveDate),
+    /// `N` units before today (`today` is zero days ago)
+    Ago(u32, Unit),
+    /// The most recent given weekday strictly before today
+    LastWeekday(Weekday),
+}
+
+impl DateExpr {
+    /// Resolve the expression to a date, taking `today` as the reference
+    ///
+    /// Months and years keep the day of the month, clamped to the length of
+    /// the target month (2024-03-31 minus 1 month is 2024-02-29).
+    #[must_use]
+    pub fn resolve(self, today: NaiveDate) -> NaiveDate {
+        let date = match self {
+            Self::Date(date) => Some(date),
+            Self::Ago(n, Unit::Day) => today.checked_sub_days(Days::new(u64::from(n))),
+            Self::Ago(n, Unit::Week) => today.checked_sub_days(Days::new(u64::from(n) * 7)),
+            Self::Ago(n, Unit::Month) => today.checked_sub_months(Months::new(n)),
+            Self::Ago(n, Unit::Year) => today.checked_sub_months(Months::new(n * 12)),
+            Self::LastWeekday(weekday) => {
+                let back = (today.weekday().num_days_from_monday() + 6
+                    - weekday.num_days_from_monday())
+                    % 7
+                    + 1;
+                today.checked_sub_days(Days::new(u64::from(back)))
+            }
+        };
+        date.unwrap_or(NaiveDate::MIN)
+    }
+}
+
+impl From<NaiveDate> for DateExpr {
+    fn from(date: NaiveDate) -> Self {
+        Self::Date(date)
+    }
+}
+
+impl FromStr for DateExpr {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let normalized = input.trim().to_ascii_lowercase();
+        if normalized.starts_with(|c: char| c.is_ascii_digit()) && normalized.contains('-') {
+            return NaiveDate::parse_from_str(&normalized, "%Y-%m-%d")
+                .map(Self::Date)
+                .map_err(|e| format!("invalid date '{input}' ({e}); {ACCEPTED}"));
+        }
+
+        let words: Vec<&str> = normalized.split_whitespace().collect();
+        let expr = match words.as_slice() {
+            ["today"] => Some(Self::Ago(0, Unit::Day)),
+            ["yesterday"] => Some(Self::Ago(1, Unit::Day)),
+            [amount, unit, "ago"] => parse_ago(input, amount, unit)?,
+            [compact, "ago"] => {
+                let split = compact
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(compact.len());
+                parse_ago(input, &compact[..split], &compact[split..])?
+            }
+            ["last", unit @ ("week" | "month" | "year")] => {
+                return Err(format!(
+                    "ambiguous date '{input}': use \"1 {unit} ago\" or a YYYY-MM-DD date"
+                ));
+            }
+            ["last", day] => day.parse::<Weekday>().ok().map(Self::LastWeekday),
+            [day] if day.parse::<Weekday>().is_ok() => {
+                return Err(format!(
+                    "ambiguous date '{input}': use \"last {day}\" for the previous {day}"
+                ));
+            }
+            _ => None,
+        };
+        expr.ok_or_else(|| format!("invalid date '{input}'; {ACCEPTED}"))
+    }
+}
+
+/// Parse the amount and unit of `N <unit> ago`, or `None` when they are not one
+fn parse_ago(input: &str, amount: &str, unit: &str) -> Result<Option<DateExpr>, String> {
+    if matches!(unit, "m" | "min" | "mins" | "minute" | "minutes") {
+        return Err(format!(
+            "ambiguous date '{input}': times are not supported; use \"months\" or \"days\""
+        ));
+    }
+    let amount = match amount {
+        "a" | "an" => Some(1),
+        amount => amount.parse::<u32>().ok(),
+    };
+    let (Some(amount), Some(unit)) = (amount, Unit::parse(unit)) else {
+        return Ok(None);
+    };
+    if amount > MAX_AMOUNT {
+        return Err(format!(
+            "invalid date '{input}': at most {MAX_AMOUNT} units ago are supported"
+        ));
+    }
+    Ok(Some(DateExpr::Ago(amount, unit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_expressions_resolve_against_frozen_today() {
+        // Wednesday
+        let today = date(2024, 3, 13);
+        let cases = [
+            ("2024-03-01", date(2024, 3, 1)),
+            ("today", today),
+            ("Yesterday", date(2024, 3, 12)),
+            ("  today  ", today),
+            ("0 days ago", today),
+            ("1 day ago", date(2024, 3, 12)),
+            ("3 days ago", date(2024, 3, 10)),
+            ("2 weeks ago", date(2024, 2, 28)),
+            ("a week ago", date(2024, 3, 6)),
+            ("2w ago", date(2024, 2, 28)),
+            ("10d ago", date(2024, 3, 3)),
+            ("1 month ago", date(2024, 2, 13)),
+            ("a year ago", date(2023, 3, 13)),
+            ("2 years ago", date(2022, 3, 13)),
+            ("last friday", date(2024, 3, 8)),
+            ("last tue", date(2024, 3, 12)),
+            ("last wednesday", date(2024, 3, 6)),
+            ("last thursday", date(2024, 3, 7)),
+        ];
+        for (input, expected) in cases {
+            let expr: DateExpr = input.parse().unwrap_or_else(|e| panic!("{input}: {e}"));
+            assert_eq!(expr.resolve(today), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_months_clamp_to_month_end() {
+        let expr: DateExpr = "1 month ago".parse().unwrap();
+        assert_eq!(expr.resolve(date(2024, 3, 31)), date(2024, 2, 29));
+
+        let expr: DateExpr = "1 year ago".parse().unwrap();
+        assert_eq!(expr.resolve(date(2024, 2, 29)), date(2023, 2, 28));
+    }
+
+    #[test]
+    fn test_invalid_expressions_list_accepted_forms() {
+        for input in [
+            "2024-13-01",
+            "yesterday-ish",
+            "2 fortnights ago",
+            "soon",
+            "",
+        ] {
+            let err = input.parse::<DateExpr>().unwrap_err();
+            assert!(err.contains("expected YYYY-MM-DD"), "{input}: {err}");
+            assert!(err.contains("last <weekday>"), "{input}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_expressions_suggest_alternatives() {
+        let err = "friday".parse::<DateExpr>().unwrap_err();
+        assert!(
+            err.contains("ambiguous") && err.contains("last friday"),
+            "{err}"
+        );
+
+        let err = "last week".parse::<DateExpr>().unwrap_err();
+        assert!(
+            err.contains("ambiguous") && err.contains("1 week ago"),
+            "{err}"
+        );
+
+        let err = "5m ago".parse::<DateExpr>().unwrap_err();
+        assert!(err.contains("ambiguous") && err.contains("months"), "{err}");
+
+        let err = "999999 days ago".parse::<DateExpr>().unwrap_err();
+        assert!(err.contains("at most"), "{err}");
+    }
+}
@@ -0,0 +1,351 @@
+//! `doctor` subcommand: validate configuration and repositories
+
+use crate::cli::args::DoctorArgs;
+use crate::config::{Config, RepoConfig, default_config_path, expand_tilde, load_config};
+use crate::error::{Error, Result};
+use crate::git::Repository;
+use crate::stats::TimeZoneMode;
+use comfy_table::{Table, presets::UTF8_FULL};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Suffix every recognized `$schema` reference ends with
+const SCHEMA_SUFFIX: &str = "schemas/config.schema.json";
+
+/// Target name used for configuration-level checks
+const CONFIG_TARGET: &str = "config";
+
+/// Result of a single doctor check
+#[derive(Debug, Serialize)]
+struct Check {
+    /// `config` or the repository name
+    target: String,
+    /// Short check name
+    #[serde(rename = "check")]
+    name: &'static str,
+    /// Whether the check passed
+    ok: bool,
+    /// What was found, or what to fix
+    message: String,
+}
+
+impl Check {
+    fn new(target: &str, name: &'static str, result: std::result::Result<String, String>) -> Self {
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        Self {
+            target: target.to_string(),
+            name,
+            ok,
+            message,
+        }
+    }
+}
+
+/// Machine-readable doctor report (`--json`)
+#[derive(Serialize)]
+struct Report<'a> {
+    ok: bool,
+    checks: &'a [Check],
+}
+
+/// Execute the `doctor` subcommand
+///
+/// Prints one row per check and fails when any check fails, so it can gate CI.
+///
+/// # Errors
+///
+/// Returns [`Error::DoctorFailed`] if any check fails, or an error if the
+/// JSON report cannot be serialized
+pub fn execute_doctor(doctor_args: &DoctorArgs, config_path: Option<PathBuf>) -> Result<()> {
+    let config_file = config_path.or_else(default_config_path);
+    let checks = run_checks(config_file.as_deref());
+    let failed = checks.iter().filter(|c| !c.ok).count();
+
+    if doctor_args.json {
+        let report = Report {
+            ok: failed == 0,
+            checks: &checks,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", render_table(&checks));
+    }
+
+    if failed > 0 {
+        return Err(Error::DoctorFailed { failed });
+    }
+    Ok(())
+}
+
+/// Run all checks against the config file and its repositories
+fn run_checks(config_file: Option<&Path>) -> Vec<Check> {
+    let Some(config_file) = config_file else {
+        return vec![Check::new(
+            CONFIG_TARGET,
+            "config file",
+            Err("no config path could be determined (home directory not found)".to_string()),
+        )];
+    };
+
+    let config = match load_config(config_file) {
+        Ok(config) => config,
+        Err(e) => {
+            return vec![Check::new(
+                CONFIG_TARGET,
+                "config file",
+                Err(config_error_message(&e)),
+            )];
+        }
+    };
+
+    let mut checks = vec![
+        Check::new(
+            CONFIG_TARGET,
+            "config file",
+            Ok(format!("{} parsed", config_file.display())),
+        ),
+        Check::new(CONFIG_TARGET, "$schema", check_schema(&config)),
+        Check::new(CONFIG_TARGET, "defaults.timezone", check_timezone(&config)),
+    ];
+    for repo in &config.repositories {
+        check_repository(repo, &mut checks);
+    }
+    checks
+}
+
+/// Describe a config loading failure in terms of what to fix
+fn config_error_message(error: &Error) -> String {
+    match error {
+        Error::ConfigNotFound { path } => format!(
+            "not found at {}; register a repository with 'kodo add <path>'",
+            path.display()
+        ),
+        Error::Json(e) => format!("invalid JSON: {e}"),
+        Error::Toml(e) => format!("invalid TOML: {e}"),
+        Error::ConfigInvalid { message } => message.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn check_schema(config: &Config) -> std::result::Result<String, String> {
+    match &config.schema {
+        None => Ok("not set".to_string()),
+        Some(schema) if schema.ends_with(SCHEMA_SUFFIX) => Ok(schema.clone()),
+        Some(schema) => Err(format!(
+            "unrecognized $schema '{schema}'; expected a reference to {SCHEMA_SUFFIX}"
+        )),
+    }
+}
+
+fn check_timezone(config: &Config) -> std::result::Result<String, String> {
+    match &config.defaults.timezone {
+        None => Ok("not set (local)".to_string()),
+        Some(timezone) => TimeZoneMode::parse(timezone).map(|_| timezone.clone()),
+    }
+}
+
+/// Check a single repository, stopping at the first check later ones depend on
+fn check_repository(repo: &RepoConfig, checks: &mut Vec<Check>) {
+    let name = repo.name.as_str();
+    let path = expand_tilde(&repo.path);
+
+    if !path.exists() {
+        checks.push(Check::new(
+            name,
+            "path exists",
+            Err(format!("{} does not exist", path.display())),
+        ));
+        return;
+    }
+    checks.push(Check::new(
+        name,
+        "path exists",
+        Ok(path.display().to_string()),
+    ));
+
+    let git_repo = match Repository::open(&path, name) {
+        Ok(git_repo) => {
+            checks.push(Check::new(name, "git repository", Ok("opened".to_string())));
+            git_repo
+        }
+        Err(e) => {
+            checks.push(Check::new(
+                name,
+                "git repository",
+                Err(format!("cannot open: {e}")),
+            ));
+            return;
+        }
+    };
+
+    let branch = match (&repo.branch, repo.all_branches) {
+        (Some(branch), true) => Err(format!(
+            "branch '{branch}' and all_branches are both set; remove one"
+        )),
+        (None, true) => Ok("all local branches".to_string()),
+        (None, false) => Ok("HEAD".to_string()),
+        (Some(branch), false) => git_repo
+            .resolve_branch(branch)
+            .map(|reference| format!("{branch} -> {reference}"))
+            .map_err(|_| {
+                format!("branch '{branch}' not found as a local or remote-tracking branch")
+            }),
+    };
+    checks.push(Check::new(name, "branch", branch));
+
+    let head = if git_repo.is_unborn() {
+        Err("HEAD is unborn (no commits yet)".to_string())
+    } else {
+        Ok("has commits".to_string())
+    };
+    checks.push(Check::new(name, "HEAD", head));
+}
+
+fn render_table(checks: &[Check]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(["Target", "Check", "Status", "Details"]);
+    for check in checks {
+        let status = if check.ok { "\u{2713}" } else { "\u{2717}" };
+        table.add_row([
+            check.target.as_str(),
+            check.name,
+            status,
+            check.message.as_str(),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(path: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .unwrap();
+    }
+
+    fn create_test_repo(with_commit: bool) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-b", "main"]);
+        git(path, &["config", "user.email", "test@example.com"]);
+        git(path, &["config", "user.name", "Test User"]);
+        if with_commit {
+            std::fs::write(path.join("README.md"), "# Test\n").unwrap();
+            git(path, &["add", "."]);
+            git(path, &["commit", "-m", "Initial commit"]);
+        }
+        dir
+    }
+
+    fn write_config(dir: &Path, config: &serde_json::Value) -> PathBuf {
+        let path = dir.join("config.json");
+        std::fs::write(&path, config.to_string()).unwrap();
+        path
+    }
+
+    fn failed(checks: &[Check]) -> Vec<(&str, &str)> {
+        checks
+            .iter()
+            .filter(|c| !c.ok)
+            .map(|c| (c.target.as_str(), c.name))
+            .collect()
+    }
+
+    #[test]
+    fn test_doctor_all_checks_pass() {
+        let repo = create_test_repo(true);
+        let config_dir = TempDir::new().unwrap();
+        let config_file = write_config(
+            config_dir.path(),
+            &serde_json::json!({
+                "$schema": "https://raw.githubusercontent.com/yumazak/kodo/main/schemas/config.schema.json",
+                "repositories": [
+                    { "name": "good", "path": repo.path(), "branch": "main" }
+                ],
+                "defaults": { "timezone": "Asia/Tokyo" }
+            }),
+        );
+
+        let checks = run_checks(Some(&config_file));
+
+        assert!(failed(&checks).is_empty(), "{checks:?}");
+        assert_eq!(checks.len(), 7);
+    }
+
+    #[test]
+    fn test_doctor_reports_each_problem() {
+        let repo = create_test_repo(true);
+        let unborn = create_test_repo(false);
+        let plain_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+        let config_file = write_config(
+            config_dir.path(),
+            &serde_json::json!({
+                "$schema": "https://example.com/other.json",
+                "repositories": [
+                    { "name": "missing", "path": "/nonexistent/kodo-doctor" },
+                    { "name": "plain", "path": plain_dir.path() },
+                    { "name": "typo", "path": repo.path(), "branch": "mian" },
+                    { "name": "empty", "path": unborn.path() }
+                ],
+                "defaults": { "timezone": "Mars/Olympus" }
+            }),
+        );
+
+        let checks = run_checks(Some(&config_file));
+
+        assert_eq!(
+            failed(&checks),
+            [
+                ("config", "$schema"),
+                ("config", "defaults.timezone"),
+                ("missing", "path exists"),
+                ("plain", "git repository"),
+                ("typo", "branch"),
+                ("empty", "HEAD"),
+            ]
+        );
+        let branch = checks
+            .iter()
+            .find(|c| c.target == "typo" && c.name == "branch");
+        assert!(branch.unwrap().message.contains("'mian' not found"));
+    }
+
+    #[test]
+    fn test_doctor_missing_and_invalid_config() {
+        let dir = TempDir::new().unwrap();
+
+        let checks = run_checks(Some(&dir.path().join("missing.json")));
+        assert_eq!(failed(&checks), [("config", "config file")]);
+        assert!(checks[0].message.contains("kodo add"));
+
+        let path = dir.path().join("broken.json");
+        std::fs::write(&path, "{ not json").unwrap();
+        let checks = run_checks(Some(&path));
+        assert_eq!(failed(&checks), [("config", "config file")]);
+        assert!(checks[0].message.starts_with("invalid JSON"));
+    }
+
+    #[test]
+    fn test_execute_doctor_fails_when_a_check_fails() {
+        let dir = TempDir::new().unwrap();
+        let result = execute_doctor(
+            &DoctorArgs { json: true },
+            Some(dir.path().join("missing.json")),
+        );
+
+        assert!(matches!(result, Err(Error::DoctorFailed { failed: 1 })));
+    }
+}
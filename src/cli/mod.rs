@@ -1,6 +1,8 @@
 //! CLI module for kodo
 
 pub mod args;
+pub mod dates;
+pub mod doctor;
 pub mod run;
 
 pub use args::{AddArgs, Args, Command};
@@ -1,33 +1,148 @@
 //! CLI execution logic
 
-use crate::cli::args::{AddArgs, Args, Command, ListArgs, OutputFormat, RemoveArgs};
+use crate::cli::args::{
+    AddArgs, Args, CacheCommand, Command, CompareArgs, ConfigCommand, ConfigFormat, Detail,
+    InitArgs, ListArgs, OutputFormat, Period, PruneArgs, RemoveArgs, RepoTarget,
+};
+use crate::cli::doctor::execute_doctor;
 use crate::config::{
-    Config, Defaults, RepoConfig, default_config_path, default_config_path_for_save, expand_tilde,
-    load_config, save_config,
+    Config, ConfigLock, Defaults, RepoConfig, default_config_path, default_config_path_for_save,
+    expand_tilde, load_config, load_config_for_update, save_config,
 };
 use crate::error::{Error, Result};
-use crate::git::{CommitInfo, Repository};
-use crate::output::{CsvFormatter, Formatter, JsonFormatter, TableFormatter};
-use crate::stats::{DateRange, TimeZoneMode, collect_activity_stats, collect_stats};
-use crate::tui::App;
+use crate::git::cache::default_cache_dir;
+use crate::git::{
+    CollectOptions, CollectProgress, CommitInfo, DEFAULT_RENAME_THRESHOLD, DiffCache,
+    IgnoreWhitespace, Repository, discover_repository, is_git_repository,
+};
+use crate::output::sparkline::sparkline;
+use crate::output::table::format_with_commas_u64;
+use crate::output::{
+    CompareReport, CsvFormatter, Formatter, GraphFormatter, HtmlFormatter, JsonFormatter,
+    NdjsonFormatter, PrometheusFormatter, TableFormatter,
+};
+use crate::stats::{
+    ActivityStats, AnalysisResult, ComparisonStats, DEFAULT_PERIOD_EXTENSIONS, DateRange, Days,
+    FileFilter, PeriodStats, RepoStats, TimeZoneMode, YearOverYear, aggregate_by_period,
+    collect_activity_stats, collect_daily_extension_lines, collect_extension_stats,
+    collect_file_details, collect_stats, collect_top_commits, collect_top_dirs, collect_top_files,
+    collect_totals, collect_work_pattern, default_min_gap, find_gaps, mark_outliers,
+    moving_averages,
+};
+use crate::tui::event::DEFAULT_TICK_MS;
+use crate::tui::loader::{LoadContext, LoadEvent, Loader};
+use crate::tui::terminal::ViewportMode;
+use crate::tui::{App, Keymap, Refresher, RepoView, Theme};
+use chrono::NaiveDate;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// ANSI sequence that clears the terminal and moves the cursor home
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
 
 /// Repository info for analysis
 struct RepoInfo {
     path: PathBuf,
     name: String,
     branch: Option<String>,
+    all_branches: bool,
+    /// File extensions from the config, used unless `--ext` is given
+    ext: Vec<String>,
+    /// Include merge commits even when the defaults exclude them
+    include_merges: bool,
+    /// Follow only the first parent of merges
+    first_parent: bool,
+    /// Timezone for bucketing this repository's commits, instead of `--timezone`
+    timezone: Option<TimeZoneMode>,
+}
+
+/// Analysis settings resolved from CLI flags, config defaults, and built-in defaults
+struct Settings {
+    range: DateRange,
+    period: Period,
+    output: OutputFormat,
+    timezone: TimeZoneMode,
+    exclude_merges: bool,
+    ignore_whitespace: IgnoreWhitespace,
+}
+
+impl Settings {
+    /// Resolve settings with precedence: CLI flag > config defaults > built-in default
+    fn resolve(args: &Args, defaults: &Defaults) -> Result<Self> {
+        let timezone = resolve_timezone(args, defaults)?;
+
+        // --from/--to take precedence; --days N yields exactly N days ending on --to or today
+        let days = Days::new(args.days.unwrap_or(defaults.days));
+        let today = timezone.now_date_naive();
+        let from = args.from.map(|expr| expr.resolve(today));
+        let to = args.to.map(|expr| expr.resolve(today));
+        let range = match (from, to) {
+            (Some(from), to) => {
+                let to = to.unwrap_or(today);
+                if to < from {
+                    return Err(Error::InvalidDateRange { from, to });
+                }
+                DateRange::new(from, to)
+            }
+            (None, Some(to)) => DateRange::ending_on(to, days),
+            (None, None) => DateRange::ending_on(today, days),
+        };
+
+        let period = args.period.or(defaults.period).unwrap_or_default();
+        if args.yoy && !matches!(period, Period::Weekly | Period::Monthly) {
+            return Err(Error::YoyPeriod {
+                period: period.to_string(),
+            });
+        }
+        let range = if args.complete_periods {
+            range
+                .complete_periods(period)
+                .ok_or_else(|| Error::NoCompletePeriod {
+                    period: period.to_string(),
+                    from: range.from,
+                    to: range.to,
+                })?
+        } else {
+            range
+        };
+
+        Ok(Self {
+            range,
+            period,
+            output: args.output.or(defaults.output).unwrap_or_default(),
+            timezone,
+            exclude_merges: !args.include_merges && defaults.exclude_merges,
+            ignore_whitespace: if args.ignore_whitespace {
+                IgnoreWhitespace::All
+            } else if args.ignore_whitespace_eol {
+                IgnoreWhitespace::Eol
+            } else {
+                defaults.ignore_whitespace.unwrap_or_default()
+            },
+        })
+    }
 }
 
 /// RAII guard for spinner to ensure cleanup on error
-struct SpinnerGuard(Option<ProgressBar>);
+struct SpinnerGuard {
+    spinner: Option<ProgressBar>,
+    /// Commits walked so far across all repositories
+    scanned: AtomicUsize,
+}
 
 impl SpinnerGuard {
+    /// Start a spinner if `enabled` and stdout is a terminal, so piped and cron output stays clean
     fn new(enabled: bool) -> Self {
-        let spinner = if enabled {
+        let spinner = if enabled && std::io::stdout().is_terminal() {
             let sp = ProgressBar::new_spinner();
             sp.set_style(
                 ProgressStyle::default_spinner()
@@ -40,11 +155,14 @@ impl SpinnerGuard {
         } else {
             None
         };
-        Self(spinner)
+        Self {
+            spinner,
+            scanned: AtomicUsize::new(0),
+        }
     }
 
     fn set_message(&self, msg: impl Into<std::borrow::Cow<'static, str>>) {
-        if let Some(sp) = &self.0 {
+        if let Some(sp) = &self.spinner {
             sp.set_message(msg);
         }
     }
@@ -52,12 +170,76 @@ impl SpinnerGuard {
 
 impl Drop for SpinnerGuard {
     fn drop(&mut self) {
-        if let Some(sp) = self.0.take() {
+        if let Some(sp) = self.spinner.take() {
             sp.finish_and_clear();
         }
     }
 }
 
+/// Where collection progress goes: the spinner, or the TUI loading screen
+trait Progress: Sync {
+    /// Collection from `total` repositories started
+    fn collecting(&self, total: usize);
+    /// `finished` of `total` repositories are done, the last being `repo` with `commits` commits
+    fn repo_finished(&self, repo: &str, commits: usize, finished: usize, total: usize);
+    /// `scanned` commits were walked so far across all repositories
+    fn commits_scanned(&self, _scanned: usize, _finished: usize, _total: usize) {}
+    /// All commits are in and statistics are being computed
+    fn summarizing(&self);
+    /// Whether the result is no longer wanted
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl Progress for SpinnerGuard {
+    fn collecting(&self, total: usize) {
+        self.scanned.store(0, Ordering::Relaxed);
+        self.set_message(format!("Collecting commits (0/{total})..."));
+    }
+
+    fn repo_finished(&self, _repo: &str, _commits: usize, finished: usize, total: usize) {
+        self.commits_scanned(self.scanned.load(Ordering::Relaxed), finished, total);
+    }
+
+    fn commits_scanned(&self, scanned: usize, finished: usize, total: usize) {
+        self.scanned.store(scanned, Ordering::Relaxed);
+        if scanned == 0 {
+            self.set_message(format!("Collecting commits ({finished}/{total})..."));
+        } else {
+            self.set_message(format!(
+                "Collecting commits ({finished}/{total}), {} commits scanned...",
+                format_with_commas_u64(scanned as u64)
+            ));
+        }
+    }
+
+    fn summarizing(&self) {
+        self.set_message("Calculating statistics...");
+    }
+}
+
+impl Progress for LoadContext {
+    fn collecting(&self, total: usize) {
+        self.send(LoadEvent::Started { total });
+    }
+
+    fn repo_finished(&self, repo: &str, commits: usize, _finished: usize, _total: usize) {
+        self.send(LoadEvent::RepoLoaded {
+            repo: repo.to_string(),
+            commits,
+        });
+    }
+
+    fn summarizing(&self) {
+        self.send(LoadEvent::Summarizing);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        Self::is_cancelled(self)
+    }
+}
+
 /// Execute the CLI with the given arguments
 ///
 /// # Errors
@@ -70,649 +252,3751 @@ impl Drop for SpinnerGuard {
 /// # Panics
 ///
 /// Panics if the progress bar style template is invalid (should never happen).
-// Takes ownership because args.command is consumed by match
-#[allow(clippy::needless_pass_by_value)]
-pub fn execute(args: Args) -> Result<()> {
+pub fn execute(mut args: Args) -> Result<()> {
     // Handle subcommands
-    if let Some(command) = args.command {
-        return match command {
-            Command::Add(add_args) => execute_add(add_args, args.config),
-            Command::Remove(remove_args) => execute_remove(remove_args, args.config),
-            Command::List(list_args) => execute_list(list_args, args.config),
-        };
+    if let Some(command) = args.command.take() {
+        return execute_command(command, &args);
+    }
+
+    if let Some(input) = args.input.take() {
+        return execute_input(&args, &input);
     }
 
     // Default: analyze repositories
-    // Create spinner for all output modes (RAII ensures cleanup on error)
-    let spinner = SpinnerGuard::new(true);
+    // A broken config only matters when it is needed for the repository list
+    let config = match load_optional_config(args.config.clone()) {
+        Ok(config) => config,
+        Err(_) if !args.repo.is_empty() || !args.paths.is_empty() => None,
+        Err(e) => return Err(e),
+    };
+    let defaults = config
+        .as_ref()
+        .map(|c| c.defaults.clone())
+        .unwrap_or_default();
 
     // Get repositories to analyze
-    let repos = get_repositories(&args)?;
+    let repos = get_repositories(&args, config.as_ref())?;
 
-    let timezone =
-        TimeZoneMode::parse(&args.timezone).map_err(|message| Error::ConfigInvalid { message })?;
+    let settings = Settings::resolve(&args, &defaults)?;
+    // The TUI opens right away and collects behind its loading screen
+    let Some(formatter) = formatter_for(settings.output, &args) else {
+        return execute_tui(args, defaults, repos, &settings);
+    };
 
-    // Calculate date range
-    let to = timezone.now_date_naive();
-    let from = to - chrono::Duration::days(i64::from(args.days));
-    let range = DateRange::new(from, to);
-    let exclude_merges = !args.include_merges;
+    // Create spinner (RAII ensures cleanup on error)
+    let spinner = SpinnerGuard::new(!args.quiet);
+    let Analysis {
+        result,
+        activity_stats,
+        failures,
+        empty_repos,
+        ..
+    } = analyze(&args, &settings, &repos, &spinner)?;
 
-    // Collect commits from all repositories (parallel)
-    spinner.set_message("Collecting commits...");
-
-    let results: Result<Vec<(String, Vec<CommitInfo>)>> = repos
-        .par_iter()
-        .map(|repo_info| {
-            let repo = Repository::open(&repo_info.path, &repo_info.name)?;
-            let branch = args.branch.as_deref().or(repo_info.branch.as_deref());
-            let commits = repo.commits_in_range(range.from, range.to, branch, exclude_merges)?;
-            Ok((repo_info.name.clone(), commits))
-        })
-        .collect();
+    // Spinner is automatically cleared by Drop when going out of scope or on error
+    drop(spinner);
 
-    let results = results?;
-    let mut all_commits: Vec<CommitInfo> = Vec::new();
-    let mut repo_names: Vec<String> = Vec::new();
-    for (name, commits) in results {
-        all_commits.extend(commits);
-        repo_names.push(name);
+    report_skipped(&failures, &empty_repos);
+
+    // Format and output
+
+    // Watch mode redraws in place when printing to a terminal
+    let clear_screen =
+        args.watch.is_some() && args.out.is_none() && std::io::stdout().is_terminal();
+    print_result(
+        formatter.as_ref(),
+        &result,
+        &activity_stats,
+        &args,
+        clear_screen,
+    )?;
+    check_not_empty(&args, &result)?;
+
+    if let Some(seconds) = args.watch {
+        watch(
+            &args,
+            &defaults,
+            &repos,
+            formatter.as_ref(),
+            Duration::from_secs(seconds.get()),
+        );
     }
 
-    // Create combined repository name
-    let combined_name = repo_names
-        .first()
-        .filter(|_| repo_names.len() == 1)
-        .cloned()
-        .unwrap_or_else(|| format!("{} repos", repo_names.len()));
+    Ok(())
+}
 
-    // Collect statistics
-    spinner.set_message("Calculating statistics...");
-    let extensions = args.ext.as_deref();
-    let activity_stats = collect_activity_stats(&all_commits, &timezone);
-    let result = collect_stats(
-        &combined_name,
-        all_commits,
-        range,
-        args.period,
-        extensions,
-        &timezone,
+/// Open the TUI on a loading screen while the data is collected in the background
+///
+/// Skipped repositories are reported once the TUI has closed.
+fn execute_tui(
+    args: Args,
+    defaults: Defaults,
+    repos: Vec<RepoInfo>,
+    settings: &Settings,
+) -> Result<()> {
+    let theme = resolve_theme(&args, &defaults)?;
+    let keymap = resolve_keymap(&defaults)?;
+    let export_dir = defaults.export_dir.as_deref().map(expand_tilde);
+    let names: Vec<&str> = repos.iter().map(|repo| repo.name.as_str()).collect();
+    // Shown in the header until the data arrives
+    let placeholder = AnalysisResult::new(
+        combined_name(&names),
+        settings.period.to_string(),
+        settings.range.from,
+        settings.range.to,
+        Vec::new(),
     );
+    let single_metric = args.single_metric;
+    let watch = args.watch;
+    let min_gap = args.min_gap;
+    let tick_ms = resolve_tick_ms(&args, &defaults);
+    let viewport = viewport_mode(&args);
 
-    // Spinner is automatically cleared by Drop when going out of scope or on error
-    drop(spinner);
+    let source = Arc::new(TuiSource {
+        args,
+        defaults,
+        repos,
+    });
+    let mut app = App::new(placeholder, ActivityStats::default(), single_metric)
+        .with_theme(theme)
+        .with_keymap(keymap)
+        .with_tick_ms(tick_ms)
+        .with_viewport(viewport)
+        .with_timezone(settings.timezone.clone())
+        .with_min_gap(min_gap)
+        .with_loader(tui_loader(Arc::clone(&source)))
+        .with_refresher(tui_refresher(source));
+    if let Some(seconds) = watch {
+        app = app.with_watch_interval(Duration::from_secs(seconds.get()));
+    }
+    if let Some(dir) = export_dir {
+        app = app.with_export_dir(dir);
+    }
 
-    // Format and output
-    match args.output {
+    let outcome = app.run();
+    for notice in app.take_notices() {
+        eprintln!("{notice}");
+    }
+    outcome
+}
+
+/// Formatter for `output`, or `None` for the TUI
+fn formatter_for(output: OutputFormat, args: &Args) -> Option<Box<dyn Formatter>> {
+    let formatter: Box<dyn Formatter> = match output {
         OutputFormat::Table => {
-            let formatter = TableFormatter::new();
-            let output = formatter.format(&result)?;
-            println!("{output}");
-        }
-        OutputFormat::Json => {
-            let formatter = JsonFormatter::new();
-            let output = formatter.format(&result)?;
-            println!("{output}");
+            let mut table = TableFormatter::new()
+                .with_ascii(!color_enabled(args))
+                .with_sort(args.sort)
+                .with_sparkline(args.sparkline)
+                .with_skip_empty(args.skip_empty);
+            if let Some(columns) = &args.columns {
+                table = table.with_columns(columns.clone());
+            }
+            Box::new(table)
         }
+        OutputFormat::Json => Box::new(JsonFormatter::new().with_skip_empty(args.skip_empty)),
+        OutputFormat::Ndjson => Box::new(NdjsonFormatter::new()),
         OutputFormat::Csv => {
-            let formatter = CsvFormatter::new();
-            let output = formatter.format(&result)?;
-            print!("{output}");
+            let mut csv = CsvFormatter::new()
+                .with_activity_sections(args.csv_sections)
+                .with_sort(args.sort)
+                .with_skip_empty(args.skip_empty);
+            if let Some(columns) = &args.columns {
+                csv = csv.with_columns(columns.clone());
+            }
+            Box::new(csv)
         }
-        OutputFormat::Tui => {
-            let mut app = App::new(result, activity_stats, args.single_metric);
-            app.run()?;
+        OutputFormat::Html => Box::new(HtmlFormatter::new()),
+        OutputFormat::Graph => {
+            let color =
+                color_enabled(args) && args.out.is_none() && std::io::stdout().is_terminal();
+            Box::new(GraphFormatter::new().with_color(color))
         }
-    }
-
-    Ok(())
+        OutputFormat::Prometheus => Box::new(PrometheusFormatter::new()),
+        OutputFormat::Tui => return None,
+    };
+    Some(formatter)
 }
 
-/// Get all repositories to analyze
-fn get_repositories(args: &Args) -> Result<Vec<RepoInfo>> {
-    // Priority: --repo flag > config file > current directory
-
-    // 1. --repo flag takes highest priority (single repo)
-    if let Some(repo_path) = &args.repo {
-        let expanded = expand_tilde(repo_path);
-        let name = expanded.file_name().map_or_else(
-            || "repository".to_string(),
-            |s| s.to_string_lossy().to_string(),
-        );
-        return Ok(vec![RepoInfo {
-            path: expanded,
-            name,
-            branch: args.branch.clone(),
-        }]);
-    }
-
-    // 2. Try to load config file
-    let config_path = args.config.clone().or_else(default_config_path);
+/// Whether colors and UTF-8 borders are allowed: not disabled by `--no-color` or `NO_COLOR`
+fn color_enabled(args: &Args) -> bool {
+    !args.no_color && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
 
-    if let Some(path) = config_path
-        && path.exists()
-    {
-        let config = load_config(&path)?;
-        let repos = filter_and_validate_repos(&config.repositories, args.repo_name.as_deref());
+/// Render a result saved with `--output json` without touching git
+///
+/// A daily result keeps its stats for period switching in the TUI.
+fn execute_input(args: &Args, input: &Path) -> Result<()> {
+    // Like with --repo, a broken config only costs its defaults
+    let defaults = load_optional_config(args.config.clone())
+        .ok()
+        .flatten()
+        .map(|c| c.defaults)
+        .unwrap_or_default();
+    let (result, activity_stats) = JsonFormatter::parse(&std::fs::read_to_string(input)?)?;
 
-        if !repos.is_empty() {
-            return Ok(repos);
+    let output = args.output.or(defaults.output).unwrap_or_default();
+    let Some(formatter) = formatter_for(output, args) else {
+        check_not_empty(args, &result)?;
+        let daily_stats = if result.period == Period::Daily.to_string() {
+            result.stats.clone()
+        } else {
+            Vec::new()
+        };
+        let mut app = App::new(result, activity_stats, args.single_metric)
+            .with_daily_stats(daily_stats)
+            .with_theme(resolve_theme(args, &defaults)?)
+            .with_keymap(resolve_keymap(&defaults)?)
+            .with_tick_ms(resolve_tick_ms(args, &defaults))
+            .with_viewport(viewport_mode(args))
+            .with_timezone(resolve_timezone(args, &defaults)?)
+            .with_min_gap(args.min_gap);
+        if let Some(dir) = defaults.export_dir.as_deref().map(expand_tilde) {
+            app = app.with_export_dir(dir);
         }
-    }
-
-    // 3. Fall back to current directory
-    let current_dir = std::env::current_dir()?;
-    let name = current_dir.file_name().map_or_else(
-        || "repository".to_string(),
-        |s| s.to_string_lossy().to_string(),
-    );
+        return app.run();
+    };
+    print_result(formatter.as_ref(), &result, &activity_stats, args, false)?;
+    check_not_empty(args, &result)
+}
 
-    // Check if current directory is a git repo
-    if !current_dir.join(".git").exists() {
-        return Err(Error::NoRepositories);
+/// With `--fail-if-empty`, fail when no commits were counted
+///
+/// Called after printing, so the (empty) result still reaches the output.
+/// Watch mode never gets here with the flag set, since it conflicts with `--watch`.
+fn check_not_empty(args: &Args, result: &AnalysisResult) -> Result<()> {
+    if args.fail_if_empty && result.total.commits == 0 {
+        return Err(Error::NoCommitsInRange {
+            from: result.from,
+            to: result.to,
+        });
     }
+    Ok(())
+}
 
-    Ok(vec![RepoInfo {
-        path: current_dir,
-        name,
-        branch: args.branch.clone(),
-    }])
+/// Print skipped-repository warnings to stderr
+fn report_skipped(failures: &[Error], empty_repos: &[String]) {
+    for notice in skipped_notices(failures, empty_repos) {
+        eprintln!("{notice}");
+    }
 }
 
-/// Filter repositories by name and validate they exist
-fn filter_and_validate_repos(repos: &[RepoConfig], filter: Option<&[String]>) -> Vec<RepoInfo> {
-    repos
+/// Warnings about failed repositories and a note about empty ones
+fn skipped_notices(failures: &[Error], empty_repos: &[String]) -> Vec<String> {
+    let mut notices: Vec<String> = failures
         .iter()
-        .filter(|repo| {
-            // Filter by name if specified
-            if let Some(names) = filter
-                && !names.iter().any(|n| n == &repo.name)
-            {
-                return false;
-            }
-
-            // Validate repository exists
-            let expanded = expand_tilde(&repo.path);
-            expanded.exists() && (expanded.join(".git").exists() || expanded.join("HEAD").exists())
-        })
-        .map(|repo| RepoInfo {
-            path: expand_tilde(&repo.path),
-            name: repo.name.clone(),
-            branch: repo.branch.clone(),
-        })
-        .collect()
+        .map(|failure| format!("Warning: skipped {failure}"))
+        .collect();
+    if !empty_repos.is_empty() {
+        notices.push(format!(
+            "Note: no commit history in {}",
+            empty_repos.join(", ")
+        ));
+    }
+    notices
 }
 
-/// Execute the `add` subcommand
-fn execute_add(add_args: AddArgs, config_path: Option<PathBuf>) -> Result<()> {
-    // Resolve the path
-    let path = expand_tilde(&add_args.path);
-    let absolute_path = if path.is_absolute() {
-        path
-    } else {
-        std::env::current_dir()?.join(&path).canonicalize()?
-    };
+/// Format a result and write it to `--out` or stdout
+///
+/// With `clear_screen`, the terminal is cleared first so watch mode redraws in place.
+fn print_result(
+    formatter: &dyn Formatter,
+    result: &AnalysisResult,
+    activity_stats: &ActivityStats,
+    args: &Args,
+    clear_screen: bool,
+) -> Result<()> {
+    let mut output = formatter.format_with_activity(result, activity_stats)?;
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    if clear_screen {
+        output.insert_str(0, CLEAR_SCREEN);
+    }
+    write_output(&output, args.out.as_deref())
+}
 
-    // Verify it's a git repository
-    if !is_git_repo(&absolute_path) {
-        return Err(Error::NotGitRepo {
-            path: absolute_path,
+/// Re-run the analysis every `interval` until the process is interrupted
+///
+/// Settings are resolved again each cycle so a `--days` range rolls over at
+/// midnight. Cycles run one after another on this thread, so a collection
+/// slower than the interval delays the next cycle instead of overlapping it.
+/// A failed cycle is reported and the previous output stays in place.
+fn watch(
+    args: &Args,
+    defaults: &Defaults,
+    repos: &[RepoInfo],
+    formatter: &dyn Formatter,
+    interval: Duration,
+) -> ! {
+    let interactive = args.out.is_none() && std::io::stdout().is_terminal();
+    loop {
+        std::thread::sleep(interval);
+        let cycle = Settings::resolve(args, defaults).and_then(|settings| {
+            let spinner = SpinnerGuard::new(interactive && !args.quiet);
+            let analysis = analyze(args, &settings, repos, &spinner)?;
+            print_result(
+                formatter,
+                &analysis.result,
+                &analysis.activity_stats,
+                args,
+                interactive,
+            )?;
+            Ok(analysis)
         });
+        match cycle {
+            Ok(analysis) => report_skipped(&analysis.failures, &analysis.empty_repos),
+            Err(e) => eprintln!("Warning: refresh failed: {e}"),
+        }
     }
+}
 
-    // Determine the repository name
-    let name = add_args.name.unwrap_or_else(|| {
-        absolute_path.file_name().map_or_else(
-            || "repository".to_string(),
-            |s| s.to_string_lossy().to_string(),
-        )
-    });
+/// Resolve the TUI theme: `--theme` > `defaults.theme` > default, then `defaults.colors` overrides
+fn resolve_theme(args: &Args, defaults: &Defaults) -> Result<Theme> {
+    let theme = Theme::preset(args.theme.or(defaults.theme).unwrap_or_default());
+    match &defaults.colors {
+        Some(colors) => theme
+            .with_overrides(colors)
+            .map_err(|message| Error::ConfigInvalid { message }),
+        None => Ok(theme),
+    }
+}
 
-    // Get config path
-    let config_file = config_path
-        .or_else(default_config_path)
-        .or_else(default_config_path_for_save)
-        .ok_or_else(|| Error::ConfigInvalid {
-            message: "Could not determine config path".to_string(),
-        })?;
+/// Parse `defaults.keybindings` into the keys the TUI checks first
+fn resolve_keymap(defaults: &Defaults) -> Result<Keymap> {
+    Keymap::from_config(&defaults.keybindings).map_err(|message| Error::ConfigInvalid { message })
+}
 
-    // Load existing config or create new one
-    let mut config = if config_file.exists() {
-        load_config(&config_file)?
-    } else {
-        Config {
-            schema: Some(
-                "https://raw.githubusercontent.com/yumazak/kodo/main/schemas/config.schema.json"
-                    .to_string(),
-            ),
-            repositories: Vec::new(),
-            defaults: Defaults::default(),
-        }
-    };
+/// Timezone from `--timezone` or `defaults.timezone`, local by default
+fn resolve_timezone(args: &Args, defaults: &Defaults) -> Result<TimeZoneMode> {
+    let input = args
+        .timezone
+        .as_deref()
+        .or(defaults.timezone.as_deref())
+        .unwrap_or("local");
+    TimeZoneMode::parse(input).map_err(|message| Error::ConfigInvalid { message })
+}
 
-    // Format path for storage (use ~ for home directory)
-    let path_for_storage = shorten_home_path(&absolute_path);
+/// Milliseconds between TUI ticks, from `--tick-ms` or `defaults.tick_ms`
+fn resolve_tick_ms(args: &Args, defaults: &Defaults) -> NonZeroU64 {
+    args.tick_ms.or(defaults.tick_ms).unwrap_or(DEFAULT_TICK_MS)
+}
 
-    // Check for duplicates
-    if config
-        .repositories
-        .iter()
-        .any(|r| expand_tilde(&r.path) == absolute_path)
-    {
-        println!("Repository already exists in config: {name}");
-        return Ok(());
-    }
+/// Inline rows from `--inline`, or the alternate screen
+fn viewport_mode(args: &Args) -> ViewportMode {
+    args.inline.map_or(ViewportMode::Fullscreen, |rows| {
+        ViewportMode::Inline(rows.get())
+    })
+}
 
-    // Add the repository
-    let repo_config = RepoConfig {
-        name: name.clone(),
-        path: path_for_storage.clone(),
-        branch: add_args.branch,
-    };
-    config.repositories.push(repo_config);
+/// What the TUI collects its data from, shared by its loader and refresher
+struct TuiSource {
+    args: Args,
+    defaults: Defaults,
+    repos: Vec<RepoInfo>,
+}
 
-    // Save the config
-    save_config(&config, &config_file)?;
+impl TuiSource {
+    /// Analyze the repositories, over `days` days ending on the usual day when given
+    ///
+    /// Settings are resolved again on every call so a `--days` range keeps
+    /// ending today when the TUI stays open across midnight.
+    fn analyze(&self, days: Option<u32>, progress: &dyn Progress) -> Result<Analysis> {
+        let mut settings = Settings::resolve(&self.args, &self.defaults)?;
+        if let Some(days) = days
+            && settings.range.num_days() != i64::from(days)
+        {
+            settings.range = DateRange::ending_on(settings.range.to, Days::new(days));
+        }
+        analyze(&self.args, &settings, &self.repos, progress)
+    }
+}
 
-    println!("Added repository: {name}");
-    println!("  Path: {}", path_for_storage.display());
-    println!("  Config: {}", config_file.display());
+/// Build the TUI loader for the data it opens with
+///
+/// With `--fail-if-empty`, an empty result closes the TUI with that error.
+fn tui_loader(source: Arc<TuiSource>) -> Loader {
+    Box::new(move |context| {
+        let analysis = source.analyze(None, context)?;
+        for notice in skipped_notices(&analysis.failures, &analysis.empty_repos) {
+            context.send(LoadEvent::Notice(notice));
+        }
+        check_not_empty(&source.args, &analysis.result)?;
+        Ok(analysis.into_views())
+    })
+}
 
-    Ok(())
+/// Build the TUI refresh callback
+///
+/// When the TUI asks for a different number of days, the range is resized
+/// to end on the same day.
+fn tui_refresher(source: Arc<TuiSource>) -> Refresher {
+    Arc::new(move |days| {
+        let analysis = source.analyze(Some(days), &SpinnerGuard::new(false))?;
+        Ok(analysis.into_views())
+    })
 }
 
-/// Execute the `remove` subcommand
-// Takes ownership because we consume identifier from remove_args
-#[allow(clippy::needless_pass_by_value)]
-fn execute_remove(remove_args: RemoveArgs, config_path: Option<PathBuf>) -> Result<()> {
-    // Get config path
-    let config_file =
-        config_path
-            .or_else(default_config_path)
-            .ok_or_else(|| Error::ConfigNotFound {
-                path: PathBuf::from("~/.config/kodo/config.json"),
-            })?;
+/// Everything produced by one analysis run
+struct Analysis {
+    result: AnalysisResult,
+    activity_stats: ActivityStats,
+    /// Per-day stats behind `result` (TUI output only)
+    daily_stats: Vec<PeriodStats>,
+    /// Each repository on its own (TUI output with several repositories only)
+    repo_views: Vec<RepoView>,
+    /// Repositories that failed and were skipped
+    failures: Vec<Error>,
+    /// Repositories with no commits at all
+    empty_repos: Vec<String>,
+}
 
-    // Config must exist to remove from it
-    if !config_file.exists() {
-        return Err(Error::ConfigNotFound { path: config_file });
+impl Analysis {
+    /// The combined view and the per-repository views, for the TUI
+    fn into_views(self) -> (RepoView, Vec<RepoView>) {
+        let combined = RepoView {
+            result: self.result,
+            activity_stats: self.activity_stats,
+            daily_stats: self.daily_stats,
+        };
+        (combined, self.repo_views)
     }
+}
 
-    // Load config
-    let mut config = load_config(&config_file)?;
+/// Collect commits from all repositories and compute statistics
+fn analyze(
+    args: &Args,
+    settings: &Settings,
+    repos: &[RepoInfo],
+    progress: &dyn Progress,
+) -> Result<Analysis> {
+    let Settings {
+        range,
+        ref timezone,
+        exclude_merges,
+        ignore_whitespace,
+        ..
+    } = *settings;
+    let filter = FileFilter::new(args.ext.clone(), &args.path, &args.exclude_path)?
+        .with_count_all_commits(args.count_all_commits);
+    // Per-file details are only needed for file filtering and per-file breakdowns
+    let need_file_details = filter.is_active()
+        || args.by_extension
+        || args.top_files.is_some()
+        || args.top_dirs.is_some()
+        || args.detail.is_some()
+        || repos.iter().any(|repo| !repo.ext.is_empty());
 
-    // Resolve identifier as path
-    let identifier = &remove_args.identifier;
-    let identifier_path = expand_tilde(Path::new(identifier));
-    let absolute_identifier = if identifier_path.is_absolute() {
-        identifier_path
+    // With --compare or --yoy, a single walk covers the earlier range too
+    let previous_range = if args.yoy {
+        Some(range.year_earlier(settings.period))
     } else {
-        std::env::current_dir()?
-            .join(&identifier_path)
-            .canonicalize()
-            .unwrap_or(identifier_path)
+        args.compare.then(|| range.previous())
     };
+    let collect_range = previous_range.map_or(range, |previous| {
+        DateRange::new(previous.from.min(range.from), range.to)
+    });
 
-    // Find and remove matching repository
-    let original_len = config.repositories.len();
-
-    config.repositories.retain(|repo| {
-        // Match by name
-        if repo.name == *identifier {
-            return false;
-        }
-        // Match by path
-        let repo_path = expand_tilde(&repo.path);
-        if repo_path == absolute_identifier {
-            return false;
-        }
-        true
+    // Collect commits from all repositories (parallel)
+    let options = CollectOptions::new(collect_range.from, collect_range.to)
+        .with_exclude_merges(exclude_merges)
+        .with_merge_diffs(args.merge_diffs)
+        .with_file_details(need_file_details)
+        .with_ignore_whitespace(ignore_whitespace)
+        .with_max_commits(args.max_commits.map(NonZeroUsize::get))
+        .with_deadline(
+            args.max_seconds
+                .map(|seconds| Instant::now() + Duration::from_secs(seconds.get())),
+        );
+    let (mut results, failures) = collect_repo_commits(repos, args, &options, progress)?;
+    let previous_commits = previous_range.map(|previous| {
+        (
+            previous,
+            split_previous_commits(&mut results, previous, range.from, timezone),
+        )
     });
 
-    // Check if anything was removed
-    if config.repositories.len() == original_len {
-        return Err(Error::RepoNotInConfig {
-            identifier: identifier.clone(),
-        });
-    }
+    // The TUI can switch to each repository on its own
+    progress.summarizing();
+    let repo_views = if settings.output == OutputFormat::Tui && results.len() > 1 {
+        results
+            .iter()
+            .map(|repo| {
+                let timezone = repo.timezone.as_ref().unwrap_or(timezone);
+                let mut view = summarize(
+                    &repo.name,
+                    repo.commits.clone(),
+                    args,
+                    settings,
+                    &filter,
+                    timezone,
+                    None,
+                );
+                view.result.truncated = repo.truncated;
+                view
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-    // Save config
-    save_config(&config, &config_file)?;
+    let repo_daily_stats = repo_timezone_daily_stats(&results, settings, &filter);
+    let MergedCommits {
+        name: combined_name,
+        commits: all_commits,
+        repo_stats,
+        empty_repos,
+        truncated,
+    } = merge_repo_commits(results, &filter);
 
-    println!("Removed repository: {identifier}");
-    println!("  Config: {}", config_file.display());
+    let RepoView {
+        mut result,
+        activity_stats,
+        daily_stats,
+    } = summarize(
+        &combined_name,
+        all_commits,
+        args,
+        settings,
+        &filter,
+        timezone,
+        repo_daily_stats,
+    );
+    result.repositories = repo_stats;
+    result.truncated = truncated;
+    if let Some((previous, commits)) = previous_commits {
+        attach_earlier_range(&mut result, previous, commits, args, settings, &filter);
+    }
 
-    Ok(())
+    Ok(Analysis {
+        result,
+        activity_stats,
+        daily_stats,
+        repo_views,
+        failures,
+        empty_repos,
+    })
 }
 
-/// Execute the `list` subcommand
-// Takes ownership for consistency with other execute_* functions
-#[allow(clippy::needless_pass_by_value)]
-fn execute_list(list_args: ListArgs, config_path: Option<PathBuf>) -> Result<()> {
-    // Get config path
-    let config_file = config_path.or_else(default_config_path);
+/// Add the `--compare` or `--yoy` results for the commits of the earlier range
+fn attach_earlier_range(
+    result: &mut AnalysisResult,
+    previous: DateRange,
+    commits: Vec<CommitInfo>,
+    args: &Args,
+    settings: &Settings,
+    filter: &FileFilter,
+) {
+    if !args.yoy {
+        result.comparison = Some(ComparisonStats::new(
+            previous,
+            &result.total,
+            &collect_totals(&commits, filter),
+        ));
+    } else if settings.output != OutputFormat::Graph {
+        // The contribution graph keeps daily buckets, which have no year-earlier match
+        let earlier = collect_stats(
+            &result.repository,
+            commits,
+            previous,
+            settings.period,
+            filter,
+            &settings.timezone,
+        );
+        result.year_over_year = Some(YearOverYear::new(
+            &result.stats,
+            &earlier.stats,
+            settings.period,
+        ));
+    }
+}
 
-    // Check if config exists
-    let config_file = match config_file {
-        Some(path) if path.exists() => path,
-        _ => {
-            if list_args.json {
-                println!("[]");
-            } else {
-                println!("No repositories registered.");
-                println!("Use 'kodo add <path>' to register a repository.");
-            }
-            return Ok(());
+/// Daily stats for the combined view with each repository bucketed in its own timezone
+///
+/// `None` when no repository configures a timezone, so the combined commits
+/// are bucketed in the global timezone as usual.
+fn repo_timezone_daily_stats(
+    results: &[RepoCommits],
+    settings: &Settings,
+    filter: &FileFilter,
+) -> Option<Vec<PeriodStats>> {
+    if results.iter().all(|repo| repo.timezone.is_none()) {
+        return None;
+    }
+    let mut merged: BTreeMap<NaiveDate, PeriodStats> = BTreeMap::new();
+    for repo in results {
+        let timezone = repo.timezone.as_ref().unwrap_or(&settings.timezone);
+        let daily = collect_stats(
+            &repo.name,
+            repo.commits.clone(),
+            settings.range,
+            Period::Daily,
+            filter,
+            timezone,
+        );
+        for stat in daily.stats {
+            merged
+                .entry(stat.date)
+                .and_modify(|existing| existing.merge(&stat))
+                .or_insert(stat);
+        }
+    }
+    Some(merged.into_values().collect())
+}
+
+/// Compute period statistics, activity, and the requested breakdowns for a set of commits
+///
+/// Commits are bucketed in `timezone`, unless `daily_stats` already holds
+/// their daily buckets.
+fn summarize(
+    name: &str,
+    commits: Vec<CommitInfo>,
+    args: &Args,
+    settings: &Settings,
+    filter: &FileFilter,
+    timezone: &TimeZoneMode,
+    daily_stats: Option<Vec<PeriodStats>>,
+) -> RepoView {
+    let activity_stats = collect_activity_stats(&commits, filter, timezone);
+    let extension_stats = args
+        .by_extension
+        .then(|| collect_extension_stats(&commits, filter));
+    let mut extension_lines = args.by_extension.then(|| {
+        let limit = args.period_extensions.unwrap_or(DEFAULT_PERIOD_EXTENSIONS);
+        collect_daily_extension_lines(&commits, filter, timezone, limit)
+    });
+    let top_commits = args
+        .top_commits
+        .map(|limit| collect_top_commits(&commits, filter, limit, timezone));
+    let top_files = args
+        .top_files
+        .map(|limit| collect_top_files(&commits, filter, limit));
+    let top_dirs = args
+        .top_dirs
+        .map(|limit| collect_top_dirs(&commits, filter, limit));
+    let file_details = (args.detail == Some(Detail::Files))
+        .then(|| collect_file_details(&commits, filter, timezone));
+    let work_pattern = collect_work_pattern(
+        &commits,
+        filter,
+        timezone,
+        args.work_hours.unwrap_or_default(),
+    );
+    let mut result = match daily_stats {
+        Some(stats) => AnalysisResult::new(
+            name.to_string(),
+            Period::Daily.to_string(),
+            settings.range.from,
+            settings.range.to,
+            stats,
+        ),
+        None => collect_stats(
+            name,
+            commits,
+            settings.range,
+            Period::Daily,
+            filter,
+            timezone,
+        ),
+    };
+    if let Some(lines) = &mut extension_lines {
+        for stat in &mut result.stats {
+            stat.ext_stats = lines.remove(&stat.date).unwrap_or_default();
         }
+    }
+    // Gaps come from the zero-filled daily series, whatever the period
+    let min_gap = args
+        .min_gap
+        .unwrap_or_else(|| default_min_gap(settings.period));
+    result.gaps = find_gaps(&result.stats, min_gap);
+    // The TUI keeps the daily stats to switch periods without re-reading commits
+    let daily_stats = if settings.output == OutputFormat::Tui {
+        result.stats.clone()
+    } else {
+        Vec::new()
     };
+    // The contribution graph draws one cell per day, whatever the period
+    if settings.output != OutputFormat::Graph {
+        result.stats = aggregate_by_period(std::mem::take(&mut result.stats), settings.period);
+        result.period = settings.period.to_string();
+    }
+    mark_outliers(&mut result.stats);
+    result.moving_average = args
+        .moving_average
+        .map(|window| moving_averages(&result.stats, window.get()));
+    result.extensions = extension_stats;
+    result.top_commits = top_commits;
+    result.top_files = top_files;
+    result.top_dirs = top_dirs;
+    result.file_details = file_details;
+    result.work_pattern = Some(work_pattern);
+    RepoView {
+        result,
+        activity_stats,
+        daily_stats,
+    }
+}
 
-    // Load config
-    let config = load_config(&config_file)?;
+/// Dispatch a subcommand, passing on the global `--config` and `--quiet` flags
+fn execute_command(command: Command, args: &Args) -> Result<()> {
+    let config = args.config.clone();
+    let quiet = args.quiet;
+    match command {
+        Command::Add(add_args) => execute_add(add_args, config, quiet),
+        Command::Remove(remove_args) => execute_remove(remove_args, config, quiet),
+        Command::Prune(prune_args) => execute_prune(&prune_args, config),
+        Command::List(list_args) => execute_list(list_args, config, quiet),
+        Command::Cache(cache_args) => execute_cache(cache_args.action, quiet),
+        Command::Config(config_args) => match config_args.action {
+            ConfigCommand::Init(init_args) => execute_config_init(init_args, config, quiet),
+        },
+        Command::Doctor(doctor_args) => execute_doctor(&doctor_args, config),
+        Command::Compare(compare_args) => execute_compare(compare_args, config, quiet),
+    }
+}
 
-    // Check if there are any repositories
-    if config.repositories.is_empty() {
-        if list_args.json {
-            println!("[]");
+/// Execute the `compare` subcommand
+///
+/// Both targets are analyzed over the same resolved date range and period so
+/// their rows line up.
+fn execute_compare(
+    compare_args: CompareArgs,
+    config_path: Option<PathBuf>,
+    quiet: bool,
+) -> Result<()> {
+    let spinner = SpinnerGuard::new(!quiet);
+
+    let config = load_optional_config(config_path)?;
+    let defaults = config
+        .as_ref()
+        .map(|c| c.defaults.clone())
+        .unwrap_or_default();
+
+    let args = Args {
+        days: compare_args.days,
+        from: compare_args.from,
+        to: compare_args.to,
+        period: compare_args.period,
+        timezone: compare_args.timezone,
+        include_merges: compare_args.include_merges,
+        ext: compare_args.ext,
+        no_cache: compare_args.no_cache,
+        ..Args::default()
+    };
+    let settings = Settings::resolve(&args, &defaults)?;
+
+    let mut results = Vec::with_capacity(2);
+    for target in [&compare_args.left, &compare_args.right] {
+        let repo = resolve_target(target, config.as_ref())?;
+        let analysis = analyze(&args, &settings, &[repo], &spinner)?;
+        report_skipped(&analysis.failures, &analysis.empty_repos);
+        results.push(analysis.result);
+    }
+    drop(spinner);
+
+    let report = CompareReport::new(&results[0], &results[1]);
+    if compare_args.json {
+        println!("{}", report.to_json()?);
+    } else {
+        println!("{}", report.to_table());
+    }
+    Ok(())
+}
+
+/// Resolve a compare target to a repository
+///
+/// A configured repository name takes precedence over a path spelled the same
+/// way; an empty repository means the current directory. A branch in the
+/// target overrides the configured branch selection.
+fn resolve_target(target: &RepoTarget, config: Option<&Config>) -> Result<RepoInfo> {
+    let configured = config.and_then(|config| {
+        config
+            .repositories
+            .iter()
+            .find(|repo| repo.name == target.repo)
+    });
+
+    let mut info = if let Some(repo) = configured {
+        RepoInfo {
+            path: expand_tilde(&repo.path),
+            name: repo.name.clone(),
+            branch: repo.branch.clone(),
+            all_branches: repo.all_branches,
+            ext: repo.ext.clone(),
+            include_merges: repo.include_merges,
+            first_parent: repo.first_parent,
+            timezone: repo_timezone(repo),
+        }
+    } else {
+        let path = if target.repo.is_empty() {
+            std::env::current_dir()?
         } else {
-            println!("No repositories registered.");
-            println!("Use 'kodo add <path>' to register a repository.");
+            expand_tilde(Path::new(&target.repo))
+        };
+        if !path.exists() {
+            return Err(Error::RepoNotFound { path });
         }
-        return Ok(());
+        // Name relative targets like `.` after the directory they point to
+        let path = path.canonicalize().unwrap_or(path);
+        let name = path
+            .file_name()
+            .map_or_else(|| target.repo.clone(), |s| s.to_string_lossy().to_string());
+        RepoInfo {
+            path,
+            name,
+            branch: None,
+            all_branches: false,
+            ext: Vec::new(),
+            include_merges: false,
+            first_parent: false,
+            timezone: None,
+        }
+    };
+
+    if let Some(branch) = &target.branch {
+        info.name = format!("{}@{branch}", info.name);
+        info.branch = Some(branch.clone());
+        info.all_branches = false;
     }
+    Ok(info)
+}
 
-    // Build repository info list
-    let repos: Vec<_> = config
-        .repositories
+/// Commits collected from a single repository
+struct RepoCommits {
+    name: String,
+    commits: Vec<CommitInfo>,
+    /// The repository has no commits at all (unborn HEAD)
+    unborn: bool,
+    /// `--max-commits` or `--max-seconds` left commits uncollected
+    truncated: bool,
+    /// The repository's configured timezone, if any
+    timezone: Option<TimeZoneMode>,
+}
+
+/// Collect commits from all repositories in parallel
+///
+/// Runs on a thread pool bounded by `--jobs` and reports progress as commits
+/// are scanned and repositories finish. Results are sorted by repository name
+/// so output does not depend on completion order. Failed repositories are
+/// returned separately unless `--strict` is set or every repository failed,
+/// in which case the first failure is returned as the error. Once `progress`
+/// is cancelled, the remaining repositories are skipped and
+/// [`Error::Cancelled`] is returned.
+fn collect_repo_commits(
+    repos: &[RepoInfo],
+    args: &Args,
+    options: &CollectOptions,
+    progress: &dyn Progress,
+) -> Result<(Vec<RepoCommits>, Vec<Error>)> {
+    let total = repos.len();
+    let done = AtomicUsize::new(0);
+    let scanned = AtomicUsize::new(0);
+    progress.collecting(total);
+
+    let collect = || -> Vec<(String, Result<RepoCommits>)> {
+        repos
+            .par_iter()
+            .map(|repo_info| {
+                if progress.is_cancelled() {
+                    return (repo_info.name.clone(), Err(Error::Cancelled));
+                }
+                // Each repository reports its own running count; add up the growth
+                let repo_scanned = Cell::new(0);
+                let on_progress = |collected: CollectProgress| {
+                    let grown =
+                        collected.commits_scanned - repo_scanned.replace(collected.commits_scanned);
+                    let scanned = scanned.fetch_add(grown, Ordering::Relaxed) + grown;
+                    progress.commits_scanned(scanned, done.load(Ordering::Relaxed), total);
+                };
+                let result = collect_one_repo(repo_info, args, options, &on_progress);
+                let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                let commits = result.as_ref().map_or(0, |repo| repo.commits.len());
+                progress.repo_finished(&repo_info.name, commits, finished, total);
+                (repo_info.name.clone(), result)
+            })
+            .collect()
+    };
+
+    let mut results = match args.jobs {
+        Some(jobs) => rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.get())
+            .build()
+            .map_err(std::io::Error::other)?
+            .install(collect),
+        None => collect(),
+    };
+    if progress.is_cancelled() {
+        return Err(Error::Cancelled);
+    }
+    results.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut collected = Vec::with_capacity(results.len());
+    let mut failures = Vec::new();
+    for (repo, result) in results {
+        match result {
+            Ok(commits) => collected.push(commits),
+            Err(source) => failures.push(Error::RepoFailed {
+                repo,
+                source: Box::new(source),
+            }),
+        }
+    }
+
+    if !failures.is_empty() && (args.strict || collected.is_empty()) {
+        return Err(failures.swap_remove(0));
+    }
+
+    Ok((collected, failures))
+}
+
+/// Collect commits from a single repository
+///
+/// `--branch` or `--all-branches` on the command line overrides the
+/// repository's configured branch selection; the branch selection in
+/// `options` is ignored.
+fn collect_one_repo(
+    repo_info: &RepoInfo,
+    args: &Args,
+    options: &CollectOptions,
+    on_progress: &dyn Fn(CollectProgress),
+) -> Result<RepoCommits> {
+    let rename_threshold =
+        (!args.no_renames).then(|| args.rename_threshold.unwrap_or(DEFAULT_RENAME_THRESHOLD));
+    // Mainline-only history keeps merges, which carry the merged branch's changes
+    let first_parent = args.first_parent || repo_info.first_parent;
+    let mut repo =
+        Repository::open(&repo_info.path, &repo_info.name)?.with_rename_threshold(rename_threshold);
+    if !args.no_cache
+        && let Some(cache) =
+            DiffCache::for_repo(repo.path(), rename_threshold, options.ignore_whitespace())
+    {
+        repo = repo.with_cache(cache);
+    }
+    let (branch, all_branches) = if args.branch.is_some() || args.all_branches {
+        (args.branch.as_deref(), args.all_branches)
+    } else {
+        (repo_info.branch.as_deref(), repo_info.all_branches)
+    };
+    let options = options
+        .clone()
+        .with_branch(branch)
+        .with_all_branches(all_branches)
+        .with_first_parent(first_parent)
+        .with_exclude_merges(
+            options.exclude_merges() && !repo_info.include_merges && !first_parent,
+        );
+    let mut commits = repo.collect_commits_with_progress(&options, on_progress)?;
+    // The repository's own extensions apply before merging, unless `--ext` overrides them
+    if args.ext.is_none() && !repo_info.ext.is_empty() {
+        commits = FileFilter::with_extensions(repo_info.ext.clone())
+            .with_count_all_commits(args.count_all_commits)
+            .narrow_commits(commits);
+    }
+    Ok(RepoCommits {
+        name: repo_info.name.clone(),
+        commits,
+        unborn: repo.is_unborn(),
+        truncated: repo.truncated(),
+        timezone: repo_info.timezone.clone(),
+    })
+}
+
+/// Remove commits dated before `from` from every repository and return those dated in `previous`
+///
+/// Used by `--compare` and `--yoy`, where commits for the earlier range are
+/// collected in the same history walk as the analyzed range. The ranges may
+/// overlap when `--yoy` covers more than a year, so matching commits are copied.
+fn split_previous_commits(
+    results: &mut [RepoCommits],
+    previous: DateRange,
+    from: NaiveDate,
+    timezone: &TimeZoneMode,
+) -> Vec<CommitInfo> {
+    let mut earlier = Vec::new();
+    for repo in results {
+        earlier.extend(
+            repo.commits
+                .iter()
+                .filter(|commit| previous.contains(timezone.date_naive(commit.timestamp)))
+                .cloned(),
+        );
+        repo.commits
+            .retain(|commit| timezone.date_naive(commit.timestamp) >= from);
+    }
+    earlier
+}
+
+/// Commits from all analyzed repositories merged into one list
+struct MergedCommits {
+    /// Repository name, or "N repos" when several were analyzed
+    name: String,
+    commits: Vec<CommitInfo>,
+    /// Per-repository totals (empty when only one repository was analyzed)
+    repo_stats: Vec<RepoStats>,
+    /// Repositories with no commits at all
+    empty_repos: Vec<String>,
+    /// Some repository was cut short by `--max-commits` or `--max-seconds`
+    truncated: bool,
+}
+
+/// Merge per-repository commits, computing per-repository totals along the way
+fn merge_repo_commits(results: Vec<RepoCommits>, filter: &FileFilter) -> MergedCommits {
+    let multi_repo = results.len() > 1;
+    let mut all_commits: Vec<CommitInfo> = Vec::new();
+    let mut repo_names: Vec<String> = Vec::new();
+    let mut repo_stats: Vec<RepoStats> = Vec::new();
+    let mut empty_repos: Vec<String> = Vec::new();
+    let mut truncated = false;
+    for RepoCommits {
+        name,
+        commits,
+        unborn,
+        truncated: repo_truncated,
+        ..
+    } in results
+    {
+        truncated |= repo_truncated;
+        if unborn {
+            empty_repos.push(name.clone());
+        }
+        if multi_repo {
+            repo_stats.push(RepoStats {
+                name: name.clone(),
+                total: collect_totals(&commits, filter),
+            });
+        }
+        all_commits.extend(commits);
+        repo_names.push(name);
+    }
+
+    let names: Vec<&str> = repo_names.iter().map(String::as_str).collect();
+    let name = combined_name(&names);
+
+    MergedCommits {
+        name,
+        commits: all_commits,
+        repo_stats,
+        empty_repos,
+        truncated,
+    }
+}
+
+/// Name for the combined view: the repository's own name, or "N repos"
+fn combined_name(names: &[&str]) -> String {
+    match names {
+        [name] => (*name).to_string(),
+        _ => format!("{} repos", names.len()),
+    }
+}
+
+/// Write formatted output to a file, or stdout when no path is given
+fn write_output(output: &str, path: Option<&Path>) -> Result<()> {
+    if let Some(path) = path {
+        std::fs::write(path, output)?;
+    } else {
+        print!("{output}");
+    }
+    Ok(())
+}
+
+/// Load the config file if one exists at the given or default path
+fn load_optional_config(config_path: Option<PathBuf>) -> Result<Option<Config>> {
+    match config_path.or_else(default_config_path) {
+        Some(path) if path.exists() => load_config(&path).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Get all repositories to analyze
+fn get_repositories(args: &Args, config: Option<&Config>) -> Result<Vec<RepoInfo>> {
+    // Priority: --repo flags / paths > config file > current directory
+
+    // 1. --repo flags and positional paths take highest priority
+    let paths: Vec<PathBuf> = args
+        .repo
         .iter()
-        .map(|repo| {
-            let expanded_path = expand_tilde(&repo.path);
-            let exists = is_git_repo(&expanded_path);
-            (repo, exists)
-        })
+        .chain(&args.paths)
+        .map(|path| expand_tilde(path))
         .collect();
-
-    if list_args.json {
-        // JSON output
-        let json_repos: Vec<_> = repos
-            .iter()
-            .map(|(repo, exists)| {
-                serde_json::json!({
-                    "name": repo.name,
-                    "path": repo.path.display().to_string(),
-                    "branch": repo.branch,
-                    "exists": exists,
-                })
+    if !paths.is_empty() {
+        let names = unique_names(paths.iter().map(|path| {
+            path.file_name().map_or_else(
+                || "repository".to_string(),
+                |s| s.to_string_lossy().to_string(),
+            )
+        }));
+        return Ok(paths
+            .into_iter()
+            .zip(names)
+            .map(|(path, name)| RepoInfo {
+                path,
+                name,
+                branch: args.branch.clone(),
+                all_branches: args.all_branches,
+                ext: Vec::new(),
+                include_merges: false,
+                first_parent: false,
+                timezone: None,
             })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&json_repos)?);
+            .collect());
+    }
+
+    // 2. Use repositories from the config file
+    if let Some(config) = config {
+        let repos = filter_and_validate_repos(
+            &config.repositories,
+            args.repo_name.as_deref(),
+            args.group.as_deref(),
+        );
+
+        if !repos.is_empty() {
+            return Ok(repos);
+        }
+    }
+
+    // 3. Fall back to the repository containing the current directory
+    let Some(path) = discover_repository(&std::env::current_dir()?) else {
+        return Err(Error::NoRepositories);
+    };
+    let name = path.file_name().map_or_else(
+        || "repository".to_string(),
+        |s| s.to_string_lossy().to_string(),
+    );
+
+    Ok(vec![RepoInfo {
+        path,
+        name,
+        branch: args.branch.clone(),
+        all_branches: args.all_branches,
+        ext: Vec::new(),
+        include_merges: false,
+        first_parent: false,
+        timezone: None,
+    }])
+}
+
+/// Make names unique by suffixing `-2`, `-3`, ... to later repeats
+fn unique_names(names: impl Iterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for name in names {
+        let mut candidate = name.clone();
+        let mut n = 1;
+        while unique.contains(&candidate) {
+            n += 1;
+            candidate = format!("{name}-{n}");
+        }
+        unique.push(candidate);
+    }
+    unique
+}
+
+/// Filter repositories by name and group and validate they exist
+///
+/// A repository must match one of `names` (if given) and belong to one of
+/// `groups` (if given).
+fn filter_and_validate_repos(
+    repos: &[RepoConfig],
+    names: Option<&[String]>,
+    groups: Option<&[String]>,
+) -> Vec<RepoInfo> {
+    repos
+        .iter()
+        .filter(|repo| {
+            // Filter by name if specified
+            if let Some(names) = names
+                && !names.iter().any(|n| n == &repo.name)
+            {
+                return false;
+            }
+
+            if !in_any_group(repo, groups) {
+                return false;
+            }
+
+            // Validate repository exists
+            let expanded = expand_tilde(&repo.path);
+            is_git_repository(&expanded)
+        })
+        .map(|repo| RepoInfo {
+            path: expand_tilde(&repo.path),
+            name: repo.name.clone(),
+            branch: repo.branch.clone(),
+            all_branches: repo.all_branches,
+            ext: repo.ext.clone(),
+            include_merges: repo.include_merges,
+            first_parent: repo.first_parent,
+            timezone: repo_timezone(repo),
+        })
+        .collect()
+}
+
+/// A repository's configured timezone
+///
+/// `load_config` already rejects invalid names, so one that fails to parse
+/// here falls back to the global timezone.
+fn repo_timezone(repo: &RepoConfig) -> Option<TimeZoneMode> {
+    repo.timezone
+        .as_deref()
+        .and_then(|timezone| TimeZoneMode::parse(timezone).ok())
+}
+
+/// Check whether a repository belongs to at least one of `groups`
+///
+/// Always true when no group filter is given.
+fn in_any_group(repo: &RepoConfig, groups: Option<&[String]>) -> bool {
+    groups.is_none_or(|groups| repo.groups.iter().any(|g| groups.contains(g)))
+}
+
+/// Execute the `add` subcommand
+fn execute_add(add_args: AddArgs, config_path: Option<PathBuf>, quiet: bool) -> Result<()> {
+    // Resolve the path
+    let path = expand_tilde(&add_args.path);
+    let absolute_path = if path.is_absolute() {
+        normalize_lexically(&path)
     } else {
-        // Table output
-        print_repo_table(&repos);
+        std::env::current_dir()?.join(&path).canonicalize()?
+    };
+
+    if add_args.scan {
+        return execute_add_scan(&absolute_path, &add_args, config_path, quiet);
+    }
+
+    // Verify it's a git repository
+    if !is_git_repository(&absolute_path) {
+        return Err(Error::NotGitRepo {
+            path: absolute_path,
+        });
+    }
+
+    // Determine the repository name
+    let named = add_args.name.is_some();
+    let name = add_args.name.unwrap_or_else(|| {
+        absolute_path.file_name().map_or_else(
+            || "repository".to_string(),
+            |s| s.to_string_lossy().to_string(),
+        )
+    });
+
+    // Get config path and load existing config or create new one
+    let config_file = config_path_for_add(config_path)?;
+    let _lock = ConfigLock::acquire(&config_file)?;
+    let mut config = load_or_new_config(&config_file)?;
+
+    // Format path for storage (use ~ for home directory)
+    let path_for_storage = shorten_home_path(&absolute_path);
+
+    // The same directory may be registered through a symlink or another spelling
+    let canonical = canonicalize_or_normalize(&absolute_path);
+    if let Some(existing) = config
+        .repositories
+        .iter_mut()
+        .find(|r| canonicalize_or_normalize(&expand_tilde(&r.path)) == canonical)
+    {
+        let updated = update_existing_repo(
+            existing,
+            named.then_some(name),
+            add_args.branch,
+            add_args.yes,
+            quiet,
+        )?;
+        if updated {
+            save_config(&config, &config_file)?;
+        }
+        return Ok(());
+    }
+
+    // Add the repository
+    let repo_config = RepoConfig {
+        name: name.clone(),
+        path: path_for_storage.clone(),
+        branch: add_args.branch,
+        all_branches: false,
+        groups: add_args.group,
+        ext: Vec::new(),
+        include_merges: false,
+        first_parent: false,
+        timezone: None,
+    };
+    let groups = repo_config.groups.join(", ");
+    config.repositories.push(repo_config);
+
+    // Save the config
+    save_config(&config, &config_file)?;
+    if quiet {
+        return Ok(());
+    }
+
+    println!("Added repository: {name}");
+    println!("  Path: {}", path_for_storage.display());
+    if !groups.is_empty() {
+        println!("  Groups: {groups}");
+    }
+    println!("  Config: {}", config_file.display());
+
+    Ok(())
+}
+
+/// Offer to give an already registered repository the name and branch passed to `add`
+///
+/// Asks on a terminal unless `yes`; elsewhere the entry is left alone.
+/// Returns whether the entry was changed.
+fn update_existing_repo(
+    existing: &mut RepoConfig,
+    name: Option<String>,
+    branch: Option<String>,
+    yes: bool,
+    quiet: bool,
+) -> Result<bool> {
+    let name = name.filter(|name| *name != existing.name);
+    let branch = branch.filter(|branch| existing.branch.as_ref() != Some(branch));
+    let mut changes = Vec::new();
+    if let Some(name) = &name {
+        changes.push(format!("name {} -> {name}", existing.name));
+    }
+    if let Some(branch) = &branch {
+        let current = existing.branch.as_deref().unwrap_or("-");
+        changes.push(format!("branch {current} -> {branch}"));
+    }
+    if changes.is_empty() {
+        if !quiet {
+            println!("Repository already exists in config: {}", existing.name);
+        }
+        return Ok(false);
+    }
+
+    let changes = changes.join(", ");
+    let interactive = !yes && !quiet && std::io::stdin().is_terminal();
+    let accepted = yes
+        || (interactive
+            && confirm(&format!(
+                "{} is already registered; update {changes}?",
+                existing.name
+            ))?);
+    if !accepted {
+        if !quiet {
+            println!(
+                "Repository already exists in config: {} (rerun with --yes to update {changes})",
+                existing.name
+            );
+        }
+        return Ok(false);
+    }
+
+    if !quiet {
+        println!("Updated repository {}: {changes}", existing.name);
+    }
+    if let Some(name) = name {
+        existing.name = name;
+    }
+    if branch.is_some() {
+        existing.branch = branch;
+    }
+    Ok(true)
+}
+
+/// Execute `add --scan`: register every git repository found under a directory
+///
+/// With `quiet`, only a dry run reports anything.
+fn execute_add_scan(
+    root: &Path,
+    add_args: &AddArgs,
+    config_path: Option<PathBuf>,
+    quiet: bool,
+) -> Result<()> {
+    if !root.is_dir() {
+        return Err(Error::RepoNotFound {
+            path: root.to_path_buf(),
+        });
+    }
+
+    let config_file = config_path_for_add(config_path)?;
+    let _lock = ConfigLock::acquire(&config_file)?;
+    let mut config = load_or_new_config(&config_file)?;
+
+    let registered: Vec<PathBuf> = config
+        .repositories
+        .iter()
+        .map(|r| canonicalize_or_normalize(&expand_tilde(&r.path)))
+        .collect();
+
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+
+    for repo_path in find_git_repos(root, add_args.depth) {
+        let name = repo_path.file_name().map_or_else(
+            || "repository".to_string(),
+            |s| s.to_string_lossy().to_string(),
+        );
+
+        if registered.contains(&canonicalize_or_normalize(&repo_path)) {
+            skipped.push(name);
+            continue;
+        }
+
+        let path_for_storage = shorten_home_path(&repo_path);
+        added.push((name.clone(), path_for_storage.clone()));
+        config.repositories.push(RepoConfig {
+            name,
+            path: path_for_storage,
+            branch: add_args.branch.clone(),
+            all_branches: false,
+            groups: add_args.group.clone(),
+            ext: Vec::new(),
+            include_merges: false,
+            first_parent: false,
+            timezone: None,
+        });
+    }
+
+    let verb = if add_args.dry_run {
+        "Would add"
+    } else {
+        "Added"
+    };
+    if !quiet || add_args.dry_run {
+        for (name, path) in &added {
+            println!("{verb}: {name} ({})", path.display());
+        }
+        for name in &skipped {
+            println!("Skipped (already registered): {name}");
+        }
+        println!(
+            "{verb} {} repositories, skipped {}",
+            added.len(),
+            skipped.len()
+        );
+    }
+
+    if !add_args.dry_run && !added.is_empty() {
+        save_config(&config, &config_file)?;
+        if !quiet {
+            println!("  Config: {}", config_file.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Find git repositories under `root`, descending at most `max_depth` levels
+///
+/// Directories that are themselves repositories are not descended into, so
+/// nested repositories (e.g. vendored checkouts) are ignored. Symlinks are skipped.
+fn find_git_repos(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut stack = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        if is_git_repository(&dir) {
+            found.push(dir);
+            continue;
+        }
+        if depth >= max_depth {
+            continue;
+        }
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            if is_dir {
+                stack.push((entry.path(), depth + 1));
+            }
+        }
+    }
+
+    found.sort();
+    found
+}
+
+/// Canonicalize a path, normalizing it lexically instead if it doesn't exist
+fn canonicalize_or_normalize(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .unwrap_or_else(|_| normalize_lexically(path))
+}
+
+/// Drop `.` components, resolve `..` against the preceding component, and
+/// lose any trailing slash, without touching the filesystem
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            // `..` at the root stays at the root
+            Component::ParentDir if normalized.has_root() => {}
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Determine the config file that `add` writes to
+fn config_path_for_add(config_path: Option<PathBuf>) -> Result<PathBuf> {
+    config_path
+        .or_else(default_config_path)
+        .or_else(default_config_path_for_save)
+        .ok_or_else(|| Error::ConfigInvalid {
+            message: "Could not determine config path".to_string(),
+        })
+}
+
+/// Load an existing config, or create an empty one if the file doesn't exist
+fn load_or_new_config(config_file: &Path) -> Result<Config> {
+    if config_file.exists() {
+        return load_config_for_update(config_file);
+    }
+
+    Ok(new_config())
+}
+
+/// An empty config pointing at the published JSON schema
+fn new_config() -> Config {
+    Config {
+        schema: Some(
+            "https://raw.githubusercontent.com/yumazak/kodo/main/schemas/config.schema.json"
+                .to_string(),
+        ),
+        repositories: Vec::new(),
+        defaults: Defaults::default(),
+    }
+}
+
+/// Execute `config init`: write a fresh config, optionally with scanned repositories
+///
+/// Scanned repositories are confirmed one by one when stdin is a terminal;
+/// otherwise only `--yes` adds them.
+// Takes ownership for consistency with other execute_* functions
+#[allow(clippy::needless_pass_by_value)]
+fn execute_config_init(
+    init_args: InitArgs,
+    config_path: Option<PathBuf>,
+    quiet: bool,
+) -> Result<()> {
+    let config_file = config_path_for_init(config_path, init_args.format)?;
+    if config_file.exists() && !init_args.force {
+        return Err(Error::ConfigExists { path: config_file });
+    }
+
+    let mut config = new_config();
+    if ConfigFormat::from_path(&config_file) == ConfigFormat::Toml {
+        // The schema only describes the JSON file
+        config.schema = None;
+    }
+    config.defaults.period = Some(Period::Daily);
+    config.defaults.output = Some(OutputFormat::Tui);
+
+    if let Some(dir) = &init_args.scan {
+        let root = std::env::current_dir()?
+            .join(expand_tilde(dir))
+            .canonicalize()?;
+        let found = find_git_repos(&root, init_args.depth);
+        let interactive = !init_args.yes && std::io::stdin().is_terminal();
+        if !init_args.yes && !interactive && !quiet && !found.is_empty() {
+            println!(
+                "Found {} repositories under {}; rerun with --yes to add them",
+                found.len(),
+                root.display()
+            );
+        }
+
+        for repo_path in found {
+            let name = repo_path.file_name().map_or_else(
+                || "repository".to_string(),
+                |s| s.to_string_lossy().to_string(),
+            );
+            let path_for_storage = shorten_home_path(&repo_path);
+            let accepted = init_args.yes
+                || (interactive
+                    && confirm(&format!("Add {name} ({})?", path_for_storage.display()))?);
+            if accepted {
+                config.repositories.push(RepoConfig {
+                    name,
+                    path: path_for_storage,
+                    branch: None,
+                    all_branches: false,
+                    groups: Vec::new(),
+                    ext: Vec::new(),
+                    include_merges: false,
+                    first_parent: false,
+                    timezone: None,
+                });
+            }
+        }
+    }
+
+    save_config(&config, &config_file)?;
+    if quiet {
+        return Ok(());
+    }
+
+    println!("Created config: {}", config_file.display());
+    println!("  Repositories: {}", config.repositories.len());
+    println!();
+    println!("Next steps:");
+    println!("  kodo add <path>    register a repository");
+    println!("  kodo doctor        check the config and repositories");
+    println!("  kodo               browse the last 7 days in the TUI");
+
+    Ok(())
+}
+
+/// Config path for `config init`, with the extension matching `--format`
+///
+/// An explicit `--config` must already use the requested format's extension.
+fn config_path_for_init(
+    config_path: Option<PathBuf>,
+    format: Option<ConfigFormat>,
+) -> Result<PathBuf> {
+    match (config_path, format) {
+        (Some(path), Some(format)) if ConfigFormat::from_path(&path) != format => {
+            Err(Error::ConfigInvalid {
+                message: format!(
+                    "{} does not match --format {}; use a .{} file",
+                    path.display(),
+                    format.extension(),
+                    format.extension()
+                ),
+            })
+        }
+        (None, Some(format)) => default_config_path_for_save()
+            .map(|path| path.with_extension(format.extension()))
+            .ok_or_else(|| Error::ConfigInvalid {
+                message: "Could not determine config path".to_string(),
+            }),
+        (config_path, _) => config_path_for_add(config_path),
+    }
+}
+
+/// Ask a yes/no question on stdin, defaulting to no
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::Write;
+
+    print!("{question} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Execute the `remove` subcommand
+// Takes ownership because we consume identifier from remove_args
+#[allow(clippy::needless_pass_by_value)]
+fn execute_remove(
+    remove_args: RemoveArgs,
+    config_path: Option<PathBuf>,
+    quiet: bool,
+) -> Result<()> {
+    // Get config path
+    let config_file =
+        config_path
+            .or_else(default_config_path)
+            .ok_or_else(|| Error::ConfigNotFound {
+                path: PathBuf::from("~/.config/kodo/config.json"),
+            })?;
+
+    // Config must exist to remove from it
+    if !config_file.exists() {
+        return Err(Error::ConfigNotFound { path: config_file });
+    }
+
+    // Load config, keeping other kodo processes out until it is saved
+    let _lock = ConfigLock::acquire(&config_file)?;
+    let mut config = load_config_for_update(&config_file)?;
+
+    let mut remove = vec![remove_args.all; config.repositories.len()];
+    if remove_args.all {
+        let count = config.repositories.len();
+        let interactive = !remove_args.yes && !quiet && std::io::stdin().is_terminal();
+        let accepted = remove_args.yes
+            || (interactive
+                && confirm(&format!(
+                    "Remove all {count} repositories from {}?",
+                    config_file.display()
+                ))?);
+        if !accepted {
+            if !quiet {
+                println!("Kept {count} repositories (rerun with --yes to remove them all)");
+            }
+            return Ok(());
+        }
+    }
+
+    // Every identifier must match before anything is removed
+    for identifier in &remove_args.identifier {
+        let matches = matching_repos(&config.repositories, identifier)?;
+        if matches.is_empty() {
+            return Err(Error::RepoNotInConfig {
+                identifier: identifier.clone(),
+                suggestions: close_matches(identifier, &config.repositories),
+            });
+        }
+        for i in matches {
+            remove[i] = true;
+        }
+    }
+
+    let mut removed = Vec::new();
+    let mut flags = remove.into_iter();
+    config.repositories.retain(|repo| {
+        let remove = flags.next().unwrap_or_default();
+        if remove {
+            removed.push(repo.name.clone());
+        }
+        !remove
+    });
+
+    // Save config
+    save_config(&config, &config_file)?;
+
+    if !quiet {
+        for name in &removed {
+            println!("Removed repository: {name}");
+        }
+        println!("  Config: {}", config_file.display());
+    }
+
+    Ok(())
+}
+
+/// Indices of the repositories named `identifier` or registered at that path
+fn matching_repos(repos: &[RepoConfig], identifier: &str) -> Result<Vec<usize>> {
+    // Resolve identifier as path
+    let identifier_path = expand_tilde(Path::new(identifier));
+    let absolute_identifier = if identifier_path.is_absolute() {
+        identifier_path
+    } else {
+        std::env::current_dir()?
+            .join(&identifier_path)
+            .canonicalize()
+            .unwrap_or(identifier_path)
+    };
+
+    Ok(repos
+        .iter()
+        .enumerate()
+        .filter(|(_, repo)| {
+            repo.name == identifier || expand_tilde(&repo.path) == absolute_identifier
+        })
+        .map(|(i, _)| i)
+        .collect())
+}
+
+/// Most names suggested for an identifier that matched nothing
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Registered names close to `identifier`, closest first
+///
+/// A name is close when one contains the other (ignoring case) or when few
+/// single-character edits turn one into the other.
+fn close_matches(identifier: &str, repos: &[RepoConfig]) -> Vec<String> {
+    let wanted = identifier.to_lowercase();
+    let max_distance = (wanted.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, &str)> = repos
+        .iter()
+        .filter_map(|repo| {
+            let name = repo.name.to_lowercase();
+            let distance = levenshtein(&wanted, &name);
+            let contains = name.contains(&wanted) || wanted.contains(&name);
+            (contains || distance <= max_distance).then_some((distance, repo.name.as_str()))
+        })
+        .collect();
+    candidates.sort_unstable();
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Number of single-character insertions, deletions, or substitutions between `a` and `b`
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Why `prune` drops a repository
+#[derive(Debug, PartialEq, Eq)]
+enum PruneReason {
+    /// The path no longer exists or is not a git repository
+    Missing,
+    /// An earlier entry has the same canonical path
+    Duplicate { of: String },
+}
+
+/// Execute the `prune` subcommand
+fn execute_prune(prune_args: &PruneArgs, config_path: Option<PathBuf>) -> Result<()> {
+    let config_file =
+        config_path
+            .or_else(default_config_path)
+            .ok_or_else(|| Error::ConfigNotFound {
+                path: PathBuf::from("~/.config/kodo/config.json"),
+            })?;
+    let _lock = ConfigLock::acquire(&config_file)?;
+    let mut config = load_config(&config_file)?;
+
+    let pruned = prune_repositories(&mut config.repositories, prune_args.missing_only);
+    if pruned.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+
+    let verb = if prune_args.dry_run {
+        "Would remove"
+    } else {
+        "Removed"
+    };
+    for (repo, reason) in &pruned {
+        let why = match reason {
+            PruneReason::Missing => "not a git repository".to_string(),
+            PruneReason::Duplicate { of } => format!("duplicate of {of}"),
+        };
+        println!("{verb}: {} ({}) - {why}", repo.name, repo.path.display());
+    }
+    println!(
+        "{verb} {} repositories, kept {}",
+        pruned.len(),
+        config.repositories.len()
+    );
+
+    if !prune_args.dry_run {
+        save_config(&config, &config_file)?;
+        println!("  Config: {}", config_file.display());
+    }
+
+    Ok(())
+}
+
+/// Take out repositories that are missing or, unless `missing_only`, duplicates
+///
+/// A duplicate is an entry whose canonical path matches an earlier kept entry;
+/// the first registration wins.
+fn prune_repositories(
+    repositories: &mut Vec<RepoConfig>,
+    missing_only: bool,
+) -> Vec<(RepoConfig, PruneReason)> {
+    let mut kept: Vec<(PathBuf, RepoConfig)> = Vec::new();
+    let mut pruned = Vec::new();
+
+    for repo in repositories.drain(..) {
+        let path = expand_tilde(&repo.path);
+        if !is_git_repository(&path) {
+            pruned.push((repo, PruneReason::Missing));
+            continue;
+        }
+        let canonical = canonicalize_or_normalize(&path);
+        if !missing_only && let Some((_, first)) = kept.iter().find(|(p, _)| *p == canonical) {
+            let of = first.name.clone();
+            pruned.push((repo, PruneReason::Duplicate { of }));
+            continue;
+        }
+        kept.push((canonical, repo));
+    }
+
+    repositories.extend(kept.into_iter().map(|(_, repo)| repo));
+    pruned
+}
+
+/// Execute the `cache` subcommand
+fn execute_cache(action: CacheCommand, quiet: bool) -> Result<()> {
+    match action {
+        CacheCommand::Clear => {
+            let Some(dir) = default_cache_dir().filter(|dir| dir.exists()) else {
+                if !quiet {
+                    println!("Cache is already empty.");
+                }
+                return Ok(());
+            };
+            std::fs::remove_dir_all(&dir)?;
+            if !quiet {
+                println!("Cleared cache: {}", shorten_home_path(&dir).display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Execute the `list` subcommand
+// Takes ownership for consistency with other execute_* functions
+#[allow(clippy::needless_pass_by_value)]
+fn execute_list(list_args: ListArgs, config_path: Option<PathBuf>, quiet: bool) -> Result<()> {
+    // Get config path
+    let config_file = config_path.or_else(default_config_path);
+
+    // Check if config exists
+    let config_file = match config_file {
+        Some(path) if path.exists() => path,
+        _ => {
+            if list_args.json {
+                println!("[]");
+            } else if !quiet {
+                println!("No repositories registered.");
+                println!("Use 'kodo add <path>' to register a repository.");
+            }
+            return Ok(());
+        }
+    };
+
+    // Load config
+    let config = load_config_for_update(&config_file)?;
+
+    // Check if there are any repositories
+    if config.repositories.is_empty() {
+        if list_args.json {
+            println!("[]");
+        } else if !quiet {
+            println!("No repositories registered.");
+            println!("Use 'kodo add <path>' to register a repository.");
+        }
+        return Ok(());
+    }
+
+    // Build repository info list
+    let repos: Vec<_> = config
+        .repositories
+        .iter()
+        .filter(|repo| in_any_group(repo, list_args.group.as_deref()))
+        .map(|repo| {
+            let expanded_path = expand_tilde(&repo.path);
+            let exists = is_git_repository(&expanded_path);
+            (repo, exists)
+        })
+        .collect();
+
+    // Only --verbose opens the repositories; plain listing just reads the config
+    let details: Option<Vec<Result<RepoDetails>>> = list_args.verbose.then(|| {
+        repos
+            .par_iter()
+            .map(|(repo, _)| RepoDetails::read(repo))
+            .collect()
+    });
+
+    if list_args.json {
+        // JSON output
+        let json_repos: Vec<_> = repos
+            .iter()
+            .enumerate()
+            .map(|(i, (repo, exists))| {
+                let mut json = serde_json::json!({
+                    "name": repo.name,
+                    "path": repo.path.display().to_string(),
+                    "branch": repo.branch,
+                    "groups": repo.groups,
+                    "ext": repo.ext,
+                    "include_merges": repo.include_merges,
+                    "first_parent": repo.first_parent,
+                    "timezone": repo.timezone,
+                    "exists": exists,
+                });
+                if let Some(details) = &details {
+                    add_details_json(&mut json, &details[i]);
+                }
+                json
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_repos)?);
+    } else if repos.is_empty() {
+        if !quiet {
+            let groups = list_args.group.as_deref().unwrap_or_default().join(", ");
+            println!("No repositories in group: {groups}");
+        }
+    } else {
+        // Table output
+        print_repo_table(&repos, details.as_deref());
+    }
+
+    Ok(())
+}
+
+/// Most commits `list --verbose` counts per repository
+const LIST_COMMIT_LIMIT: usize = 10_000;
+
+/// Days of commit activity in the `list --verbose` sparkline, ending today
+const LIST_TREND_DAYS: u32 = 14;
+
+/// What `list --verbose` finds by opening a repository
+#[derive(Debug)]
+struct RepoDetails {
+    /// Branch HEAD points to (`HEAD` when detached)
+    head: Option<String>,
+    /// Local date of the commit HEAD points to (`None` without commits)
+    last_commit: Option<NaiveDate>,
+    /// Commits reachable from HEAD, up to [`LIST_COMMIT_LIMIT`]
+    commits: usize,
+    /// Whether the configured branch resolves (`None` when none is configured)
+    branch_exists: Option<bool>,
+    /// Commits per day over the last [`LIST_TREND_DAYS`] days, oldest first
+    recent_commits: Vec<u64>,
+}
+
+impl RepoDetails {
+    /// Open the repository and read its details
+    fn read(repo: &RepoConfig) -> Result<Self> {
+        let repository = Repository::open(&repo.path, &repo.name)?;
+        let last_commit = repository
+            .head_commit_time()?
+            .map(|time| time.with_timezone(&chrono::Local).date_naive());
+        // Days follow the repository's timezone, like its analysis does
+        let timezone = repo_timezone(repo).unwrap_or(TimeZoneMode::Local);
+        let days = DateRange::ending_on(timezone.now_date_naive(), Days::new(LIST_TREND_DAYS));
+        // A day early in UTC covers the first local day in any timezone
+        let since = (days.from - chrono::Days::new(1))
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc();
+        let mut recent_commits = vec![0; days.iter_days().count()];
+        for time in repository.head_commit_times_since(since)? {
+            let date = timezone.date_naive(time);
+            if let Ok(day) = usize::try_from((date - days.from).num_days())
+                && let Some(count) = recent_commits.get_mut(day)
+            {
+                *count += 1;
+            }
+        }
+        Ok(Self {
+            head: repository.head_shorthand(),
+            last_commit,
+            commits: repository.count_head_commits(LIST_COMMIT_LIMIT)?,
+            branch_exists: repo
+                .branch
+                .as_deref()
+                .map(|branch| repository.resolve_branch(branch).is_ok()),
+            recent_commits,
+        })
+    }
+
+    /// Whether the count stopped at [`LIST_COMMIT_LIMIT`]
+    const fn commits_capped(&self) -> bool {
+        self.commits >= LIST_COMMIT_LIMIT
+    }
+
+    /// Commit count for the table, e.g. `1234` or `10k+`
+    fn commits_label(&self) -> String {
+        if self.commits_capped() {
+            format!("{}k+", LIST_COMMIT_LIMIT / 1000)
+        } else {
+            self.commits.to_string()
+        }
+    }
+}
+
+/// Add the `list --verbose` fields to a repository's JSON object
+fn add_details_json(json: &mut serde_json::Value, details: &Result<RepoDetails>) {
+    let fields = match details {
+        Ok(details) => serde_json::json!({
+            "head": details.head,
+            "last_commit": details.last_commit.map(|date| date.to_string()),
+            "commits": details.commits,
+            "commits_capped": details.commits_capped(),
+            "branch_exists": details.branch_exists,
+            "recent_commits": details.recent_commits,
+            "error": null,
+        }),
+        Err(e) => serde_json::json!({
+            "head": null,
+            "last_commit": null,
+            "commits": null,
+            "commits_capped": false,
+            "branch_exists": null,
+            "recent_commits": null,
+            "error": e.to_string(),
+        }),
+    };
+    if let (Some(json), serde_json::Value::Object(fields)) = (json.as_object_mut(), fields) {
+        json.extend(fields);
+    }
+}
+
+/// Print repositories in table format, with the `--verbose` columns when `details` are given
+fn print_repo_table(repos: &[(&RepoConfig, bool)], details: Option<&[Result<RepoDetails>]>) {
+    let mut header = vec!["Name", "Path", "Branch", "Groups"];
+    if details.is_some() {
+        header.extend(["Head", "Last commit", "Commits", "Trend"]);
+    }
+    header.push("Status");
+
+    let rows: Vec<Vec<String>> = repos
+        .iter()
+        .enumerate()
+        .map(|(i, (repo, exists))| {
+            let groups = if repo.groups.is_empty() {
+                "-".to_string()
+            } else {
+                repo.groups.join(",")
+            };
+            let mut row = vec![
+                repo.name.clone(),
+                repo.path.display().to_string(),
+                repo.branch.clone().unwrap_or_else(|| "-".to_string()),
+                groups,
+            ];
+            let status = match details.map(|details| &details[i]) {
+                None if *exists => "\u{2713}".to_string(),
+                None => "\u{2717}".to_string(),
+                Some(Ok(details)) => {
+                    row.push(details.head.clone().unwrap_or_else(|| "-".to_string()));
+                    row.push(
+                        details
+                            .last_commit
+                            .map_or_else(|| "-".to_string(), |date| date.to_string()),
+                    );
+                    row.push(details.commits_label());
+                    row.push(sparkline(
+                        &details.recent_commits,
+                        details.recent_commits.len(),
+                    ));
+                    if details.branch_exists == Some(false) {
+                        "\u{2717} branch not found".to_string()
+                    } else {
+                        "\u{2713}".to_string()
+                    }
+                }
+                Some(Err(e)) => {
+                    row.extend(["-", "-", "-", "-"].map(str::to_string));
+                    format!("\u{2717} {e}")
+                }
+            };
+            row.push(status);
+            row
+        })
+        .collect();
+
+    // Every column but the last is padded to its widest cell
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let print_row = |cells: &[&str]| {
+        let (last, padded) = cells.split_last().unwrap_or((&"", &[]));
+        let mut line = String::new();
+        for (cell, width) in padded.iter().zip(&widths) {
+            let _ = write!(line, "{cell:<width$}  ");
+        }
+        println!("{line}{last}");
+    };
+    print_row(&header);
+    for row in &rows {
+        print_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+}
+
+/// Shorten path by replacing home directory with ~
+fn shorten_home_path(path: &Path) -> PathBuf {
+    if let Some(home) = dirs::home_dir()
+        && let Ok(relative) = path.strip_prefix(&home)
+    {
+        return PathBuf::from("~").join(relative);
+    }
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::dates::DateExpr;
+    use chrono::{NaiveDate, Utc};
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn create_test_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+
+        Command::new("git")
+            .args(["init"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        Command::new("git")
+            .args(["config", "user.email", "test@example.com"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        Command::new("git")
+            .args(["config", "user.name", "Test User"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        std::fs::write(path.join("README.md"), "# Test\n").unwrap();
+
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        Command::new("git")
+            .args(["commit", "-m", "Initial commit"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_execute_with_repo_arg() {
+        let dir = create_test_repo();
+
+        let args = Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Json),
+            period: Some(Period::Daily),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        };
+
+        let result = execute(args);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_execute_with_repo_arg_table_output() {
+        let dir = create_test_repo();
+
+        let args = Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Table),
+            period: Some(Period::Daily),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        };
+
+        let result = execute(args);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_execute_with_repo_arg_csv_output() {
+        let dir = create_test_repo();
+
+        let args = Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Csv),
+            period: Some(Period::Daily),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        };
+
+        let result = execute(args);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_execute_html_output_to_file() {
+        let dir = create_test_repo();
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("report.html");
+
+        let args = Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Html),
+            out: Some(out_path.clone()),
+            period: Some(Period::Daily),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        };
+
+        execute(args).unwrap();
+        let html = std::fs::read_to_string(out_path).unwrap();
+        assert!(html.contains("<svg"));
+    }
+
+    #[test]
+    fn test_execute_input_reproduces_json_output() {
+        let dir = create_test_repo();
+        let out_dir = TempDir::new().unwrap();
+        let saved = out_dir.path().join("saved.json");
+        let rendered = out_dir.path().join("rendered.json");
+
+        execute(Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Json),
+            out: Some(saved.clone()),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        })
+        .unwrap();
+        execute(Args {
+            input: Some(saved.clone()),
+            output: Some(OutputFormat::Json),
+            out: Some(rendered.clone()),
+            ..default_args()
+        })
+        .unwrap();
+
+        let saved = std::fs::read_to_string(saved).unwrap();
+        assert!(saved.contains("\"schema_version\""));
+        assert_eq!(std::fs::read_to_string(rendered).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_execute_input_missing_file_is_error() {
+        let out_dir = TempDir::new().unwrap();
+        let result = execute(Args {
+            input: Some(out_dir.path().join("missing.json")),
+            output: Some(OutputFormat::Table),
+            ..default_args()
+        });
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_execute_file_details_csv_quotes_paths_and_filters() {
+        let dir = create_test_repo();
+        let path = dir.path();
+        std::fs::write(path.join("a,b.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(path.join("notes.md"), "skip\n").unwrap();
+        for git_args in [&["add", "."][..], &["commit", "-m", "Add files"]] {
+            Command::new("git")
+                .args(git_args)
+                .current_dir(path)
+                .output()
+                .unwrap();
+        }
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("files.csv");
+
+        execute(Args {
+            repo: vec![path.to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Csv),
+            out: Some(out_path.clone()),
+            ext: Some(vec!["txt".to_string()]),
+            detail: Some(Detail::Files),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        })
+        .unwrap();
+
+        let csv = std::fs::read_to_string(out_path).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "commit,date,path,additions,deletions");
+        assert_eq!(lines.len(), 2, "{csv}");
+        assert!(lines[1].ends_with(",\"a,b.txt\",2,0"), "{csv}");
+    }
+
+    #[test]
+    fn test_execute_analyzes_linked_worktree() {
+        let dir = create_test_repo();
+        let worktrees = TempDir::new().unwrap();
+        let worktree = worktrees.path().join("feature");
+        for git_args in [
+            vec![
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                worktree.to_str().unwrap(),
+            ],
+            vec![
+                "-C",
+                worktree.to_str().unwrap(),
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "wip",
+            ],
+        ] {
+            let status = Command::new("git")
+                .args(git_args)
+                .current_dir(dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("out.json");
+
+        execute(Args {
+            repo: vec![worktree.clone()],
+            days: Some(7),
+            output: Some(OutputFormat::Json),
+            out: Some(out_path.clone()),
+            include_merges: true,
+            ..default_args()
+        })
+        .unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out_path).unwrap()).unwrap();
+        assert_eq!(report["repository"], "feature");
+        assert_eq!(report["total"]["commits"], 2);
+    }
+
+    #[test]
+    fn test_execute_by_extension_table_output() {
+        let dir = create_test_repo();
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("table.txt");
+
+        let args = Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Table),
+            out: Some(out_path.clone()),
+            period: Some(Period::Daily),
+            by_extension: true,
+            timezone: Some("local".to_string()),
+            no_color: true,
+            ..default_args()
+        };
+
+        execute(args).unwrap();
+
+        // The fixture commits one line to README.md
+        let table = std::fs::read_to_string(out_path).unwrap();
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .filter(|line| line.starts_with("| "))
+            .map(|line| {
+                line.split('|')
+                    .map(str::trim)
+                    .filter(|cell| !cell.is_empty())
+                    .collect()
+            })
+            .collect();
+        let header = rows
+            .iter()
+            .position(|row| row.first() == Some(&"Extension"))
+            .unwrap_or_else(|| panic!("no extension section:\n{table}"));
+        assert_eq!(
+            rows[header],
+            ["Extension", "Commits", "+Lines", "-Lines", "Files"]
+        );
+        assert_eq!(rows[header + 1], ["md", "1", "1", "0", "1"], "{table}");
+    }
+
+    #[test]
+    fn test_get_repositories_with_positional_paths() {
+        let args = Args {
+            repo: vec![PathBuf::from("/tmp/a/kodo")],
+            paths: vec![PathBuf::from("/tmp/b/kodo"), PathBuf::from("/tmp/web")],
+            ..default_args()
+        };
+
+        let repos = get_repositories(&args, None).unwrap();
+        let names: Vec<_> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["kodo", "kodo-2", "web"]);
+        assert_eq!(repos[1].path, PathBuf::from("/tmp/b/kodo"));
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let names = unique_names(["a", "a", "b", "a", "a-2"].into_iter().map(String::from));
+        assert_eq!(names, ["a", "a-2", "b", "a-3", "a-2-2"]);
+    }
+
+    #[test]
+    fn test_execute_two_positional_repos() {
+        let first = create_test_repo();
+        let second = create_test_repo();
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("out.json");
+
+        execute(Args {
+            paths: vec![first.path().to_path_buf(), second.path().to_path_buf()],
+            days: Some(7),
+            output: Some(OutputFormat::Json),
+            out: Some(out_path.clone()),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        })
+        .unwrap();
+
+        let (result, _) =
+            JsonFormatter::parse(&std::fs::read_to_string(out_path).unwrap()).unwrap();
+        assert_eq!(result.repository, "2 repos");
+        assert_eq!(result.repositories.len(), 2);
+        assert_eq!(result.total.commits, 2);
+    }
+
+    /// Run git in `dir`, panicking when it fails
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn test_execute_json_includes_moving_average() {
+        let dir = create_test_repo();
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("out.json");
+
+        let run = |moving_average| {
+            execute(Args {
+                repo: vec![dir.path().to_path_buf()],
+                days: Some(7),
+                output: Some(OutputFormat::Json),
+                out: Some(out_path.clone()),
+                timezone: Some("local".to_string()),
+                moving_average,
+                ..default_args()
+            })
+            .unwrap();
+            JsonFormatter::parse(&std::fs::read_to_string(&out_path).unwrap())
+                .unwrap()
+                .0
+        };
+
+        let result = run(std::num::NonZeroUsize::new(3));
+        let average = result.moving_average.unwrap();
+        assert_eq!(average.window, 3);
+        assert_eq!(average.commits.len(), result.stats.len());
+        // The only commit is today's, the last period
+        assert_eq!(average.commits.last(), Some(&(1.0 / 3.0)));
+        assert!(
+            average.commits[..average.commits.len() - 1]
+                .iter()
+                .all(|&commits| commits == 0.0)
+        );
+
+        assert!(run(None).moving_average.is_none());
+    }
+
+    #[test]
+    fn test_execute_applies_per_repo_ext() {
+        let docs = create_test_repo();
+        let code = create_test_repo();
+        for dir in [&docs, &code] {
+            std::fs::write(dir.path().join("main.rs"), "fn main() {}\n// end\n").unwrap();
+            git(dir.path(), &["add", "."]);
+            git(dir.path(), &["commit", "-m", "Add main"]);
+        }
+        let out_dir = TempDir::new().unwrap();
+        let config_path = out_dir.path().join("config.json");
+        let out_path = out_dir.path().join("out.json");
+        let config = Config {
+            schema: None,
+            repositories: vec![
+                RepoConfig {
+                    ext: vec!["md".to_string()],
+                    ..grouped_repo("docs", docs.path(), &[])
+                },
+                grouped_repo("code", code.path(), &[]),
+            ],
+            defaults: Defaults::default(),
+        };
+        save_config(&config, &config_path).unwrap();
+
+        let run = |ext: Option<Vec<String>>| {
+            execute(Args {
+                config: Some(config_path.clone()),
+                days: Some(7),
+                output: Some(OutputFormat::Json),
+                out: Some(out_path.clone()),
+                ext,
+                timezone: Some("local".to_string()),
+                ..default_args()
+            })
+            .unwrap();
+            let (result, _) =
+                JsonFormatter::parse(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+            result
+                .repositories
+                .into_iter()
+                .map(|repo| (repo.name, repo.total.commits, repo.total.additions))
+                .collect::<Vec<_>>()
+        };
+
+        // Only the docs repository drops its Rust commit
+        assert_eq!(
+            run(None),
+            [("code".to_string(), 2, 3), ("docs".to_string(), 1, 1)]
+        );
+        // --ext replaces the per-repository extensions
+        assert_eq!(
+            run(Some(vec!["rs".to_string()])),
+            [("code".to_string(), 1, 2), ("docs".to_string(), 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_collect_one_repo_per_repo_include_merges() {
+        let dir = create_test_repo();
+        let path = dir.path();
+        git(path, &["checkout", "-q", "-b", "side"]);
+        std::fs::write(path.join("side.txt"), "side\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-m", "Side"]);
+        git(path, &["checkout", "-q", "-"]);
+        git(path, &["merge", "--no-ff", "-m", "Merge side", "side"]);
+
+        let today = Utc::now().date_naive();
+        let range = DateRange::new(today.pred_opt().unwrap(), today.succ_opt().unwrap());
+        let mut info = repo_info("repo", path, None);
+        let count = |info: &RepoInfo| {
+            collect_one_repo(info, &default_args(), &merges_excluded(range), &|_| {})
+                .unwrap()
+                .commits
+                .len()
+        };
+        assert_eq!(count(&info), 2);
+        info.include_merges = true;
+        assert_eq!(count(&info), 3);
+
+        // First-parent history keeps the merge but skips the side commit
+        info.include_merges = false;
+        info.first_parent = true;
+        assert_eq!(count(&info), 2);
+        let commits = collect_one_repo(&info, &default_args(), &merges_excluded(range), &|_| {})
+            .unwrap()
+            .commits;
+        assert!(commits.iter().any(|c| c.is_merge));
+    }
+
+    /// A repository whose only commit is from 2020
+    fn create_old_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q"]);
+        git(path, &["config", "user.email", "test@example.com"]);
+        git(path, &["config", "user.name", "Test User"]);
+        std::fs::write(path.join("README.md"), "# Old\n").unwrap();
+        git(path, &["add", "."]);
+        let status = Command::new("git")
+            .args(["commit", "-q", "-m", "Old commit"])
+            .env("GIT_AUTHOR_DATE", "2020-01-01T12:00:00Z")
+            .env("GIT_COMMITTER_DATE", "2020-01-01T12:00:00Z")
+            .current_dir(path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        dir
+    }
+
+    #[test]
+    fn test_execute_empty_range_succeeds_by_default() {
+        let dir = create_old_repo();
+
+        let result = execute(Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(1),
+            output: Some(OutputFormat::Json),
+            timezone: Some("utc".to_string()),
+            ..default_args()
+        });
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_execute_fail_if_empty_after_printing() {
+        let dir = create_old_repo();
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("out.json");
+        let args = |days| Args {
+            repo: vec![dir.path().to_path_buf()],
+            days: Some(days),
+            output: Some(OutputFormat::Json),
+            out: Some(out_path.clone()),
+            timezone: Some("utc".to_string()),
+            fail_if_empty: true,
+            ..default_args()
+        };
+
+        let err = execute(args(1)).unwrap_err();
+        assert!(matches!(err, Error::NoCommitsInRange { .. }), "{err}");
+        assert_eq!(err.exit_code(), 5);
+        let (result, _) =
+            JsonFormatter::parse(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+        assert_eq!(result.total.commits, 0);
+
+        // A range reaching back to the commit passes
+        let days = u32::try_from(
+            (Utc::now().date_naive() - NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()).num_days(),
+        )
+        .unwrap()
+            + 1;
+        execute(args(days)).unwrap();
+    }
+
+    #[test]
+    fn test_get_repositories_with_repo_arg() {
+        let args = Args {
+            repo: vec![PathBuf::from("/tmp/test-repo")],
+            days: Some(7),
+            output: Some(OutputFormat::Json),
+            period: Some(Period::Daily),
+            timezone: Some("local".to_string()),
+            ..default_args()
+        };
+
+        let result = get_repositories(&args, None);
+        assert!(result.is_ok());
+
+        let repos = result.unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].path, PathBuf::from("/tmp/test-repo"));
+        assert_eq!(repos[0].name, "test-repo");
+    }
+
+    fn default_args() -> Args {
+        Args {
+            // Keep tests away from the user's cache directory
+            no_cache: true,
+            ..Args::default()
+        }
+    }
+
+    /// Options the CLI collects `range` with by default: merges excluded, totals only
+    fn merges_excluded(range: DateRange) -> CollectOptions {
+        CollectOptions::new(range.from, range.to).with_exclude_merges(true)
+    }
+
+    #[test]
+    fn test_settings_use_config_defaults_when_flags_absent() {
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+        std::fs::write(
+            &config_path,
+            r#"{
+                "repositories": [{"name": "repo", "path": "/tmp/repo"}],
+                "defaults": {
+                    "days": 30,
+                    "exclude_merges": false,
+                    "period": "weekly",
+                    "output": "json",
+                    "timezone": "utc",
+                    "ignore_whitespace": "eol"
+                }
+            }"#,
+        )
+        .unwrap();
+        let config = load_config(&config_path).unwrap();
+
+        let settings = Settings::resolve(&default_args(), &config.defaults).unwrap();
+
+        assert_eq!(settings.range.iter_days().count(), 30);
+        assert_eq!(settings.period, Period::Weekly);
+        assert_eq!(settings.output, OutputFormat::Json);
+        assert!(matches!(settings.timezone, TimeZoneMode::Utc));
+        assert!(!settings.exclude_merges);
+        assert_eq!(settings.ignore_whitespace, IgnoreWhitespace::Eol);
+    }
+
+    #[test]
+    fn test_settings_cli_flags_override_config_defaults() {
+        let defaults = Defaults {
+            days: 30,
+            period: Some(Period::Weekly),
+            output: Some(OutputFormat::Json),
+            ignore_whitespace: Some(IgnoreWhitespace::Eol),
+            ..Defaults::default()
+        };
+        let args = Args {
+            days: Some(14),
+            period: Some(Period::Monthly),
+            output: Some(OutputFormat::Csv),
+            include_merges: true,
+            ignore_whitespace: true,
+            ..default_args()
+        };
+
+        let settings = Settings::resolve(&args, &defaults).unwrap();
+
+        assert_eq!(settings.range.iter_days().count(), 14);
+        assert_eq!(settings.period, Period::Monthly);
+        assert_eq!(settings.output, OutputFormat::Csv);
+        assert!(!settings.exclude_merges);
+        assert_eq!(settings.ignore_whitespace, IgnoreWhitespace::All);
+    }
+
+    #[test]
+    fn test_settings_builtin_defaults_without_config() {
+        let settings = Settings::resolve(&default_args(), &Defaults::default()).unwrap();
+
+        assert_eq!(settings.range.iter_days().count(), 7);
+        assert_eq!(settings.period, Period::Daily);
+        assert_eq!(settings.output, OutputFormat::Table);
+        assert!(settings.exclude_merges);
+        assert_eq!(settings.ignore_whitespace, IgnoreWhitespace::Off);
+    }
+
+    #[test]
+    fn test_no_color_draws_ascii_table() {
+        let args = Args {
+            no_color: true,
+            ..default_args()
+        };
+        assert!(!color_enabled(&args));
+
+        let formatter = formatter_for(OutputFormat::Table, &args).unwrap();
+        let result = AnalysisResult::new(
+            "repo".to_string(),
+            "daily".to_string(),
+            Utc::now().date_naive(),
+            Utc::now().date_naive(),
+            Vec::new(),
+        );
+        let table = formatter.format(&result).unwrap();
+        assert!(table.starts_with('+'), "{table}");
+        assert!(!table.contains('┌'));
+    }
+
+    #[test]
+    fn test_columns_and_sort_select_and_order_rows() {
+        let args = <Args as clap::Parser>::parse_from([
+            "kodo",
+            "--columns",
+            "period,net",
+            "--sort",
+            "net:desc",
+            "--no-color",
+        ]);
+        let day = |d: u32, additions: u64| {
+            let date = NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+            PeriodStats {
+                label: date.to_string(),
+                commits: 1,
+                additions,
+                net_lines: i64::try_from(additions).unwrap(),
+                ..PeriodStats::new(date)
+            }
+        };
+        let result = AnalysisResult::new(
+            "repo".to_string(),
+            "daily".to_string(),
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 3).unwrap(),
+            vec![day(1, 10), day(2, 30), day(3, 20)],
+        );
+
+        let table = formatter_for(OutputFormat::Table, &args)
+            .unwrap()
+            .format(&result)
+            .unwrap();
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .filter(|line| line.starts_with("| "))
+            .map(|line| {
+                line.split('|')
+                    .map(str::trim)
+                    .filter(|cell| !cell.is_empty())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                vec!["Period", "Net"],
+                vec!["2024-03-02", "30"],
+                vec!["2024-03-03", "20"],
+                vec!["2024-03-01", "10"],
+                vec!["TOTAL", "60"],
+            ]
+        );
+
+        let csv = formatter_for(OutputFormat::Csv, &args)
+            .unwrap()
+            .format(&result)
+            .unwrap();
+        assert_eq!(
+            csv,
+            "date,net_lines\n2024-03-02,30\n2024-03-03,20\n2024-03-01,10\nTOTAL,60\n"
+        );
+    }
+
+    #[test]
+    fn test_settings_from_and_to() {
+        let args = Args {
+            from: NaiveDate::from_ymd_opt(2024, 3, 1).map(DateExpr::from),
+            to: NaiveDate::from_ymd_opt(2024, 3, 14).map(DateExpr::from),
+            days: Some(7),
+            ..default_args()
+        };
+
+        let settings = Settings::resolve(&args, &Defaults::default()).unwrap();
+
+        assert_eq!(
+            settings.range.from,
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+        );
+        assert_eq!(
+            settings.range.to,
+            NaiveDate::from_ymd_opt(2024, 3, 14).unwrap()
+        );
+        assert_eq!(settings.range.iter_days().count(), 14);
+    }
+
+    #[test]
+    fn test_settings_from_without_to_ends_today() {
+        let from = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let args = Args {
+            from: Some(from.into()),
+            timezone: Some("utc".to_string()),
+            ..default_args()
+        };
+
+        let settings = Settings::resolve(&args, &Defaults::default()).unwrap();
+
+        assert_eq!(settings.range.from, from);
+        assert_eq!(settings.range.to, chrono::Utc::now().date_naive());
+    }
+
+    #[test]
+    fn test_settings_relative_dates_use_timezone_today() {
+        let args = Args {
+            from: Some("2 weeks ago".parse().unwrap()),
+            to: Some("yesterday".parse().unwrap()),
+            timezone: Some("utc".to_string()),
+            ..default_args()
+        };
+
+        let settings = Settings::resolve(&args, &Defaults::default()).unwrap();
+
+        let today = chrono::Utc::now().date_naive();
+        assert_eq!(settings.range.from, today - chrono::Days::new(14));
+        assert_eq!(settings.range.to, today - chrono::Days::new(1));
+    }
+
+    #[test]
+    fn test_settings_to_without_from_uses_days() {
+        let to = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let args = Args {
+            to: Some(to.into()),
+            days: Some(7),
+            ..default_args()
+        };
+
+        let settings = Settings::resolve(&args, &Defaults::default()).unwrap();
+
+        assert_eq!(
+            settings.range.from,
+            NaiveDate::from_ymd_opt(2024, 3, 8).unwrap()
+        );
+        assert_eq!(settings.range.to, to);
+    }
+
+    #[test]
+    fn test_settings_to_before_from_is_error() {
+        let args = Args {
+            from: NaiveDate::from_ymd_opt(2024, 3, 14).map(DateExpr::from),
+            to: NaiveDate::from_ymd_opt(2024, 3, 1).map(DateExpr::from),
+            ..default_args()
+        };
+
+        let result = Settings::resolve(&args, &Defaults::default());
+        assert!(matches!(result, Err(Error::InvalidDateRange { .. })));
+    }
+
+    #[test]
+    fn test_settings_complete_periods_trims_partial_weeks() {
+        // Wednesday 2024-03-06 to Wednesday 2024-03-27
+        let args = Args {
+            from: NaiveDate::from_ymd_opt(2024, 3, 6).map(DateExpr::from),
+            to: NaiveDate::from_ymd_opt(2024, 3, 27).map(DateExpr::from),
+            period: Some(Period::Weekly),
+            complete_periods: true,
+            ..default_args()
+        };
+
+        let settings = Settings::resolve(&args, &Defaults::default()).unwrap();
+        assert_eq!(
+            settings.range,
+            DateRange::new(
+                NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 24).unwrap()
+            )
+        );
+
+        let monthly = Args {
+            period: Some(Period::Monthly),
+            ..args
+        };
+        let result = Settings::resolve(&monthly, &Defaults::default());
+        assert!(matches!(result, Err(Error::NoCompletePeriod { .. })));
+        assert_eq!(result.err().map(|e| e.exit_code()), Some(2));
+    }
+
+    #[test]
+    fn test_settings_yoy_needs_weekly_or_monthly_period() {
+        let args = |period| Args {
+            period: Some(period),
+            yoy: true,
+            ..default_args()
+        };
+
+        for period in [Period::Weekly, Period::Monthly] {
+            assert!(Settings::resolve(&args(period), &Defaults::default()).is_ok());
+        }
+        for period in [Period::Daily, Period::Yearly] {
+            let result = Settings::resolve(&args(period), &Defaults::default());
+            assert!(matches!(result, Err(Error::YoyPeriod { .. })));
+            assert_eq!(result.err().map(|e| e.exit_code()), Some(2));
+        }
+    }
+
+    #[test]
+    fn test_filter_and_validate_repos() {
+        // Empty list should return empty
+        let repos: Vec<RepoConfig> = vec![];
+        let result = filter_and_validate_repos(&repos, None, None);
+        assert!(result.is_empty());
+    }
+
+    fn grouped_repo(name: &str, path: &Path, groups: &[&str]) -> RepoConfig {
+        RepoConfig {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            branch: None,
+            all_branches: false,
+            groups: groups.iter().map(ToString::to_string).collect(),
+            ext: Vec::new(),
+            include_merges: false,
+            first_parent: false,
+            timezone: None,
+        }
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_in_any_group() {
+        let dir = Path::new("/tmp/repo");
+        let work = grouped_repo("api", dir, &["work", "backend"]);
+        let ungrouped = grouped_repo("dotfiles", dir, &[]);
+
+        // No filter matches everything, including repos without groups
+        assert!(in_any_group(&work, None));
+        assert!(in_any_group(&ungrouped, None));
+
+        // Any listed group is enough
+        assert!(in_any_group(
+            &work,
+            Some(&strings(&["personal", "backend"]))
+        ));
+        assert!(!in_any_group(&work, Some(&strings(&["personal"]))));
+        assert!(!in_any_group(&ungrouped, Some(&strings(&["work"]))));
+    }
+
+    #[test]
+    fn test_filter_and_validate_repos_by_name_and_group() {
+        let work = create_test_repo();
+        let personal = create_test_repo();
+        let repos = vec![
+            grouped_repo("api", work.path(), &["work"]),
+            grouped_repo("blog", personal.path(), &["personal"]),
+            grouped_repo("scratch", personal.path(), &[]),
+        ];
+        let names = |infos: Vec<RepoInfo>| infos.into_iter().map(|r| r.name).collect::<Vec<_>>();
+
+        let groups = strings(&["work", "personal"]);
+        assert_eq!(
+            names(filter_and_validate_repos(&repos, None, Some(&groups))),
+            ["api", "blog"]
+        );
+
+        // Names and groups must both match
+        let repo_names = strings(&["api", "scratch"]);
+        assert_eq!(
+            names(filter_and_validate_repos(
+                &repos,
+                Some(&repo_names),
+                Some(&groups)
+            )),
+            ["api"]
+        );
+        assert_eq!(
+            names(filter_and_validate_repos(&repos, Some(&repo_names), None)),
+            ["api", "scratch"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        let repo = create_test_repo();
+        let config = Config {
+            schema: None,
+            repositories: vec![RepoConfig {
+                name: "app".to_string(),
+                path: repo.path().to_path_buf(),
+                branch: None,
+                all_branches: true,
+                groups: Vec::new(),
+                ext: Vec::new(),
+                include_merges: false,
+                first_parent: false,
+                timezone: None,
+            }],
+            defaults: Defaults::default(),
+        };
+        let target = |repo: &str, branch: Option<&str>| RepoTarget {
+            repo: repo.to_string(),
+            branch: branch.map(str::to_string),
+        };
+
+        let configured = resolve_target(&target("app", None), Some(&config)).unwrap();
+        assert_eq!(configured.name, "app");
+        assert!(configured.all_branches);
+
+        // A branch in the target overrides the configured branch settings
+        let with_branch = resolve_target(&target("app", Some("dev")), Some(&config)).unwrap();
+        assert_eq!(with_branch.name, "app@dev");
+        assert_eq!(with_branch.branch.as_deref(), Some("dev"));
+        assert!(!with_branch.all_branches);
+
+        let by_path = resolve_target(&target(repo.path().to_str().unwrap(), None), None).unwrap();
+        assert_eq!(by_path.path, repo.path().canonicalize().unwrap());
+
+        let missing = resolve_target(&target("/nonexistent/kodo-compare", None), Some(&config));
+        assert!(matches!(missing, Err(Error::RepoNotFound { .. })));
+    }
+
+    #[test]
+    fn test_execute_list_no_config() {
+        // Test list with non-existent config file
+        let list_args = ListArgs {
+            json: false,
+            group: None,
+            verbose: false,
+        };
+        let result = execute_list(
+            list_args,
+            Some(PathBuf::from("/nonexistent/config.json")),
+            false,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_execute_list_json_no_config() {
+        // Test list --json with non-existent config file
+        let list_args = ListArgs {
+            json: true,
+            group: None,
+            verbose: false,
+        };
+        let result = execute_list(
+            list_args,
+            Some(PathBuf::from("/nonexistent/config.json")),
+            false,
+        );
+        assert!(result.is_ok());
+    }
+
+    /// Config file listing `names` (at made-up paths), in a directory kept alive by the `TempDir`
+    fn config_with_repos(names: &[&str]) -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.json");
+        let config = Config {
+            repositories: names
+                .iter()
+                .map(|name| grouped_repo(name, &dir.path().join(name), &[]))
+                .collect(),
+            ..new_config()
+        };
+        save_config(&config, &config_path).unwrap();
+        (dir, config_path)
+    }
+
+    fn remove_args(identifiers: &[&str]) -> RemoveArgs {
+        RemoveArgs {
+            identifier: strings(identifiers),
+            all: false,
+            yes: false,
+        }
+    }
+
+    fn repo_names(config_path: &Path) -> Vec<String> {
+        load_config_for_update(config_path)
+            .unwrap()
+            .repositories
+            .into_iter()
+            .map(|repo| repo.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_execute_remove_several_by_name_and_path() {
+        let (dir, config_path) = config_with_repos(&["api", "web", "docs"]);
+        let docs_path = dir.path().join("docs").display().to_string();
+
+        execute_remove(
+            remove_args(&["api", &docs_path]),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(repo_names(&config_path), ["web"]);
+    }
+
+    #[test]
+    fn test_execute_remove_unknown_keeps_config_and_suggests() {
+        let (_dir, config_path) = config_with_repos(&["backend-api", "frontend", "docs"]);
+
+        let err = execute_remove(
+            remove_args(&["docs", "api"]),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Repository not found in config: api (did you mean: backend-api?)"
+        );
+        assert_eq!(
+            repo_names(&config_path),
+            ["backend-api", "frontend", "docs"]
+        );
+
+        let err = execute_remove(remove_args(&["frontent"]), Some(config_path.clone()), true)
+            .unwrap_err();
+        assert!(
+            err.to_string().ends_with("(did you mean: frontend?)"),
+            "{err}"
+        );
+
+        let err = execute_remove(remove_args(&["zzz"]), Some(config_path), true).unwrap_err();
+        assert_eq!(err.to_string(), "Repository not found in config: zzz");
+    }
+
+    #[test]
+    fn test_execute_remove_all() {
+        let (_dir, config_path) = config_with_repos(&["api", "web"]);
+        let all = |yes| RemoveArgs {
+            identifier: Vec::new(),
+            all: true,
+            yes,
+        };
+
+        // Without --yes and without a terminal to ask on, nothing is removed
+        execute_remove(all(false), Some(config_path.clone()), true).unwrap();
+        assert_eq!(repo_names(&config_path), ["api", "web"]);
+
+        execute_remove(all(true), Some(config_path.clone()), true).unwrap();
+        assert!(repo_names(&config_path).is_empty());
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_execute_list_with_repos() {
+        // Create a test repo and config
+        let dir = create_test_repo();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+
+        // Create config with the test repo
+        let config = Config {
+            schema: None,
+            repositories: vec![RepoConfig {
+                name: "test-repo".to_string(),
+                path: dir.path().to_path_buf(),
+                branch: Some("main".to_string()),
+                all_branches: false,
+                groups: Vec::new(),
+                ext: Vec::new(),
+                include_merges: false,
+                first_parent: false,
+                timezone: None,
+            }],
+            defaults: Defaults::default(),
+        };
+        save_config(&config, &config_path).unwrap();
+
+        // Test list
+        let list_args = ListArgs {
+            json: false,
+            group: None,
+            verbose: false,
+        };
+        let result = execute_list(list_args, Some(config_path.clone()), false);
+        assert!(result.is_ok());
+
+        // Test list --json
+        let list_args = ListArgs {
+            json: true,
+            group: None,
+            verbose: false,
+        };
+        let result = execute_list(list_args, Some(config_path), false);
+        assert!(result.is_ok());
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_repo_details_reads_head_and_last_commit() {
+        let dir = create_test_repo();
+        Command::new("git")
+            .args(["branch", "-M", "main"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        let repo = |branch: &str| RepoConfig {
+            name: "test-repo".to_string(),
+            path: dir.path().to_path_buf(),
+            branch: Some(branch.to_string()),
+            all_branches: false,
+            groups: Vec::new(),
+            ext: Vec::new(),
+            include_merges: false,
+            first_parent: false,
+            timezone: None,
+        };
 
-/// Print repositories in table format
-fn print_repo_table(repos: &[(&crate::config::RepoConfig, bool)]) {
-    // Calculate column widths
-    let name_width = repos
-        .iter()
-        .map(|(r, _)| r.name.len())
-        .max()
-        .unwrap_or(4)
-        .max(4); // "Name" header
+        let details = RepoDetails::read(&repo("main")).unwrap();
+        assert_eq!(details.head.as_deref(), Some("main"));
+        assert_eq!(details.last_commit, Some(chrono::Local::now().date_naive()));
+        assert_eq!(details.commits_label(), "1");
+        assert_eq!(details.branch_exists, Some(true));
+        assert_eq!(details.recent_commits.len(), 14);
+        assert_eq!(details.recent_commits.last(), Some(&1));
+        assert_eq!(details.recent_commits.iter().sum::<u64>(), 1);
 
-    let path_width = repos
-        .iter()
-        .map(|(r, _)| r.path.display().to_string().len())
-        .max()
-        .unwrap_or(4)
-        .max(4); // "Path" header
+        let details = RepoDetails::read(&repo("develop")).unwrap();
+        assert_eq!(details.branch_exists, Some(false));
 
-    let branch_width = repos
-        .iter()
-        .map(|(r, _)| r.branch.as_ref().map_or(1, String::len))
-        .max()
-        .unwrap_or(6)
-        .max(6); // "Branch" header
+        let mut missing = repo("main");
+        missing.path = dir.path().join("missing");
+        let error = RepoDetails::read(&missing).unwrap_err();
+        let mut json = serde_json::json!({ "name": "missing" });
+        add_details_json(&mut json, &Err(error));
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("Repository not found")
+        );
+        assert_eq!(json["name"], "missing");
+    }
 
-    // Print header
-    println!(
-        "{:<name_width$}  {:<path_width$}  {:<branch_width$}  Status",
-        "Name", "Path", "Branch"
-    );
+    #[test]
+    fn test_execute_list_verbose_keeps_going_past_missing_repo() {
+        let dir = create_test_repo();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+        let repo = |name: &str, path: PathBuf| RepoConfig {
+            name: name.to_string(),
+            path,
+            branch: None,
+            all_branches: false,
+            groups: Vec::new(),
+            ext: Vec::new(),
+            include_merges: false,
+            first_parent: false,
+            timezone: None,
+        };
+        let config = Config {
+            schema: None,
+            repositories: vec![
+                repo("gone", dir.path().join("gone")),
+                repo("test-repo", dir.path().to_path_buf()),
+            ],
+            defaults: Defaults::default(),
+        };
+        save_config(&config, &config_path).unwrap();
 
-    // Print rows
-    for (repo, exists) in repos {
-        let branch = repo.branch.as_deref().unwrap_or("-");
-        let status = if *exists { "\u{2713}" } else { "\u{2717}" };
-        println!(
-            "{:<name_width$}  {:<path_width$}  {:<branch_width$}  {}",
-            repo.name,
-            repo.path.display(),
-            branch,
-            status
-        );
+        for json in [false, true] {
+            let list_args = ListArgs {
+                json,
+                group: None,
+                verbose: true,
+            };
+            assert!(execute_list(list_args, Some(config_path.clone()), false).is_ok());
+        }
     }
-}
 
-/// Check if a path is a git repository
-fn is_git_repo(path: &Path) -> bool {
-    path.join(".git").exists() || path.join("HEAD").exists()
-}
+    #[test]
+    fn test_find_git_repos_skips_plain_and_nested() {
+        let root = TempDir::new().unwrap();
+        let repo_a = root.path().join("a");
+        let repo_b = root.path().join("group").join("b");
+        let plain = root.path().join("plain");
+        git2::Repository::init(&repo_a).unwrap();
+        git2::Repository::init(&repo_b).unwrap();
+        std::fs::create_dir_all(&plain).unwrap();
+        // Nested repo inside an already detected one
+        git2::Repository::init(repo_a.join("vendor").join("nested")).unwrap();
 
-/// Shorten path by replacing home directory with ~
-fn shorten_home_path(path: &Path) -> PathBuf {
-    if let Some(home) = dirs::home_dir()
-        && let Ok(relative) = path.strip_prefix(&home)
-    {
-        return PathBuf::from("~").join(relative);
+        let found = find_git_repos(root.path(), 2);
+        assert_eq!(found, vec![repo_a.clone(), repo_b]);
+
+        let shallow = find_git_repos(root.path(), 1);
+        assert_eq!(shallow, vec![repo_a]);
     }
-    path.to_path_buf()
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::process::Command;
-    use tempfile::TempDir;
+    #[test]
+    fn test_execute_add_scan() {
+        let root = TempDir::new().unwrap();
+        for name in ["one", "two"] {
+            let repo = create_test_repo();
+            std::fs::rename(repo.path(), root.path().join(name)).unwrap();
+        }
+        std::fs::create_dir_all(root.path().join("notes")).unwrap();
 
-    fn create_test_repo() -> TempDir {
-        let dir = TempDir::new().unwrap();
-        let path = dir.path();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+        let scan_args = || AddArgs {
+            path: root.path().to_path_buf(),
+            name: None,
+            branch: None,
+            group: vec!["work".to_string()],
+            scan: true,
+            depth: 2,
+            dry_run: false,
+            yes: false,
+        };
 
-        Command::new("git")
-            .args(["init"])
-            .current_dir(path)
-            .output()
-            .unwrap();
+        // Dry run doesn't write the config
+        let dry_run = AddArgs {
+            dry_run: true,
+            ..scan_args()
+        };
+        execute_add(dry_run, Some(config_path.clone()), false).unwrap();
+        assert!(!config_path.exists());
 
-        Command::new("git")
-            .args(["config", "user.email", "test@example.com"])
-            .current_dir(path)
-            .output()
-            .unwrap();
+        execute_add(scan_args(), Some(config_path.clone()), false).unwrap();
+        let config = load_config(&config_path).unwrap();
+        let names: Vec<_> = config
+            .repositories
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, ["one", "two"]);
+        assert!(config.repositories.iter().all(|r| r.groups == ["work"]));
 
-        Command::new("git")
-            .args(["config", "user.name", "Test User"])
-            .current_dir(path)
-            .output()
-            .unwrap();
+        // Second scan skips already registered repositories
+        execute_add(scan_args(), Some(config_path.clone()), false).unwrap();
+        let config = load_config(&config_path).unwrap();
+        assert_eq!(config.repositories.len(), 2);
+    }
 
-        std::fs::write(path.join("README.md"), "# Test\n").unwrap();
+    fn init_args(scan: Option<&Path>) -> InitArgs {
+        InitArgs {
+            force: false,
+            scan: scan.map(Path::to_path_buf),
+            depth: 2,
+            yes: true,
+            format: None,
+        }
+    }
 
-        Command::new("git")
-            .args(["add", "."])
-            .current_dir(path)
-            .output()
-            .unwrap();
+    #[test]
+    fn test_execute_config_init_with_yes() {
+        let root = TempDir::new().unwrap();
+        for name in ["one", "two"] {
+            let repo = create_test_repo();
+            std::fs::rename(repo.path(), root.path().join(name)).unwrap();
+        }
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("kodo").join("config.json");
 
-        Command::new("git")
-            .args(["commit", "-m", "Initial commit"])
-            .current_dir(path)
-            .output()
-            .unwrap();
+        execute_config_init(
+            init_args(Some(root.path())),
+            Some(config_path.clone()),
+            false,
+        )
+        .unwrap();
 
-        dir
+        let config = load_config(&config_path).unwrap();
+        assert!(config.schema.is_some());
+        let names: Vec<_> = config
+            .repositories
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, ["one", "two"]);
+        assert_eq!(config.defaults.days, 7);
+        assert_eq!(config.defaults.period, Some(Period::Daily));
+        assert_eq!(config.defaults.output, Some(OutputFormat::Tui));
     }
 
     #[test]
-    fn test_execute_with_repo_arg() {
-        let dir = create_test_repo();
+    fn test_execute_config_init_refuses_to_overwrite() {
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+        std::fs::write(&config_path, r#"{"repositories": []}"#).unwrap();
 
-        let args = Args {
-            command: None,
-            config: None,
-            repo: Some(dir.path().to_path_buf()),
-            days: 7,
-            include_merges: false,
-            output: OutputFormat::Json,
-            period: crate::cli::args::Period::Daily,
-            branch: None,
-            ext: None,
-            single_metric: false,
-            timezone: "local".to_string(),
-            repo_name: None,
-        };
+        let err =
+            execute_config_init(init_args(None), Some(config_path.clone()), false).unwrap_err();
+        assert!(matches!(err, Error::ConfigExists { .. }), "{err}");
+        assert_eq!(
+            std::fs::read_to_string(&config_path).unwrap(),
+            r#"{"repositories": []}"#
+        );
 
-        let result = execute(args);
-        assert!(result.is_ok());
+        let force = InitArgs {
+            force: true,
+            ..init_args(None)
+        };
+        execute_config_init(force, Some(config_path.clone()), false).unwrap();
+        // Without repositories the file is valid JSON but not yet loadable
+        let config: Config =
+            serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert!(config.repositories.is_empty());
+        assert_eq!(config.defaults.output, Some(OutputFormat::Tui));
     }
 
     #[test]
-    fn test_execute_with_repo_arg_table_output() {
-        let dir = create_test_repo();
+    fn test_execute_config_init_toml() {
+        let root = TempDir::new().unwrap();
+        let repo = create_test_repo();
+        std::fs::rename(repo.path(), root.path().join("one")).unwrap();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.toml");
 
-        let args = Args {
-            command: None,
-            config: None,
-            repo: Some(dir.path().to_path_buf()),
-            days: 7,
-            include_merges: false,
-            output: OutputFormat::Table,
-            period: crate::cli::args::Period::Daily,
-            branch: None,
-            ext: None,
-            single_metric: false,
-            timezone: "local".to_string(),
-            repo_name: None,
+        let args = InitArgs {
+            format: Some(ConfigFormat::Toml),
+            ..init_args(Some(root.path()))
         };
+        execute_config_init(args, Some(config_path.clone()), false).unwrap();
 
-        let result = execute(args);
-        assert!(result.is_ok());
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains("[[repositories]]"), "{content}");
+        assert!(!content.contains("$schema"), "{content}");
+        let config = load_config(&config_path).unwrap();
+        assert_eq!(config.repositories[0].name, "one");
+        assert_eq!(config.defaults.period, Some(Period::Daily));
     }
 
     #[test]
-    fn test_execute_with_repo_arg_csv_output() {
-        let dir = create_test_repo();
+    fn test_execute_config_init_rejects_mismatched_format() {
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
 
-        let args = Args {
-            command: None,
-            config: None,
-            repo: Some(dir.path().to_path_buf()),
-            days: 7,
-            include_merges: false,
-            output: OutputFormat::Csv,
-            period: crate::cli::args::Period::Daily,
-            branch: None,
-            ext: None,
-            single_metric: false,
-            timezone: "local".to_string(),
-            repo_name: None,
+        let args = InitArgs {
+            format: Some(ConfigFormat::Toml),
+            ..init_args(None)
         };
+        let err = execute_config_init(args, Some(config_path.clone()), false).unwrap_err();
+        assert!(matches!(err, Error::ConfigInvalid { .. }), "{err}");
+        assert!(!config_path.exists());
+    }
 
-        let result = execute(args);
-        assert!(result.is_ok());
+    /// Config with a live repository, a deleted path, and the live one again
+    fn write_prune_config(dir: &Path, live: &Path) -> PathBuf {
+        let mut config = new_config();
+        config.repositories = vec![
+            grouped_repo("live", live, &[]),
+            grouped_repo("gone", &dir.join("deleted-repo"), &[]),
+            grouped_repo("live-again", &live.join("."), &[]),
+        ];
+        let config_path = dir.join("config.json");
+        save_config(&config, &config_path).unwrap();
+        config_path
+    }
+
+    fn config_names(config_path: &Path) -> Vec<String> {
+        load_config(config_path)
+            .unwrap()
+            .repositories
+            .into_iter()
+            .map(|r| r.name)
+            .collect()
     }
 
     #[test]
-    fn test_get_repositories_with_repo_arg() {
-        let args = Args {
-            command: None,
-            config: None,
-            repo: Some(PathBuf::from("/tmp/test-repo")),
-            days: 7,
-            include_merges: false,
-            output: OutputFormat::Json,
-            period: crate::cli::args::Period::Daily,
+    #[cfg(unix)]
+    fn test_execute_add_detects_symlinked_duplicate() {
+        let repo = create_test_repo();
+        let links = TempDir::new().unwrap();
+        let link = links.path().join("linked");
+        std::os::unix::fs::symlink(repo.path(), &link).unwrap();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+        let add = |path: PathBuf, name: Option<&str>, yes| AddArgs {
+            path,
+            name: name.map(str::to_string),
             branch: None,
-            ext: None,
-            single_metric: false,
-            timezone: "local".to_string(),
-            repo_name: None,
+            group: Vec::new(),
+            scan: false,
+            depth: 2,
+            dry_run: false,
+            yes,
         };
 
-        let result = get_repositories(&args);
-        assert!(result.is_ok());
+        execute_add(
+            add(repo.path().join("."), Some("proj"), false),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap();
+        let stored = load_config(&config_path).unwrap().repositories[0]
+            .path
+            .clone();
+        assert_eq!(stored, shorten_home_path(repo.path()));
 
-        let repos = result.unwrap();
-        assert_eq!(repos.len(), 1);
-        assert_eq!(repos[0].path, PathBuf::from("/tmp/test-repo"));
-        assert_eq!(repos[0].name, "test-repo");
+        // Through the symlink and with a trailing slash, it is the same repository
+        let mut trailing = link.clone().into_os_string();
+        trailing.push("/");
+        execute_add(
+            add(link.clone(), None, false),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap();
+        execute_add(
+            add(trailing.into(), None, false),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap();
+        assert_eq!(config_names(&config_path), ["proj"]);
+
+        // A new name is only applied when confirmed
+        execute_add(
+            add(link.clone(), Some("renamed"), false),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap();
+        assert_eq!(config_names(&config_path), ["proj"]);
+        execute_add(
+            add(link, Some("renamed"), true),
+            Some(config_path.clone()),
+            true,
+        )
+        .unwrap();
+        assert_eq!(config_names(&config_path), ["renamed"]);
     }
 
     #[test]
-    fn test_filter_and_validate_repos() {
-        // Empty list should return empty
-        let repos: Vec<RepoConfig> = vec![];
-        let result = filter_and_validate_repos(&repos, None);
-        assert!(result.is_empty());
+    fn test_concurrent_adds_keep_every_repository() {
+        let repos: Vec<TempDir> = (0..4).map(|_| create_test_repo()).collect();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = config_dir.path().join("config.json");
+
+        std::thread::scope(|scope| {
+            for (i, repo) in repos.iter().enumerate() {
+                let config_path = config_path.clone();
+                scope.spawn(move || {
+                    let add_args = AddArgs {
+                        path: repo.path().to_path_buf(),
+                        name: Some(format!("repo{i}")),
+                        branch: None,
+                        group: Vec::new(),
+                        scan: false,
+                        depth: 2,
+                        dry_run: false,
+                        yes: false,
+                    };
+                    execute_add(add_args, Some(config_path), true).unwrap();
+                });
+            }
+        });
+
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&content).is_ok());
+        let mut names = config_names(&config_path);
+        names.sort();
+        assert_eq!(names, ["repo0", "repo1", "repo2", "repo3"]);
+        assert!(!config_dir.path().join("config.json.lock").exists());
     }
 
     #[test]
-    fn test_execute_list_no_config() {
-        // Test list with non-existent config file
-        let list_args = ListArgs { json: false };
-        let result = execute_list(list_args, Some(PathBuf::from("/nonexistent/config.json")));
-        assert!(result.is_ok());
+    fn test_normalize_lexically() {
+        assert_eq!(
+            normalize_lexically(Path::new("/gone/./repo/../other/")),
+            PathBuf::from("/gone/other")
+        );
+        assert_eq!(normalize_lexically(Path::new("/..")), PathBuf::from("/"));
+        assert_eq!(
+            canonicalize_or_normalize(Path::new("/nonexistent/kodo/./repo/")),
+            PathBuf::from("/nonexistent/kodo/repo")
+        );
     }
 
     #[test]
-    fn test_execute_list_json_no_config() {
-        // Test list --json with non-existent config file
-        let list_args = ListArgs { json: true };
-        let result = execute_list(list_args, Some(PathBuf::from("/nonexistent/config.json")));
-        assert!(result.is_ok());
+    fn test_execute_prune_removes_missing_and_duplicates() {
+        let live = create_test_repo();
+        let config_dir = TempDir::new().unwrap();
+        let config_path = write_prune_config(config_dir.path(), live.path());
+        let prune = |dry_run, missing_only| PruneArgs {
+            dry_run,
+            missing_only,
+        };
+
+        execute_prune(&prune(true, false), Some(config_path.clone())).unwrap();
+        assert_eq!(config_names(&config_path), ["live", "gone", "live-again"]);
+
+        execute_prune(&prune(false, true), Some(config_path.clone())).unwrap();
+        assert_eq!(config_names(&config_path), ["live", "live-again"]);
+
+        execute_prune(&prune(false, false), Some(config_path.clone())).unwrap();
+        assert_eq!(config_names(&config_path), ["live"]);
+
+        // Nothing left to prune is not an error
+        execute_prune(&prune(false, false), Some(config_path.clone())).unwrap();
+        assert_eq!(config_names(&config_path), ["live"]);
     }
 
     #[test]
-    fn test_execute_list_with_repos() {
-        // Create a test repo and config
-        let dir = create_test_repo();
+    fn test_prune_repositories_reasons() {
+        let live = create_test_repo();
         let config_dir = TempDir::new().unwrap();
-        let config_path = config_dir.path().join("config.json");
+        let config_path = write_prune_config(config_dir.path(), live.path());
+        let mut repositories = load_config(&config_path).unwrap().repositories;
 
-        // Create config with the test repo
-        let config = Config {
-            schema: None,
-            repositories: vec![RepoConfig {
-                name: "test-repo".to_string(),
-                path: dir.path().to_path_buf(),
-                branch: Some("main".to_string()),
-            }],
-            defaults: Defaults::default(),
-        };
-        save_config(&config, &config_path).unwrap();
-
-        // Test list
-        let list_args = ListArgs { json: false };
-        let result = execute_list(list_args, Some(config_path.clone()));
-        assert!(result.is_ok());
+        let pruned = prune_repositories(&mut repositories, false);
 
-        // Test list --json
-        let list_args = ListArgs { json: true };
-        let result = execute_list(list_args, Some(config_path));
-        assert!(result.is_ok());
+        let reasons: Vec<_> = pruned
+            .iter()
+            .map(|(repo, reason)| (repo.name.as_str(), reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                ("gone", &PruneReason::Missing),
+                (
+                    "live-again",
+                    &PruneReason::Duplicate {
+                        of: "live".to_string()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(repositories.len(), 1);
     }
 
     #[test]
-    fn test_is_git_repo() {
+    fn test_is_git_repository() {
         let dir = create_test_repo();
-        assert!(is_git_repo(dir.path()));
+        assert!(is_git_repository(dir.path()));
 
         let non_git_dir = TempDir::new().unwrap();
-        assert!(!is_git_repo(non_git_dir.path()));
+        assert!(!is_git_repository(non_git_dir.path()));
     }
 
     #[test]
@@ -731,11 +4015,23 @@ mod tests {
                     name: "repo1".to_string(),
                     path: repo1.path().to_path_buf(),
                     branch: None,
+                    all_branches: false,
+                    groups: Vec::new(),
+                    ext: Vec::new(),
+                    include_merges: false,
+                    first_parent: false,
+                    timezone: None,
                 },
                 RepoConfig {
                     name: "repo2".to_string(),
                     path: repo2.path().to_path_buf(),
                     branch: None,
+                    all_branches: false,
+                    groups: Vec::new(),
+                    ext: Vec::new(),
+                    include_merges: false,
+                    first_parent: false,
+                    timezone: None,
                 },
             ],
             defaults: Defaults::default(),
@@ -744,21 +4040,244 @@ mod tests {
 
         // Execute with multiple repos (tests parallel processing)
         let args = Args {
-            command: None,
             config: Some(config_path),
-            repo: None,
-            days: 7,
-            include_merges: false,
-            output: OutputFormat::Json,
-            period: crate::cli::args::Period::Daily,
-            branch: None,
-            ext: None,
-            single_metric: false,
-            timezone: "local".to_string(),
-            repo_name: None,
+            days: Some(7),
+            output: Some(OutputFormat::Json),
+            period: Some(Period::Daily),
+            timezone: Some("local".to_string()),
+            ..default_args()
         };
 
         let result = execute(args);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_execute_empty_repository_reports_zero_totals() {
+        let repo_dir = TempDir::new().unwrap();
+        std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(repo_dir.path())
+            .output()
+            .unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let out_path = out_dir.path().join("report.json");
+
+        let args = Args {
+            config: Some(out_dir.path().join("missing.json")),
+            repo: vec![repo_dir.path().to_path_buf()],
+            output: Some(OutputFormat::Json),
+            out: Some(out_path.clone()),
+            ..default_args()
+        };
+        execute(args).unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out_path).unwrap()).unwrap();
+        assert_eq!(report["total"]["commits"], 0);
+        assert_eq!(report["total"]["additions"], 0);
+    }
+
+    #[test]
+    fn test_split_previous_commits() {
+        let commit = |id: &str, day: u32| {
+            let timestamp = NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc();
+            CommitInfo::new(
+                id.to_string(),
+                timestamp,
+                false,
+                crate::git::DiffStats::default(),
+            )
+        };
+        let repos = || {
+            vec![
+                RepoCommits {
+                    name: "alpha".to_string(),
+                    commits: vec![commit("a3", 9), commit("a2", 8), commit("a1", 2)],
+                    unborn: false,
+                    truncated: false,
+                    timezone: None,
+                },
+                RepoCommits {
+                    name: "beta".to_string(),
+                    commits: vec![commit("b1", 7)],
+                    unborn: false,
+                    truncated: false,
+                    timezone: None,
+                },
+            ]
+        };
+
+        let day = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let ids = |commits: &[CommitInfo]| commits.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+
+        // An overlapping earlier range shares its commits with the analyzed range
+        let mut overlapping = repos();
+        let previous = split_previous_commits(
+            &mut overlapping,
+            DateRange::new(day(2), day(8)),
+            day(8),
+            &TimeZoneMode::Utc,
+        );
+        assert_eq!(ids(&previous), ["a2", "a1", "b1"]);
+        assert_eq!(ids(&overlapping[0].commits), ["a3", "a2"]);
+
+        let mut results = repos();
+        let previous = split_previous_commits(
+            &mut results,
+            DateRange::new(day(1), day(7)),
+            day(8),
+            &TimeZoneMode::Utc,
+        );
+        assert_eq!(ids(&previous), ["a1", "b1"]);
+        assert_eq!(ids(&results[0].commits), ["a3", "a2"]);
+        assert!(results[1].commits.is_empty());
+    }
+
+    #[test]
+    fn test_repo_timezone_daily_stats_buckets_each_repo_in_its_timezone() {
+        // 20:30 UTC on March 8 is already March 9 in Tokyo
+        let timestamp = NaiveDate::from_ymd_opt(2024, 3, 8)
+            .unwrap()
+            .and_hms_opt(20, 30, 0)
+            .unwrap()
+            .and_utc();
+        let repo = |name: &str, timezone: &str| RepoCommits {
+            name: name.to_string(),
+            commits: vec![CommitInfo::new(
+                format!("{name}1"),
+                timestamp,
+                false,
+                crate::git::DiffStats::default(),
+            )],
+            unborn: false,
+            truncated: false,
+            timezone: Some(TimeZoneMode::parse(timezone).unwrap()),
+        };
+        let results = vec![repo("london", "UTC"), repo("tokyo", "Asia/Tokyo")];
+        let settings = Settings {
+            range: DateRange::new(
+                NaiveDate::from_ymd_opt(2024, 3, 7).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 10).unwrap(),
+            ),
+            ..Settings::resolve(&default_args(), &Defaults::default()).unwrap()
+        };
+        let filter = FileFilter::default();
+
+        let commits_by_day = |stats: &[PeriodStats]| {
+            stats
+                .iter()
+                .map(|s| (s.date.format("%m-%d").to_string(), s.commits))
+                .collect::<Vec<_>>()
+        };
+        let daily = repo_timezone_daily_stats(&results, &settings, &filter).unwrap();
+        assert_eq!(
+            commits_by_day(&daily),
+            [
+                ("03-07".to_string(), 0),
+                ("03-08".to_string(), 1),
+                ("03-09".to_string(), 1),
+                ("03-10".to_string(), 0),
+            ]
+        );
+
+        let no_overrides: Vec<_> = results
+            .into_iter()
+            .map(|repo| RepoCommits {
+                timezone: None,
+                ..repo
+            })
+            .collect();
+        assert!(repo_timezone_daily_stats(&no_overrides, &settings, &filter).is_none());
+    }
+
+    fn repo_info(name: &str, path: &Path, branch: Option<&str>) -> RepoInfo {
+        RepoInfo {
+            path: path.to_path_buf(),
+            name: name.to_string(),
+            branch: branch.map(str::to_string),
+            all_branches: false,
+            ext: Vec::new(),
+            include_merges: false,
+            first_parent: false,
+            timezone: None,
+        }
+    }
+
+    #[test]
+    fn test_collect_repo_commits_sorted_by_name() {
+        let repos: Vec<_> = ["charlie", "alpha", "bravo"]
+            .into_iter()
+            .map(|name| (name, create_test_repo()))
+            .collect();
+        let infos: Vec<_> = repos
+            .iter()
+            .map(|(name, dir)| repo_info(name, dir.path(), None))
+            .collect();
+        let args = Args {
+            jobs: std::num::NonZeroUsize::new(2),
+            ..default_args()
+        };
+        let today = Utc::now().date_naive();
+
+        let (collected, failures) = collect_repo_commits(
+            &infos,
+            &args,
+            &merges_excluded(DateRange::new(today, today)),
+            &SpinnerGuard::new(false),
+        )
+        .unwrap();
+
+        assert!(failures.is_empty());
+        let names: Vec<_> = collected.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "bravo", "charlie"]);
+        assert!(collected.iter().all(|r| r.commits.len() == 1));
+    }
+
+    #[test]
+    fn test_collect_repo_commits_skips_failed_repo_unless_strict() {
+        let good = create_test_repo();
+        let bad = create_test_repo();
+        let infos = vec![
+            repo_info("good", good.path(), None),
+            repo_info("bad", bad.path(), Some("no-such-branch")),
+        ];
+        let today = Utc::now().date_naive();
+        let range = DateRange::new(today, today);
+        let spinner = SpinnerGuard::new(false);
+
+        let (collected, failures) =
+            collect_repo_commits(&infos, &default_args(), &merges_excluded(range), &spinner)
+                .unwrap();
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].name, "good");
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].to_string().starts_with("bad: "));
+
+        let strict = Args {
+            strict: true,
+            ..default_args()
+        };
+        let result = collect_repo_commits(&infos, &strict, &merges_excluded(range), &spinner);
+        assert!(matches!(result, Err(Error::RepoFailed { repo, .. }) if repo == "bad"));
+    }
+
+    #[test]
+    fn test_collect_repo_commits_errors_when_all_fail() {
+        let bad = create_test_repo();
+        let infos = vec![repo_info("bad", bad.path(), Some("no-such-branch"))];
+        let today = Utc::now().date_naive();
+
+        let result = collect_repo_commits(
+            &infos,
+            &default_args(),
+            &merges_excluded(DateRange::new(today, today)),
+            &SpinnerGuard::new(false),
+        );
+        assert!(matches!(result, Err(Error::RepoFailed { .. })));
+    }
 }
@@ -1,19 +1,49 @@
 //! Configuration loading and path utilities
 
+use crate::cli::args::ConfigFormat;
 use crate::config::Config;
 use crate::error::{Error, Result};
+use crate::stats::TimeZoneMode;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Load configuration from a JSON file
+/// Config file names looked up in a config directory, most preferred first
+const CONFIG_FILE_NAMES: [&str; 2] = ["config.json", "config.toml"];
+
+/// Load configuration from a JSON or TOML file
+///
+/// The format follows the file extension: `.toml` is parsed as TOML, anything
+/// else as JSON.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The file does not exist
 /// - The file cannot be read
-/// - The JSON is invalid
+/// - The JSON or TOML is invalid
 pub fn load_config(path: &Path) -> Result<Config> {
+    let config = load_config_for_update(path)?;
+
+    // Validate that we have at least one repository
+    if config.repositories.is_empty() {
+        return Err(Error::ConfigInvalid {
+            message: "No repositories configured".to_string(),
+        });
+    }
+
+    Ok(config)
+}
+
+/// Load a configuration that is about to be modified
+///
+/// Like [`load_config`], but a config listing no repositories is accepted so
+/// that repositories can be added to it again.
+///
+/// # Errors
+///
+/// Returns an error if the file does not exist or cannot be read, if the JSON
+/// or TOML is invalid, or if a repository names an invalid timezone.
+pub fn load_config_for_update(path: &Path) -> Result<Config> {
     if !path.exists() {
         return Err(Error::ConfigNotFound {
             path: path.to_path_buf(),
@@ -21,13 +51,17 @@ pub fn load_config(path: &Path) -> Result<Config> {
     }
 
     let content = fs::read_to_string(path)?;
-    let config: Config = serde_json::from_str(&content)?;
+    let config: Config = match ConfigFormat::from_path(path) {
+        ConfigFormat::Json => serde_json::from_str(&content)?,
+        ConfigFormat::Toml => toml::from_str(&content)?,
+    };
 
-    // Validate that we have at least one repository
-    if config.repositories.is_empty() {
-        return Err(Error::ConfigInvalid {
-            message: "No repositories configured".to_string(),
-        });
+    for repo in &config.repositories {
+        if let Some(timezone) = &repo.timezone {
+            TimeZoneMode::parse(timezone).map_err(|message| Error::ConfigInvalid {
+                message: format!("Repository '{}': {message}", repo.name),
+            })?;
+        }
     }
 
     Ok(config)
@@ -36,32 +70,51 @@ pub fn load_config(path: &Path) -> Result<Config> {
 /// Get the default configuration file path
 ///
 /// Checks in order:
-/// 1. `~/.config/kodo/config.json` (XDG style, preferred)
-/// 2. Platform-specific config dir (e.g., `~/Library/Application Support` on macOS)
+/// 1. `~/.config/kodo/config.json`, then `config.toml` (XDG style, preferred)
+/// 2. Platform-specific config dir (e.g., `~/Library/Application Support` on macOS),
+///    again `config.json` before `config.toml`
+///
+/// When neither exists, returns `config.json` in the platform-specific dir.
 #[must_use]
 pub fn default_config_path() -> Option<PathBuf> {
-    // First, try XDG-style path (~/.config/kodo/config.json)
-    if let Some(home) = dirs::home_dir() {
-        let xdg_path = home.join(".config").join("kodo").join("config.json");
-        if xdg_path.exists() {
-            return Some(xdg_path);
-        }
+    // First, try XDG-style paths (~/.config/kodo/config.{json,toml})
+    if let Some(home) = dirs::home_dir()
+        && let Some(path) = existing_config_in(&home.join(".config").join("kodo"))
+    {
+        return Some(path);
     }
 
     // Fall back to platform-specific config dir
-    dirs::config_dir().map(|p| p.join("kodo").join("config.json"))
+    let dir = dirs::config_dir()?.join("kodo");
+    existing_config_in(&dir).or_else(|| Some(dir.join(CONFIG_FILE_NAMES[0])))
+}
+
+/// First config file in `dir` that exists, preferring JSON over TOML
+fn existing_config_in(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
 }
 
-/// Save configuration to a JSON file
+/// Save configuration to a JSON or TOML file
 ///
-/// Creates parent directories if they don't exist.
+/// The format follows the file extension like in [`load_config`], so a
+/// loaded config is written back in the format it was read from. Creates
+/// parent directories if they don't exist.
+///
+/// The content goes to a temporary file that is then renamed over the
+/// config, so a crash mid-write leaves the previous config intact. A
+/// symlinked config is replaced at its target. Hold a
+/// [`ConfigLock`](crate::config::ConfigLock) across loading and saving to
+/// keep concurrent updates from overwriting each other.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Parent directory cannot be created
 /// - The file cannot be written
-/// - JSON serialization fails
+/// - JSON or TOML serialization fails
 pub fn save_config(config: &Config, path: &Path) -> Result<()> {
     // Create parent directories if needed
     if let Some(parent) = path.parent()
@@ -70,8 +123,20 @@ pub fn save_config(config: &Config, path: &Path) -> Result<()> {
         fs::create_dir_all(parent)?;
     }
 
-    let content = serde_json::to_string_pretty(config)?;
-    fs::write(path, content)?;
+    let content = match ConfigFormat::from_path(path) {
+        ConfigFormat::Json => serde_json::to_string_pretty(config)?,
+        ConfigFormat::Toml => toml::to_string_pretty(config)?,
+    };
+    // Keep a symlink (e.g. into a dotfiles repository) by replacing its target
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut tmp_name = target.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = target.with_file_name(tmp_name);
+    fs::write(&tmp_path, content)?;
+    if let Err(e) = fs::rename(&tmp_path, &target) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
 
     Ok(())
 }
@@ -139,6 +204,23 @@ mod tests {
 
         let result = load_config(file.path());
         assert!(matches!(result, Err(Error::ConfigInvalid { .. })));
+
+        let config = load_config_for_update(file.path()).unwrap();
+        assert!(config.repositories.is_empty());
+    }
+
+    #[test]
+    fn test_load_config_invalid_repo_timezone() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"{{"repositories": [{{"name": "test", "path": "/tmp", "timezone": "Mars/Base"}}]}}"#
+        )
+        .unwrap();
+
+        let err = load_config(file.path()).unwrap_err();
+        assert!(matches!(err, Error::ConfigInvalid { .. }), "{err}");
+        assert!(err.to_string().contains("'test'"), "{err}");
     }
 
     #[test]
@@ -150,6 +232,132 @@ mod tests {
         assert!(matches!(result, Err(Error::Json(_))));
     }
 
+    #[test]
+    fn test_load_config_toml_matches_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let json_path = dir.path().join("config.json");
+        let toml_path = dir.path().join("config.toml");
+        fs::write(
+            &json_path,
+            r#"{
+                "repositories": [
+                    {"name": "app", "path": "~/src/app", "branch": "main", "groups": ["work"]},
+                    {"name": "docs", "path": "/docs", "ext": ["md"], "include_merges": true}
+                ],
+                "defaults": {
+                    "days": 30,
+                    "period": "weekly",
+                    "timezone": "Asia/Tokyo",
+                    "colors": {"addition": "lightblue"}
+                }
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            &toml_path,
+            r#"
+            # Repositories are listed as an array of tables
+            [[repositories]]
+            name = "app"
+            path = "~/src/app"
+            branch = "main"
+            groups = ["work"]
+
+            [[repositories]]
+            name = "docs"
+            path = "/docs"
+            ext = ["md"]
+            include_merges = true
+
+            [defaults]
+            days = 30
+            period = "weekly"
+            timezone = "Asia/Tokyo"
+
+            [defaults.colors]
+            addition = "lightblue"
+            "#,
+        )
+        .unwrap();
+
+        let json = load_config(&json_path).unwrap();
+        let toml = load_config(&toml_path).unwrap();
+        assert_eq!(json, toml);
+        assert_eq!(toml.repositories[1].ext, ["md"]);
+        assert!(toml.defaults.exclude_merges);
+    }
+
+    #[test]
+    fn test_save_config_round_trips_toml() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[[repositories]]\nname = \"app\"\npath = \"/app\"\n\n[defaults]\ndays = 14\n",
+        )
+        .unwrap();
+
+        let config = load_config(&path).unwrap();
+        save_config(&config, &path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(toml::from_str::<toml::Table>(&content).is_ok(), "{content}");
+        assert_eq!(load_config(&path).unwrap(), config);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_save_config_replaces_symlink_target_without_leftovers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let target = dir.path().join("dotfiles.json");
+        let link = dir.path().join("config.json");
+        fs::write(
+            &target,
+            r#"{"repositories": [{"name": "app", "path": "/app"}]}"#,
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let mut config = load_config(&link).unwrap();
+        config.repositories[0].name = "renamed".to_string();
+        save_config(&config, &link).unwrap();
+
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(
+            load_config(&target).unwrap().repositories[0].name,
+            "renamed"
+        );
+        let mut files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["config.json", "dotfiles.json"]);
+    }
+
+    #[test]
+    fn test_load_config_invalid_toml() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "repositories = [").unwrap();
+
+        let result = load_config(&path);
+        assert!(matches!(result, Err(Error::Toml(_))));
+    }
+
+    #[test]
+    fn test_existing_config_in_prefers_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(existing_config_in(dir.path()), None);
+
+        let toml_path = dir.path().join("config.toml");
+        fs::write(&toml_path, "").unwrap();
+        assert_eq!(existing_config_in(dir.path()), Some(toml_path));
+
+        let json_path = dir.path().join("config.json");
+        fs::write(&json_path, "").unwrap();
+        assert_eq!(existing_config_in(dir.path()), Some(json_path));
+    }
+
     #[test]
     fn test_expand_tilde_home() {
         let expanded = expand_tilde(Path::new("~"));
@@ -0,0 +1,245 @@
+//! Advisory lock serializing config file updates between kodo processes
+
+use crate::error::{Error, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long to wait for another process to release the lock
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Age at which a lock is assumed to be left behind by a crashed process
+///
+/// A held lock is touched every quarter of this, also while a prompt waits
+/// for an answer, so only a lock nobody keeps fresh gets this old.
+pub const STALE_LOCK_AGE: Duration = Duration::from_mins(2);
+
+/// Delay between attempts to take a held lock
+const RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Locks taken by this process so far, making each token unique
+static LOCKS_TAKEN: AtomicU64 = AtomicU64::new(0);
+
+/// Exclusive lock on a config file, held across load, modify, and save
+///
+/// The lock is a `<config>.lock` file next to the config, created atomically
+/// and removed again when the guard is dropped. It holds a token naming its
+/// owner, so a process only ever removes or refreshes a lock it took itself.
+/// It only keeps out other kodo processes that take it too.
+#[derive(Debug)]
+pub struct ConfigLock {
+    path: PathBuf,
+    token: String,
+    /// Dropping it stops the thread that keeps the lock fresh
+    heartbeat: Option<mpsc::Sender<()>>,
+}
+
+impl ConfigLock {
+    /// Take the lock for `config`, waiting up to [`LOCK_TIMEOUT`]
+    ///
+    /// A lock older than [`STALE_LOCK_AGE`] is taken over.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigLocked`] if the lock is still held after the
+    /// timeout, or an error if the lock file cannot be created.
+    pub fn acquire(config: &Path) -> Result<Self> {
+        Self::acquire_with(config, LOCK_TIMEOUT, STALE_LOCK_AGE)
+    }
+
+    fn acquire_with(config: &Path, timeout: Duration, stale_after: Duration) -> Result<Self> {
+        let path = lock_path(config);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let token = new_token();
+
+        let start = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{token}")?;
+                    let heartbeat = keep_fresh(path.clone(), token.clone(), stale_after / 4);
+                    return Ok(Self {
+                        path,
+                        token,
+                        heartbeat: Some(heartbeat),
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            if lock_age(&path).is_some_and(|age| age >= stale_after)
+                && let Some(stale) = read_token(&path)
+            {
+                remove_stale(&path, &stale, &token);
+                continue;
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::ConfigLocked { path });
+            }
+            thread::sleep(RETRY_INTERVAL);
+        }
+    }
+
+    /// Path of the lock file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        self.heartbeat.take();
+        // A lock taken over after all is left to its new owner
+        if read_token(&self.path).as_deref() == Some(self.token.as_str()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// `config.json` is locked through `config.json.lock`
+fn lock_path(config: &Path) -> PathBuf {
+    let mut name = config.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    config.with_file_name(name)
+}
+
+/// Owner token written into a lock: the pid, the time, and a per-process count
+fn new_token() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let count = LOCKS_TAKEN.fetch_add(1, Ordering::Relaxed);
+    format!("{} {nanos} {count}", std::process::id())
+}
+
+/// Token of the lock at `path`, if it still exists
+fn read_token(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+}
+
+/// Time since the lock file was written or refreshed, if it still exists
+fn lock_age(path: &Path) -> Option<Duration> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    SystemTime::now().duration_since(modified).ok()
+}
+
+/// Remove the lock at `path` if it is still the stale one holding `stale`
+///
+/// The lock is first moved aside, which no other process can do at the
+/// same time, and only deleted once its token proves it is the stale one. A
+/// fresh lock moved aside by mistake is put back, unless a new one was
+/// created in the meantime.
+fn remove_stale(path: &Path, stale: &str, token: &str) {
+    let mut aside = path.as_os_str().to_os_string();
+    aside.push(format!(".{}", token.replace(' ', "-")));
+    let aside = PathBuf::from(aside);
+    if fs::rename(path, &aside).is_err() {
+        return;
+    }
+    if read_token(&aside).as_deref() != Some(stale) {
+        let _ = fs::hard_link(&aside, path);
+    }
+    let _ = fs::remove_file(&aside);
+}
+
+/// Touch the lock every `interval` while it is still ours, until the
+/// returned sender is dropped
+fn keep_fresh(path: PathBuf, token: String, interval: Duration) -> mpsc::Sender<()> {
+    let interval = interval.max(RETRY_INTERVAL);
+    let (stop, stopped) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            if read_token(&path).as_deref() != Some(token.as_str()) {
+                break;
+            }
+            if let Ok(file) = OpenOptions::new().write(true).open(&path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+        }
+    });
+    stop
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive_until_dropped() {
+        let dir = TempDir::new().unwrap();
+        let config = dir.path().join("config.json");
+
+        let lock = ConfigLock::acquire(&config).unwrap();
+        assert_eq!(lock.path(), dir.path().join("config.json.lock"));
+        let busy = ConfigLock::acquire_with(&config, Duration::from_millis(50), STALE_LOCK_AGE);
+        assert!(matches!(busy, Err(Error::ConfigLocked { .. })));
+
+        drop(lock);
+        assert!(!dir.path().join("config.json.lock").exists());
+        assert!(ConfigLock::acquire(&config).is_ok());
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let dir = TempDir::new().unwrap();
+        let config = dir.path().join("config.toml");
+        fs::write(dir.path().join("config.toml.lock"), "12345\n").unwrap();
+
+        let lock = ConfigLock::acquire_with(&config, Duration::from_millis(50), Duration::ZERO);
+        assert!(lock.is_ok());
+    }
+
+    #[test]
+    fn test_held_lock_stays_fresh() {
+        let dir = TempDir::new().unwrap();
+        let config = dir.path().join("config.json");
+        let stale_after = Duration::from_millis(400);
+
+        let _lock = ConfigLock::acquire_with(&config, LOCK_TIMEOUT, stale_after).unwrap();
+        // Long enough to go stale without the heartbeat, like a waiting prompt
+        thread::sleep(stale_after * 2);
+        let busy = ConfigLock::acquire_with(&config, Duration::from_millis(50), stale_after);
+        assert!(matches!(busy, Err(Error::ConfigLocked { .. })));
+    }
+
+    #[test]
+    fn test_drop_leaves_a_lock_taken_over() {
+        let dir = TempDir::new().unwrap();
+        let config = dir.path().join("config.json");
+
+        let lock = ConfigLock::acquire(&config).unwrap();
+        fs::write(lock.path(), "999 1 0\n").unwrap();
+        let path = lock.path().to_path_buf();
+        drop(lock);
+        assert_eq!(read_token(&path).as_deref(), Some("999 1 0"));
+    }
+
+    #[test]
+    fn test_remove_stale_keeps_a_newer_lock() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json.lock");
+
+        // Another process replaced the stale lock before this one got to it
+        fs::write(&path, "2 2 0\n").unwrap();
+        remove_stale(&path, "1 1 0", "3 3 0");
+        assert_eq!(read_token(&path).as_deref(), Some("2 2 0"));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        remove_stale(&path, "2 2 0", "3 3 0");
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -1,9 +1,12 @@
 //! Configuration module for kodo
 
 pub mod loader;
+pub mod lock;
 pub mod schema;
 
 pub use loader::{
-    default_config_path, default_config_path_for_save, expand_tilde, load_config, save_config,
+    default_config_path, default_config_path_for_save, expand_tilde, load_config,
+    load_config_for_update, save_config,
 };
-pub use schema::{Config, Defaults, RepoConfig};
+pub use lock::ConfigLock;
+pub use schema::{Config, Defaults, RepoConfig, ThemeColors};
@@ -1,10 +1,14 @@
 //! Configuration schema definitions
 
+use crate::cli::args::{OutputFormat, Period, ThemeName};
+use crate::git::IgnoreWhitespace;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 
 /// Root configuration structure
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Config {
     /// JSON Schema reference (for IDE support)
     #[serde(rename = "$schema")]
@@ -19,7 +23,7 @@ pub struct Config {
 }
 
 /// Single repository configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct RepoConfig {
     /// Display name
     pub name: String,
@@ -29,10 +33,34 @@ pub struct RepoConfig {
 
     /// Default branch to analyze
     pub branch: Option<String>,
+
+    /// Analyze commits from every local branch instead of a single branch
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_branches: bool,
+
+    /// Groups this repository belongs to, for selecting it with `--group`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+
+    /// File extensions to count when `--ext` is not given (empty counts all files)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ext: Vec<String>,
+
+    /// Include merge commits when `--include-merges` is not given
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_merges: bool,
+
+    /// Follow only the first parent of merge commits, as with `--first-parent`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub first_parent: bool,
+
+    /// Timezone for bucketing this repository's commits (`local`, `utc`, or IANA name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
 }
 
 /// Default settings
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Defaults {
     /// Number of days to analyze
     #[serde(default = "default_days")]
@@ -41,6 +69,64 @@ pub struct Defaults {
     /// Exclude merge commits
     #[serde(default = "default_true")]
     pub exclude_merges: bool,
+
+    /// Aggregation period
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<Period>,
+
+    /// Output format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+
+    /// Timezone for aggregation (`local`, `utc`, or IANA name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// TUI color theme preset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeName>,
+
+    /// TUI colors overriding the theme preset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colors: Option<ThemeColors>,
+
+    /// Directory TUI exports are written to (supports ~; default: current directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_dir: Option<PathBuf>,
+
+    /// Whitespace changes left out of line counts (`off`, `eol`, or `all`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_whitespace: Option<IgnoreWhitespace>,
+
+    /// Milliseconds between TUI ticks (default: 250)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick_ms: Option<NonZeroU64>,
+
+    /// TUI keys per action name (like `scroll_down`), checked before the built-in keys
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keybindings: BTreeMap<String, Vec<String>>,
+}
+
+/// Individual TUI color overrides (color names, `#rrggbb`, or 0-255)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeColors {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deletion: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bar_primary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bar_secondary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_dim: Option<String>,
 }
 
 const fn default_days() -> u32 {
@@ -56,6 +142,15 @@ impl Default for Defaults {
         Self {
             days: default_days(),
             exclude_merges: default_true(),
+            period: None,
+            output: None,
+            timezone: None,
+            theme: None,
+            colors: None,
+            export_dir: None,
+            ignore_whitespace: None,
+            tick_ms: None,
+            keybindings: BTreeMap::new(),
         }
     }
 }
@@ -87,9 +182,25 @@ mod tests {
         );
         assert_eq!(config.repositories.len(), 1);
         assert_eq!(config.repositories[0].name, "test-repo");
+        assert!(config.repositories[0].ext.is_empty());
+        assert!(!config.repositories[0].include_merges);
         assert_eq!(config.defaults.days, 7);
     }
 
+    #[test]
+    fn test_repo_ext_and_include_merges_round_trip() {
+        let json =
+            r#"{"name": "docs", "path": "/docs", "ext": ["md", "rst"], "include_merges": true}"#;
+        let repo: RepoConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(repo.ext, ["md", "rst"]);
+        assert!(repo.include_merges);
+
+        let plain: RepoConfig = serde_json::from_str(r#"{"name": "a", "path": "/a"}"#).unwrap();
+        let serialized = serde_json::to_string(&plain).unwrap();
+        assert!(!serialized.contains("ext"), "{serialized}");
+        assert!(!serialized.contains("include_merges"), "{serialized}");
+    }
+
     #[test]
     fn test_config_with_defaults() {
         let json = r#"{
@@ -105,6 +216,74 @@ mod tests {
         let config: Config = serde_json::from_str(json).unwrap();
         assert_eq!(config.defaults.days, 30);
         assert!(!config.defaults.exclude_merges);
+        assert!(config.defaults.period.is_none());
+    }
+
+    #[test]
+    fn test_config_with_extended_defaults() {
+        let json = r#"{
+            "repositories": [
+                {"name": "repo", "path": "/path"}
+            ],
+            "defaults": {
+                "period": "weekly",
+                "output": "json",
+                "timezone": "Asia/Tokyo"
+            }
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.defaults.days, 7);
+        assert_eq!(config.defaults.period, Some(Period::Weekly));
+        assert_eq!(config.defaults.output, Some(OutputFormat::Json));
+        assert_eq!(config.defaults.timezone.as_deref(), Some("Asia/Tokyo"));
+    }
+
+    #[test]
+    fn test_config_with_theme() {
+        let json = r#"{
+            "repositories": [],
+            "defaults": {
+                "theme": "colorblind",
+                "colors": {"addition": "lightblue", "text_dim": "244"}
+            }
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.defaults.theme, Some(ThemeName::Colorblind));
+        let colors = config.defaults.colors.unwrap();
+        assert_eq!(colors.addition.as_deref(), Some("lightblue"));
+        assert_eq!(colors.text_dim.as_deref(), Some("244"));
+        assert!(colors.header.is_none());
+        assert!(config.defaults.export_dir.is_none());
+
+        let typo = r#"{"repositories": [], "defaults": {"colors": {"adition": "red"}}}"#;
+        assert!(serde_json::from_str::<Config>(typo).is_err());
+    }
+
+    #[test]
+    fn test_config_with_keybindings() {
+        let json = r#"{
+            "repositories": [],
+            "defaults": {"keybindings": {"scroll_down": ["n", "ctrl+n"]}}
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.defaults.keybindings["scroll_down"], ["n", "ctrl+n"]);
+
+        // An empty map is left out when saving
+        let serialized = serde_json::to_string(&Defaults::default()).unwrap();
+        assert!(!serialized.contains("keybindings"), "{serialized}");
+    }
+
+    #[test]
+    fn test_config_with_tick_ms() {
+        let json = r#"{"repositories": [], "defaults": {"tick_ms": 1000}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.defaults.tick_ms.map(NonZeroU64::get), Some(1000));
+
+        let json = r#"{"repositories": [], "defaults": {"tick_ms": 0}}"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
     }
 
     #[test]
@@ -112,5 +291,14 @@ mod tests {
         let json = r#"{"name": "repo", "path": "/path", "branch": "main"}"#;
         let repo: RepoConfig = serde_json::from_str(json).unwrap();
         assert_eq!(repo.branch, Some("main".to_string()));
+        assert!(!repo.all_branches);
+    }
+
+    #[test]
+    fn test_repo_config_all_branches() {
+        let json = r#"{"name": "repo", "path": "/path", "all_branches": true}"#;
+        let repo: RepoConfig = serde_json::from_str(json).unwrap();
+        assert!(repo.all_branches);
+        assert!(repo.branch.is_none());
     }
 }
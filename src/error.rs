@@ -1,5 +1,6 @@
 //! Error types for kodo
 
+use chrono::NaiveDate;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -10,6 +11,16 @@ pub enum Error {
     #[error("Configuration file not found: {path}")]
     ConfigNotFound { path: PathBuf },
 
+    /// Configuration file already exists and would be overwritten
+    #[error("Configuration file already exists: {path} (use --force to overwrite)")]
+    ConfigExists { path: PathBuf },
+
+    /// Another kodo process holds the configuration file lock
+    #[error(
+        "Configuration file is being updated by another kodo process: {path} (delete it if no kodo is running)"
+    )]
+    ConfigLocked { path: PathBuf },
+
     /// Configuration file is invalid or malformed
     #[error("Invalid configuration: {message}")]
     ConfigInvalid { message: String },
@@ -34,13 +45,123 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// TOML config parsing error
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// TOML config serialization error
+    #[error("TOML error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    /// Requested branch has no local or remote-tracking ref
+    #[error("Branch '{branch}' not found in repository {repo}")]
+    BranchNotFound { branch: String, repo: String },
+
+    /// A single branch and all-branches mode were both requested
+    #[error("Repository {repo}: branch '{branch}' cannot be combined with all-branches mode")]
+    ConflictingBranchOptions { branch: String, repo: String },
+
+    /// Analysis of a single repository failed
+    #[error("{repo}: {source}")]
+    RepoFailed {
+        repo: String,
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Path filter pattern is not a valid glob
+    #[error("Invalid glob pattern '{pattern}': {message}")]
+    InvalidGlob { pattern: String, message: String },
+
     /// No repositories configured or specified
     #[error("No repositories to analyze")]
     NoRepositories,
 
+    /// Date range end is before its start
+    #[error("Invalid date range: --to {to} is before --from {from}")]
+    InvalidDateRange { from: NaiveDate, to: NaiveDate },
+
+    /// `--complete-periods` left no whole period in the range
+    #[error(
+        "No complete {period} period between {from} and {to}; widen the range or drop --complete-periods"
+    )]
+    NoCompletePeriod {
+        period: String,
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+
+    /// `--yoy` was combined with a period it cannot align by
+    #[error("--yoy needs --period weekly or monthly, not {period}")]
+    YoyPeriod { period: String },
+
+    /// One or more `doctor` checks failed
+    #[error("{failed} doctor check(s) failed")]
+    DoctorFailed { failed: usize },
+
+    /// Input file has a missing or unsupported `schema_version`
+    #[error("Unsupported input schema: {message}")]
+    UnsupportedSchema { message: String },
+
     /// Repository not found in configuration
-    #[error("Repository not found in config: {identifier}")]
-    RepoNotInConfig { identifier: String },
+    #[error("Repository not found in config: {identifier}{}", did_you_mean(.suggestions))]
+    RepoNotInConfig {
+        identifier: String,
+        /// Registered names close to `identifier`
+        suggestions: Vec<String>,
+    },
+
+    /// `--fail-if-empty` was given and no commits were counted
+    #[error("No commits between {from} and {to}")]
+    NoCommitsInRange { from: NaiveDate, to: NaiveDate },
+
+    /// The user quit the TUI before its data finished loading
+    #[error("Loading cancelled")]
+    Cancelled,
+}
+
+impl Error {
+    /// Process exit code for this error
+    ///
+    /// `2` is also what clap uses for command-line usage errors.
+    #[must_use]
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::InvalidGlob { .. }
+            | Self::InvalidDateRange { .. }
+            | Self::NoCompletePeriod { .. }
+            | Self::YoyPeriod { .. }
+            | Self::ConflictingBranchOptions { .. } => 2,
+            Self::ConfigNotFound { .. }
+            | Self::ConfigExists { .. }
+            | Self::ConfigLocked { .. }
+            | Self::ConfigInvalid { .. } => 3,
+            Self::RepoNotFound { .. }
+            | Self::NotGitRepo { .. }
+            | Self::Git(_)
+            | Self::BranchNotFound { .. }
+            | Self::RepoFailed { .. }
+            | Self::NoRepositories
+            | Self::RepoNotInConfig { .. } => 4,
+            Self::NoCommitsInRange { .. } => 5,
+            Self::Io(_)
+            | Self::Json(_)
+            | Self::Toml(_)
+            | Self::TomlSerialize(_)
+            | Self::DoctorFailed { .. }
+            | Self::UnsupportedSchema { .. }
+            | Self::Cancelled => 1,
+        }
+    }
+}
+
+/// ` (did you mean: a, b?)` after a name that matched nothing, or nothing without suggestions
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(" (did you mean: {}?)", suggestions.join(", "))
+    }
 }
 
 /// Result type alias using our Error type
@@ -64,6 +185,15 @@ mod tests {
         assert_eq!(err.to_string(), "No repositories to analyze");
     }
 
+    #[test]
+    fn test_error_branch_not_found() {
+        let err = Error::BranchNotFound {
+            branch: "dev".to_string(),
+            repo: "kodo".to_string(),
+        };
+        assert_eq!(err.to_string(), "Branch 'dev' not found in repository kodo");
+    }
+
     #[test]
     fn test_error_not_git_repo() {
         let err = Error::NotGitRepo {
@@ -71,4 +201,33 @@ mod tests {
         };
         assert!(err.to_string().contains("Not a git repository"));
     }
+
+    #[test]
+    fn test_exit_codes_by_category() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let config = Error::ConfigInvalid {
+            message: "bad".to_string(),
+        };
+        let repo = Error::RepoFailed {
+            repo: "kodo".to_string(),
+            source: Box::new(Error::NoRepositories),
+        };
+        let empty = Error::NoCommitsInRange { from: day, to: day };
+        let usage = Error::InvalidDateRange { from: day, to: day };
+        let other = Error::DoctorFailed { failed: 1 };
+
+        assert_eq!(config.exit_code(), 3);
+        let locked = Error::ConfigLocked {
+            path: PathBuf::from("config.json.lock"),
+        };
+        assert_eq!(locked.exit_code(), 3);
+        assert_eq!(repo.exit_code(), 4);
+        assert_eq!(empty.exit_code(), 5);
+        assert_eq!(usage.exit_code(), 2);
+        assert_eq!(other.exit_code(), 1);
+        assert_eq!(
+            empty.to_string(),
+            "No commits between 2024-03-01 and 2024-03-01"
+        );
+    }
 }
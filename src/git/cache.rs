@@ -0,0 +1,312 @@
+//! Persistent per-commit diff cache
+//!
+//! Diff statistics for a commit never change, so they are stored on disk keyed
+//! by commit oid and reused across runs. Each repository gets its own cache
+//! file under the kodo cache directory, one per rename detection and whitespace
+//! setting since both change the recorded line counts.
+
+use crate::error::Result;
+use crate::git::{DiffStats, FileChange, IgnoreWhitespace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Cache format version; bump when the on-disk layout or what an entry
+/// records changes
+///
+/// Files written with a different version are discarded on load. Version 3
+/// lists binary and mode-only changes among the per-file changes; version 4
+/// moved whitespace-ignoring diffs out into files of their own.
+pub const CACHE_VERSION: u32 = 4;
+
+/// Cached diff statistics for a single commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedDiff {
+    additions: u64,
+    deletions: u64,
+    files_changed: u32,
+    /// Per-file changes as `(path, additions, deletions)`; `None` when only totals were computed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<(String, u64, u64)>>,
+    /// Changed paths of a totals-only entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    paths: Vec<String>,
+}
+
+/// On-disk cache file layout
+#[derive(Debug, Default, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: HashMap<String, CachedDiff>,
+}
+
+/// Borrowed form of [`CacheFile`] used when writing
+#[derive(Serialize)]
+struct CacheFileRef<'a> {
+    version: u32,
+    entries: &'a HashMap<String, CachedDiff>,
+}
+
+/// Per-repository diff cache backed by a JSON file
+#[derive(Debug)]
+pub struct DiffCache {
+    path: PathBuf,
+    entries: HashMap<String, CachedDiff>,
+    dirty: bool,
+}
+
+impl DiffCache {
+    /// Open the cache stored at `path`
+    ///
+    /// A missing, unreadable, or outdated cache file yields an empty cache, so a
+    /// broken cache never prevents analysis.
+    #[must_use]
+    pub fn open(path: PathBuf) -> Self {
+        let entries = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == CACHE_VERSION)
+            .map(|file| file.entries)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            entries,
+            dirty: false,
+        }
+    }
+
+    /// Open the cache for the repository at `repo_path` in the default cache directory
+    ///
+    /// `rename_threshold` must match the repository's rename detection setting
+    /// and `ignore_whitespace` the whitespace setting commits are collected with.
+    #[must_use]
+    pub fn for_repo(
+        repo_path: &Path,
+        rename_threshold: Option<u16>,
+        ignore_whitespace: IgnoreWhitespace,
+    ) -> Option<Self> {
+        default_cache_dir().map(|dir| {
+            Self::open(dir.join(cache_file_name(
+                repo_path,
+                rename_threshold,
+                ignore_whitespace,
+            )))
+        })
+    }
+
+    /// Look up cached stats for a commit
+    ///
+    /// When `need_file_details` is set, entries that only hold totals are treated as misses.
+    #[must_use]
+    pub fn get(&self, oid: &str, need_file_details: bool) -> Option<DiffStats> {
+        let cached = self.entries.get(oid)?;
+        match (&cached.files, need_file_details) {
+            (Some(files), _) => {
+                let mut stats = DiffStats::default();
+                for (path, additions, deletions) in files {
+                    stats.add_file(FileChange::new(path.clone(), *additions, *deletions));
+                }
+                Some(stats)
+            }
+            (None, false) => Some(
+                DiffStats::new(cached.additions, cached.deletions, cached.files_changed)
+                    .with_paths(cached.paths.clone()),
+            ),
+            (None, true) => None,
+        }
+    }
+
+    /// Store stats for a commit
+    ///
+    /// `has_file_details` tells whether `stats.files` holds the full per-file list.
+    pub fn insert(&mut self, oid: String, stats: &DiffStats, has_file_details: bool) {
+        let files = has_file_details.then(|| {
+            stats
+                .files
+                .iter()
+                .map(|f| (f.path.clone(), f.additions, f.deletions))
+                .collect()
+        });
+        let paths = if has_file_details {
+            Vec::new()
+        } else {
+            stats.paths.clone()
+        };
+        self.entries.insert(
+            oid,
+            CachedDiff {
+                additions: stats.additions,
+                deletions: stats.deletions,
+                files_changed: stats.files_changed,
+                files,
+                paths,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Number of cached commits
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no commits
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the cache to disk if it changed since it was opened
+    ///
+    /// The file is written to a temporary path and renamed into place so an
+    /// interrupted write never leaves a truncated cache behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory or file cannot be written
+    pub fn save(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_vec(&CacheFileRef {
+            version: CACHE_VERSION,
+            entries: &self.entries,
+        })?;
+
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Get the kodo cache directory
+///
+/// Uses `$XDG_CACHE_HOME/kodo` when set, otherwise `~/.cache/kodo`.
+#[must_use]
+pub fn default_cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cache")))
+        .map(|dir| dir.join("kodo"))
+}
+
+/// Cache file name for a repository, derived from its canonical path and diff settings
+fn cache_file_name(
+    repo_path: &Path,
+    rename_threshold: Option<u16>,
+    ignore_whitespace: IgnoreWhitespace,
+) -> String {
+    let canonical = repo_path
+        .canonicalize()
+        .unwrap_or_else(|_| repo_path.to_path_buf());
+    let renames = rename_threshold.map_or_else(|| "norenames".to_string(), |t| format!("r{t}"));
+    format!(
+        "{:016x}-{renames}{}.json",
+        fnv1a(canonical.to_string_lossy().as_bytes()),
+        ignore_whitespace.cache_file_suffix()
+    )
+}
+
+/// 64-bit FNV-1a hash (stable across Rust versions, unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn detailed_stats() -> DiffStats {
+        let mut stats = DiffStats::default();
+        stats.add_file(FileChange::new("src/main.rs".to_string(), 10, 2));
+        stats.add_file(FileChange::new("README.md".to_string(), 3, 0));
+        stats
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let mut cache = DiffCache::open(path.clone());
+        assert!(cache.is_empty());
+        cache.insert("abc".to_string(), &detailed_stats(), true);
+        let totals = DiffStats::new(5, 1, 1).with_paths(vec!["src/lib.rs".to_string()]);
+        cache.insert("def".to_string(), &totals, false);
+        cache.save().unwrap();
+
+        let cache = DiffCache::open(path);
+        assert_eq!(cache.len(), 2);
+
+        let stats = cache.get("abc", true).unwrap();
+        assert_eq!(stats.additions, 13);
+        assert_eq!(stats.files.len(), 2);
+        assert_eq!(stats.files[0].path, "src/main.rs");
+
+        // Totals-only entries satisfy totals lookups but not detailed ones
+        let totals = cache.get("def", false).unwrap();
+        assert_eq!(totals.additions, 5);
+        assert_eq!(totals.paths, ["src/lib.rs"]);
+        assert!(cache.get("def", true).is_none());
+        assert!(cache.get("missing", false).is_none());
+    }
+
+    #[test]
+    fn test_cache_discards_other_versions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+        std::fs::write(
+            &path,
+            r#"{"version": 0, "entries": {"abc": {"additions": 1, "deletions": 0, "files_changed": 1}}}"#,
+        )
+        .unwrap();
+
+        assert!(DiffCache::open(path).is_empty());
+    }
+
+    #[test]
+    fn test_cache_ignores_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(DiffCache::open(path).is_empty());
+    }
+
+    #[test]
+    fn test_cache_file_name_is_stable() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let dir = TempDir::new().unwrap();
+        let name = |renames, whitespace| cache_file_name(dir.path(), renames, whitespace);
+        assert_eq!(
+            name(Some(50), IgnoreWhitespace::Off),
+            name(Some(50), IgnoreWhitespace::Off)
+        );
+        // Rename detection and ignored whitespace change line counts, so each
+        // combination of settings gets its own file
+        assert_ne!(
+            name(Some(50), IgnoreWhitespace::Off),
+            name(None, IgnoreWhitespace::Off)
+        );
+        assert!(name(None, IgnoreWhitespace::Off).ends_with("-norenames.json"));
+        assert!(name(Some(50), IgnoreWhitespace::Eol).ends_with("-r50-ws-eol.json"));
+        assert!(name(None, IgnoreWhitespace::All).ends_with("-norenames-ws-all.json"));
+    }
+}
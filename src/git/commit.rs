@@ -3,6 +3,9 @@
 use crate::git::DiffStats;
 use chrono::{DateTime, Utc};
 
+/// Maximum length of a stored commit summary, in characters
+pub const MAX_SUMMARY_LEN: usize = 80;
+
 /// Extracted commit information
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
@@ -15,6 +18,9 @@ pub struct CommitInfo {
     /// Is this a merge commit?
     pub is_merge: bool,
 
+    /// First line of the commit message, truncated to [`MAX_SUMMARY_LEN`] characters
+    pub summary: String,
+
     /// Diff statistics for this commit
     pub diff: DiffStats,
 }
@@ -27,10 +33,18 @@ impl CommitInfo {
             id,
             timestamp,
             is_merge,
+            summary: String::new(),
             diff,
         }
     }
 
+    /// Set the commit summary, truncating it to [`MAX_SUMMARY_LEN`] characters
+    #[must_use]
+    pub fn with_summary(mut self, summary: &str) -> Self {
+        self.summary = truncate_summary(summary);
+        self
+    }
+
     /// Get the date portion of the timestamp (UTC)
     #[must_use]
     pub fn date(&self) -> chrono::NaiveDate {
@@ -38,6 +52,17 @@ impl CommitInfo {
     }
 }
 
+/// Truncate a summary to [`MAX_SUMMARY_LEN`] characters, marking the cut with an ellipsis
+fn truncate_summary(summary: &str) -> String {
+    let summary = summary.trim();
+    if summary.chars().count() <= MAX_SUMMARY_LEN {
+        return summary.to_string();
+    }
+    let mut truncated: String = summary.chars().take(MAX_SUMMARY_LEN - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,4 +88,20 @@ mod tests {
 
         assert!(commit.is_merge);
     }
+
+    #[test]
+    fn test_commit_info_summary_truncated() {
+        let commit = CommitInfo::new(
+            "abc1234".to_string(),
+            Utc::now(),
+            false,
+            DiffStats::default(),
+        );
+        assert_eq!(commit.clone().with_summary("Fix bug").summary, "Fix bug");
+
+        let long = "é".repeat(100);
+        let summary = commit.with_summary(&long).summary;
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_LEN);
+        assert!(summary.ends_with('…'));
+    }
 }
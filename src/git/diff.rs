@@ -14,6 +14,9 @@ pub struct DiffStats {
 
     /// Per-file changes
     pub files: Vec<FileChange>,
+
+    /// Changed paths when only totals were computed and `files` is empty
+    pub paths: Vec<String>,
 }
 
 impl DiffStats {
@@ -25,9 +28,25 @@ impl DiffStats {
             deletions,
             files_changed,
             files: Vec::new(),
+            paths: Vec::new(),
         }
     }
 
+    /// Set the changed paths of totals-only stats
+    #[must_use]
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Paths of the changed files, from `files` or, for totals-only stats, `paths`
+    pub fn changed_paths(&self) -> impl Iterator<Item = &str> {
+        self.files
+            .iter()
+            .map(|file| file.path.as_str())
+            .chain(self.paths.iter().map(String::as_str))
+    }
+
     /// Calculate net line change (additions - deletions)
     #[must_use]
     // Line counts will never exceed i64::MAX in practice
@@ -69,6 +88,16 @@ impl FileChange {
         }
     }
 
+    /// Get the file extension as reported by `Path::extension`
+    ///
+    /// Dotfiles such as `.gitignore` have no extension.
+    #[must_use]
+    pub fn extension(&self) -> Option<&str> {
+        std::path::Path::new(&self.path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+    }
+
     /// Check if file matches any of the given extensions
     #[must_use]
     pub fn matches_extensions(&self, extensions: &[String]) -> bool {
@@ -76,9 +105,7 @@ impl FileChange {
             return true;
         }
 
-        let path = std::path::Path::new(&self.path);
-        path.extension()
-            .and_then(|ext| ext.to_str())
+        self.extension()
             .is_some_and(|ext| extensions.iter().any(|e| e == ext))
     }
 }
@@ -111,6 +138,19 @@ mod tests {
         assert_eq!(stats.files.len(), 2);
     }
 
+    #[test]
+    fn test_diff_stats_changed_paths() {
+        let mut detailed = DiffStats::default();
+        detailed.add_file(FileChange::new("src/main.rs".to_string(), 10, 5));
+        assert_eq!(
+            detailed.changed_paths().collect::<Vec<_>>(),
+            ["src/main.rs"]
+        );
+
+        let totals = DiffStats::new(3, 1, 2).with_paths(vec!["a.rs".into(), "b.rs".into()]);
+        assert_eq!(totals.changed_paths().collect::<Vec<_>>(), ["a.rs", "b.rs"]);
+    }
+
     #[test]
     fn test_file_change_matches_extensions() {
         let file = FileChange::new("src/main.rs".to_string(), 10, 5);
@@ -120,6 +160,18 @@ mod tests {
         assert!(file.matches_extensions(&[])); // Empty = match all
     }
 
+    #[test]
+    fn test_file_change_extension() {
+        let file = FileChange::new("src/main.rs".to_string(), 1, 0);
+        assert_eq!(file.extension(), Some("rs"));
+
+        let dotfile = FileChange::new(".gitignore".to_string(), 1, 0);
+        assert_eq!(dotfile.extension(), None);
+
+        let nested_dotfile = FileChange::new("config/.env.local".to_string(), 1, 0);
+        assert_eq!(nested_dotfile.extension(), Some("local"));
+    }
+
     #[test]
     fn test_file_change_no_extension() {
         let file = FileChange::new("Makefile".to_string(), 10, 5);
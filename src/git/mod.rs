@@ -1,9 +1,15 @@
 //! Git repository interface module
 
+pub mod cache;
 pub mod commit;
 pub mod diff;
+pub mod options;
 pub mod repository;
 
+pub use cache::DiffCache;
 pub use commit::CommitInfo;
 pub use diff::{DiffStats, FileChange};
-pub use repository::Repository;
+pub use options::{CollectOptions, CollectProgress, IgnoreWhitespace};
+pub use repository::{
+    DEFAULT_RENAME_THRESHOLD, Repository, discover_repository, is_git_repository,
+};
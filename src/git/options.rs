@@ -0,0 +1,264 @@
+//! Options for collecting commits from a repository
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Whitespace changes left out of line counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoreWhitespace {
+    /// Count every changed line
+    #[default]
+    Off,
+    /// Ignore whitespace at the end of lines, like `git diff --ignore-space-at-eol`
+    Eol,
+    /// Ignore all whitespace, like `git diff -w`
+    All,
+}
+
+impl IgnoreWhitespace {
+    /// Cache file name suffix keeping diffs of different whitespace settings apart
+    pub(crate) const fn cache_file_suffix(self) -> &'static str {
+        match self {
+            Self::Off => "",
+            Self::Eol => "-ws-eol",
+            Self::All => "-ws-all",
+        }
+    }
+}
+
+/// Which commits [`Repository::collect_commits`](crate::git::Repository::collect_commits)
+/// collects and how it diffs them
+///
+/// Built with [`CollectOptions::new`] and the `with_*` methods. Everything
+/// but the date range is off by default: HEAD only, every parent of merges
+/// walked, merges included but not diffed, totals without per-file changes, every whitespace change counted,
+/// and no limit on commits or time.
+// Independent switches, each set by its own builder method
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectOptions {
+    from: NaiveDate,
+    to: NaiveDate,
+    branch: Option<String>,
+    all_branches: bool,
+    first_parent: bool,
+    exclude_merges: bool,
+    merge_diffs: bool,
+    need_file_details: bool,
+    ignore_whitespace: IgnoreWhitespace,
+    max_commits: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl CollectOptions {
+    /// Collect the commits dated from `from` to `to`, both inclusive
+    #[must_use]
+    pub const fn new(from: NaiveDate, to: NaiveDate) -> Self {
+        Self {
+            from,
+            to,
+            branch: None,
+            all_branches: false,
+            first_parent: false,
+            exclude_merges: false,
+            merge_diffs: false,
+            need_file_details: false,
+            ignore_whitespace: IgnoreWhitespace::Off,
+            max_commits: None,
+            deadline: None,
+        }
+    }
+
+    /// Start from a branch name or fully-qualified ref instead of HEAD
+    ///
+    /// See [`Repository::resolve_branch`](crate::git::Repository::resolve_branch).
+    #[must_use]
+    pub fn with_branch(mut self, branch: Option<&str>) -> Self {
+        self.branch = branch.map(str::to_string);
+        self
+    }
+
+    /// Walk every local branch instead of a single starting point
+    #[must_use]
+    pub const fn with_all_branches(mut self, all_branches: bool) -> Self {
+        self.all_branches = all_branches;
+        self
+    }
+
+    /// Walk only the first parent of each merge commit (mainline history)
+    ///
+    /// Commits brought in by a merge are then skipped; the merge commit itself
+    /// is diffed against its first parent, so it carries the merged changes.
+    #[must_use]
+    pub const fn with_first_parent(mut self, first_parent: bool) -> Self {
+        self.first_parent = first_parent;
+        self
+    }
+
+    /// Skip merge commits
+    #[must_use]
+    pub const fn with_exclude_merges(mut self, exclude_merges: bool) -> Self {
+        self.exclude_merges = exclude_merges;
+        self
+    }
+
+    /// Diff merge commits against their first parent
+    ///
+    /// Such a diff repeats the lines of every commit the merge brought in, so
+    /// by default merges are kept with an empty diff: they count as merges but
+    /// add no lines or files. Walking only first parents diffs them anyway,
+    /// since the merged commits are skipped then.
+    #[must_use]
+    pub const fn with_merge_diffs(mut self, merge_diffs: bool) -> Self {
+        self.merge_diffs = merge_diffs;
+        self
+    }
+
+    /// Compute per-file changes (e.g. for extension filtering)
+    ///
+    /// Without them, only totals are computed using git2's built-in diff
+    /// stats, which is much faster.
+    #[must_use]
+    pub const fn with_file_details(mut self, need_file_details: bool) -> Self {
+        self.need_file_details = need_file_details;
+        self
+    }
+
+    /// Leave whitespace changes out of line counts
+    #[must_use]
+    pub const fn with_ignore_whitespace(mut self, ignore_whitespace: IgnoreWhitespace) -> Self {
+        self.ignore_whitespace = ignore_whitespace;
+        self
+    }
+
+    /// Keep only the newest `max_commits` commits in range
+    ///
+    /// Older commits are dropped before any diffing, so a huge range costs
+    /// little more than walking its history.
+    #[must_use]
+    pub const fn with_max_commits(mut self, max_commits: Option<usize>) -> Self {
+        self.max_commits = max_commits;
+        self
+    }
+
+    /// Stop collecting once `deadline` has passed, keeping the commits diffed so far
+    #[must_use]
+    pub const fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Start date (inclusive)
+    #[must_use]
+    pub const fn from(&self) -> NaiveDate {
+        self.from
+    }
+
+    /// End date (inclusive)
+    #[must_use]
+    pub const fn to(&self) -> NaiveDate {
+        self.to
+    }
+
+    /// Branch to start from (`None` for HEAD)
+    #[must_use]
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    /// Whether every local branch is walked
+    #[must_use]
+    pub const fn all_branches(&self) -> bool {
+        self.all_branches
+    }
+
+    /// Whether only first parents of merges are walked
+    #[must_use]
+    pub const fn first_parent(&self) -> bool {
+        self.first_parent
+    }
+
+    /// Whether merge commits are skipped
+    #[must_use]
+    pub const fn exclude_merges(&self) -> bool {
+        self.exclude_merges
+    }
+
+    /// Whether merge commits are diffed
+    #[must_use]
+    pub const fn merge_diffs(&self) -> bool {
+        self.merge_diffs
+    }
+
+    /// Whether per-file changes are computed
+    #[must_use]
+    pub const fn need_file_details(&self) -> bool {
+        self.need_file_details
+    }
+
+    /// Whitespace changes left out of line counts
+    #[must_use]
+    pub const fn ignore_whitespace(&self) -> IgnoreWhitespace {
+        self.ignore_whitespace
+    }
+
+    /// Most commits kept (`None` for all)
+    #[must_use]
+    pub const fn max_commits(&self) -> Option<usize> {
+        self.max_commits
+    }
+
+    /// When collection stops early (`None` for never)
+    #[must_use]
+    pub const fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+/// How far [`Repository::collect_commits_with_progress`](crate::git::Repository::collect_commits_with_progress)
+/// has come
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollectProgress {
+    /// Commits walked so far, including those outside the date range
+    pub commits_scanned: usize,
+    /// Commit diffs computed so far (cache hits are not counted)
+    pub diffs_computed: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_options_builder() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let options = CollectOptions::new(from, to);
+
+        assert_eq!((options.from(), options.to()), (from, to));
+        assert_eq!(options.branch(), None);
+        assert!(!options.all_branches());
+        assert!(!options.exclude_merges());
+        assert!(!options.merge_diffs());
+        assert!(!options.need_file_details());
+        assert_eq!(options.ignore_whitespace(), IgnoreWhitespace::Off);
+        assert_eq!(options.max_commits(), None);
+        assert_eq!(options.deadline(), None);
+
+        let options = options
+            .with_branch(Some("main"))
+            .with_exclude_merges(true)
+            .with_merge_diffs(true)
+            .with_file_details(true)
+            .with_ignore_whitespace(IgnoreWhitespace::All)
+            .with_max_commits(Some(10));
+        assert_eq!(options.branch(), Some("main"));
+        assert!(options.exclude_merges());
+        assert!(options.merge_diffs());
+        assert!(options.need_file_details());
+        assert_eq!(options.ignore_whitespace(), IgnoreWhitespace::All);
+        assert_eq!(options.max_commits(), Some(10));
+    }
+}
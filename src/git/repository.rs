@@ -4,15 +4,77 @@
 
 use crate::config::expand_tilde;
 use crate::error::{Error, Result};
-use crate::git::{CommitInfo, DiffStats, FileChange};
+use crate::git::{
+    CollectOptions, CollectProgress, CommitInfo, DiffCache, DiffStats, FileChange, IgnoreWhitespace,
+};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
-use git2::{DiffOptions, Repository as Git2Repository};
-use std::path::Path;
+use git2::{
+    DiffFindOptions, DiffOptions, ErrorCode, Repository as Git2Repository, RepositoryOpenFlags,
+};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+/// Default similarity (percent) at which a delete + add pair counts as a rename
+pub const DEFAULT_RENAME_THRESHOLD: u16 = 50;
+
+/// Consecutive commits older than the range start seen before the walk stops
+///
+/// Rebased and cherry-picked histories contain commits dated before their
+/// children. Stopping at the first older commit is only correct while the
+/// time-sorted walk orders all of history; tolerating a run of older commits
+/// keeps in-range commits behind a stale-dated one counted either way.
+pub const STALE_COMMIT_TOLERANCE: usize = 100;
+
+/// Open the repository at exactly `path`, without searching parent directories
+///
+/// `path` may be a working tree whose `.git` is a directory or a `gitdir:`
+/// file (linked worktrees, submodules), or a bare repository or git directory.
+fn open_exact(path: &Path) -> std::result::Result<Git2Repository, git2::Error> {
+    Git2Repository::open_ext(
+        path,
+        RepositoryOpenFlags::NO_SEARCH,
+        std::iter::empty::<&OsStr>(),
+    )
+}
+
+/// Whether `path` itself is a git repository, as accepted by [`Repository::open`]
+///
+/// A plain directory inside a repository does not count.
+#[must_use]
+pub fn is_git_repository(path: &Path) -> bool {
+    open_exact(path).is_ok()
+}
+
+/// Root of the repository containing `path`, searching parent directories
+///
+/// The root is the working tree, or the git directory of a bare repository.
+#[must_use]
+pub fn discover_repository(path: &Path) -> Option<PathBuf> {
+    let repo = Git2Repository::discover(path).ok()?;
+    let root = repo.workdir().unwrap_or_else(|| repo.path());
+    // git2 reports directories with a trailing separator
+    Some(root.components().collect())
+}
+
+/// Commits walked or diffed between two progress reports
+pub const PROGRESS_INTERVAL: usize = 100;
 
 /// Wrapper around `git2::Repository` with convenience methods
 pub struct Repository {
     inner: Git2Repository,
     name: String,
+    cache: Option<Mutex<DiffCache>>,
+    /// Similarity threshold for rename detection; `None` disables it
+    rename_threshold: Option<u16>,
+    /// Number of commit diffs computed (i.e. not served from the cache)
+    diffs_computed: AtomicUsize,
+    /// A commit or time limit left commits in range uncollected
+    truncated: AtomicBool,
 }
 
 impl Repository {
@@ -30,54 +92,256 @@ impl Repository {
             return Err(Error::RepoNotFound { path: expanded });
         }
 
-        if !expanded.join(".git").exists() && !expanded.join("HEAD").exists() {
-            return Err(Error::NotGitRepo { path: expanded });
-        }
-
-        let inner = Git2Repository::open(&expanded)?;
+        let inner = match open_exact(&expanded) {
+            Ok(inner) => inner,
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                return Err(Error::NotGitRepo { path: expanded });
+            }
+            Err(e) => return Err(e.into()),
+        };
 
         Ok(Self {
             inner,
             name: name.to_string(),
+            cache: None,
+            rename_threshold: Some(DEFAULT_RENAME_THRESHOLD),
+            diffs_computed: AtomicUsize::new(0),
+            truncated: AtomicBool::new(false),
         })
     }
 
+    /// Set the rename detection threshold (percent similarity), or `None` to disable it
+    ///
+    /// With detection on, a renamed file only counts the lines that actually
+    /// changed, under its new path. Without it, a rename counts as deleting
+    /// every line of the old file and adding every line of the new one.
+    #[must_use]
+    pub const fn with_rename_threshold(mut self, threshold: Option<u16>) -> Self {
+        self.rename_threshold = threshold;
+        self
+    }
+
+    /// Get the rename detection threshold (`None` when disabled)
+    #[must_use]
+    pub const fn rename_threshold(&self) -> Option<u16> {
+        self.rename_threshold
+    }
+
+    /// Use a persistent diff cache for commits in this repository
+    #[must_use]
+    pub fn with_cache(mut self, cache: DiffCache) -> Self {
+        self.cache = Some(Mutex::new(cache));
+        self
+    }
+
+    /// Get the repository's working directory, or the git directory for bare repositories
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.inner.workdir().unwrap_or_else(|| self.inner.path())
+    }
+
     /// Get the repository name
     #[must_use]
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// Check whether HEAD points to a branch with no commits yet
+    ///
+    /// This is the state of a freshly initialized repository.
+    #[must_use]
+    pub fn is_unborn(&self) -> bool {
+        matches!(
+            self.inner.head(),
+            Err(e) if matches!(e.code(), git2::ErrorCode::UnbornBranch | git2::ErrorCode::NotFound)
+        )
+    }
+
     /// Get commits in the specified date range
     ///
+    /// Merge commits are diffed like any other commit.
+    ///
     /// # Arguments
     ///
     /// * `from` - Start date (inclusive)
     /// * `to` - End date (inclusive)
-    /// * `branch` - Optional branch name (defaults to HEAD)
+    /// * `branch` - Optional branch name or fully-qualified ref (defaults to HEAD);
+    ///   see [`Repository::resolve_branch`]
+    /// * `all_branches` - Walk every local branch instead of a single starting point
     /// * `exclude_merges` - Whether to exclude merge commits
+    /// * `need_file_details` - Whether per-file changes are required
     ///
     /// # Errors
     ///
-    /// Returns an error if git operations fail
+    /// Returns an error if git operations fail, or if both `branch` and
+    /// `all_branches` are given
+    #[deprecated(
+        since = "0.7.0",
+        note = "use `Repository::collect_commits` with `CollectOptions`"
+    )]
     pub fn commits_in_range(
         &self,
         from: NaiveDate,
         to: NaiveDate,
         branch: Option<&str>,
+        all_branches: bool,
         exclude_merges: bool,
+        need_file_details: bool,
     ) -> Result<Vec<CommitInfo>> {
-        let mut revwalk = self.inner.revwalk()?;
+        self.collect_commits(
+            &CollectOptions::new(from, to)
+                .with_branch(branch)
+                .with_all_branches(all_branches)
+                .with_exclude_merges(exclude_merges)
+                .with_merge_diffs(true)
+                .with_file_details(need_file_details),
+        )
+    }
 
-        // Start from the specified branch or HEAD
-        if let Some(branch_name) = branch {
-            let reference = self
+    /// Short name of the branch HEAD points to, or `HEAD` when it is detached
+    ///
+    /// Also names the branch of a repository with no commits yet.
+    #[must_use]
+    pub fn head_shorthand(&self) -> Option<String> {
+        match self.inner.head() {
+            Ok(head) => head.shorthand().map(str::to_string),
+            Err(_) => self
                 .inner
-                .find_reference(&format!("refs/heads/{branch_name}"))?;
-            revwalk.push_ref(reference.name().unwrap_or("HEAD"))?;
+                .find_reference("HEAD")
+                .ok()?
+                .symbolic_target()
+                .map(|target| target.trim_start_matches("refs/heads/").to_string()),
+        }
+    }
+
+    /// Commit time of HEAD, or `None` when the repository has no commits
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if HEAD cannot be read
+    pub fn head_commit_time(&self) -> Result<Option<DateTime<Utc>>> {
+        if self.is_unborn() {
+            return Ok(None);
+        }
+        let commit = self.inner.head()?.peel_to_commit()?;
+        Ok(Some(Self::git_time_to_datetime(commit.time())))
+    }
+
+    /// Number of commits reachable from HEAD, counting no further than `limit`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking history fails
+    pub fn count_head_commits(&self, limit: usize) -> Result<usize> {
+        if self.is_unborn() {
+            return Ok(0);
+        }
+        let mut revwalk = self.inner.revwalk()?;
+        revwalk.push_head()?;
+        let mut count = 0;
+        for oid in revwalk.take(limit) {
+            oid?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Times of the commits reachable from HEAD back to `since`, newest first
+    ///
+    /// Like [`Repository::collect_commits`], the walk skips commits older than
+    /// `since` and only stops after [`STALE_COMMIT_TOLERANCE`] of them in a row,
+    /// so a stale-dated commit does not hide the newer ones behind it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking history fails
+    pub fn head_commit_times_since(&self, since: DateTime<Utc>) -> Result<Vec<DateTime<Utc>>> {
+        if self.is_unborn() {
+            return Ok(Vec::new());
+        }
+        let mut revwalk = self.inner.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.push_head()?;
+        let mut times = Vec::new();
+        let mut stale_run = 0;
+        for oid in revwalk {
+            let time = Self::git_time_to_datetime(self.inner.find_commit(oid?)?.time());
+            if time < since {
+                stale_run += 1;
+                if stale_run >= STALE_COMMIT_TOLERANCE {
+                    break;
+                }
+                continue;
+            }
+            stale_run = 0;
+            times.push(time);
+        }
+        Ok(times)
+    }
+
+    /// Get the commits selected by `options`
+    ///
+    /// Returns an empty list when analyzing HEAD of a repository with no commits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git operations fail, or if `options` name both a
+    /// branch and all branches
+    pub fn collect_commits(&self, options: &CollectOptions) -> Result<Vec<CommitInfo>> {
+        self.collect_commits_with_progress(options, &|_| {})
+    }
+
+    /// Get the commits selected by `options`, reporting progress as it goes
+    ///
+    /// `on_progress` is called every [`PROGRESS_INTERVAL`] commits while
+    /// walking history, after each batch of that many diffs, and once more
+    /// when collection is done. Diffs are computed in parallel, but the
+    /// callback always runs on the calling thread between batches, so it
+    /// need not be `Sync`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git operations fail, or if `options` name both a
+    /// branch and all branches
+    pub fn collect_commits_with_progress(
+        &self,
+        options: &CollectOptions,
+        on_progress: &dyn Fn(CollectProgress),
+    ) -> Result<Vec<CommitInfo>> {
+        let (from, to) = (options.from(), options.to());
+        let branch = options.branch();
+        let all_branches = options.all_branches();
+        let first_parent = options.first_parent();
+        let exclude_merges = options.exclude_merges();
+        // Without the merged commits, a mainline merge is the only record of their lines
+        let merge_diffs = options.merge_diffs() || first_parent;
+        let need_file_details = options.need_file_details();
+        let ignore_whitespace = options.ignore_whitespace();
+        let past_deadline = || options.deadline().is_some_and(|d| Instant::now() >= d);
+        if let (Some(branch), true) = (branch, all_branches) {
+            return Err(Error::ConflictingBranchOptions {
+                branch: branch.to_string(),
+                repo: self.name.clone(),
+            });
+        }
+        if branch.is_none() && !all_branches && self.is_unborn() {
+            return Ok(Vec::new());
+        }
+
+        let mut revwalk = self.inner.revwalk()?;
+
+        // Start from every local branch, the specified branch, or HEAD.
+        // The revwalk yields each commit once even when reachable from several tips.
+        if all_branches {
+            revwalk.push_glob("refs/heads/*")?;
+        } else if let Some(branch_name) = branch {
+            revwalk.push_ref(&self.resolve_branch(branch_name)?)?;
         } else {
             revwalk.push_head()?;
         }
+        if first_parent {
+            revwalk.simplify_first_parent()?;
+        }
 
         // Sort by time (newest first)
         revwalk.set_sorting(git2::Sort::TIME)?;
@@ -88,19 +352,39 @@ impl Repository {
             .checked_add_signed(chrono::Duration::days(1))
             .unwrap_or(to_end);
 
-        let mut commits = Vec::new();
+        // First pass: walk history and collect in-range commit oids
+        let mut candidates = Vec::new();
+        let mut stale_run = 0;
+        let mut scanned = 0;
 
         for oid_result in revwalk {
             let oid = oid_result?;
+            scanned += 1;
+            if scanned % PROGRESS_INTERVAL == 0 {
+                on_progress(CollectProgress {
+                    commits_scanned: scanned,
+                    diffs_computed: 0,
+                });
+                if past_deadline() {
+                    self.truncated.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
             let commit = self.inner.find_commit(oid)?;
 
             // Convert git timestamp to DateTime<Utc>
             let timestamp = Self::git_time_to_datetime(commit.time());
 
-            // Skip commits outside date range
+            // Skip commits outside date range. A single older commit does not
+            // end the walk, since its parents may be dated later.
             if timestamp < from_datetime {
-                break; // Since we're sorted by time, no need to continue
+                stale_run += 1;
+                if stale_run >= STALE_COMMIT_TOLERANCE {
+                    break;
+                }
+                continue;
             }
+            stale_run = 0;
             if timestamp >= to_datetime {
                 continue;
             }
@@ -111,83 +395,180 @@ impl Repository {
                 continue;
             }
 
-            // Calculate diff stats
-            let diff_stats = self.calculate_diff_stats(&commit)?;
+            candidates.push((oid, timestamp, is_merge));
+        }
 
-            let commit_info = CommitInfo::new(
-                oid.to_string()[..7].to_string(),
-                timestamp,
-                is_merge,
-                diff_stats,
-            );
+        // The walk is only roughly newest first, so sort before keeping the newest
+        if let Some(max_commits) = options.max_commits()
+            && candidates.len() > max_commits
+        {
+            candidates.sort_by_key(|&(_, timestamp, _)| std::cmp::Reverse(timestamp));
+            candidates.truncate(max_commits);
+            self.truncated.store(true, Ordering::Relaxed);
+        }
 
-            commits.push(commit_info);
+        // Look up cached diffs so only the remaining commits need diffing;
+        // merges that are not diffed get an empty one, which is never cached
+        let undiffed = |is_merge: bool| (is_merge && !merge_diffs).then(DiffStats::default);
+        let cached: Vec<Option<DiffStats>> = match &self.cache {
+            Some(cache) => {
+                let cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+                candidates
+                    .iter()
+                    .map(|&(oid, _, is_merge)| {
+                        undiffed(is_merge)
+                            .or_else(|| cache.get(&oid.to_string(), need_file_details))
+                    })
+                    .collect()
+            }
+            None => candidates
+                .iter()
+                .map(|&(_, _, is_merge)| undiffed(is_merge))
+                .collect(),
+        };
+
+        // Second pass: compute diffs in parallel, a batch at a time so progress
+        // is reported from this thread. git2 handles are not Sync, so each
+        // worker opens its own handle to the repository.
+        let repo_path = self.inner.path().to_path_buf();
+        let renames = self.rename_threshold;
+        let mut results = Vec::with_capacity(candidates.len());
+        let mut diffs_computed = 0;
+        let mut cached = cached.into_iter();
+        for batch in candidates.chunks(PROGRESS_INTERVAL) {
+            if past_deadline() {
+                self.truncated.store(true, Ordering::Relaxed);
+                break;
+            }
+            let batch_cached: Vec<_> = cached.by_ref().take(batch.len()).collect();
+            let batch_results = batch
+                .par_iter()
+                .copied()
+                .zip(batch_cached)
+                .map_init(
+                    || Git2Repository::open(&repo_path),
+                    |repo, ((oid, timestamp, is_merge), cached)| {
+                        let repo = repo
+                            .as_ref()
+                            .map_err(|e| git2::Error::from_str(e.message()))?;
+                        let commit = repo.find_commit(oid)?;
+                        let computed = cached.is_none();
+                        let diff_stats = match cached {
+                            Some(stats) => stats,
+                            None if need_file_details => {
+                                calculate_diff_stats(repo, &commit, renames, ignore_whitespace)?
+                            }
+                            None => {
+                                calculate_diff_totals(repo, &commit, renames, ignore_whitespace)?
+                            }
+                        };
+
+                        let summary =
+                            String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default());
+                        let info = CommitInfo::new(
+                            oid.to_string()[..7].to_string(),
+                            timestamp,
+                            is_merge,
+                            diff_stats,
+                        )
+                        .with_summary(&summary);
+                        Ok((oid, info, computed))
+                    },
+                )
+                .collect::<Result<Vec<_>>>()?;
+            diffs_computed += batch_results
+                .iter()
+                .filter(|(_, _, computed)| *computed)
+                .count();
+            results.extend(batch_results);
+            // The last batch is covered by the final report below
+            if results.len() < candidates.len() {
+                on_progress(CollectProgress {
+                    commits_scanned: scanned,
+                    diffs_computed,
+                });
+            }
         }
 
-        Ok(commits)
+        let computed: Vec<_> = results
+            .iter()
+            .filter(|(_, _, computed)| *computed)
+            .map(|(oid, info, _)| (oid, &info.diff))
+            .collect();
+        self.diffs_computed
+            .fetch_add(computed.len(), Ordering::Relaxed);
+
+        if let Some(cache) = &self.cache
+            && !computed.is_empty()
+        {
+            let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+            for (oid, diff) in computed {
+                cache.insert(oid.to_string(), diff, need_file_details);
+            }
+            // The cache only speeds up later runs; failing to write it is not an error
+            let _ = cache.save();
+        }
+
+        on_progress(CollectProgress {
+            commits_scanned: scanned,
+            diffs_computed,
+        });
+        Ok(results.into_iter().map(|(_, info, _)| info).collect())
     }
 
-    /// Calculate diff statistics for a commit
-    fn calculate_diff_stats(&self, commit: &git2::Commit) -> Result<DiffStats> {
-        let tree = commit.tree()?;
+    /// Number of commit diffs computed so far (cache hits are not counted)
+    #[must_use]
+    pub fn diffs_computed(&self) -> usize {
+        self.diffs_computed.load(Ordering::Relaxed)
+    }
 
-        let parent_tree = if commit.parent_count() > 0 {
-            Some(commit.parent(0)?.tree()?)
-        } else {
-            None
-        };
+    /// Whether a commit or time limit stopped a collection before all
+    /// commits in range were collected
+    #[must_use]
+    pub fn truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
 
-        let mut diff_opts = DiffOptions::new();
-        diff_opts.ignore_whitespace(false);
-
-        let diff = self.inner.diff_tree_to_tree(
-            parent_tree.as_ref(),
-            Some(&tree),
-            Some(&mut diff_opts),
-        )?;
-
-        let mut stats = DiffStats::default();
-
-        diff.foreach(
-            &mut |_, _| true,
-            None,
-            None,
-            Some(&mut |delta, _hunk, line| {
-                let path = delta
-                    .new_file()
-                    .path()
-                    .or_else(|| delta.old_file().path())
-                    .map(|p| p.to_string_lossy().to_string())
-                    .unwrap_or_default();
-
-                match line.origin() {
-                    '+' => {
-                        // Find or create file entry
-                        if let Some(file) = stats.files.iter_mut().find(|f| f.path == path) {
-                            file.additions += 1;
-                        } else {
-                            stats.files.push(FileChange::new(path, 1, 0));
-                        }
-                    }
-                    '-' => {
-                        if let Some(file) = stats.files.iter_mut().find(|f| f.path == path) {
-                            file.deletions += 1;
-                        } else {
-                            stats.files.push(FileChange::new(path, 0, 1));
-                        }
-                    }
-                    _ => {}
-                }
-                true
-            }),
-        )?;
+    /// Resolve a branch name to a reference name
+    ///
+    /// Fully-qualified refs (starting with `refs/`) are used as-is. Otherwise
+    /// the local branch is preferred, falling back to `origin` and then any
+    /// other remote's remote-tracking branch.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BranchNotFound`] if no matching reference exists
+    pub fn resolve_branch(&self, branch: &str) -> Result<String> {
+        let mut candidates = Vec::new();
+        if branch.starts_with("refs/") {
+            candidates.push(branch.to_string());
+        } else {
+            candidates.push(format!("refs/heads/{branch}"));
+            candidates.push(format!("refs/remotes/origin/{branch}"));
 
-        // Aggregate stats from files
-        stats.additions = stats.files.iter().map(|f| f.additions).sum();
-        stats.deletions = stats.files.iter().map(|f| f.deletions).sum();
-        stats.files_changed = stats.files.len() as u32;
+            let mut remotes: Vec<String> = self
+                .inner
+                .remotes()?
+                .iter()
+                .flatten()
+                .filter(|remote| *remote != "origin")
+                .map(str::to_string)
+                .collect();
+            remotes.sort();
+            candidates.extend(
+                remotes
+                    .iter()
+                    .map(|remote| format!("refs/remotes/{remote}/{branch}")),
+            );
+        }
 
-        Ok(stats)
+        candidates
+            .into_iter()
+            .find(|name| self.inner.find_reference(name).is_ok())
+            .ok_or_else(|| Error::BranchNotFound {
+                branch: branch.to_string(),
+                repo: self.name.clone(),
+            })
     }
 
     /// Convert `NaiveDate` to `DateTime<Utc>` at midnight
@@ -205,6 +586,126 @@ impl Repository {
     }
 }
 
+/// Diff a commit against its first parent (or the empty tree for root commits)
+///
+/// With a `rename_threshold`, deleted and added files at least that similar
+/// are paired up as renames.
+fn commit_diff<'r>(
+    repo: &'r Git2Repository,
+    commit: &git2::Commit,
+    rename_threshold: Option<u16>,
+    ignore_whitespace: IgnoreWhitespace,
+) -> Result<git2::Diff<'r>> {
+    let tree = commit.tree()?;
+
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .ignore_whitespace(ignore_whitespace == IgnoreWhitespace::All)
+        .ignore_whitespace_eol(ignore_whitespace == IgnoreWhitespace::Eol);
+
+    let mut diff =
+        repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))?;
+    if let Some(threshold) = rename_threshold {
+        let mut find_opts = DiffFindOptions::new();
+        find_opts.renames(true).rename_threshold(threshold);
+        diff.find_similar(Some(&mut find_opts))?;
+    }
+    Ok(diff)
+}
+
+/// Calculate diff totals for a commit without per-file line counts
+///
+/// The changed paths come from the diff's deltas, which needs no line-level work.
+fn calculate_diff_totals(
+    repo: &Git2Repository,
+    commit: &git2::Commit,
+    rename_threshold: Option<u16>,
+    ignore_whitespace: IgnoreWhitespace,
+) -> Result<DiffStats> {
+    let diff = commit_diff(repo, commit, rename_threshold, ignore_whitespace)?;
+    let stats = diff.stats()?;
+    let paths = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+
+    Ok(DiffStats::new(
+        stats.insertions() as u64,
+        stats.deletions() as u64,
+        stats.files_changed() as u32,
+    )
+    .with_paths(paths))
+}
+
+/// Calculate diff statistics for a commit, including per-file changes
+///
+/// Every delta is a changed file, as in [`calculate_diff_totals`]; binary
+/// files and mode-only changes are listed with zero lines.
+fn calculate_diff_stats(
+    repo: &Git2Repository,
+    commit: &git2::Commit,
+    rename_threshold: Option<u16>,
+    ignore_whitespace: IgnoreWhitespace,
+) -> Result<DiffStats> {
+    let diff = commit_diff(repo, commit, rename_threshold, ignore_whitespace)?;
+
+    // Accumulate (additions, deletions) per path
+    let mut per_file: HashMap<String, (u64, u64)> = HashMap::new();
+
+    // Register every changed file up front, like the totals do: pure renames,
+    // binary files, and mode changes have no changed lines but still count
+    for delta in diff.deltas() {
+        if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+            per_file
+                .entry(path.to_string_lossy().to_string())
+                .or_default();
+        }
+    }
+
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        None,
+        Some(&mut |delta, _hunk, line| {
+            let counts = match line.origin() {
+                '+' => (1, 0),
+                '-' => (0, 1),
+                _ => return true,
+            };
+
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let entry = per_file.entry(path).or_default();
+            entry.0 += counts.0;
+            entry.1 += counts.1;
+            true
+        }),
+    )?;
+
+    // Sort by path so output ordering is deterministic
+    let mut files: Vec<_> = per_file.into_iter().collect();
+    files.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+    let mut stats = DiffStats::default();
+    for (path, (additions, deletions)) in files {
+        stats.add_file(FileChange::new(path, additions, deletions));
+    }
+
+    Ok(stats)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,6 +752,38 @@ mod tests {
         (dir, repo)
     }
 
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Clone `origin` after adding a `dev` branch to it, so the clone only
+    /// has `dev` as a remote-tracking ref
+    fn clone_with_remote_only_branch(origin: &Path) -> (TempDir, Repository) {
+        git(origin, &["checkout", "-q", "-b", "dev"]);
+        std::fs::write(origin.join("dev.txt"), "dev\n").unwrap();
+        git(origin, &["add", "."]);
+        git(origin, &["commit", "-q", "-m", "Dev commit"]);
+
+        let clone_dir = TempDir::new().unwrap();
+        git(
+            clone_dir.path(),
+            &["clone", "-q", origin.to_str().unwrap(), "clone"],
+        );
+        let clone_path = clone_dir.path().join("clone");
+        // Check out a different local branch so `dev` exists only as origin/dev
+        git(&clone_path, &["checkout", "-q", "-b", "local", "HEAD~1"]);
+        git(&clone_path, &["branch", "-q", "-D", "dev"]);
+
+        let repo = Repository::open(&clone_path, "clone").unwrap();
+        (clone_dir, repo)
+    }
+
     #[test]
     fn test_open_valid_repo() {
         let (_dir, repo) = create_test_repo();
@@ -270,6 +803,509 @@ mod tests {
         assert!(matches!(result, Err(Error::NotGitRepo { .. })));
     }
 
+    #[test]
+    fn test_head_details() {
+        let (dir, repo) = create_test_repo();
+        git(dir.path(), &["branch", "-M", "main"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "Second"],
+        );
+
+        assert_eq!(repo.head_shorthand().as_deref(), Some("main"));
+        let time = repo.head_commit_time().unwrap().unwrap();
+        assert_eq!(time.date_naive(), Utc::now().date_naive());
+        assert_eq!(repo.count_head_commits(10).unwrap(), 2);
+        assert_eq!(repo.count_head_commits(1).unwrap(), 1);
+
+        git(dir.path(), &["checkout", "-q", "--detach"]);
+        assert_eq!(repo.head_shorthand().as_deref(), Some("HEAD"));
+    }
+
+    #[test]
+    fn test_unborn_head_yields_no_commits() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let repo = Repository::open(dir.path(), "empty").unwrap();
+
+        assert!(repo.is_unborn());
+        let today = Utc::now().date_naive();
+        let commits = repo
+            .collect_commits(&CollectOptions::new(
+                today - chrono::Duration::days(7),
+                today,
+            ))
+            .unwrap();
+        assert!(commits.is_empty());
+        assert!(repo.head_shorthand().is_some());
+        assert_eq!(repo.head_commit_time().unwrap(), None);
+        assert_eq!(repo.count_head_commits(10).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_stale_dated_commit_does_not_hide_older_commits() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        let commit = |file: &str, date: Option<&str>| {
+            std::fs::write(path.join(file), "content\n").unwrap();
+            git(path, &["add", "."]);
+            let mut command = Command::new("git");
+            command.args(["commit", "-q", "-m", file]).current_dir(path);
+            if let Some(date) = date {
+                command
+                    .env("GIT_AUTHOR_DATE", date)
+                    .env("GIT_COMMITTER_DATE", date);
+            }
+            assert!(command.status().unwrap().success());
+        };
+        // A cherry-picked commit keeping its 2020 date, between two recent ones
+        commit("before.txt", None);
+        commit("stale.txt", Some("2020-01-01T12:00:00Z"));
+        commit("after.txt", None);
+
+        let today = Utc::now().date_naive();
+        let commits = repo
+            .collect_commits(&CollectOptions::new(
+                today - chrono::Duration::days(1),
+                today,
+            ))
+            .unwrap();
+
+        let summaries: Vec<_> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries.len(), 3, "{summaries:?}");
+        assert!(summaries.contains(&"before.txt"));
+        assert!(summaries.contains(&"Initial commit"));
+        assert!(!summaries.contains(&"stale.txt"));
+    }
+
+    #[test]
+    fn test_all_branches_includes_side_branch_commits() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+
+        // A side branch with a unique commit, leaving HEAD on the original branch
+        git(path, &["checkout", "-q", "-b", "side"]);
+        std::fs::write(path.join("side.txt"), "side\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Side commit"]);
+        git(path, &["checkout", "-q", "-"]);
+
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(1);
+        let head_only = repo
+            .collect_commits(&CollectOptions::new(from, today))
+            .unwrap();
+        let all = repo
+            .collect_commits(&CollectOptions::new(from, today).with_all_branches(true))
+            .unwrap();
+
+        assert_eq!(head_only.len(), 1);
+        // The shared initial commit is only counted once
+        assert_eq!(all.len(), 2);
+    }
+
+    #[test]
+    fn test_all_branches_conflicts_with_branch() {
+        let (_dir, repo) = create_test_repo();
+        let today = Utc::now().date_naive();
+
+        let result = repo.collect_commits(
+            &CollectOptions::new(today, today)
+                .with_branch(Some("main"))
+                .with_all_branches(true),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::ConflictingBranchOptions { .. })
+        ));
+    }
+
+    #[test]
+    fn test_first_parent_counts_merge_and_mainline_only() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        for i in 0..2 {
+            std::fs::write(path.join(format!("feature{i}.txt")), "a\nb\n").unwrap();
+            git(path, &["add", "."]);
+            git(path, &["commit", "-q", "-m", &format!("Feature {i}")]);
+        }
+        git(path, &["checkout", "-q", "-"]);
+        std::fs::write(path.join("main.txt"), "main\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Main"]);
+        git(
+            path,
+            &["merge", "-q", "--no-ff", "-m", "Merge feature", "feature"],
+        );
+
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(1);
+        let summaries = |commits: &[CommitInfo]| {
+            let mut summaries: Vec<_> = commits.iter().map(|c| c.summary.clone()).collect();
+            summaries.sort();
+            summaries
+        };
+
+        let all = repo
+            .collect_commits(&CollectOptions::new(from, today))
+            .unwrap();
+        assert_eq!(all.len(), 5);
+
+        let mainline = repo
+            .collect_commits(&CollectOptions::new(from, today).with_first_parent(true))
+            .unwrap();
+        assert_eq!(
+            summaries(&mainline),
+            ["Initial commit", "Main", "Merge feature"]
+        );
+        // The merge is diffed against main, so it carries the feature's lines
+        let merge = mainline.iter().find(|c| c.is_merge).unwrap();
+        assert_eq!(merge.diff.additions, 4);
+        assert_eq!(merge.diff.files_changed, 2);
+    }
+
+    #[test]
+    fn test_merges_are_counted_apart_and_not_diffed_by_default() {
+        use crate::cli::args::Period;
+        use crate::stats::{DateRange, FileFilter, TimeZoneMode, collect_stats};
+
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        std::fs::write(path.join("feature.txt"), "a\nb\nc\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Feature"]);
+        git(path, &["checkout", "-q", "-"]);
+        git(
+            path,
+            &["merge", "-q", "--no-ff", "-m", "Merge feature", "feature"],
+        );
+
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(1);
+        let options = CollectOptions::new(from, today);
+        let commits = repo.collect_commits(&options).unwrap();
+        let merge = commits.iter().find(|c| c.is_merge).unwrap();
+        assert_eq!(merge.diff.additions, 0);
+        assert_eq!(merge.diff.files_changed, 0);
+
+        let stats = |commits| {
+            collect_stats(
+                "test",
+                commits,
+                DateRange::new(from, today),
+                Period::Daily,
+                &FileFilter::default(),
+                &TimeZoneMode::Utc,
+            )
+            .total
+        };
+        // Initial commit (1 line) and Feature (3 lines); the merge adds none
+        let total = stats(commits);
+        assert_eq!((total.commits, total.merge_commits), (2, 1));
+        assert_eq!(total.additions, 4);
+
+        // With merge diffs, the merge repeats the feature's lines
+        let commits = repo
+            .collect_commits(&options.with_merge_diffs(true))
+            .unwrap();
+        let total = stats(commits);
+        assert_eq!((total.commits, total.merge_commits), (2, 1));
+        assert_eq!(total.additions, 7);
+    }
+
+    #[test]
+    fn test_warm_cache_skips_diffing() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        for i in 0..3 {
+            std::fs::write(path.join(format!("file{i}.rs")), "fn f() {}\n").unwrap();
+            git(path, &["add", "."]);
+            git(path, &["commit", "-q", "-m", &format!("Commit {i}")]);
+        }
+        drop(repo);
+
+        let cache_dir = TempDir::new().unwrap();
+        let cache_path = cache_dir.path().join("cache.json");
+        let open = || {
+            Repository::open(path, "test-repo")
+                .unwrap()
+                .with_cache(DiffCache::open(cache_path.clone()))
+        };
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(1);
+
+        let cold = open();
+        let cold_commits = cold
+            .collect_commits(&CollectOptions::new(from, today).with_file_details(true))
+            .unwrap();
+        assert_eq!(cold.diffs_computed(), 4);
+
+        let warm = open();
+        let warm_commits = warm
+            .collect_commits(&CollectOptions::new(from, today).with_file_details(true))
+            .unwrap();
+        assert_eq!(warm.diffs_computed(), 0);
+        assert_eq!(warm_commits.len(), cold_commits.len());
+        for (warm, cold) in warm_commits.iter().zip(&cold_commits) {
+            assert_eq!(warm.id, cold.id);
+            assert_eq!(warm.summary, cold.summary);
+            assert_eq!(warm.diff.additions, cold.diff.additions);
+            assert_eq!(warm.diff.files.len(), cold.diff.files.len());
+        }
+
+        // Detailed entries also serve totals-only requests
+        let totals = open();
+        totals
+            .collect_commits(&CollectOptions::new(from, today))
+            .unwrap();
+        assert_eq!(totals.diffs_computed(), 0);
+    }
+
+    #[test]
+    fn test_totals_cache_recomputed_for_file_details() {
+        let (dir, repo) = create_test_repo();
+        drop(repo);
+        let cache_dir = TempDir::new().unwrap();
+        let cache_path = cache_dir.path().join("cache.json");
+        let open = || {
+            Repository::open(dir.path(), "test-repo")
+                .unwrap()
+                .with_cache(DiffCache::open(cache_path.clone()))
+        };
+        let today = Utc::now().date_naive();
+
+        open()
+            .collect_commits(&CollectOptions::new(today, today))
+            .unwrap();
+
+        let detailed = open();
+        let commits = detailed
+            .collect_commits(&CollectOptions::new(today, today).with_file_details(true))
+            .unwrap();
+        assert_eq!(detailed.diffs_computed(), 1);
+        assert_eq!(commits[0].diff.files.len(), 1);
+    }
+
+    /// Commit a 2,000-line file, then `git mv` it; returns the rename commit's diff
+    #[test]
+    fn test_progress_reported_every_interval() {
+        let (_dir, repo) = create_test_repo();
+        let signature = repo.inner.signature().unwrap();
+        let mut parent = repo.inner.head().unwrap().peel_to_commit().unwrap();
+        let tree = parent.tree().unwrap();
+        for i in 0..249 {
+            let message = format!("Commit {i}");
+            let oid = repo
+                .inner
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    &message,
+                    &tree,
+                    &[&parent],
+                )
+                .unwrap();
+            parent = repo.inner.find_commit(oid).unwrap();
+        }
+
+        // A RefCell is not Sync, which the callback does not need to be
+        let reports = std::cell::RefCell::new(Vec::new());
+        let today = Utc::now().date_naive();
+        let options = CollectOptions::new(today - chrono::Duration::days(1), today);
+        let commits = repo
+            .collect_commits_with_progress(&options, &|progress| {
+                reports.borrow_mut().push(progress);
+            })
+            .unwrap();
+
+        assert_eq!(commits.len(), 250);
+        let reports: Vec<_> = reports
+            .into_inner()
+            .into_iter()
+            .map(|progress| (progress.commits_scanned, progress.diffs_computed))
+            .collect();
+        assert_eq!(
+            reports,
+            [(100, 0), (200, 0), (250, 100), (250, 200), (250, 250)]
+        );
+    }
+
+    /// Add a commit on HEAD per timestamp, each touching its own file
+    fn commit_at_times(repo: &Repository, times: &[i64]) {
+        let mut parent = repo.inner.head().unwrap().peel_to_commit().unwrap();
+        for (i, &time) in times.iter().enumerate() {
+            let path = repo.path().join(format!("file{i}.rs"));
+            std::fs::write(path, "fn f() {}\n").unwrap();
+            let mut index = repo.inner.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.inner.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature =
+                git2::Signature::new("Test User", "test@example.com", &git2::Time::new(time, 0))
+                    .unwrap();
+            let message = format!("Commit {i}");
+            let oid = repo
+                .inner
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    &message,
+                    &tree,
+                    &[&parent],
+                )
+                .unwrap();
+            parent = repo.inner.find_commit(oid).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_git_repository_detection() {
+        let (dir, _repo) = create_test_repo();
+        let worktrees = TempDir::new().unwrap();
+        let worktree = worktrees.path().join("feature");
+        let status = Command::new("git")
+            .args(["worktree", "add", "-q", "-b", "feature"])
+            .arg(&worktree)
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let subdir = worktree.join("src");
+        std::fs::create_dir_all(&subdir).unwrap();
+        let bare = TempDir::new().unwrap();
+        Git2Repository::init_bare(bare.path()).unwrap();
+        let plain = TempDir::new().unwrap();
+
+        // A linked worktree's .git is a `gitdir:` file
+        assert!(worktree.join(".git").is_file());
+        assert!(is_git_repository(dir.path()));
+        assert!(is_git_repository(&worktree));
+        assert!(is_git_repository(&dir.path().join(".git")));
+        assert!(is_git_repository(bare.path()));
+        assert!(!is_git_repository(plain.path()));
+        assert!(!is_git_repository(&subdir));
+        assert!(matches!(
+            Repository::open(plain.path(), "plain"),
+            Err(Error::NotGitRepo { .. })
+        ));
+
+        let canonical = |path: &Path| path.canonicalize().unwrap();
+        assert_eq!(
+            discover_repository(&subdir).map(|root| canonical(&root)),
+            Some(canonical(&worktree))
+        );
+        assert_eq!(
+            discover_repository(bare.path()).map(|root| canonical(&root)),
+            Some(canonical(bare.path()))
+        );
+        assert_eq!(discover_repository(plain.path()), None);
+    }
+
+    #[test]
+    fn test_max_commits_keeps_newest() {
+        let (_dir, repo) = create_test_repo();
+        let start = repo.inner.head().unwrap().peel_to_commit().unwrap().time();
+        let at = |seconds| start.seconds() + seconds;
+        // The newest commit is not the tip, so the walk alone does not order them
+        commit_at_times(&repo, &[at(3), at(4), at(2), at(1)]);
+        let today = Utc::now().date_naive();
+        let options = CollectOptions::new(today - chrono::Duration::days(1), today);
+
+        let commits = repo
+            .collect_commits(&options.clone().with_max_commits(Some(2)))
+            .unwrap();
+        let summaries: Vec<_> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, ["Commit 1", "Commit 0"]);
+        assert!(repo.truncated());
+        assert_eq!(repo.diffs_computed(), 2);
+
+        let untouched = Repository::open(repo.path(), "test-repo").unwrap();
+        let commits = untouched
+            .collect_commits(&options.with_max_commits(Some(10)))
+            .unwrap();
+        assert_eq!(commits.len(), 5);
+        assert!(!untouched.truncated());
+    }
+
+    #[test]
+    fn test_head_commit_times_since_looks_past_stale_dated_tip() {
+        let (_dir, repo) = create_test_repo();
+        let start = repo.inner.head().unwrap().peel_to_commit().unwrap().time();
+        let at = |seconds| start.seconds() + seconds;
+        // A rebased tip dated before `since` sits in front of in-range commits
+        commit_at_times(&repo, &[at(20), at(30), at(5)]);
+
+        let since = DateTime::from_timestamp(at(15), 0).unwrap();
+        let times: Vec<_> = repo
+            .head_commit_times_since(since)
+            .unwrap()
+            .iter()
+            .map(DateTime::timestamp)
+            .collect();
+        assert_eq!(times, [at(30), at(20)]);
+    }
+
+    #[test]
+    fn test_past_deadline_stops_before_diffing() {
+        let (_dir, repo) = create_test_repo();
+        let today = Utc::now().date_naive();
+        let options = CollectOptions::new(today - chrono::Duration::days(1), today)
+            .with_deadline(Some(Instant::now()));
+
+        let commits = repo.collect_commits(&options).unwrap();
+        assert!(commits.is_empty());
+        assert!(repo.truncated());
+        assert_eq!(repo.diffs_computed(), 0);
+    }
+
+    fn rename_commit_diff(threshold: Option<u16>, need_file_details: bool) -> DiffStats {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        std::fs::write(path.join("old.txt"), "line\n".repeat(2000)).unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Add file"]);
+        git(path, &["mv", "old.txt", "new.txt"]);
+        git(path, &["commit", "-q", "-m", "Rename file"]);
+
+        let today = Utc::now().date_naive();
+        let commits = repo
+            .with_rename_threshold(threshold)
+            .collect_commits(
+                &CollectOptions::new(today, today).with_file_details(need_file_details),
+            )
+            .unwrap();
+        let rename = commits.iter().find(|c| c.summary == "Rename file").unwrap();
+        rename.diff.clone()
+    }
+
+    #[test]
+    fn test_rename_detection_counts_no_churn() {
+        let diff = rename_commit_diff(Some(DEFAULT_RENAME_THRESHOLD), true);
+        assert_eq!((diff.additions, diff.deletions), (0, 0));
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, "new.txt");
+
+        let totals = rename_commit_diff(Some(DEFAULT_RENAME_THRESHOLD), false);
+        assert_eq!((totals.additions, totals.deletions), (0, 0));
+        assert_eq!(totals.files_changed, 1);
+    }
+
+    #[test]
+    fn test_no_renames_counts_full_delete_and_add() {
+        let diff = rename_commit_diff(None, true);
+        assert_eq!((diff.additions, diff.deletions), (2000, 2000));
+        assert_eq!(diff.files.len(), 2);
+
+        let totals = rename_commit_diff(None, false);
+        assert_eq!((totals.additions, totals.deletions), (2000, 2000));
+    }
+
     #[test]
     fn test_commits_in_range() {
         let (_dir, repo) = create_test_repo();
@@ -277,10 +1313,353 @@ mod tests {
         let today = Utc::now().date_naive();
         let from = today - chrono::Duration::days(7);
 
-        let commits = repo.commits_in_range(from, today, None, false).unwrap();
+        let commits = repo
+            .collect_commits(&CollectOptions::new(from, today).with_file_details(true))
+            .unwrap();
 
         // Should have at least the initial commit
         assert!(!commits.is_empty());
+        assert_eq!(commits[0].summary, "Initial commit");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_commits_in_range_matches_collect_commits() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        std::fs::write(path.join("feature.rs"), "fn f() {}\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Feature"]);
+        git(path, &["checkout", "-q", "-"]);
+        git(
+            path,
+            &["merge", "-q", "--no-ff", "-m", "Merge feature", "feature"],
+        );
+
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(1);
+        let summarize = |commits: Vec<CommitInfo>| {
+            let mut rows: Vec<_> = commits
+                .into_iter()
+                .map(|c| (c.id, c.diff.additions, c.diff.files.len()))
+                .collect();
+            rows.sort();
+            rows
+        };
+
+        for (branch, all_branches, exclude_merges, need_file_details) in [
+            (None, false, false, false),
+            (None, true, true, true),
+            (Some("feature"), false, true, false),
+        ] {
+            let legacy = repo
+                .commits_in_range(
+                    from,
+                    today,
+                    branch,
+                    all_branches,
+                    exclude_merges,
+                    need_file_details,
+                )
+                .unwrap();
+            let options = CollectOptions::new(from, today)
+                .with_branch(branch)
+                .with_all_branches(all_branches)
+                .with_exclude_merges(exclude_merges)
+                .with_merge_diffs(true)
+                .with_file_details(need_file_details);
+            let built = repo.collect_commits(&options).unwrap();
+            assert!(!built.is_empty());
+            assert_eq!(summarize(legacy), summarize(built));
+        }
+
+        let conflicting = CollectOptions::new(from, today)
+            .with_branch(Some("feature"))
+            .with_all_branches(true);
+        assert!(matches!(
+            repo.collect_commits(&conflicting),
+            Err(Error::ConflictingBranchOptions { .. })
+        ));
+    }
+
+    #[test]
+    fn test_branch_falls_back_to_remote_tracking_ref() {
+        let (origin_dir, _origin) = create_test_repo();
+        let (_clone_dir, repo) = clone_with_remote_only_branch(origin_dir.path());
+
+        assert_eq!(
+            repo.resolve_branch("dev").unwrap(),
+            "refs/remotes/origin/dev"
+        );
+
+        let today = Utc::now().date_naive();
+        let commits = repo
+            .collect_commits(
+                &CollectOptions::new(today - chrono::Duration::days(1), today)
+                    .with_branch(Some("dev")),
+            )
+            .unwrap();
+        assert_eq!(commits.len(), 2);
+    }
+
+    #[test]
+    fn test_branch_accepts_fully_qualified_ref() {
+        let (origin_dir, _origin) = create_test_repo();
+        let (_clone_dir, repo) = clone_with_remote_only_branch(origin_dir.path());
+
+        assert_eq!(
+            repo.resolve_branch("refs/remotes/origin/dev").unwrap(),
+            "refs/remotes/origin/dev"
+        );
+        assert!(matches!(
+            repo.resolve_branch("refs/heads/dev"),
+            Err(Error::BranchNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_branch_falls_back_to_other_remotes() {
+        let (origin_dir, _origin) = create_test_repo();
+        let (clone_dir, repo) = clone_with_remote_only_branch(origin_dir.path());
+        let clone_path = clone_dir.path().join("clone");
+
+        // Move dev from origin to a differently named remote
+        git(&clone_path, &["remote", "rename", "origin", "upstream"]);
+
+        assert_eq!(
+            repo.resolve_branch("dev").unwrap(),
+            "refs/remotes/upstream/dev"
+        );
+    }
+
+    #[test]
+    fn test_branch_not_found_names_branch_and_repo() {
+        let (_dir, repo) = create_test_repo();
+
+        let result = repo.resolve_branch("missing");
+        match result {
+            Err(Error::BranchNotFound { branch, repo }) => {
+                assert_eq!(branch, "missing");
+                assert_eq!(repo, "test-repo");
+            }
+            other => panic!("expected BranchNotFound, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parallel_diff_matches_serial() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+
+        for i in 0..24 {
+            let content = "line\n".repeat(i + 1);
+            std::fs::write(path.join(format!("file{}.txt", i % 5)), content).unwrap();
+            Command::new("git")
+                .args(["add", "."])
+                .current_dir(path)
+                .output()
+                .unwrap();
+            Command::new("git")
+                .args(["commit", "-m", &format!("Commit {i}")])
+                .current_dir(path)
+                .output()
+                .unwrap();
+        }
+
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(7);
+        let commits = repo
+            .collect_commits(&CollectOptions::new(from, today).with_file_details(true))
+            .unwrap();
+        assert_eq!(commits.len(), 25);
+
+        // Serial reference: walk HEAD and compute diffs one by one
+        let mut revwalk = repo.inner.revwalk().unwrap();
+        revwalk.push_head().unwrap();
+        revwalk.set_sorting(git2::Sort::TIME).unwrap();
+        let mut serial = DiffStats::default();
+        for oid in revwalk {
+            let commit = repo.inner.find_commit(oid.unwrap()).unwrap();
+            let stats = calculate_diff_stats(
+                &repo.inner,
+                &commit,
+                repo.rename_threshold(),
+                IgnoreWhitespace::Off,
+            )
+            .unwrap();
+            serial.additions += stats.additions;
+            serial.deletions += stats.deletions;
+            serial.files_changed += stats.files_changed;
+        }
+
+        let additions: u64 = commits.iter().map(|c| c.diff.additions).sum();
+        let deletions: u64 = commits.iter().map(|c| c.diff.deletions).sum();
+        let files_changed: u32 = commits.iter().map(|c| c.diff.files_changed).sum();
+        assert_eq!(additions, serial.additions);
+        assert_eq!(deletions, serial.deletions);
+        assert_eq!(files_changed, serial.files_changed);
+    }
+
+    #[test]
+    fn test_diff_stats_many_files_sorted_by_path() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+
+        for i in 0..120 {
+            let content = "line\n".repeat(i % 7 + 1);
+            std::fs::write(path.join(format!("f{i:03}.txt")), content).unwrap();
+        }
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "Many files"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        let head = repo.inner.head().unwrap().peel_to_commit().unwrap();
+        let stats = calculate_diff_stats(&repo.inner, &head, None, IgnoreWhitespace::Off).unwrap();
+
+        let expected_additions: u64 = (0..120u64).map(|i| i % 7 + 1).sum();
+        assert_eq!(stats.files_changed, 120);
+        assert_eq!(stats.additions, expected_additions);
+        assert_eq!(stats.deletions, 0);
+        assert!(stats.files.windows(2).all(|w| w[0].path < w[1].path));
+        assert_eq!(stats.files[0].path, "f000.txt");
+        assert_eq!(stats.files[0].additions, 1);
+    }
+
+    #[test]
+    fn test_ignore_whitespace_skips_whitespace_only_lines() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+        std::fs::write(path.join("main.rs"), "fn main() {\n    run();\n}\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "Add main"]);
+        // Re-indent one line and add trailing spaces to another
+        std::fs::write(path.join("main.rs"), "fn main() {\n\trun();\n}  \n").unwrap();
+        git(path, &["commit", "-q", "-am", "Reformat"]);
+        drop(repo);
+
+        let cache_dir = TempDir::new().unwrap();
+        let today = Utc::now().date_naive();
+        let lines = |ignore_whitespace: IgnoreWhitespace, need_file_details| {
+            // One cache file per whitespace setting, like `DiffCache::for_repo`
+            let cache_file = format!("cache{}.json", ignore_whitespace.cache_file_suffix());
+            let repo = Repository::open(path, "test-repo")
+                .unwrap()
+                .with_cache(DiffCache::open(cache_dir.path().join(cache_file)));
+            let commits = repo
+                .collect_commits(
+                    &CollectOptions::new(today, today)
+                        .with_file_details(need_file_details)
+                        .with_ignore_whitespace(ignore_whitespace),
+                )
+                .unwrap();
+            let reformat = commits.iter().find(|c| c.summary == "Reformat").unwrap();
+            (
+                reformat.diff.additions,
+                reformat.diff.deletions,
+                repo.diffs_computed(),
+            )
+        };
+
+        // Each setting's first run diffs all three commits, and the file
+        // details run recomputes the totals-only entries
+        for need_file_details in [false, true] {
+            assert_eq!(lines(IgnoreWhitespace::Off, need_file_details), (2, 2, 3));
+            assert_eq!(lines(IgnoreWhitespace::Eol, need_file_details), (1, 1, 3));
+            assert_eq!(lines(IgnoreWhitespace::All, need_file_details), (0, 0, 3));
+        }
+        // Warm caches answer without diffing
+        assert_eq!(lines(IgnoreWhitespace::All, true), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_fast_diff_totals_match_detailed_stats() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+
+        std::fs::write(path.join("README.md"), "# Test\nmore\n").unwrap();
+        std::fs::write(path.join("main.rs"), "fn main() {}\n").unwrap();
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "Second commit"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+
+        let head = repo.inner.head().unwrap().peel_to_commit().unwrap();
+        let detailed =
+            calculate_diff_stats(&repo.inner, &head, None, IgnoreWhitespace::Off).unwrap();
+        let fast = calculate_diff_totals(&repo.inner, &head, None, IgnoreWhitespace::Off).unwrap();
+
+        assert_eq!(fast.additions, detailed.additions);
+        assert_eq!(fast.deletions, detailed.deletions);
+        assert_eq!(fast.files_changed, detailed.files_changed);
+        assert!(fast.files.is_empty());
+        assert_eq!(
+            fast.changed_paths().collect::<Vec<_>>(),
+            detailed.changed_paths().collect::<Vec<_>>()
+        );
+
+        let today = Utc::now().date_naive();
+        let from = today - chrono::Duration::days(7);
+        let fast_commits = repo
+            .collect_commits(&CollectOptions::new(from, today))
+            .unwrap();
+        let detailed_commits = repo
+            .collect_commits(&CollectOptions::new(from, today).with_file_details(true))
+            .unwrap();
+        let sum = |commits: &[CommitInfo]| -> (u64, u64) {
+            commits.iter().fold((0, 0), |acc, c| {
+                (acc.0 + c.diff.additions, acc.1 + c.diff.deletions)
+            })
+        };
+        assert_eq!(sum(&fast_commits), sum(&detailed_commits));
+    }
+
+    #[test]
+    fn test_binary_and_mode_changes_count_with_and_without_details() {
+        let (dir, repo) = create_test_repo();
+        let path = dir.path();
+
+        std::fs::write(path.join("logo.png"), [0x89, b'P', b'N', b'G', 0, 1, 2, 0]).unwrap();
+        for git_args in [
+            vec!["add", "logo.png"],
+            vec!["update-index", "--chmod=+x", "README.md"],
+            vec!["commit", "-m", "Binary and mode change"],
+        ] {
+            let status = Command::new("git")
+                .args(git_args)
+                .current_dir(path)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        let head = repo.inner.head().unwrap().peel_to_commit().unwrap();
+        let detailed =
+            calculate_diff_stats(&repo.inner, &head, None, IgnoreWhitespace::Off).unwrap();
+        let fast = calculate_diff_totals(&repo.inner, &head, None, IgnoreWhitespace::Off).unwrap();
+
+        assert_eq!(fast.files_changed, 2);
+        assert_eq!(detailed.files_changed, 2);
+        assert_eq!((detailed.additions, detailed.deletions), (0, 0));
+        let files: Vec<_> = detailed
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.additions, f.deletions))
+            .collect();
+        assert_eq!(files, [("README.md", 0, 0), ("logo.png", 0, 0)]);
     }
 
     #[test]
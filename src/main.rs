@@ -18,7 +18,7 @@ fn main() -> ExitCode {
             source = cause.source();
         }
 
-        return ExitCode::FAILURE;
+        return ExitCode::from(e.exit_code());
     }
 
     ExitCode::SUCCESS
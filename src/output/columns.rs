@@ -0,0 +1,282 @@
+//! Column model shared by the table and CSV formatters
+
+use crate::stats::{PeriodStats, TotalStats};
+use clap::ValueEnum;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// A column of the per-period table, selected with `--columns`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// Period label (CSV: the period's start date)
+    Period,
+    /// Number of commits
+    Commits,
+    /// Added lines
+    Additions,
+    /// Deleted lines
+    Deletions,
+    /// Net line change
+    Net,
+    /// Changed files, counted once per commit
+    Files,
+    /// Distinct files changed
+    Unique,
+    /// Merge commits, counted apart from commits with `--include-merges`
+    Merges,
+}
+
+/// Columns of the table output when `--columns` is not given
+pub const TABLE_COLUMNS: &[Column] = &[
+    Column::Period,
+    Column::Commits,
+    Column::Additions,
+    Column::Deletions,
+    Column::Net,
+    Column::Files,
+    Column::Unique,
+];
+
+/// Columns of the CSV output when `--columns` is not given
+pub const CSV_COLUMNS: &[Column] = &[
+    Column::Period,
+    Column::Commits,
+    Column::Additions,
+    Column::Deletions,
+    Column::Net,
+    Column::Files,
+];
+
+impl Column {
+    /// Name used by `--columns` and `--sort`
+    #[must_use]
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Header in the table output
+    #[must_use]
+    pub const fn table_header(self) -> &'static str {
+        match self {
+            Self::Period => "Period",
+            Self::Commits => "Commits",
+            Self::Additions => "+Lines",
+            Self::Deletions => "-Lines",
+            Self::Net => "Net",
+            Self::Files => "Files",
+            Self::Unique => "Unique",
+            Self::Merges => "Merges",
+        }
+    }
+
+    /// Header in the CSV output
+    #[must_use]
+    pub const fn csv_header(self) -> &'static str {
+        match self {
+            Self::Period => "date",
+            Self::Commits => "commits",
+            Self::Additions => "additions",
+            Self::Deletions => "deletions",
+            Self::Net => "net_lines",
+            Self::Files => "files_changed",
+            Self::Unique => "unique_files",
+            Self::Merges => "merge_commits",
+        }
+    }
+
+    /// Value of this column in `row`, or `None` for the period column
+    fn value(self, row: Row<'_>) -> Option<Number> {
+        let (commits, additions, deletions, net, files, unique, merges) = match row {
+            Row::Period(stat) => (
+                stat.commits,
+                stat.additions,
+                stat.deletions,
+                stat.net_lines,
+                stat.files_changed,
+                stat.unique_files,
+                stat.merge_commits,
+            ),
+            Row::Total(total) => (
+                total.commits,
+                total.additions,
+                total.deletions,
+                total.net_lines,
+                total.files_changed,
+                total.unique_files,
+                total.merge_commits,
+            ),
+        };
+        Some(match self {
+            Self::Period => return None,
+            Self::Commits => Number::Unsigned(u64::from(commits)),
+            Self::Additions => Number::Unsigned(additions),
+            Self::Deletions => Number::Unsigned(deletions),
+            Self::Net => Number::Signed(net),
+            Self::Files => Number::Unsigned(u64::from(files)),
+            Self::Unique => Number::Unsigned(u64::from(unique)),
+            Self::Merges => Number::Unsigned(u64::from(merges)),
+        })
+    }
+
+    /// Order two periods by this column, the period column by date
+    fn compare(self, a: &PeriodStats, b: &PeriodStats) -> Ordering {
+        match (self.value(Row::Period(a)), self.value(Row::Period(b))) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a.date.cmp(&b.date),
+        }
+    }
+}
+
+/// Row order requested with `--sort <column>[:asc|desc]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortKey {
+    /// Column to sort by
+    pub column: Column,
+    /// Largest values first
+    pub descending: bool,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (name, direction) = input.split_once(':').unwrap_or((input, "asc"));
+        let column = Column::from_str(name, true).map_err(|_| {
+            let names: Vec<String> = Column::value_variants().iter().map(|c| c.name()).collect();
+            format!(
+                "unknown column '{name}' (valid columns: {})",
+                names.join(", ")
+            )
+        })?;
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            _ => {
+                return Err(format!(
+                    "unknown sort direction '{direction}' (use asc or desc)"
+                ));
+            }
+        };
+        Ok(Self { column, descending })
+    }
+}
+
+/// A row of the per-period table: one period or the total
+#[derive(Clone, Copy)]
+pub(crate) enum Row<'a> {
+    Period(&'a PeriodStats),
+    Total(&'a TotalStats),
+}
+
+/// A numeric cell, formatted by each formatter in its own way
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Number {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+/// Cells of `row` in `columns` order
+///
+/// The period column shows `label`; numbers are rendered with `number`.
+pub(crate) fn project(
+    columns: &[Column],
+    row: Row<'_>,
+    label: &str,
+    number: impl Fn(Number) -> String,
+) -> Vec<String> {
+    columns
+        .iter()
+        .map(|column| column.value(row).map_or_else(|| label.to_string(), &number))
+        .collect()
+}
+
+/// Periods in the order requested by `sort`, or in date order without one,
+/// leaving out empty periods with `skip_empty`
+///
+/// Ties keep their date order.
+pub(crate) fn sorted_rows(
+    stats: &[PeriodStats],
+    sort: Option<SortKey>,
+    skip_empty: bool,
+) -> Vec<&PeriodStats> {
+    let mut rows: Vec<&PeriodStats> = stats
+        .iter()
+        .filter(|stat| !(skip_empty && stat.is_empty()))
+        .collect();
+    if let Some(SortKey { column, descending }) = sort {
+        rows.sort_by(|a, b| {
+            let ordering = column.compare(a, b);
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_sort_key_parse() {
+        assert_eq!(
+            "net:desc".parse::<SortKey>(),
+            Ok(SortKey {
+                column: Column::Net,
+                descending: true,
+            })
+        );
+        assert_eq!(
+            "commits".parse::<SortKey>(),
+            Ok(SortKey {
+                column: Column::Commits,
+                descending: false,
+            })
+        );
+        assert!("net:down".parse::<SortKey>().is_err());
+
+        let err = "lines".parse::<SortKey>().unwrap_err();
+        assert!(
+            err.contains("period, commits, additions, deletions, net, files, unique"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_sorted_rows_keeps_date_order_for_ties() {
+        let stat = |day: u32, net: i64| PeriodStats {
+            net_lines: net,
+            ..PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, day).unwrap())
+        };
+        let stats = vec![stat(1, 5), stat(2, -3), stat(3, 5)];
+
+        let days = |sort: Option<SortKey>| {
+            sorted_rows(&stats, sort, false)
+                .iter()
+                .map(|s| s.date.format("%d").to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(days(None), ["01", "02", "03"]);
+        assert_eq!(days("net:desc".parse().ok()), ["01", "03", "02"]);
+        assert_eq!(days("net".parse().ok()), ["02", "01", "03"]);
+    }
+
+    #[test]
+    fn test_sorted_rows_skips_empty_periods() {
+        let stats = vec![
+            PeriodStats {
+                commits: 1,
+                ..PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            },
+            PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+        ];
+        assert_eq!(sorted_rows(&stats, None, false).len(), 2);
+        assert_eq!(sorted_rows(&stats, None, true).len(), 1);
+    }
+}
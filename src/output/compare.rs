@@ -0,0 +1,307 @@
+//! Side-by-side report for the `compare` subcommand
+
+#![allow(clippy::cast_possible_wrap)]
+
+use crate::error::Result;
+use crate::output::table::{format_with_commas_i64, format_with_commas_u64};
+use crate::stats::types::serialize_date;
+use crate::stats::{AnalysisResult, PeriodStats, TotalStats};
+use chrono::NaiveDate;
+use comfy_table::{Table, presets::UTF8_FULL};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Side-by-side comparison of two analysis results over the same range
+///
+/// Deltas are always `right - left`. Periods are matched by date, so a period
+/// missing on one side counts as zero there instead of shifting later rows.
+pub struct CompareReport<'a> {
+    left: &'a AnalysisResult,
+    right: &'a AnalysisResult,
+}
+
+/// One period with the stats of each side, if present
+struct AlignedPeriod<'a> {
+    date: NaiveDate,
+    label: &'a str,
+    left: Option<&'a PeriodStats>,
+    right: Option<&'a PeriodStats>,
+}
+
+/// Change from left to right for a single period
+#[derive(Serialize)]
+struct PeriodDelta<'a> {
+    period: &'a str,
+    #[serde(serialize_with = "serialize_date")]
+    date: NaiveDate,
+    commits: i64,
+    net_lines: i64,
+}
+
+/// Change from left to right, in totals and per period
+#[derive(Serialize)]
+struct Delta<'a> {
+    commits: i64,
+    additions: i64,
+    deletions: i64,
+    net_lines: i64,
+    files_changed: i64,
+    periods: Vec<PeriodDelta<'a>>,
+}
+
+/// JSON document layout
+#[derive(Serialize)]
+struct CompareJson<'a> {
+    left: &'a AnalysisResult,
+    right: &'a AnalysisResult,
+    delta: Delta<'a>,
+}
+
+impl<'a> CompareReport<'a> {
+    /// Create a report for two results covering the same date range
+    #[must_use]
+    pub const fn new(left: &'a AnalysisResult, right: &'a AnalysisResult) -> Self {
+        Self { left, right }
+    }
+
+    /// Pair up periods from both sides by date, oldest first
+    fn periods(&self) -> Vec<AlignedPeriod<'a>> {
+        let mut by_date: BTreeMap<NaiveDate, AlignedPeriod<'a>> = BTreeMap::new();
+        for (stat, is_left) in self
+            .left
+            .stats
+            .iter()
+            .map(|s| (s, true))
+            .chain(self.right.stats.iter().map(|s| (s, false)))
+        {
+            let entry = by_date.entry(stat.date).or_insert_with(|| AlignedPeriod {
+                date: stat.date,
+                label: &stat.label,
+                left: None,
+                right: None,
+            });
+            if is_left {
+                entry.left = Some(stat);
+            } else {
+                entry.right = Some(stat);
+            }
+        }
+        by_date.into_values().collect()
+    }
+
+    /// Render the report as a per-period table followed by a totals table
+    #[must_use]
+    pub fn to_table(&self) -> String {
+        let left_name = &self.left.repository;
+        let right_name = &self.right.repository;
+
+        let mut periods = Table::new();
+        periods.load_preset(UTF8_FULL).set_header([
+            "Period".to_string(),
+            format!("{left_name} Commits"),
+            format!("{left_name} Net"),
+            format!("{right_name} Commits"),
+            format!("{right_name} Net"),
+            "\u{394} Commits".to_string(),
+            "\u{394} Net".to_string(),
+        ]);
+        for period in self.periods() {
+            let (left_commits, left_net) = period_values(period.left);
+            let (right_commits, right_net) = period_values(period.right);
+            periods.add_row([
+                period.label.to_string(),
+                optional_cell(period.left, left_commits),
+                optional_cell(period.left, left_net),
+                optional_cell(period.right, right_commits),
+                optional_cell(period.right, right_net),
+                format_delta(right_commits - left_commits),
+                format_delta(right_net - left_net),
+            ]);
+        }
+
+        let mut totals = Table::new();
+        totals.load_preset(UTF8_FULL).set_header([
+            "Total",
+            left_name.as_str(),
+            right_name.as_str(),
+            "\u{394}",
+        ]);
+        for (label, left, right) in total_rows(&self.left.total, &self.right.total) {
+            totals.add_row([
+                label.to_string(),
+                format_with_commas_i64(left),
+                format_with_commas_i64(right),
+                format_delta(right - left),
+            ]);
+        }
+
+        format!(
+            "{} \u{2192} {} ({})\n{periods}\n\n{totals}",
+            self.left.from, self.left.to, self.left.period
+        )
+    }
+
+    /// Render the report as `{ "left": ..., "right": ..., "delta": ... }`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails
+    pub fn to_json(&self) -> Result<String> {
+        let periods = self
+            .periods()
+            .into_iter()
+            .map(|period| {
+                let (left_commits, left_net) = period_values(period.left);
+                let (right_commits, right_net) = period_values(period.right);
+                PeriodDelta {
+                    period: period.label,
+                    date: period.date,
+                    commits: right_commits - left_commits,
+                    net_lines: right_net - left_net,
+                }
+            })
+            .collect();
+
+        let [commits, additions, deletions, net_lines, files_changed] =
+            total_rows(&self.left.total, &self.right.total).map(|(_, left, right)| right - left);
+
+        let json = CompareJson {
+            left: self.left,
+            right: self.right,
+            delta: Delta {
+                commits,
+                additions,
+                deletions,
+                net_lines,
+                files_changed,
+                periods,
+            },
+        };
+        Ok(serde_json::to_string_pretty(&json)?)
+    }
+}
+
+/// Commits and net lines of a period, zero when the side has no such period
+fn period_values(stat: Option<&PeriodStats>) -> (i64, i64) {
+    stat.map_or((0, 0), |s| (i64::from(s.commits), s.net_lines))
+}
+
+/// Totals as `(label, left, right)` rows
+fn total_rows(left: &TotalStats, right: &TotalStats) -> [(&'static str, i64, i64); 5] {
+    [
+        ("Commits", i64::from(left.commits), i64::from(right.commits)),
+        ("+Lines", left.additions as i64, right.additions as i64),
+        ("-Lines", left.deletions as i64, right.deletions as i64),
+        ("Net", left.net_lines, right.net_lines),
+        (
+            "Files",
+            i64::from(left.files_changed),
+            i64::from(right.files_changed),
+        ),
+    ]
+}
+
+/// Format a cell value, or `-` when the side has no such period
+fn optional_cell(stat: Option<&PeriodStats>, value: i64) -> String {
+    stat.map_or_else(|| "-".to_string(), |_| format_with_commas_i64(value))
+}
+
+/// Format a signed difference, e.g. `+1,200`, `-35`, or `0`
+fn format_delta(value: i64) -> String {
+    if value > 0 {
+        format!("+{}", format_with_commas_u64(value.unsigned_abs()))
+    } else {
+        format_with_commas_i64(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(date: NaiveDate, commits: u32, net_lines: i64) -> PeriodStats {
+        PeriodStats {
+            label: date.to_string(),
+            date,
+            commits,
+            additions: net_lines.max(0).unsigned_abs(),
+            deletions: net_lines.min(0).unsigned_abs(),
+            net_lines,
+            files_changed: commits,
+            ..Default::default()
+        }
+    }
+
+    fn result(name: &str, stats: Vec<PeriodStats>) -> AnalysisResult {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        AnalysisResult::new(name.to_string(), "daily".to_string(), from, to, stats)
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn test_periods_align_by_date_not_index() {
+        // The left side has no entry for Jan 2
+        let left = result("a", vec![period(day(1), 2, 10), period(day(3), 1, 5)]);
+        let right = result(
+            "b",
+            vec![
+                period(day(1), 1, 4),
+                period(day(2), 3, 30),
+                period(day(3), 4, -8),
+            ],
+        );
+
+        let json = CompareReport::new(&left, &right).to_json().unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let periods = parsed["delta"]["periods"].as_array().unwrap();
+
+        assert_eq!(periods.len(), 3);
+        assert_eq!(periods[0]["commits"], -1);
+        assert_eq!(periods[1]["period"], "2024-01-02");
+        assert_eq!(periods[1]["commits"], 3);
+        assert_eq!(periods[1]["net_lines"], 30);
+        assert_eq!(periods[2]["commits"], 3);
+        assert_eq!(periods[2]["net_lines"], -13);
+    }
+
+    #[test]
+    fn test_json_contains_both_sides_and_total_delta() {
+        let left = result("a", vec![period(day(1), 2, 10)]);
+        let right = result("b", vec![period(day(1), 5, -3)]);
+
+        let json = CompareReport::new(&left, &right).to_json().unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["left"]["repository"], "a");
+        assert_eq!(parsed["right"]["repository"], "b");
+        assert_eq!(parsed["delta"]["commits"], 3);
+        assert_eq!(parsed["delta"]["net_lines"], -13);
+        assert_eq!(parsed["delta"]["deletions"], 3);
+    }
+
+    #[test]
+    fn test_table_marks_missing_periods() {
+        let left = result("a", vec![period(day(1), 2, 1_500)]);
+        let right = result("b", vec![period(day(1), 2, 0), period(day(2), 1, 7)]);
+
+        let table = CompareReport::new(&left, &right).to_table();
+
+        assert!(table.contains("a Commits"));
+        assert!(table.contains("b Net"));
+        assert!(table.contains("-1,500"));
+        let jan2 = table.lines().find(|l| l.contains("2024-01-02")).unwrap();
+        assert!(jan2.contains(" - "));
+        assert!(jan2.contains("+7"));
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(1_200), "+1,200");
+        assert_eq!(format_delta(-35), "-35");
+        assert_eq!(format_delta(0), "0");
+    }
+}
@@ -2,13 +2,23 @@
 
 use crate::error::Result;
 use crate::output::Formatter;
-use crate::stats::AnalysisResult;
+use crate::output::columns::{CSV_COLUMNS, Column, Number, Row, SortKey, project, sorted_rows};
+use crate::stats::{ActivityStats, AnalysisResult, FileDetail};
+use std::borrow::Cow;
 use std::fmt::Write;
 
 /// CSV output formatter
 pub struct CsvFormatter {
     /// Whether to include headers
     pub include_headers: bool,
+    /// Whether to append weekday and hour activity sections after the main table
+    pub activity_sections: bool,
+    /// Columns of the main table, in order
+    pub columns: Vec<Column>,
+    /// Row order of the main table (date order when `None`)
+    pub sort: Option<SortKey>,
+    /// Whether to leave periods without any activity out of the main table
+    pub skip_empty: bool,
 }
 
 impl CsvFormatter {
@@ -17,6 +27,10 @@ impl CsvFormatter {
     pub fn new() -> Self {
         Self {
             include_headers: true,
+            activity_sections: false,
+            columns: CSV_COLUMNS.to_vec(),
+            sort: None,
+            skip_empty: false,
         }
     }
 
@@ -25,8 +39,45 @@ impl CsvFormatter {
     pub fn without_headers() -> Self {
         Self {
             include_headers: false,
+            activity_sections: false,
+            columns: CSV_COLUMNS.to_vec(),
+            sort: None,
+            skip_empty: false,
         }
     }
+
+    /// Append weekday and hour activity sections when formatting with activity
+    ///
+    /// Off by default so the output stays a single machine-readable table.
+    #[must_use]
+    pub const fn with_activity_sections(mut self, activity_sections: bool) -> Self {
+        self.activity_sections = activity_sections;
+        self
+    }
+
+    /// Write only `columns`, in that order, in the main table
+    #[must_use]
+    pub fn with_columns(mut self, columns: Vec<Column>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Order the main table's rows by a column instead of by date
+    ///
+    /// The total row stays last.
+    #[must_use]
+    pub const fn with_sort(mut self, sort: Option<SortKey>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Leave periods without any activity out of the main table; the total
+    /// row still counts the whole range
+    #[must_use]
+    pub const fn with_skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
 }
 
 impl Default for CsvFormatter {
@@ -35,36 +86,98 @@ impl Default for CsvFormatter {
     }
 }
 
+impl CsvFormatter {
+    /// One row per (commit, file) instead of per period, without a total row
+    fn format_file_details(&self, details: &[FileDetail]) -> String {
+        let mut output = String::new();
+        if self.include_headers {
+            output.push_str("commit,date,path,additions,deletions\n");
+        }
+        for detail in details {
+            let _ = writeln!(
+                output,
+                "{},{},{},{},{}",
+                detail.id,
+                detail.date,
+                quote_field(&detail.path),
+                detail.additions,
+                detail.deletions
+            );
+        }
+        output
+    }
+}
+
+/// Quote a field containing a comma, quote, or line break, doubling inner quotes
+fn quote_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Format a CSV cell as a plain number
+fn format_number(number: Number) -> String {
+    match number {
+        Number::Unsigned(value) => value.to_string(),
+        Number::Signed(value) => value.to_string(),
+    }
+}
+
 impl Formatter for CsvFormatter {
     fn format(&self, result: &AnalysisResult) -> Result<String> {
+        if let Some(details) = &result.file_details {
+            return Ok(self.format_file_details(details));
+        }
+
         let mut output = String::new();
 
         // Add headers if enabled
         if self.include_headers {
-            output.push_str("date,commits,additions,deletions,net_lines,files_changed\n");
+            let headers: Vec<&str> = self.columns.iter().map(|c| c.csv_header()).collect();
+            let _ = writeln!(output, "{}", headers.join(","));
         }
 
         // Add data rows
-        for stat in &result.stats {
-            let _ = writeln!(
-                output,
-                "{},{},{},{},{},{}",
-                stat.date,
-                stat.commits,
-                stat.additions,
-                stat.deletions,
-                stat.net_lines,
-                stat.files_changed
-            );
+        for stat in sorted_rows(&result.stats, self.sort, self.skip_empty) {
+            let date = stat.date.to_string();
+            let cells = project(&self.columns, Row::Period(stat), &date, format_number);
+            let _ = writeln!(output, "{}", cells.join(","));
         }
 
         // Add total row
-        let total = &result.total;
-        let _ = writeln!(
-            output,
-            "TOTAL,{},{},{},{},{}",
-            total.commits, total.additions, total.deletions, total.net_lines, total.files_changed
+        let cells = project(
+            &self.columns,
+            Row::Total(&result.total),
+            "TOTAL",
+            format_number,
         );
+        let _ = writeln!(output, "{}", cells.join(","));
+
+        Ok(output)
+    }
+
+    fn format_with_activity(
+        &self,
+        result: &AnalysisResult,
+        activity: &ActivityStats,
+    ) -> Result<String> {
+        let mut output = self.format(result)?;
+        if !self.activity_sections {
+            return Ok(output);
+        }
+
+        // Each section is separated by a blank line and has its own header
+        output.push_str("\nweekday,commits\n");
+        for (label, count) in ActivityStats::weekday_labels().iter().zip(activity.weekday) {
+            let _ = writeln!(output, "{label},{count}");
+        }
+
+        output.push_str("\nhour,commits\n");
+        for (hour, count) in activity.hourly.iter().enumerate() {
+            let _ = writeln!(output, "{hour},{count}");
+        }
 
         Ok(output)
     }
@@ -89,6 +202,7 @@ mod tests {
                 deletions: 20,
                 net_lines: 80,
                 files_changed: 10,
+                ..Default::default()
             },
             PeriodStats {
                 label: "2024-01-02".to_string(),
@@ -98,6 +212,7 @@ mod tests {
                 deletions: 10,
                 net_lines: 40,
                 files_changed: 5,
+                ..Default::default()
             },
         ];
 
@@ -109,11 +224,25 @@ mod tests {
             stats,
             total: TotalStats {
                 commits: 8,
+                merge_commits: 0,
                 additions: 150,
                 deletions: 30,
                 net_lines: 120,
                 files_changed: 15,
+                unique_files: 15,
             },
+            extensions: None,
+            top_commits: None,
+            top_files: None,
+            top_dirs: None,
+            file_details: None,
+            repositories: Vec::new(),
+            comparison: None,
+            moving_average: None,
+            truncated: false,
+            work_pattern: None,
+            year_over_year: None,
+            gaps: Vec::new(),
         }
     }
 
@@ -130,6 +259,27 @@ mod tests {
         assert!(csv.contains("TOTAL,8,150,30,120,15\n"));
     }
 
+    #[test]
+    fn test_csv_formatter_skip_empty_keeps_total() {
+        let mut result = make_result();
+        result.stats.push(PeriodStats::new(
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+        ));
+        assert!(
+            CsvFormatter::new()
+                .format(&result)
+                .unwrap()
+                .contains("2024-01-03,0,0,0,0,0\n")
+        );
+
+        let csv = CsvFormatter::new()
+            .with_skip_empty(true)
+            .format(&result)
+            .unwrap();
+        assert_eq!(csv.lines().count(), 4, "{csv}");
+        assert!(csv.contains("TOTAL,8,150,30,120,15\n"), "{csv}");
+    }
+
     #[test]
     fn test_csv_formatter_without_headers() {
         let formatter = CsvFormatter::without_headers();
@@ -153,6 +303,29 @@ mod tests {
         assert_eq!(lines.len(), 4);
     }
 
+    #[test]
+    fn test_csv_activity_sections() {
+        let result = make_result();
+        let mut activity = ActivityStats::default();
+        activity.weekday[0] = 4;
+        activity.hourly[13] = 2;
+
+        // Without the flag the output is just the main table
+        let plain = CsvFormatter::new()
+            .format_with_activity(&result, &activity)
+            .unwrap();
+        assert_eq!(plain, CsvFormatter::new().format(&result).unwrap());
+
+        let csv = CsvFormatter::new()
+            .with_activity_sections(true)
+            .format_with_activity(&result, &activity)
+            .unwrap();
+        assert!(csv.contains("TOTAL,8,150,30,120,15\n\nweekday,commits\nMon,4\nTue,0\n"));
+        assert!(csv.contains("Sun,0\n\nhour,commits\n0,0\n"));
+        assert!(csv.contains("\n13,2\n"));
+        assert!(csv.ends_with("23,0\n"));
+    }
+
     #[test]
     fn test_csv_negative_values() {
         let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
@@ -169,14 +342,29 @@ mod tests {
                 deletions: 50,
                 net_lines: -40,
                 files_changed: 1,
+                ..Default::default()
             }],
             total: TotalStats {
                 commits: 1,
+                merge_commits: 0,
                 additions: 10,
                 deletions: 50,
                 net_lines: -40,
                 files_changed: 1,
+                unique_files: 1,
             },
+            extensions: None,
+            top_commits: None,
+            top_files: None,
+            top_dirs: None,
+            file_details: None,
+            repositories: Vec::new(),
+            comparison: None,
+            moving_average: None,
+            truncated: false,
+            work_pattern: None,
+            year_over_year: None,
+            gaps: Vec::new(),
         };
 
         let formatter = CsvFormatter::new();
@@ -184,4 +372,42 @@ mod tests {
 
         assert!(csv.contains("-40"));
     }
+
+    #[test]
+    fn test_csv_file_details_rows() {
+        let mut result = make_result();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        result.file_details = Some(vec![
+            FileDetail {
+                id: "abc1234".to_string(),
+                date,
+                path: "src/main.rs".to_string(),
+                additions: 12,
+                deletions: 3,
+            },
+            FileDetail {
+                id: "abc1234".to_string(),
+                date,
+                path: "docs/a, \"b\".md".to_string(),
+                additions: 1,
+                deletions: 0,
+            },
+        ]);
+
+        let csv = CsvFormatter::new().format(&result).unwrap();
+        assert_eq!(
+            csv,
+            "commit,date,path,additions,deletions\n\
+             abc1234,2024-01-02,src/main.rs,12,3\n\
+             abc1234,2024-01-02,\"docs/a, \"\"b\"\".md\",1,0\n"
+        );
+    }
+
+    #[test]
+    fn test_quote_field() {
+        assert_eq!(quote_field("plain/path.rs"), "plain/path.rs");
+        assert_eq!(quote_field("a,b.txt"), "\"a,b.txt\"");
+        assert_eq!(quote_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote_field("two\nlines"), "\"two\nlines\"");
+    }
 }
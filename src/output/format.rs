@@ -1,7 +1,7 @@
 //! Output formatter trait
 
 use crate::error::Result;
-use crate::stats::AnalysisResult;
+use crate::stats::{ActivityStats, AnalysisResult};
 
 /// Trait for output formatters
 pub trait Formatter {
@@ -11,4 +11,19 @@ pub trait Formatter {
     ///
     /// Returns an error if formatting fails
     fn format(&self, result: &AnalysisResult) -> Result<String>;
+
+    /// Format the analysis result together with weekday/hour activity statistics
+    ///
+    /// Formatters that have no place for activity data fall back to [`Formatter::format`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if formatting fails
+    fn format_with_activity(
+        &self,
+        result: &AnalysisResult,
+        _activity: &ActivityStats,
+    ) -> Result<String> {
+        self.format(result)
+    }
 }
@@ -0,0 +1,228 @@
+//! GitHub-style contribution graph output formatter
+
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+use crate::error::Result;
+use crate::output::Formatter;
+use crate::stats::AnalysisResult;
+use chrono::{Datelike, Days, NaiveDate};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Intensity glyphs from no commits to the busiest day
+const RAMP: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+/// 256-color shades used for the same levels on a terminal
+const PALETTE: [u8; 5] = [237, 22, 28, 34, 40];
+/// Weekday row labels from Sunday, every other one shown like on GitHub
+const ROW_LABELS: [&str; 7] = ["", "Mon", "", "Wed", "", "Fri", ""];
+/// Width of the weekday label column
+const LABEL_WIDTH: usize = 4;
+/// Width of one week column: a cell and a space
+const COLUMN_WIDTH: usize = 2;
+
+/// Contribution graph formatter
+///
+/// Draws one column per week (Sunday to Saturday) and one row per weekday,
+/// shading each day by its commits relative to the busiest day, with month
+/// names along the top. Expects daily stats.
+pub struct GraphFormatter {
+    color: bool,
+}
+
+impl GraphFormatter {
+    /// Create a new graph formatter drawing shade glyphs
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { color: false }
+    }
+
+    /// Draw colored blocks with ANSI escape codes instead of shade glyphs
+    #[must_use]
+    pub const fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// One cell at intensity `level`
+    fn cell(&self, level: usize) -> String {
+        if self.color {
+            format!("\x1b[38;5;{}m■\x1b[0m", PALETTE[level])
+        } else {
+            RAMP[level].to_string()
+        }
+    }
+}
+
+impl Default for GraphFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for GraphFormatter {
+    fn format(&self, result: &AnalysisResult) -> Result<String> {
+        let commits: HashMap<NaiveDate, u32> =
+            result.stats.iter().map(|s| (s.date, s.commits)).collect();
+        let max = commits.values().copied().max().unwrap_or(0);
+        let (from, to) = (result.from, result.to);
+
+        // The first column starts on the Sunday on or before the range start,
+        // leaving the days before it blank
+        let start = from - Days::new(u64::from(from.weekday().num_days_from_sunday()));
+        let weeks = (to - start).num_days().max(0) as usize / 7 + 1;
+
+        let mut output = month_labels(start, from, to, weeks);
+        for (row, label) in ROW_LABELS.iter().enumerate() {
+            let mut line = format!("{label:<LABEL_WIDTH$}");
+            for week in 0..weeks {
+                let day = start + Days::new((week * 7 + row) as u64);
+                if day < from || day > to {
+                    line.push_str("  ");
+                } else {
+                    let level = intensity(commits.get(&day).copied().unwrap_or(0), max);
+                    let _ = write!(line, "{} ", self.cell(level));
+                }
+            }
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+
+        let legend: Vec<String> = (0..RAMP.len()).map(|level| self.cell(level)).collect();
+        let _ = writeln!(
+            output,
+            "\n{:LABEL_WIDTH$}Less {} More",
+            "",
+            legend.join(" ")
+        );
+        Ok(output)
+    }
+}
+
+/// Map a day's commits to an intensity level (0 = none, 4 = the busiest day)
+fn intensity(commits: u32, max: u32) -> usize {
+    if commits == 0 || max == 0 {
+        return 0;
+    }
+    // Scale 1..=max onto 1..=4, rounding up so any activity is visible
+    (u64::from(commits) * 4)
+        .div_ceil(u64::from(max))
+        .clamp(1, 4) as usize
+}
+
+/// Month names above the first week column of each month in the range
+///
+/// When two names would collide, the later month wins, so a range starting at
+/// the end of a month labels the next one.
+fn month_labels(start: NaiveDate, from: NaiveDate, to: NaiveDate, weeks: usize) -> String {
+    let mut labels: Vec<(usize, String)> = Vec::new();
+    let mut previous_month = None;
+    for week in 0..weeks {
+        let first_day = (start + Days::new((week * 7) as u64)).max(from);
+        if first_day > to {
+            break;
+        }
+        let month = (first_day.year(), first_day.month());
+        if previous_month == Some(month) {
+            continue;
+        }
+        previous_month = Some(month);
+
+        let position = LABEL_WIDTH + week * COLUMN_WIDTH;
+        if labels
+            .last()
+            .is_some_and(|(last, name)| last + name.len() >= position)
+        {
+            labels.pop();
+        }
+        labels.push((position, first_day.format("%b").to_string()));
+    }
+
+    let mut line = String::new();
+    for (position, name) in labels {
+        let _ = write!(line, "{:width$}{name}", "", width = position - line.len());
+    }
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::PeriodStats;
+
+    /// Wednesday 2024-01-31 to Saturday 2024-02-17: three week columns
+    fn three_weeks() -> AnalysisResult {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 2, 17).unwrap();
+        let stats = from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .enumerate()
+            .map(|(i, date)| PeriodStats {
+                commits: [0, 1, 2, 4, 8][i % 5],
+                ..PeriodStats::new(date)
+            })
+            .collect();
+        AnalysisResult::new("kodo".to_string(), "daily".to_string(), from, to, stats)
+    }
+
+    #[test]
+    fn test_intensity_levels() {
+        assert_eq!(intensity(0, 8), 0);
+        assert_eq!(intensity(1, 8), 1);
+        assert_eq!(intensity(2, 8), 1);
+        assert_eq!(intensity(3, 8), 2);
+        assert_eq!(intensity(4, 8), 2);
+        assert_eq!(intensity(7, 8), 4);
+        assert_eq!(intensity(8, 8), 4);
+        assert_eq!(intensity(0, 0), 0);
+    }
+
+    #[test]
+    fn test_graph_renders_three_weeks() {
+        let output = GraphFormatter::new().format(&three_weeks()).unwrap();
+
+        // Sunday 2024-01-28 pads the first column; "Jan" would collide with "Feb"
+        let expected = [
+            "      Feb",
+            "      █ ░",
+            "Mon   · ░",
+            "      ░ ▒",
+            "Wed · ░ █",
+            "    ░ ▒ ·",
+            "Fri ░ █ ░",
+            "    ▒ · ░",
+            "",
+            "    Less · ░ ▒ ▓ █ More",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_graph_color_uses_ansi_blocks() {
+        let output = GraphFormatter::new()
+            .with_color(true)
+            .format(&three_weeks())
+            .unwrap();
+
+        assert!(output.contains("\x1b[38;5;40m■\x1b[0m"));
+        assert!(!output.contains('█'));
+    }
+
+    #[test]
+    fn test_graph_empty_range_is_all_blank_levels() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+        let result = AnalysisResult::new(
+            "kodo".to_string(),
+            "daily".to_string(),
+            day,
+            day,
+            vec![PeriodStats::new(day)],
+        );
+
+        let output = GraphFormatter::new().format(&result).unwrap();
+        assert!(output.starts_with("    Mar\n    ·\n"), "{output}");
+    }
+}
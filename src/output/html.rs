@@ -0,0 +1,267 @@
+//! HTML report formatter
+
+#![allow(clippy::cast_precision_loss)]
+
+use crate::error::Result;
+use crate::output::Formatter;
+use crate::stats::AnalysisResult;
+use std::fmt::Write;
+
+/// Chart drawing area width in pixels
+const CHART_WIDTH: f64 = 720.0;
+/// Chart drawing area height in pixels
+const CHART_HEIGHT: f64 = 200.0;
+
+/// Inline stylesheet (kept in the file so the report is self-contained)
+const STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Helvetica,Arial,sans-serif;margin:2rem;color:#24292f}\
+h1{margin-bottom:.25rem}\
+.range{color:#57606a;margin-top:0}\
+.summary{display:flex;gap:1.5rem;margin:1rem 0 2rem}\
+.summary div{border:1px solid #d0d7de;border-radius:6px;padding:.5rem 1rem}\
+.summary strong{display:block;font-size:1.4rem}\
+table{border-collapse:collapse;margin-top:1rem}\
+th,td{border:1px solid #d0d7de;padding:.25rem .75rem;text-align:right}\
+th:first-child,td:first-child{text-align:left}\
+tr.total{font-weight:bold}\
+svg{display:block;margin:.5rem 0 1.5rem}";
+
+/// HTML report formatter
+///
+/// Renders a standalone HTML document with inline CSS and SVG charts,
+/// so the file has no external dependencies.
+pub struct HtmlFormatter;
+
+impl HtmlFormatter {
+    /// Create a new HTML formatter
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HtmlFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for HtmlFormatter {
+    fn format(&self, result: &AnalysisResult) -> Result<String> {
+        let mut out = String::new();
+        let repository = escape(&result.repository);
+        let total = &result.total;
+
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(out, "<title>kodo report: {repository}</title>");
+        let _ = writeln!(out, "<style>{STYLE}</style>");
+        out.push_str("</head>\n<body>\n");
+
+        // Summary header
+        let _ = writeln!(out, "<h1>{repository}</h1>");
+        let _ = writeln!(
+            out,
+            "<p class=\"range\">{} &rarr; {} ({})</p>",
+            result.from, result.to, result.period
+        );
+        out.push_str("<div class=\"summary\">\n");
+        for (label, value) in [
+            ("Commits", total.commits.to_string()),
+            ("Additions", format!("+{}", total.additions)),
+            ("Deletions", format!("-{}", total.deletions)),
+            ("Net", total.net_lines.to_string()),
+            ("Files", total.files_changed.to_string()),
+        ] {
+            let _ = writeln!(out, "<div><strong>{value}</strong>{label}</div>");
+        }
+        out.push_str("</div>\n");
+
+        // Charts
+        out.push_str("<h2>Commits</h2>\n");
+        let commits: Vec<u64> = result.stats.iter().map(|s| u64::from(s.commits)).collect();
+        out.push_str(&render_bar_svg("Commits per period", &commits, "#0969da"));
+
+        out.push_str("<h2>Additions / Deletions</h2>\n");
+        let additions: Vec<u64> = result.stats.iter().map(|s| s.additions).collect();
+        let deletions: Vec<u64> = result.stats.iter().map(|s| s.deletions).collect();
+        out.push_str(&render_diverging_svg(
+            "Additions and deletions per period",
+            &additions,
+            &deletions,
+        ));
+
+        // Per-period table
+        out.push_str("<h2>Details</h2>\n<table>\n<thead><tr>");
+        for header in ["Period", "Commits", "+Lines", "-Lines", "Net", "Files"] {
+            let _ = write!(out, "<th>{header}</th>");
+        }
+        out.push_str("</tr></thead>\n<tbody>\n");
+        for stat in &result.stats {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&stat.label),
+                stat.commits,
+                stat.additions,
+                stat.deletions,
+                stat.net_lines,
+                stat.files_changed
+            );
+        }
+        let _ = writeln!(
+            out,
+            "<tr class=\"total\"><td>TOTAL</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            total.commits, total.additions, total.deletions, total.net_lines, total.files_changed
+        );
+        out.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+
+        Ok(out)
+    }
+}
+
+/// Render a simple vertical bar chart as inline SVG
+fn render_bar_svg(title: &str, values: &[u64], color: &str) -> String {
+    let mut svg = svg_open(title, CHART_HEIGHT);
+    let max = values.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let slot = CHART_WIDTH / values.len().max(1) as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+
+    for (i, &value) in values.iter().enumerate() {
+        let height = value as f64 / max * CHART_HEIGHT;
+        let x = i as f64 * slot + (slot - bar_width) / 2.0;
+        let y = CHART_HEIGHT - height;
+        let _ = write!(
+            svg,
+            "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{bar_width:.1}\" height=\"{height:.1}\" fill=\"{color}\"><title>{value}</title></rect>"
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Render additions above and deletions below a center axis as inline SVG
+fn render_diverging_svg(title: &str, additions: &[u64], deletions: &[u64]) -> String {
+    let mut svg = svg_open(title, CHART_HEIGHT);
+    let max = additions
+        .iter()
+        .chain(deletions)
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let center = CHART_HEIGHT / 2.0;
+    let slot = CHART_WIDTH / additions.len().max(1) as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+
+    for (i, (&add, &del)) in additions.iter().zip(deletions).enumerate() {
+        let x = i as f64 * slot + (slot - bar_width) / 2.0;
+        let add_height = add as f64 / max * center;
+        let del_height = del as f64 / max * center;
+        let _ = write!(
+            svg,
+            "<rect x=\"{x:.1}\" y=\"{:.1}\" width=\"{bar_width:.1}\" height=\"{add_height:.1}\" fill=\"#1a7f37\"><title>+{add}</title></rect>",
+            center - add_height
+        );
+        let _ = write!(
+            svg,
+            "<rect x=\"{x:.1}\" y=\"{center:.1}\" width=\"{bar_width:.1}\" height=\"{del_height:.1}\" fill=\"#cf222e\"><title>-{del}</title></rect>"
+        );
+    }
+
+    let _ = write!(
+        svg,
+        "<line x1=\"0\" y1=\"{center:.1}\" x2=\"{CHART_WIDTH:.1}\" y2=\"{center:.1}\" stroke=\"#8c959f\"/>"
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn svg_open(title: &str, height: f64) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH:.0}\" height=\"{height:.0}\" viewBox=\"0 0 {CHART_WIDTH:.0} {height:.0}\" role=\"img\" aria-label=\"{title}\">"
+    )
+}
+
+/// Escape text for safe inclusion in HTML
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::PeriodStats;
+    use chrono::NaiveDate;
+
+    fn make_result() -> AnalysisResult {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let stats = vec![
+            PeriodStats {
+                label: "2024-01-01".to_string(),
+                date: from,
+                commits: 5,
+                additions: 100,
+                deletions: 20,
+                net_lines: 80,
+                files_changed: 10,
+                ..Default::default()
+            },
+            PeriodStats {
+                label: "2024-01-02".to_string(),
+                date: to,
+                commits: 3,
+                additions: 50,
+                deletions: 10,
+                net_lines: 40,
+                files_changed: 5,
+                ..Default::default()
+            },
+        ];
+
+        AnalysisResult::new(
+            "test-repo".to_string(),
+            "daily".to_string(),
+            from,
+            to,
+            stats,
+        )
+    }
+
+    #[test]
+    fn test_html_formatter_contains_summary_and_charts() {
+        let html = HtmlFormatter::new().format(&make_result()).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("test-repo"));
+        assert!(html.contains("2024-01-01 &rarr; 2024-01-02"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.contains("<tr class=\"total\"><td>TOTAL</td><td>8</td>"));
+    }
+
+    #[test]
+    fn test_html_formatter_is_self_contained() {
+        let html = HtmlFormatter::new().format(&make_result()).unwrap();
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("src=\"http"));
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(escape("<a & 'b'>"), "&lt;a &amp; &#39;b&#39;&gt;");
+    }
+}
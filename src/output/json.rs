@@ -1,26 +1,103 @@
 //! JSON output formatter
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::output::Formatter;
-use crate::stats::AnalysisResult;
+use crate::stats::{ActivityStats, AnalysisResult, PeriodStats};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Version of the JSON layout; the major part changes on incompatible changes
+pub const SCHEMA_VERSION: &str = "1.0";
+
+/// Major schema version that [`JsonFormatter::parse`] accepts
+const SUPPORTED_MAJOR: &str = "1";
+
+/// Analysis result with the schema version and an optional `activity` object
+#[derive(Serialize)]
+struct Payload<'a> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    result: &'a AnalysisResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activity: Option<&'a ActivityStats>,
+}
+
+/// Owned form of [`Payload`] read back from a file
+#[derive(Deserialize)]
+struct ParsedPayload {
+    #[serde(flatten)]
+    result: AnalysisResult,
+    #[serde(default)]
+    activity: ActivityStats,
+}
+
+/// Just the version, checked before the rest of the document is interpreted
+#[derive(Deserialize)]
+struct SchemaVersion {
+    schema_version: Option<String>,
+}
 
 /// JSON output formatter
 pub struct JsonFormatter {
     /// Whether to pretty-print the output
     pub pretty: bool,
+    /// Whether to leave periods without any activity out of `stats`
+    pub skip_empty: bool,
 }
 
 impl JsonFormatter {
     /// Create a new JSON formatter with pretty printing enabled
     #[must_use]
     pub fn new() -> Self {
-        Self { pretty: true }
+        Self {
+            pretty: true,
+            skip_empty: false,
+        }
     }
 
     /// Create a compact JSON formatter (no pretty printing)
     #[must_use]
     pub fn compact() -> Self {
-        Self { pretty: false }
+        Self {
+            pretty: false,
+            skip_empty: false,
+        }
+    }
+
+    /// Leave periods without any activity out of `stats` (and the matching
+    /// `moving_average` entries); `total` still counts the whole range
+    #[must_use]
+    pub const fn with_skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    /// Read back a result and its activity from JSON written by this formatter
+    ///
+    /// A missing `activity` object yields empty activity stats.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed, or if `schema_version` is
+    /// missing or has a major version other than the one this build writes.
+    pub fn parse(input: &str) -> Result<(AnalysisResult, ActivityStats)> {
+        let SchemaVersion { schema_version } = serde_json::from_str(input)?;
+        let Some(version) = schema_version else {
+            return Err(Error::UnsupportedSchema {
+                message: "missing schema_version (expected output of `kodo --output json`)"
+                    .to_string(),
+            });
+        };
+        if version.split('.').next() != Some(SUPPORTED_MAJOR) {
+            return Err(Error::UnsupportedSchema {
+                message: format!(
+                    "schema_version {version} is not supported (this kodo reads {SUPPORTED_MAJOR}.x)"
+                ),
+            });
+        }
+
+        let payload: ParsedPayload = serde_json::from_str(input)?;
+        Ok((payload.result, payload.activity))
     }
 }
 
@@ -30,21 +107,72 @@ impl Default for JsonFormatter {
     }
 }
 
-impl Formatter for JsonFormatter {
-    fn format(&self, result: &AnalysisResult) -> Result<String> {
+impl JsonFormatter {
+    /// `result` as written, without its empty periods with `skip_empty`
+    fn visible<'a>(&self, result: &'a AnalysisResult) -> Cow<'a, AnalysisResult> {
+        if !self.skip_empty || !result.stats.iter().any(PeriodStats::is_empty) {
+            return Cow::Borrowed(result);
+        }
+        let keep: Vec<bool> = result.stats.iter().map(|stat| !stat.is_empty()).collect();
+        let mut result = result.clone();
+        result.stats.retain(|stat| !stat.is_empty());
+        if let Some(average) = &mut result.moving_average {
+            for series in [
+                &mut average.commits,
+                &mut average.additions,
+                &mut average.deletions,
+                &mut average.net_lines,
+                &mut average.files_changed,
+                &mut average.unique_files,
+            ] {
+                let mut keep = keep.iter();
+                series.retain(|_| keep.next().copied().unwrap_or(true));
+            }
+        }
+        Cow::Owned(result)
+    }
+
+    fn to_json<T: Serialize>(&self, value: &T) -> Result<String> {
         let json = if self.pretty {
-            serde_json::to_string_pretty(result)?
+            serde_json::to_string_pretty(value)?
         } else {
-            serde_json::to_string(result)?
+            serde_json::to_string(value)?
         };
         Ok(json)
     }
 }
 
+impl Formatter for JsonFormatter {
+    fn format(&self, result: &AnalysisResult) -> Result<String> {
+        self.to_json(&Payload {
+            schema_version: SCHEMA_VERSION,
+            result: &self.visible(result),
+            activity: None,
+        })
+    }
+
+    fn format_with_activity(
+        &self,
+        result: &AnalysisResult,
+        activity: &ActivityStats,
+    ) -> Result<String> {
+        self.to_json(&Payload {
+            schema_version: SCHEMA_VERSION,
+            result: &self.visible(result),
+            activity: Some(activity),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stats::{PeriodStats, TotalStats};
+    use crate::cli::args::Period;
+    use crate::stats::{
+        ComparisonStats, DateRange, ExtensionStats, FileChurn, FileDetail, RepoStats, TopCommit,
+        TotalStats, WorkPatternBucket, WorkPatternStats, YearOverYear, aggregate_by_period,
+        moving_averages,
+    };
     use chrono::NaiveDate;
 
     fn make_result() -> AnalysisResult {
@@ -60,6 +188,7 @@ mod tests {
                 deletions: 20,
                 net_lines: 80,
                 files_changed: 10,
+                ..Default::default()
             },
             PeriodStats {
                 label: "2024-01-02".to_string(),
@@ -69,6 +198,7 @@ mod tests {
                 deletions: 10,
                 net_lines: 40,
                 files_changed: 5,
+                ..Default::default()
             },
         ];
 
@@ -80,11 +210,25 @@ mod tests {
             stats,
             total: TotalStats {
                 commits: 8,
+                merge_commits: 0,
                 additions: 150,
                 deletions: 30,
                 net_lines: 120,
                 files_changed: 15,
+                unique_files: 15,
             },
+            extensions: None,
+            top_commits: None,
+            top_files: None,
+            top_dirs: None,
+            file_details: None,
+            repositories: Vec::new(),
+            comparison: None,
+            moving_average: None,
+            truncated: false,
+            work_pattern: None,
+            year_over_year: None,
+            gaps: Vec::new(),
         }
     }
 
@@ -127,6 +271,213 @@ mod tests {
         assert_eq!(parsed["stats"].as_array().unwrap().len(), 2);
     }
 
+    #[test]
+    fn test_json_extensions_array() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed.get("extensions").is_none());
+
+        result.extensions = Some(vec![
+            ExtensionStats {
+                extension: "ts".to_string(),
+                commits: 1,
+                additions: 300,
+                deletions: 30,
+                files: 1,
+            },
+            ExtensionStats {
+                extension: "none".to_string(),
+                commits: 1,
+                additions: 2,
+                deletions: 0,
+                files: 1,
+            },
+        ]);
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let extensions = parsed["extensions"].as_array().unwrap();
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(extensions[0]["extension"], "ts");
+        assert_eq!(extensions[0]["additions"], 300);
+        assert_eq!(extensions[1]["extension"], "none");
+    }
+
+    #[test]
+    fn test_json_repositories_array() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed.get("repositories").is_none());
+
+        result.repositories = vec![
+            RepoStats {
+                name: "alpha".to_string(),
+                total: TotalStats {
+                    commits: 3,
+                    merge_commits: 0,
+                    additions: 30,
+                    deletions: 5,
+                    net_lines: 25,
+                    files_changed: 4,
+                    unique_files: 4,
+                },
+            },
+            RepoStats {
+                name: "beta".to_string(),
+                total: TotalStats::default(),
+            },
+        ];
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let repositories = parsed["repositories"].as_array().unwrap();
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(repositories[0]["name"], "alpha");
+        assert_eq!(repositories[0]["commits"], 3);
+        assert_eq!(repositories[0]["net_lines"], 25);
+        assert_eq!(repositories[1]["name"], "beta");
+    }
+
+    #[test]
+    fn test_json_top_commits_array() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed.get("top_commits").is_none());
+
+        result.top_commits = Some(vec![TopCommit {
+            id: "abc1234".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            summary: "Rewrite parser".to_string(),
+            additions: 250,
+            deletions: 40,
+        }]);
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let top = parsed["top_commits"].as_array().unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0]["id"], "abc1234");
+        assert_eq!(top[0]["date"], "2024-01-15");
+        assert_eq!(top[0]["summary"], "Rewrite parser");
+        assert_eq!(top[0]["additions"], 250);
+    }
+
+    #[test]
+    fn test_json_top_files_and_dirs_arrays() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed.get("top_files").is_none());
+        assert!(parsed.get("top_dirs").is_none());
+
+        result.top_files = Some(vec![FileChurn {
+            path: "src/main.rs".to_string(),
+            additions: 120,
+            deletions: 30,
+            commits: 3,
+        }]);
+        result.top_dirs = Some(Vec::new());
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let files = parsed["top_files"].as_array().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["path"], "src/main.rs");
+        assert_eq!(files[0]["additions"], 120);
+        assert_eq!(files[0]["commits"], 3);
+        assert_eq!(parsed["top_dirs"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_json_comparison_object() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed.get("comparison").is_none());
+
+        let previous_range = DateRange::new(
+            NaiveDate::from_ymd_opt(2023, 12, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+        );
+        result.comparison = Some(ComparisonStats::new(
+            previous_range,
+            &result.total,
+            &TotalStats::default(),
+        ));
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let comparison = &parsed["comparison"];
+        assert_eq!(comparison["previous_from"], "2023-12-01");
+        assert_eq!(comparison["previous_to"], "2023-12-31");
+        assert_eq!(comparison["commits"]["previous"], 0);
+        assert_eq!(
+            comparison["commits"]["change"],
+            comparison["commits"]["current"]
+        );
+        assert!(comparison["commits"]["percent"].is_null());
+    }
+
+    #[test]
+    fn test_json_year_over_year_object() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+        let parsed: serde_json::Value =
+            serde_json::from_str(&formatter.format(&result).unwrap()).unwrap();
+        assert!(parsed.get("year_over_year").is_none());
+
+        let month = |y, commits| PeriodStats {
+            commits,
+            ..PeriodStats::new(NaiveDate::from_ymd_opt(y, 1, 1).unwrap())
+        };
+        result.year_over_year = Some(YearOverYear::new(
+            &[month(2024, 4)],
+            &[month(2023, 2)],
+            Period::Monthly,
+        ));
+        let parsed: serde_json::Value =
+            serde_json::from_str(&formatter.format(&result).unwrap()).unwrap();
+        let yoy = &parsed["year_over_year"];
+        assert_eq!(yoy["current"][0]["commits"], 4);
+        assert_eq!(yoy["previous"][0]["date"], "2023-01-01");
+        assert_eq!(yoy["delta"][0]["commits"]["change"], 2);
+        assert_eq!(yoy["delta"][0]["commits"]["percent"], 100.0);
+    }
+
+    #[test]
+    fn test_json_activity_object() {
+        let formatter = JsonFormatter::new();
+        let result = make_result();
+        let mut activity = ActivityStats::default();
+        activity.weekday[4] = 7;
+        activity.hourly[9] = 5;
+
+        let json = formatter.format_with_activity(&result, &activity).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["repository"], "test-repo");
+        let weekday = parsed["activity"]["weekday"].as_object().unwrap();
+        for label in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+            assert!(weekday.contains_key(label), "missing weekday {label}");
+        }
+        assert_eq!(weekday["Fri"], 7);
+        assert_eq!(parsed["activity"]["hourly"]["9"], 5);
+        assert_eq!(parsed["activity"]["hourly"].as_object().unwrap().len(), 24);
+
+        // Plain format() leaves activity out
+        let json = formatter.format(&result).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(parsed.get("activity").is_none());
+    }
+
     #[test]
     fn test_json_date_format() {
         let formatter = JsonFormatter::new();
@@ -138,4 +489,230 @@ mod tests {
         assert!(json.contains("\"from\": \"2024-01-01\""));
         assert!(json.contains("\"to\": \"2024-01-07\""));
     }
+
+    #[test]
+    fn test_json_schema_version_first() {
+        let json = JsonFormatter::compact().format(&make_result()).unwrap();
+        assert!(json.starts_with(r#"{"schema_version":"1.0","repository":"test-repo""#));
+    }
+
+    /// A result with every optional section filled in
+    fn make_full_result() -> (AnalysisResult, ActivityStats) {
+        let mut result = make_result();
+        result.extensions = Some(vec![ExtensionStats {
+            extension: "rs".to_string(),
+            commits: 2,
+            additions: 90,
+            deletions: 12,
+            files: 3,
+        }]);
+        result.top_commits = Some(vec![TopCommit {
+            id: "abc1234".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            summary: "Rewrite \"parser\" ✨".to_string(),
+            additions: 50,
+            deletions: 10,
+        }]);
+        result.top_files = Some(vec![FileChurn {
+            path: "src/main.rs".to_string(),
+            additions: 40,
+            deletions: 5,
+            commits: 2,
+        }]);
+        result.top_dirs = Some(Vec::new());
+        result.file_details = Some(vec![FileDetail {
+            id: "abc1234".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            path: "docs/a, b.md".to_string(),
+            additions: 4,
+            deletions: 1,
+        }]);
+        result.repositories = vec![RepoStats {
+            name: "alpha".to_string(),
+            total: result.total.clone(),
+        }];
+        let previous = TotalStats {
+            commits: 3,
+            merge_commits: 0,
+            additions: 70,
+            deletions: 90,
+            net_lines: -20,
+            files_changed: 7,
+            unique_files: 7,
+        };
+        result.comparison = Some(ComparisonStats::new(
+            DateRange::new(
+                NaiveDate::from_ymd_opt(2023, 12, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+            ),
+            &result.total,
+            &previous,
+        ));
+
+        let mut activity = ActivityStats::default();
+        activity.weekday[0] = 8;
+        activity.hourly[10] = 6;
+        activity.heatmap[0][10] = 6;
+        (result, activity)
+    }
+
+    #[test]
+    fn test_json_round_trip_is_byte_identical() {
+        let (result, activity) = make_full_result();
+        for formatter in [JsonFormatter::new(), JsonFormatter::compact()] {
+            let json = formatter.format_with_activity(&result, &activity).unwrap();
+            let (parsed, parsed_activity) = JsonFormatter::parse(&json).unwrap();
+            let again = formatter
+                .format_with_activity(&parsed, &parsed_activity)
+                .unwrap();
+            assert_eq!(again, json);
+        }
+    }
+
+    #[test]
+    fn test_json_skip_empty_drops_periods_and_their_averages() {
+        let mut result = make_result();
+        result.stats.push(PeriodStats::new(
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+        ));
+        result.moving_average = Some(moving_averages(&result.stats, 2));
+
+        let json = JsonFormatter::new()
+            .with_skip_empty(true)
+            .format(&result)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["stats"].as_array().unwrap().len(),
+            result.stats.len() - 1
+        );
+        assert_eq!(
+            value["moving_average"]["commits"],
+            serde_json::json!([5.0, 4.0])
+        );
+        assert_eq!(value["total"]["commits"], result.total.commits);
+
+        // Nothing to drop leaves the output as it was
+        let full = JsonFormatter::new().format(&make_result()).unwrap();
+        let skipped = JsonFormatter::new()
+            .with_skip_empty(true)
+            .format(&make_result())
+            .unwrap();
+        assert_eq!(skipped, full);
+    }
+
+    #[test]
+    fn test_json_round_trip_keeps_unique_files_when_re_aggregated() {
+        let mut result = make_result();
+        result.stats[0].unique_files = 4;
+        result.stats[1].unique_files = 2;
+        let json = JsonFormatter::new().format(&result).unwrap();
+
+        let (parsed, _) = JsonFormatter::parse(&json).unwrap();
+        assert!(parsed.stats.iter().all(|stat| stat.paths.is_empty()));
+        let weekly = aggregate_by_period(parsed.stats, Period::Weekly);
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].unique_files, 6);
+        assert_eq!(weekly[0].files_changed, 15);
+    }
+
+    #[test]
+    fn test_json_round_trip_without_optional_sections() {
+        let formatter = JsonFormatter::new();
+        let json = formatter.format(&make_result()).unwrap();
+
+        let (parsed, activity) = JsonFormatter::parse(&json).unwrap();
+        assert_eq!(formatter.format(&parsed).unwrap(), json);
+        assert!(parsed.repositories.is_empty());
+        assert_eq!(activity.weekday, [0; 7]);
+    }
+
+    #[test]
+    fn test_json_truncated_flag() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+        let json = formatter.format(&result).unwrap();
+        assert!(json.contains(r#""truncated": false"#), "{json}");
+
+        result.truncated = true;
+        let json = formatter.format(&result).unwrap();
+        let (parsed, _) = JsonFormatter::parse(&json).unwrap();
+        assert!(parsed.truncated);
+
+        // Output from before the flag existed was never truncated
+        let old = json.replace(r#""truncated": true"#, r#""unused": null"#);
+        assert!(!JsonFormatter::parse(&old).unwrap().0.truncated);
+    }
+
+    #[test]
+    fn test_json_outlier_flag() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+        let json = formatter.format(&result).unwrap();
+        assert!(json.contains(r#""is_outlier": false"#), "{json}");
+
+        result.stats[0].is_outlier = true;
+        let json = formatter.format(&result).unwrap();
+        let (parsed, _) = JsonFormatter::parse(&json).unwrap();
+        assert!(parsed.stats[0].is_outlier);
+
+        // Output from before the flag existed had no outliers
+        let old = json.replace(r#""is_outlier": true"#, r#""unused": null"#);
+        assert!(!JsonFormatter::parse(&old).unwrap().0.stats[0].is_outlier);
+    }
+
+    #[test]
+    fn test_json_work_pattern_round_trip() {
+        let formatter = JsonFormatter::new();
+        let mut result = make_result();
+        assert!(!formatter.format(&result).unwrap().contains("work_pattern"));
+
+        let work_pattern = WorkPatternStats {
+            weekends: WorkPatternBucket {
+                commits: 2,
+                additions: 30,
+                deletions: 4,
+            },
+            ..WorkPatternStats::default()
+        };
+        result.work_pattern = Some(work_pattern);
+        let json = formatter.format(&result).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["work_pattern"]["work_hours"]["start"], 9);
+        assert_eq!(value["work_pattern"]["weekends"]["commits"], 2);
+
+        let (parsed, _) = JsonFormatter::parse(&json).unwrap();
+        assert_eq!(parsed.work_pattern, Some(work_pattern));
+    }
+
+    #[test]
+    fn test_json_parse_accepts_newer_minor_version() {
+        let json = JsonFormatter::new()
+            .format(&make_result())
+            .unwrap()
+            .replace(r#""schema_version": "1.0""#, r#""schema_version": "1.7""#);
+        let (parsed, _) = JsonFormatter::parse(&json).unwrap();
+        assert_eq!(parsed.repository, "test-repo");
+    }
+
+    #[test]
+    fn test_json_parse_rejects_unknown_major_version() {
+        let json = JsonFormatter::new()
+            .format(&make_result())
+            .unwrap()
+            .replace(r#""schema_version": "1.0""#, r#""schema_version": "2.0""#);
+        let err = JsonFormatter::parse(&json).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsupported input schema: schema_version 2.0 is not supported (this kodo reads 1.x)"
+        );
+    }
+
+    #[test]
+    fn test_json_parse_rejects_missing_version() {
+        let json = serde_json::to_string(&make_result()).unwrap();
+        let err = JsonFormatter::parse(&json).unwrap_err();
+        assert!(matches!(err, Error::UnsupportedSchema { .. }), "{err}");
+        assert!(err.to_string().contains("missing schema_version"));
+    }
 }
@@ -1,11 +1,23 @@
 //! Output formatting module
 
+pub mod columns;
+pub mod compare;
 pub mod csv;
 pub mod format;
+pub mod graph;
+pub mod html;
 pub mod json;
+pub mod ndjson;
+pub mod prometheus;
+pub mod sparkline;
 pub mod table;
 
+pub use compare::CompareReport;
 pub use csv::CsvFormatter;
 pub use format::Formatter;
+pub use graph::GraphFormatter;
+pub use html::HtmlFormatter;
 pub use json::JsonFormatter;
+pub use ndjson::NdjsonFormatter;
+pub use prometheus::PrometheusFormatter;
 pub use table::TableFormatter;
@@ -0,0 +1,160 @@
+//! Newline-delimited JSON output formatter
+
+use crate::error::Result;
+use crate::output::Formatter;
+use crate::stats::types::serialize_date;
+use crate::stats::{AnalysisResult, PeriodStats, TotalStats};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// One line per period
+#[derive(Serialize)]
+struct PeriodLine<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    repository: &'a str,
+    period: &'a str,
+    #[serde(flatten)]
+    stats: &'a PeriodStats,
+}
+
+/// Final line with totals across all periods
+#[derive(Serialize)]
+struct TotalLine<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    repository: &'a str,
+    period: &'a str,
+    #[serde(serialize_with = "serialize_date")]
+    from: NaiveDate,
+    #[serde(serialize_with = "serialize_date")]
+    to: NaiveDate,
+    #[serde(flatten)]
+    total: &'a TotalStats,
+}
+
+/// NDJSON output formatter
+///
+/// Emits one compact JSON object per period (`"type":"period"`) followed by a
+/// final totals object (`"type":"total"`), each on its own line, for use with
+/// line-oriented tools like `jq`.
+pub struct NdjsonFormatter;
+
+impl NdjsonFormatter {
+    /// Create a new NDJSON formatter
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for NdjsonFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for NdjsonFormatter {
+    fn format(&self, result: &AnalysisResult) -> Result<String> {
+        let mut output = String::new();
+
+        for stats in &result.stats {
+            output.push_str(&serde_json::to_string(&PeriodLine {
+                kind: "period",
+                repository: &result.repository,
+                period: &result.period,
+                stats,
+            })?);
+            output.push('\n');
+        }
+
+        output.push_str(&serde_json::to_string(&TotalLine {
+            kind: "total",
+            repository: &result.repository,
+            period: &result.period,
+            from: result.from,
+            to: result.to,
+            total: &result.total,
+        })?);
+        output.push('\n');
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_result(stats: Vec<PeriodStats>) -> AnalysisResult {
+        AnalysisResult::new(
+            "test-repo".to_string(),
+            "daily".to_string(),
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            stats,
+        )
+    }
+
+    fn parse_lines(output: &str) -> Vec<serde_json::Value> {
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ndjson_one_line_per_period_then_total() {
+        let stats = vec![
+            PeriodStats {
+                label: "2024-01-01".to_string(),
+                date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                commits: 5,
+                additions: 100,
+                deletions: 20,
+                net_lines: 80,
+                files_changed: 10,
+                ..Default::default()
+            },
+            PeriodStats {
+                label: "2024-01-02".to_string(),
+                date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+                commits: 3,
+                additions: 50,
+                deletions: 10,
+                net_lines: 40,
+                files_changed: 5,
+                ..Default::default()
+            },
+        ];
+        let output = NdjsonFormatter::new().format(&make_result(stats)).unwrap();
+
+        assert!(output.ends_with('\n'));
+        let lines = parse_lines(&output);
+        assert_eq!(lines.len(), 3);
+
+        assert_eq!(lines[0]["type"], "period");
+        assert_eq!(lines[0]["repository"], "test-repo");
+        assert_eq!(lines[0]["period"], "daily");
+        assert_eq!(lines[0]["label"], "2024-01-01");
+        assert_eq!(lines[0]["date"], "2024-01-01");
+        assert_eq!(lines[0]["commits"], 5);
+        assert_eq!(lines[1]["date"], "2024-01-02");
+
+        assert_eq!(lines[2]["type"], "total");
+        assert_eq!(lines[2]["from"], "2024-01-01");
+        assert_eq!(lines[2]["to"], "2024-01-02");
+        assert_eq!(lines[2]["commits"], 8);
+        assert_eq!(lines[2]["net_lines"], 120);
+    }
+
+    #[test]
+    fn test_ndjson_empty_stats_only_total() {
+        let output = NdjsonFormatter::new().format(&make_result(vec![])).unwrap();
+
+        let lines = parse_lines(&output);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["type"], "total");
+        assert_eq!(lines[0]["commits"], 0);
+    }
+}
@@ -0,0 +1,314 @@
+//! Prometheus text exposition output formatter
+
+use crate::error::Result;
+use crate::output::Formatter;
+use crate::stats::{AnalysisResult, TotalStats};
+use chrono::Utc;
+use std::fmt::Write;
+
+/// A gauge written per repository
+struct Gauge {
+    name: &'static str,
+    help: &'static str,
+    value: fn(&TotalStats) -> u64,
+}
+
+const GAUGES: [Gauge; 4] = [
+    Gauge {
+        name: "kodo_commits_total",
+        help: "Commits in the analyzed range.",
+        value: |t| u64::from(t.commits),
+    },
+    Gauge {
+        name: "kodo_lines_added_total",
+        help: "Lines added in the analyzed range.",
+        value: |t| t.additions,
+    },
+    Gauge {
+        name: "kodo_lines_deleted_total",
+        help: "Lines deleted in the analyzed range.",
+        value: |t| t.deletions,
+    },
+    Gauge {
+        name: "kodo_files_changed_total",
+        help: "File changes in the analyzed range.",
+        value: |t| u64::from(t.files_changed),
+    },
+];
+
+/// Prometheus text format formatter, for node exporter's textfile collector
+///
+/// Writes one sample per repository for each gauge (not the merged totals when
+/// several repositories are analyzed). Unless the period is daily, the merged
+/// stats of each period follow with an extra `period` label.
+pub struct PrometheusFormatter {
+    timestamp: i64,
+}
+
+impl PrometheusFormatter {
+    /// Create a new Prometheus formatter stamped with the current time
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            timestamp: Utc::now().timestamp(),
+        }
+    }
+
+    /// Report `seconds` since the Unix epoch as the last run time
+    #[must_use]
+    pub const fn with_timestamp(mut self, seconds: i64) -> Self {
+        self.timestamp = seconds;
+        self
+    }
+}
+
+impl Default for PrometheusFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for PrometheusFormatter {
+    fn format(&self, result: &AnalysisResult) -> Result<String> {
+        let repositories: Vec<(&str, &TotalStats)> = if result.repositories.is_empty() {
+            vec![(result.repository.as_str(), &result.total)]
+        } else {
+            result
+                .repositories
+                .iter()
+                .map(|repo| (repo.name.as_str(), &repo.total))
+                .collect()
+        };
+        let repository = escape_label_value(&result.repository);
+
+        let mut output = String::new();
+        for Gauge { name, help, value } in GAUGES {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} gauge");
+            for (repo, total) in &repositories {
+                let repo = escape_label_value(repo);
+                let _ = writeln!(output, "{name}{{repository=\"{repo}\"}} {}", value(total));
+            }
+            if result.period != "daily" {
+                for stats in &result.stats {
+                    let total = TotalStats::from_periods(std::slice::from_ref(stats));
+                    let _ = writeln!(
+                        output,
+                        "{name}{{repository=\"{repository}\",period=\"{}\"}} {}",
+                        escape_label_value(&stats.label),
+                        value(&total)
+                    );
+                }
+            }
+        }
+
+        output.push_str(
+            "# HELP kodo_last_run_timestamp_seconds Unix time when kodo produced this output.\n",
+        );
+        output.push_str("# TYPE kodo_last_run_timestamp_seconds gauge\n");
+        let _ = writeln!(output, "kodo_last_run_timestamp_seconds {}", self.timestamp);
+        Ok(output)
+    }
+}
+
+/// Escape a label value: backslash, double quote, and line feed
+fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::{PeriodStats, RepoStats};
+    use chrono::NaiveDate;
+    use std::collections::HashSet;
+
+    /// Check `text` line by line against the exposition format, returning the sample count
+    ///
+    /// Every sample must follow `# TYPE` for its metric, series must be unique,
+    /// and label values may only contain the escapes `\\`, `\"`, and `\n`.
+    fn validate(text: &str) -> std::result::Result<usize, String> {
+        fn is_name(name: &str, colons: bool) -> bool {
+            let mut chars = name.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || (colons && c == ':'))
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || (colons && c == ':'))
+        }
+
+        let mut typed = HashSet::new();
+        let mut series = HashSet::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut parts = comment.splitn(3, ' ');
+                let (kind, name, rest) = (parts.next(), parts.next(), parts.next());
+                let name = name.filter(|n| is_name(n, true)).ok_or(line)?;
+                match (kind, rest) {
+                    (Some("HELP"), Some(help)) if !help.is_empty() => {}
+                    (Some("TYPE"), Some("gauge" | "counter")) => {
+                        if !typed.insert(name.to_string()) {
+                            return Err(format!("duplicate TYPE: {line}"));
+                        }
+                    }
+                    _ => return Err(format!("bad comment: {line}")),
+                }
+                continue;
+            }
+
+            let (series_key, value) = line.rsplit_once(' ').ok_or(line)?;
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("bad value: {line}"))?;
+            let (name, labels) = match series_key.split_once('{') {
+                Some((name, labels)) => (name, labels.strip_suffix('}').ok_or(line)?),
+                None => (series_key, ""),
+            };
+            if !is_name(name, true) || !typed.contains(name) {
+                return Err(format!("untyped or invalid metric: {line}"));
+            }
+
+            let mut rest = labels;
+            while !rest.is_empty() {
+                let (label, after) = rest.split_once("=\"").ok_or(line)?;
+                if !is_name(label, false) {
+                    return Err(format!("bad label name: {line}"));
+                }
+                let mut chars = after.char_indices();
+                let end = loop {
+                    match chars.next() {
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '\\' | '"' | 'n')) => {}
+                            _ => return Err(format!("bad escape: {line}")),
+                        },
+                        Some((i, '"')) => break i,
+                        Some(_) => {}
+                        None => return Err(format!("unterminated label: {line}")),
+                    }
+                };
+                rest = &after[end + 1..];
+                rest = rest.strip_prefix(',').unwrap_or(rest);
+            }
+            if !series.insert(series_key.to_string()) {
+                return Err(format!("duplicate series: {line}"));
+            }
+        }
+        Ok(series.len())
+    }
+
+    fn make_result(period: &str) -> AnalysisResult {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 14).unwrap();
+        let stats = vec![
+            PeriodStats {
+                commits: 3,
+                additions: 40,
+                deletions: 5,
+                net_lines: 35,
+                files_changed: 4,
+                ..PeriodStats::with_label(from, "2024-W01".to_string())
+            },
+            PeriodStats {
+                commits: 1,
+                additions: 2,
+                deletions: 8,
+                net_lines: -6,
+                files_changed: 1,
+                ..PeriodStats::with_label(
+                    NaiveDate::from_ymd_opt(2024, 1, 8).unwrap(),
+                    "2024-W02".to_string(),
+                )
+            },
+        ];
+        AnalysisResult::new("kodo".to_string(), period.to_string(), from, to, stats)
+    }
+
+    #[test]
+    fn test_prometheus_single_repository() {
+        let output = PrometheusFormatter::new()
+            .with_timestamp(1_700_000_000)
+            .format(&make_result("daily"))
+            .unwrap();
+
+        assert_eq!(validate(&output), Ok(5));
+        assert!(output.starts_with(
+            "# HELP kodo_commits_total Commits in the analyzed range.\n\
+             # TYPE kodo_commits_total gauge\n\
+             kodo_commits_total{repository=\"kodo\"} 4\n"
+        ));
+        assert!(output.contains("kodo_lines_added_total{repository=\"kodo\"} 42\n"));
+        assert!(output.contains("kodo_lines_deleted_total{repository=\"kodo\"} 13\n"));
+        assert!(output.contains("kodo_files_changed_total{repository=\"kodo\"} 5\n"));
+        assert!(output.ends_with("kodo_last_run_timestamp_seconds 1700000000\n"));
+        assert!(!output.contains("period="));
+    }
+
+    #[test]
+    fn test_prometheus_period_labels_unless_daily() {
+        let output = PrometheusFormatter::new()
+            .format(&make_result("weekly"))
+            .unwrap();
+
+        assert_eq!(validate(&output), Ok(13));
+        assert!(output.contains("kodo_commits_total{repository=\"kodo\",period=\"2024-W01\"} 3\n"));
+        assert!(
+            output
+                .contains("kodo_lines_deleted_total{repository=\"kodo\",period=\"2024-W02\"} 8\n")
+        );
+    }
+
+    #[test]
+    fn test_prometheus_one_sample_per_repository() {
+        let mut result = make_result("daily");
+        result.repository = "2 repos".to_string();
+        result.repositories = vec![
+            RepoStats {
+                name: "alpha".to_string(),
+                total: TotalStats {
+                    commits: 3,
+                    ..TotalStats::default()
+                },
+            },
+            RepoStats {
+                name: "beta".to_string(),
+                total: TotalStats {
+                    commits: 1,
+                    ..TotalStats::default()
+                },
+            },
+        ];
+
+        let output = PrometheusFormatter::new().format(&result).unwrap();
+        assert_eq!(validate(&output), Ok(9));
+        assert!(output.contains("kodo_commits_total{repository=\"alpha\"} 3\n"));
+        assert!(output.contains("kodo_commits_total{repository=\"beta\"} 1\n"));
+        assert!(!output.contains("2 repos"));
+    }
+
+    #[test]
+    fn test_prometheus_escapes_label_values() {
+        let mut result = make_result("daily");
+        result.repository = "we\"ird\\repo\nname".to_string();
+
+        let output = PrometheusFormatter::new().format(&result).unwrap();
+        assert_eq!(validate(&output), Ok(5));
+        assert!(output.contains(r#"kodo_commits_total{repository="we\"ird\\repo\nname"} 4"#));
+    }
+
+    #[test]
+    fn test_validator_rejects_malformed_lines() {
+        assert!(validate("kodo_commits_total 1\n").is_err());
+        assert!(validate("# TYPE 1bad gauge\n").is_err());
+        assert!(validate("# TYPE m gauge\nm{repository=\"a\\x\"} 1\n").is_err());
+        assert!(validate("# TYPE m gauge\nm{repository=\"a\"} one\n").is_err());
+        assert!(validate("# TYPE m gauge\nm 1\nm 2\n").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Compact sparklines drawn with Unicode block characters
+
+/// Blocks from lowest to highest
+const BLOCKS: [char; 8] = [
+    '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+];
+
+/// Render `values` as a sparkline at most `width` characters wide
+///
+/// Each value is scaled against the largest one; zero gets the lowest block
+/// and any other value at least the second lowest. A series of zeros is a
+/// flat line of low blocks. Series longer than `width` are split into equal
+/// buckets that show their largest value.
+#[must_use]
+pub fn sparkline(values: &[u64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+    let bucket = values.len().div_ceil(width);
+    let values: Vec<u64> = values
+        .chunks(bucket)
+        .map(|chunk| chunk.iter().copied().max().unwrap_or_default())
+        .collect();
+    let max = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|&value| BLOCKS[level(value, max)])
+        .collect()
+}
+
+/// Index into [`BLOCKS`] for `value` on a scale up to `max`
+fn level(value: u64, max: u64) -> usize {
+    if max == 0 {
+        return 0;
+    }
+    let top = (BLOCKS.len() - 1) as u128;
+    let level = (u128::from(value) * top).div_ceil(u128::from(max));
+    usize::try_from(level).unwrap_or(BLOCKS.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_to_largest_value() {
+        assert_eq!(sparkline(&[0, 1, 2, 3, 4, 5, 6, 7], 8), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&[1, 100], 8), "▂█");
+        assert_eq!(sparkline(&[5, 5, 5], 8), "███");
+    }
+
+    #[test]
+    fn test_sparkline_all_zero_is_flat() {
+        assert_eq!(sparkline(&[0, 0, 0, 0], 8), "▁▁▁▁");
+    }
+
+    #[test]
+    fn test_sparkline_downsamples_by_bucket_max() {
+        // Buckets of three: [0, 7, 0], [1, 0, 0], [0, 0, 3], [0]
+        let values = [0, 7, 0, 1, 0, 0, 0, 0, 3, 0];
+        assert_eq!(sparkline(&values, 4), "█▂▄▁");
+    }
+
+    #[test]
+    fn test_sparkline_empty() {
+        assert_eq!(sparkline(&[], 8), "");
+        assert_eq!(sparkline(&[1, 2], 0), "");
+    }
+}
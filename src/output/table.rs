@@ -2,21 +2,131 @@
 
 use crate::error::Result;
 use crate::output::Formatter;
-use crate::stats::AnalysisResult;
-use comfy_table::{Table, presets::UTF8_FULL};
+use crate::output::columns::{Column, Number, Row, SortKey, TABLE_COLUMNS, project, sorted_rows};
+use crate::output::sparkline::sparkline;
+use crate::stats::{
+    AnalysisResult, ComparisonStats, FileChurn, MetricDelta, OUTLIER_MADS, WorkPatternStats,
+    YearOverYear, longest_gap,
+};
+use comfy_table::{
+    Table,
+    presets::{ASCII_FULL, UTF8_FULL},
+};
+use std::fmt::Write as _;
 
 /// Table output formatter
-pub struct TableFormatter;
+pub struct TableFormatter {
+    ascii: bool,
+    columns: Vec<Column>,
+    sort: Option<SortKey>,
+    sparkline: bool,
+    skip_empty: bool,
+}
+
+/// Widest sparkline in the Trend cell; longer ranges are downsampled
+const TREND_WIDTH: usize = 30;
+
+/// Appended to the label of outlier periods, explained in a footnote
+///
+/// `*` already marks partial periods.
+const OUTLIER_MARKER: &str = "!";
 
 impl TableFormatter {
-    /// Create a new table formatter
+    /// Create a new table formatter drawing UTF-8 borders
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ascii: false,
+            columns: TABLE_COLUMNS.to_vec(),
+            sort: None,
+            sparkline: false,
+            skip_empty: false,
+        }
+    }
+
+    /// Draw borders with plain ASCII (`+`, `-`, `|`) instead of box-drawing characters
+    #[must_use]
+    pub const fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Show only `columns`, in that order, in the per-period table
     #[must_use]
-    pub const fn new() -> Self {
-        Self
+    pub fn with_columns(mut self, columns: Vec<Column>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Order the per-period rows by a column instead of by date
+    ///
+    /// The total row stays last.
+    #[must_use]
+    pub const fn with_sort(mut self, sort: Option<SortKey>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Add a Trend column whose TOTAL cell is a sparkline of commits per period
+    #[must_use]
+    pub const fn with_sparkline(mut self, sparkline: bool) -> Self {
+        self.sparkline = sparkline;
+        self
+    }
+
+    /// Leave periods without any activity out of the per-period table,
+    /// noting how many in a footnote; the TOTAL row still counts them
+    #[must_use]
+    pub const fn with_skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    /// The per-period table with its TOTAL row
+    fn period_table(&self, result: &AnalysisResult) -> Table {
+        let mut table = Table::new();
+        let mut header: Vec<&str> = self.columns.iter().map(|c| c.table_header()).collect();
+        if self.sparkline {
+            header.push("Trend");
+        }
+        table.load_preset(self.preset()).set_header(header);
+
+        for stat in sorted_rows(&result.stats, self.sort, self.skip_empty) {
+            let label = if stat.is_outlier {
+                format!("{}{OUTLIER_MARKER}", stat.label)
+            } else {
+                stat.label.clone()
+            };
+            let mut row = project(&self.columns, Row::Period(stat), &label, format_number);
+            if self.sparkline {
+                row.push(String::new());
+            }
+            table.add_row(row);
+        }
+        let mut total_row = project(
+            &self.columns,
+            Row::Total(&result.total),
+            "TOTAL",
+            format_number,
+        );
+        if self.sparkline {
+            // Always in date order, whatever --sort did to the rows above
+            let commits: Vec<u64> = result.stats.iter().map(|s| u64::from(s.commits)).collect();
+            total_row.push(sparkline(&commits, TREND_WIDTH));
+        }
+        table.add_row(total_row);
+
+        table
+    }
+
+    /// The comfy-table preset for the selected border style
+    const fn preset(&self) -> &'static str {
+        if self.ascii { ASCII_FULL } else { UTF8_FULL }
     }
 }
 
-fn format_with_commas_u64(value: u64) -> String {
+/// Format an unsigned number with thousands separators, e.g. `1,234`
+pub(crate) fn format_with_commas_u64(value: u64) -> String {
     let s = value.to_string();
     let mut out = String::with_capacity(s.len() + s.len() / 3);
     for (i, ch) in s.chars().rev().enumerate() {
@@ -28,7 +138,8 @@ fn format_with_commas_u64(value: u64) -> String {
     out.chars().rev().collect()
 }
 
-fn format_with_commas_i64(value: i64) -> String {
+/// Format a signed number with thousands separators, e.g. `-1,234`
+pub(crate) fn format_with_commas_i64(value: i64) -> String {
     if value < 0 {
         format!("-{}", format_with_commas_u64(value.unsigned_abs()))
     } else {
@@ -36,6 +147,14 @@ fn format_with_commas_i64(value: i64) -> String {
     }
 }
 
+/// Format a table cell with thousands separators
+fn format_number(number: Number) -> String {
+    match number {
+        Number::Unsigned(value) => format_with_commas_u64(value),
+        Number::Signed(value) => format_with_commas_i64(value),
+    }
+}
+
 impl Default for TableFormatter {
     fn default() -> Self {
         Self::new()
@@ -44,40 +163,259 @@ impl Default for TableFormatter {
 
 impl Formatter for TableFormatter {
     fn format(&self, result: &AnalysisResult) -> Result<String> {
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .set_header(["Period", "Commits", "+Lines", "-Lines", "Net", "Files"]);
-
-        for stat in &result.stats {
-            table.add_row([
-                stat.label.clone(),
-                format_with_commas_u64(u64::from(stat.commits)),
-                format_with_commas_u64(stat.additions),
-                format_with_commas_u64(stat.deletions),
-                format_with_commas_i64(stat.net_lines),
-                format_with_commas_u64(u64::from(stat.files_changed)),
+        let mut output = String::new();
+
+        // Per-repository summary comes first when several repositories were analyzed
+        if result.repositories.len() > 1 {
+            output.push_str(&repo_table(result, self.preset()).to_string());
+            output.push_str("\n\n");
+        }
+
+        output.push_str(&self.period_table(result).to_string());
+        if result.stats.iter().any(|stat| stat.is_outlier) {
+            let _ = write!(
+                output,
+                "\n{OUTLIER_MARKER} unusually large period: changed lines more than \
+                 {OUTLIER_MADS} median absolute deviations above the median"
+            );
+        }
+        let hidden = if self.skip_empty {
+            result.stats.iter().filter(|stat| stat.is_empty()).count()
+        } else {
+            0
+        };
+        if hidden > 0 {
+            let unit = if hidden == 1 { "period" } else { "periods" };
+            let _ = write!(output, "\n{hidden} empty {unit} hidden");
+        }
+        if let Some(gap) = longest_gap(&result.gaps) {
+            let _ = write!(output, "\nLongest gap: {gap}");
+        }
+
+        if let Some(comparison) = &result.comparison {
+            output.push_str("\n\n");
+            output.push_str(&comparison_table(comparison, self.preset()).to_string());
+        }
+
+        if let Some(year_over_year) = &result.year_over_year {
+            output.push_str("\n\n");
+            output.push_str(&year_over_year_table(year_over_year, self.preset()).to_string());
+        }
+
+        if let Some(work_pattern) = &result.work_pattern {
+            output.push_str("\n\n");
+            output.push_str(&work_pattern_table(work_pattern, self.preset()).to_string());
+        }
+
+        if let Some(extensions) = &result.extensions {
+            let mut ext_table = Table::new();
+            ext_table.load_preset(self.preset()).set_header([
+                "Extension",
+                "Commits",
+                "+Lines",
+                "-Lines",
+                "Files",
             ]);
+
+            for ext in extensions {
+                ext_table.add_row([
+                    ext.extension.clone(),
+                    format_with_commas_u64(u64::from(ext.commits)),
+                    format_with_commas_u64(ext.additions),
+                    format_with_commas_u64(ext.deletions),
+                    format_with_commas_u64(u64::from(ext.files)),
+                ]);
+            }
+
+            output.push_str("\n\n");
+            output.push_str(&ext_table.to_string());
+        }
+
+        if let Some(top_commits) = &result.top_commits {
+            let mut top_table = Table::new();
+            top_table
+                .load_preset(self.preset())
+                .set_header(["Commit", "Date", "Summary", "+Lines", "-Lines"]);
+
+            for commit in top_commits {
+                top_table.add_row([
+                    commit.id.clone(),
+                    commit.date.to_string(),
+                    commit.summary.clone(),
+                    format_with_commas_u64(commit.additions),
+                    format_with_commas_u64(commit.deletions),
+                ]);
+            }
+
+            output.push_str("\n\n");
+            output.push_str(&top_table.to_string());
         }
 
-        let total = &result.total;
+        if let Some(top_files) = &result.top_files {
+            output.push_str("\n\n");
+            output.push_str(&churn_table("File", top_files, self.preset()).to_string());
+        }
+
+        if let Some(top_dirs) = &result.top_dirs {
+            output.push_str("\n\n");
+            output.push_str(&churn_table("Directory", top_dirs, self.preset()).to_string());
+        }
+
+        if result.truncated {
+            output.push_str(
+                "\n\nWarning: stopped early by --max-commits or --max-seconds; \
+                 older commits in range are not counted",
+            );
+        }
+
+        Ok(output)
+    }
+}
+
+/// Build the business hours / evenings / weekends table
+fn work_pattern_table(work_pattern: &WorkPatternStats, preset: &str) -> Table {
+    let mut table = Table::new();
+    table.load_preset(preset).set_header([
+        format!("When (work hours {})", work_pattern.work_hours),
+        "Commits".to_string(),
+        "%".to_string(),
+        "+Lines".to_string(),
+        "-Lines".to_string(),
+    ]);
+    for (label, bucket) in work_pattern.buckets() {
         table.add_row([
-            "TOTAL".to_string(),
+            label.to_string(),
+            format_with_commas_u64(u64::from(bucket.commits)),
+            format!("{:.0}%", work_pattern.commit_percent(bucket)),
+            format_with_commas_u64(bucket.additions),
+            format_with_commas_u64(bucket.deletions),
+        ]);
+    }
+    table
+}
+
+/// Build the per-repository totals table
+fn repo_table(result: &AnalysisResult, preset: &str) -> Table {
+    let mut table = Table::new();
+    table.load_preset(preset).set_header([
+        "Repository",
+        "Commits",
+        "+Lines",
+        "-Lines",
+        "Net",
+        "Files",
+        "Unique",
+    ]);
+
+    for repo in &result.repositories {
+        let total = &repo.total;
+        table.add_row([
+            repo.name.clone(),
             format_with_commas_u64(u64::from(total.commits)),
             format_with_commas_u64(total.additions),
             format_with_commas_u64(total.deletions),
             format_with_commas_i64(total.net_lines),
             format_with_commas_u64(u64::from(total.files_changed)),
+            format_with_commas_u64(u64::from(total.unique_files)),
+        ]);
+    }
+    table
+}
+
+/// Build a top files / top directories table
+fn churn_table(header: &str, entries: &[FileChurn], preset: &str) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(preset)
+        .set_header([header, "Commits", "+Lines", "-Lines", "Changed"]);
+
+    for entry in entries {
+        table.add_row([
+            entry.path.clone(),
+            format_with_commas_u64(u64::from(entry.commits)),
+            format_with_commas_u64(entry.additions),
+            format_with_commas_u64(entry.deletions),
+            format_with_commas_u64(entry.lines_changed()),
+        ]);
+    }
+    table
+}
+
+/// Build the "vs previous period" table with ▲/▼ change indicators
+fn comparison_table(comparison: &ComparisonStats, preset: &str) -> Table {
+    let mut table = Table::new();
+    table.load_preset(preset).set_header([
+        format!(
+            "vs previous period ({} - {})",
+            comparison.previous_from, comparison.previous_to
+        ),
+        "Current".to_string(),
+        "Previous".to_string(),
+        "Change".to_string(),
+        "%".to_string(),
+    ]);
+
+    for (label, delta) in [
+        ("Commits", &comparison.commits),
+        ("+Lines", &comparison.additions),
+        ("-Lines", &comparison.deletions),
+        ("Net", &comparison.net_lines),
+        ("Files", &comparison.files_changed),
+    ] {
+        table.add_row([
+            label.to_string(),
+            format_with_commas_i64(delta.current),
+            format_with_commas_i64(delta.previous),
+            format_change(delta),
+            delta.percent_label(),
         ]);
+    }
+    table
+}
+
+/// Build the year-over-year table comparing each period's commits with the same period a year earlier
+///
+/// Periods with no year-earlier counterpart, such as ISO week 53, show `-`.
+fn year_over_year_table(year_over_year: &YearOverYear, preset: &str) -> Table {
+    let mut table = Table::new();
+    table.load_preset(preset).set_header([
+        "Commits vs a year earlier",
+        "This Year",
+        "Last Year",
+        "Δ%",
+    ]);
 
-        Ok(table.to_string())
+    for (previous, delta) in year_over_year.previous.iter().zip(&year_over_year.delta) {
+        table.add_row([
+            delta.label.clone(),
+            format_with_commas_i64(delta.commits.current),
+            previous.as_ref().map_or_else(
+                || "-".to_string(),
+                |_| format_with_commas_i64(delta.commits.previous),
+            ),
+            delta.commits.percent_label(),
+        ]);
     }
+    table
+}
+
+/// Format an absolute change with its trend indicator, e.g. `▲ +1,200`
+fn format_change(delta: &MetricDelta) -> String {
+    let sign = if delta.change > 0 { "+" } else { "" };
+    format!(
+        "{} {sign}{}",
+        delta.indicator(),
+        format_with_commas_i64(delta.change)
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stats::{AnalysisResult, PeriodStats};
+    use crate::stats::{
+        AnalysisResult, DateRange, ExtensionStats, Gap, PeriodStats, RepoStats, TopCommit,
+        TotalStats, WorkPatternBucket,
+    };
     use chrono::NaiveDate;
 
     fn make_result() -> AnalysisResult {
@@ -91,6 +429,7 @@ mod tests {
             deletions: 5,
             net_lines: 15,
             files_changed: 3,
+            ..Default::default()
         }];
 
         AnalysisResult::new(
@@ -113,9 +452,336 @@ mod tests {
         assert!(table.contains('┐'));
         assert!(table.contains("Period"));
         assert!(table.contains("Commits"));
+        assert!(table.contains("Unique"));
         assert!(table.contains("TOTAL"));
     }
 
+    #[test]
+    fn test_table_formatter_merges_column_is_opt_in() {
+        let mut result = make_result();
+        result.stats[0].merge_commits = 4;
+        result.total = TotalStats::from_periods(&result.stats);
+        assert!(
+            !TableFormatter::new()
+                .format(&result)
+                .unwrap()
+                .contains("Merges")
+        );
+
+        let table = TableFormatter::new()
+            .with_columns(vec![Column::Period, Column::Commits, Column::Merges])
+            .format(&result)
+            .unwrap();
+        let total = table.lines().find(|line| line.contains("TOTAL")).unwrap();
+        assert!(table.contains("Merges"), "{table}");
+        assert!(total.contains(" 2 ") && total.contains(" 4 "), "{table}");
+    }
+
+    #[test]
+    fn test_table_formatter_ascii_preset() {
+        let table = TableFormatter::new()
+            .with_ascii(true)
+            .format(&make_result())
+            .unwrap();
+
+        assert!(table.starts_with('+'), "{table}");
+        assert!(table.contains('|'));
+        assert!(!table.contains('┌'));
+        assert!(table.is_ascii(), "{table}");
+        assert!(table.contains("TOTAL"));
+    }
+
+    #[test]
+    fn test_table_formatter_sparkline_on_total_row() {
+        let mut result = make_result();
+        let quiet_day = PeriodStats {
+            label: "2024-01-02".to_string(),
+            commits: 1,
+            ..PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
+        };
+        result.stats.extend([
+            quiet_day,
+            PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, 3).unwrap()),
+        ]);
+
+        let table = TableFormatter::new()
+            .with_sparkline(true)
+            .format(&result)
+            .unwrap();
+        let header = table.lines().nth(1).unwrap();
+        assert!(header.contains("Trend"), "{table}");
+        let total = table.lines().find(|line| line.contains("TOTAL")).unwrap();
+        assert!(total.contains("█▅▁"), "{table}");
+
+        let plain = TableFormatter::new().format(&result).unwrap();
+        assert!(!plain.contains("Trend"));
+    }
+
+    #[test]
+    fn test_table_formatter_work_pattern_section() {
+        let mut result = make_result();
+        assert!(
+            !TableFormatter::new()
+                .format(&result)
+                .unwrap()
+                .contains("Weekends")
+        );
+
+        result.work_pattern = Some(WorkPatternStats {
+            business_hours: WorkPatternBucket {
+                commits: 3,
+                additions: 1200,
+                deletions: 40,
+            },
+            weekends: WorkPatternBucket {
+                commits: 1,
+                additions: 5,
+                deletions: 0,
+            },
+            ..WorkPatternStats::default()
+        });
+        let table = TableFormatter::new().format(&result).unwrap();
+        assert!(table.contains("When (work hours 9-18)"), "{table}");
+        let row = |label: &str| {
+            table
+                .lines()
+                .find(|line| line.contains(label))
+                .unwrap()
+                .split(['│', '┆'])
+                .map(str::trim)
+                .filter(|cell| !cell.is_empty())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            row("Business hours"),
+            ["Business hours", "3", "75%", "1,200", "40"]
+        );
+        assert_eq!(
+            row("Weekday evenings"),
+            ["Weekday evenings", "0", "0%", "0", "0"]
+        );
+        assert_eq!(row("Weekends"), ["Weekends", "1", "25%", "5", "0"]);
+    }
+
+    #[test]
+    fn test_table_formatter_marks_outliers_with_footnote() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        let plain = formatter.format(&result).unwrap();
+        assert!(!plain.contains("2024-01-01!"));
+        assert!(!plain.contains("unusually large"));
+
+        result.stats[0].is_outlier = true;
+        let table = formatter.format(&result).unwrap();
+        assert!(table.contains("2024-01-01!"), "{table}");
+        assert!(
+            table.contains("! unusually large period: changed lines more than 3 median"),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn test_table_formatter_longest_gap_footer() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        assert!(!formatter.format(&result).unwrap().contains("Longest gap"));
+
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let gap = |from, to, length| Gap {
+            from: date(from),
+            to: date(to),
+            length,
+            open_ended: false,
+        };
+        result.gaps = vec![gap(2, 5, 4), gap(10, 15, 6), gap(20, 25, 6)];
+        let table = formatter.format(&result).unwrap();
+        assert!(
+            table.contains("\nLongest gap: 6 days, Mar 10\u{2013}15"),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn test_table_formatter_skip_empty_footnote() {
+        let mut result = make_result();
+        let rows = result.stats.len();
+        for day in [8, 9] {
+            result.stats.push(PeriodStats::new(
+                NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            ));
+        }
+        let plain = TableFormatter::new().format(&result).unwrap();
+        assert!(plain.contains("2024-01-08"), "{plain}");
+        assert!(!plain.contains("hidden"), "{plain}");
+
+        let table = TableFormatter::new()
+            .with_skip_empty(true)
+            .format(&result)
+            .unwrap();
+        assert!(!table.contains("2024-01-08"), "{table}");
+        assert!(table.contains("\n2 empty periods hidden"), "{table}");
+        assert_eq!(
+            table
+                .lines()
+                .filter(|line| line.starts_with("│ 2024"))
+                .count(),
+            rows,
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn test_table_formatter_truncated_warning() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        assert!(!formatter.format(&result).unwrap().contains("Warning"));
+
+        result.truncated = true;
+        let table = formatter.format(&result).unwrap();
+        assert!(
+            table.ends_with("older commits in range are not counted"),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn test_table_formatter_extension_section() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        assert!(!formatter.format(&result).unwrap().contains("Extension"));
+
+        result.extensions = Some(vec![ExtensionStats {
+            extension: "rs".to_string(),
+            commits: 2,
+            additions: 1_500,
+            deletions: 5,
+            files: 3,
+        }]);
+        let table = formatter.format(&result).unwrap();
+
+        assert!(table.contains("Extension"));
+        assert!(table.contains("rs"));
+        assert!(table.contains("1,500"));
+    }
+
+    #[test]
+    fn test_table_formatter_top_commits_section() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        assert!(!formatter.format(&result).unwrap().contains("Summary"));
+
+        result.top_commits = Some(vec![TopCommit {
+            id: "abc1234".to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            summary: "Rewrite parser".to_string(),
+            additions: 2_500,
+            deletions: 40,
+        }]);
+        let table = formatter.format(&result).unwrap();
+
+        assert!(table.contains("Summary"));
+        assert!(table.contains("abc1234"));
+        assert!(table.contains("Rewrite parser"));
+        assert!(table.contains("2,500"));
+    }
+
+    #[test]
+    fn test_table_formatter_churn_sections() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        let table = formatter.format(&result).unwrap();
+        assert!(!table.contains("Changed"));
+        assert!(!table.contains("Directory"));
+
+        result.top_files = Some(vec![FileChurn {
+            path: "src/cli/run.rs".to_string(),
+            additions: 1_200,
+            deletions: 300,
+            commits: 4,
+        }]);
+        result.top_dirs = Some(vec![FileChurn {
+            path: "src/cli".to_string(),
+            additions: 1_250,
+            deletions: 300,
+            commits: 5,
+        }]);
+        let table = formatter.format(&result).unwrap();
+
+        assert!(table.contains("Changed"));
+        assert!(table.contains("src/cli/run.rs"));
+        assert!(table.contains("1,500"));
+        assert!(table.contains("Directory"));
+        assert!(table.contains("1,550"));
+    }
+
+    #[test]
+    fn test_table_formatter_comparison_section() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        assert!(
+            !formatter
+                .format(&result)
+                .unwrap()
+                .contains("vs previous period")
+        );
+
+        let previous = TotalStats {
+            commits: 0,
+            merge_commits: 0,
+            additions: 40,
+            deletions: 5,
+            net_lines: 35,
+            files_changed: 3,
+            unique_files: 3,
+        };
+        let previous_range = DateRange::new(
+            NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+        );
+        result.comparison = Some(ComparisonStats::new(
+            previous_range,
+            &result.total,
+            &previous,
+        ));
+        let table = formatter.format(&result).unwrap();
+
+        assert!(table.contains("vs previous period (2023-12-31 - 2023-12-31)"));
+        assert!(table.contains("▲ +2"));
+        assert!(table.contains("▼ -20"));
+        assert!(table.contains("-50.0%"));
+        assert!(table.contains("= 0"));
+        // Previous period had no commits, so the percentage is not available
+        assert!(table.contains("n/a"));
+    }
+
+    #[test]
+    fn test_table_formatter_repository_section() {
+        let formatter = TableFormatter::new();
+        let mut result = make_result();
+        assert!(!formatter.format(&result).unwrap().contains("Repository"));
+
+        let repo = |name: &str, commits| RepoStats {
+            name: name.to_string(),
+            total: TotalStats {
+                commits,
+                merge_commits: 0,
+                additions: 10,
+                deletions: 2,
+                net_lines: 8,
+                files_changed: 1,
+                unique_files: 1,
+            },
+        };
+        result.repositories = vec![repo("alpha", 1), repo("beta", 1)];
+        let table = formatter.format(&result).unwrap();
+
+        let repo_section = table.find("Repository").unwrap();
+        let merged_section = table.find("Period").unwrap();
+        assert!(repo_section < merged_section);
+        assert!(table.contains("alpha"));
+        assert!(table.contains("beta"));
+    }
+
     #[test]
     fn test_table_formatter_formats_numbers_with_commas() {
         let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
@@ -128,6 +794,7 @@ mod tests {
             deletions: 12_345,
             net_lines: -1_234_567,
             files_changed: 9_999,
+            ..Default::default()
         }];
 
         let result = AnalysisResult::new(
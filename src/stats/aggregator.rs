@@ -1,9 +1,14 @@
 //! Statistics aggregation utilities
 //!
-//! This module provides additional aggregation functions beyond what's in collector.
-//! Currently, most aggregation is done in collector.rs.
+//! This module provides additional aggregation functions beyond what's in collector:
+//! merging, running totals, moving averages, outlier detection, and gaps.
+//! Most per-period aggregation is done in collector.rs.
 
-use crate::stats::PeriodStats;
+#![allow(clippy::cast_precision_loss)]
+
+use crate::cli::args::Period;
+use crate::stats::{Gap, MovingAverage, PeriodStats};
+use std::ops::Range;
 
 /// Merge multiple period stats into one
 #[must_use]
@@ -19,13 +24,11 @@ pub fn merge_stats(stats: &[PeriodStats]) -> PeriodStats {
     result
 }
 
-/// Filter stats to only include non-zero periods
+/// Drop empty periods, keeping those with any commits, merge commits, changed
+/// lines, or changed files (see [`PeriodStats::is_empty`])
 #[must_use]
 pub fn filter_non_zero(stats: Vec<PeriodStats>) -> Vec<PeriodStats> {
-    stats
-        .into_iter()
-        .filter(|s| s.commits > 0 || s.additions > 0 || s.deletions > 0)
-        .collect()
+    stats.into_iter().filter(|s| !s.is_empty()).collect()
 }
 
 /// Calculate running totals for each period
@@ -36,9 +39,12 @@ pub fn running_totals(stats: &[PeriodStats]) -> Vec<PeriodStats> {
 
     for stat in stats {
         running.commits += stat.commits;
+        running.merge_commits += stat.merge_commits;
         running.additions += stat.additions;
         running.deletions += stat.deletions;
         running.files_changed += stat.files_changed;
+        running.paths.extend(stat.paths.iter().cloned());
+        running.update_unique_files();
         running.update_net_lines();
 
         let mut period = running.clone();
@@ -50,10 +56,157 @@ pub fn running_totals(stats: &[PeriodStats]) -> Vec<PeriodStats> {
     result
 }
 
+/// Trailing moving average of `values` over `window` entries
+///
+/// Entries before the window fills average over what is available, so the
+/// result starts at the first value rather than being padded with zeros.
+/// A `window` of 0 is treated as 1.
+#[must_use]
+pub fn compute_moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            sum += value;
+            if i >= window {
+                sum -= values[i - window];
+            }
+            sum / (i + 1).min(window) as f64
+        })
+        .collect()
+}
+
+/// Moving averages of every per-period series in `stats`
+#[must_use]
+pub fn moving_averages(stats: &[PeriodStats], window: usize) -> MovingAverage {
+    let series = |value: fn(&PeriodStats) -> f64| {
+        let values: Vec<f64> = stats.iter().map(value).collect();
+        compute_moving_average(&values, window)
+    };
+    MovingAverage {
+        window,
+        commits: series(|s| f64::from(s.commits)),
+        additions: series(|s| s.additions as f64),
+        deletions: series(|s| s.deletions as f64),
+        net_lines: series(|s| s.net_lines as f64),
+        files_changed: series(|s| f64::from(s.files_changed)),
+        unique_files: series(|s| f64::from(s.unique_files)),
+    }
+}
+
+/// How many median absolute deviations above the median make a period an outlier
+pub const OUTLIER_MADS: f64 = 3.0;
+
+/// Median of `values`, averaging the middle two for an even count
+///
+/// Returns `None` for an empty slice.
+#[must_use]
+pub fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    Some(if sorted.len().is_multiple_of(2) {
+        f64::midpoint(sorted[mid - 1], sorted[mid])
+    } else {
+        sorted[mid]
+    })
+}
+
+/// Median absolute deviation: the median distance of `values` from their median
+///
+/// Returns `None` for an empty slice.
+#[must_use]
+pub fn median_absolute_deviation(values: &[f64]) -> Option<f64> {
+    let center = median(values)?;
+    let deviations: Vec<f64> = values.iter().map(|value| (value - center).abs()).collect();
+    median(&deviations)
+}
+
+/// Flag periods with unusually many changed lines as outliers
+///
+/// Additions plus deletions of each non-empty period are compared with the
+/// median over all non-empty periods; a period more than [`OUTLIER_MADS`]
+/// median absolute deviations above it is flagged. When the deviation is
+/// zero (a single period, or most periods equal) nothing is flagged. Flags
+/// from an earlier call are cleared.
+pub fn mark_outliers(stats: &mut [PeriodStats]) {
+    let lines = |s: &PeriodStats| (s.additions + s.deletions) as f64;
+    let non_empty: Vec<f64> = stats
+        .iter()
+        .filter(|s| s.commits > 0 || s.additions > 0 || s.deletions > 0)
+        .map(lines)
+        .collect();
+    let threshold = median(&non_empty).zip(median_absolute_deviation(&non_empty));
+    for stat in stats.iter_mut() {
+        stat.is_outlier = threshold
+            .is_some_and(|(center, mad)| mad > 0.0 && lines(stat) > center + OUTLIER_MADS * mad);
+    }
+}
+
+/// Days a gap must exceed to be reported when `--min-gap` is not given:
+/// three days for daily stats, otherwise one whole period
+#[must_use]
+pub const fn default_min_gap(period: Period) -> u32 {
+    match period {
+        Period::Daily => 3,
+        Period::Weekly => 7,
+        Period::Monthly => 30,
+        Period::Yearly => 365,
+    }
+}
+
+/// Runs of days without commits longer than `min_days`, oldest first
+///
+/// `daily_stats` must be the zero-filled daily series, one entry per day of
+/// the range. Days with only merge commits count as active. Runs at the start
+/// or end of the series are reported as open-ended.
+#[must_use]
+pub fn find_gaps(daily_stats: &[PeriodStats], min_days: u32) -> Vec<Gap> {
+    let mut gaps = Vec::new();
+    let mut start = None;
+    for (index, stat) in daily_stats.iter().enumerate() {
+        let idle = stat.commits == 0 && stat.merge_commits == 0;
+        match (idle, start) {
+            (true, None) => start = Some(index),
+            (false, Some(first)) => {
+                gaps.extend(gap(daily_stats, first..index, min_days));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(first) = start {
+        gaps.extend(gap(daily_stats, first..daily_stats.len(), min_days));
+    }
+    gaps
+}
+
+/// The gap over the days `run` of `daily_stats`, if it is longer than `min_days`
+fn gap(daily_stats: &[PeriodStats], run: Range<usize>, min_days: u32) -> Option<Gap> {
+    let length = u32::try_from(run.len()).unwrap_or(u32::MAX);
+    (length > min_days).then(|| Gap {
+        from: daily_stats[run.start].date,
+        to: daily_stats[run.end - 1].date,
+        length,
+        open_ended: run.start == 0 || run.end == daily_stats.len(),
+    })
+}
+
+/// The longest of `gaps`, the earliest one on a tie
+#[must_use]
+pub fn longest_gap(gaps: &[Gap]) -> Option<&Gap> {
+    gaps.iter().rev().max_by_key(|gap| gap.length)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use chrono::{Datelike, NaiveDate};
 
     #[test]
     fn test_merge_stats_empty() {
@@ -107,6 +260,33 @@ mod tests {
         assert_eq!(filtered.len(), 2);
     }
 
+    #[test]
+    fn test_filter_non_zero_keeps_any_activity() {
+        let day = |day| PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, day).unwrap());
+        let stats = vec![
+            day(1),
+            PeriodStats {
+                files_changed: 2,
+                ..day(2)
+            },
+            PeriodStats {
+                merge_commits: 1,
+                ..day(3)
+            },
+            PeriodStats {
+                deletions: 4,
+                ..day(4)
+            },
+            day(5),
+        ];
+
+        let days: Vec<u32> = filter_non_zero(stats)
+            .iter()
+            .map(|s| s.date.day())
+            .collect();
+        assert_eq!(days, [2, 3, 4]);
+    }
+
     #[test]
     fn test_running_totals() {
         let stats = vec![
@@ -137,4 +317,207 @@ mod tests {
         assert_eq!(running[2].commits, 10); // 5 + 3 + 2
         assert_eq!(running[2].additions, 180); // 100 + 50 + 30
     }
+
+    #[test]
+    fn test_compute_moving_average_shrinks_window_at_start() {
+        let values = [3.0, 5.0, 1.0, 7.0, 4.0];
+        assert_eq!(
+            compute_moving_average(&values, 3),
+            [3.0, 4.0, 3.0, 13.0 / 3.0, 4.0]
+        );
+        assert_eq!(compute_moving_average(&values, 1), values);
+        assert_eq!(compute_moving_average(&values, 0), values);
+        assert!(compute_moving_average(&[], 7).is_empty());
+    }
+
+    #[test]
+    fn test_compute_moving_average_window_larger_than_series() {
+        // Every entry averages everything seen so far
+        assert_eq!(compute_moving_average(&[2.0, 4.0, 0.0], 7), [2.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_moving_averages_cover_every_series() {
+        let stats = vec![
+            PeriodStats {
+                commits: 4,
+                additions: 10,
+                net_lines: 10,
+                files_changed: 2,
+                unique_files: 2,
+                ..Default::default()
+            },
+            PeriodStats {
+                commits: 2,
+                deletions: 6,
+                net_lines: -6,
+                files_changed: 1,
+                unique_files: 1,
+                ..Default::default()
+            },
+        ];
+
+        let average = moving_averages(&stats, 2);
+        assert_eq!(average.window, 2);
+        assert_eq!(average.commits, [4.0, 3.0]);
+        assert_eq!(average.additions, [10.0, 5.0]);
+        assert_eq!(average.deletions, [0.0, 3.0]);
+        assert_eq!(average.net_lines, [10.0, 2.0]);
+        assert_eq!(average.files_changed, [2.0, 1.5]);
+        assert_eq!(average.unique_files, [2.0, 1.5]);
+    }
+
+    #[test]
+    fn test_median_odd_even_and_empty() {
+        assert_eq!(median(&[5.0, 1.0, 3.0]), Some(3.0));
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), Some(2.5));
+        assert_eq!(median(&[7.0]), Some(7.0));
+        assert_eq!(median(&[]), None);
+    }
+
+    #[test]
+    fn test_median_absolute_deviation() {
+        // Deviations from 3: [2, 1, 0, 1, 97]
+        assert_eq!(
+            median_absolute_deviation(&[1.0, 2.0, 3.0, 4.0, 100.0]),
+            Some(1.0)
+        );
+        assert_eq!(median_absolute_deviation(&[4.0, 4.0, 4.0]), Some(0.0));
+        assert_eq!(median_absolute_deviation(&[]), None);
+    }
+
+    fn period(day: u32, commits: u32, additions: u64, deletions: u64) -> PeriodStats {
+        PeriodStats {
+            commits,
+            additions,
+            deletions,
+            ..PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, day).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_mark_outliers_flags_large_period() {
+        let mut stats = vec![
+            period(1, 3, 80, 20),
+            period(2, 2, 100, 10),
+            period(3, 0, 0, 0),
+            period(4, 4, 90, 30),
+            period(5, 1, 4_000, 1_000),
+            period(6, 2, 70, 20),
+        ];
+        mark_outliers(&mut stats);
+
+        let flagged: Vec<bool> = stats.iter().map(|s| s.is_outlier).collect();
+        assert_eq!(flagged, [false, false, false, false, true, false]);
+    }
+
+    #[test]
+    fn test_mark_outliers_all_equal_flags_nothing() {
+        let mut stats: Vec<PeriodStats> = (1..=5).map(|day| period(day, 2, 50, 10)).collect();
+        mark_outliers(&mut stats);
+        assert!(stats.iter().all(|s| !s.is_outlier));
+    }
+
+    #[test]
+    fn test_mark_outliers_single_period_flags_nothing() {
+        let mut stats = vec![period(1, 1, 10_000, 5_000)];
+        mark_outliers(&mut stats);
+        assert!(!stats[0].is_outlier);
+
+        let mut empty: Vec<PeriodStats> = Vec::new();
+        mark_outliers(&mut empty);
+    }
+
+    #[test]
+    fn test_mark_outliers_clears_stale_flags() {
+        let mut stats: Vec<PeriodStats> = (1..=3).map(|day| period(day, 1, 10, 0)).collect();
+        stats[1].is_outlier = true;
+        mark_outliers(&mut stats);
+        assert!(stats.iter().all(|s| !s.is_outlier));
+    }
+
+    /// Daily stats from Jan 1 2024 with the given commits per day
+    fn days(commits: &[u32]) -> Vec<PeriodStats> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        commits
+            .iter()
+            .zip(start.iter_days())
+            .map(|(&commits, date)| PeriodStats {
+                commits,
+                ..PeriodStats::new(date)
+            })
+            .collect()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_find_gaps_interior() {
+        let gaps = find_gaps(&days(&[1, 0, 0, 0, 0, 2, 0, 0, 1]), 3);
+        assert_eq!(
+            gaps,
+            [Gap {
+                from: date(2),
+                to: date(5),
+                length: 4,
+                open_ended: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_gaps_leading_and_trailing_are_open_ended() {
+        let gaps = find_gaps(&days(&[0, 0, 1, 0, 0, 0]), 1);
+        assert_eq!(
+            gaps,
+            [
+                Gap {
+                    from: date(1),
+                    to: date(2),
+                    length: 2,
+                    open_ended: true,
+                },
+                Gap {
+                    from: date(4),
+                    to: date(6),
+                    length: 3,
+                    open_ended: true,
+                },
+            ]
+        );
+
+        let idle = find_gaps(&days(&[0, 0]), 0);
+        assert_eq!(idle.len(), 1);
+        assert!(idle[0].open_ended);
+    }
+
+    #[test]
+    fn test_find_gaps_threshold_and_merges() {
+        // Exactly the threshold is not longer than it
+        assert!(find_gaps(&days(&[1, 0, 0, 0, 1]), 3).is_empty());
+
+        // A merge-only day splits the run in two
+        let mut stats = days(&[1, 0, 0, 0, 0, 1]);
+        assert_eq!(find_gaps(&stats, 3).len(), 1);
+        stats[3].merge_commits = 1;
+        assert!(find_gaps(&stats, 2).is_empty());
+        assert!(find_gaps(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_longest_gap_prefers_earliest() {
+        let gaps = find_gaps(&days(&[1, 0, 0, 1, 0, 0, 1, 0, 0, 0, 1]), 1);
+        assert_eq!(gaps.len(), 3);
+        assert_eq!(longest_gap(&gaps).map(|gap| gap.from), Some(date(8)));
+        assert_eq!(longest_gap(&gaps[..2]).map(|gap| gap.from), Some(date(2)));
+        assert!(longest_gap(&[]).is_none());
+    }
+
+    #[test]
+    fn test_default_min_gap() {
+        assert_eq!(default_min_gap(Period::Daily), 3);
+        assert_eq!(default_min_gap(Period::Weekly), 7);
+    }
 }
@@ -1,62 +1,58 @@
 //! Statistics collection from commits
 
-#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 
 use crate::cli::args::Period;
 use crate::git::CommitInfo;
+use crate::stats::filter::FileFilter;
 use crate::stats::timezone::TimeZoneMode;
-use crate::stats::types::{ActivityStats, AnalysisResult, DateRange, PeriodStats};
+use crate::stats::types::{
+    ActivityStats, AnalysisResult, DateRange, ExtensionLines, ExtensionStats, FileChurn,
+    FileDetail, PeriodStats, TopCommit, TotalStats, WorkHours, WorkPatternStats, period_bounds,
+};
 use chrono::{Datelike, NaiveDate, Timelike};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Collect statistics from a list of commits
 ///
 /// Groups commits by the specified period and calculates aggregate statistics.
-/// Days with no commits are included with zero values.
+/// Days with no commits are included with zero values. Commits with no files
+/// passing `filter` are skipped entirely (see [`FileFilter::includes_commit`]).
 #[must_use]
 pub fn collect_stats(
     repo_name: &str,
     commits: Vec<CommitInfo>,
     range: DateRange,
     period: Period,
-    extensions: Option<&[String]>,
+    filter: &FileFilter,
     timezone: &TimeZoneMode,
 ) -> AnalysisResult {
     // Group commits by date
     let mut daily_stats: HashMap<NaiveDate, PeriodStats> = HashMap::new();
 
     for commit in commits {
-        let date = timezone.date_naive(commit.timestamp);
-
-        // Filter by extensions if specified
-        let (additions, deletions, files_changed) = if let Some(exts) = extensions {
-            let filtered: Vec<_> = commit
-                .diff
-                .files
-                .iter()
-                .filter(|f| f.matches_extensions(exts))
-                .collect();
-
-            (
-                filtered.iter().map(|f| f.additions).sum(),
-                filtered.iter().map(|f| f.deletions).sum(),
-                filtered.len() as u32,
-            )
-        } else {
-            (
-                commit.diff.additions,
-                commit.diff.deletions,
-                commit.diff.files_changed,
-            )
+        let Some((additions, deletions, files_changed)) = filtered_counts(&commit, filter) else {
+            continue;
         };
+        let date = timezone.date_naive(commit.timestamp);
 
         let entry = daily_stats
             .entry(date)
             .or_insert_with(|| PeriodStats::new(date));
-        entry.commits += 1;
+        if commit.is_merge {
+            entry.merge_commits += 1;
+        } else {
+            entry.commits += 1;
+        }
         entry.additions += additions;
         entry.deletions += deletions;
         entry.files_changed += files_changed;
+        entry.paths.extend(
+            filtered_paths(&commit, filter)
+                .into_iter()
+                .map(str::to_string),
+        );
+        entry.update_unique_files();
         entry.update_net_lines();
     }
 
@@ -71,89 +67,177 @@ pub fn collect_stats(
     let mut stats: Vec<_> = daily_stats.into_values().collect();
     stats.sort_by_key(|s| s.date);
 
-    // Apply period aggregation if not daily
-    let stats = match period {
-        Period::Daily => stats,
-        Period::Weekly => aggregate_by_week(stats),
-        Period::Monthly => aggregate_by_month(stats),
-        Period::Yearly => aggregate_by_year(stats),
-    };
-
     AnalysisResult::new(
         repo_name.to_string(),
         period.to_string(),
         range.from,
         range.to,
-        stats,
+        aggregate_by_period(stats, period),
     )
 }
 
-/// Aggregate daily stats by ISO week
-fn aggregate_by_week(daily_stats: Vec<PeriodStats>) -> Vec<PeriodStats> {
-    let mut weekly: HashMap<(i32, u32), PeriodStats> = HashMap::new();
+/// Group daily stats, sorted by date and covering every day of the range, into
+/// buckets of `period`
+///
+/// Periods cut off by the start or end of the range are marked with
+/// [`PARTIAL_PERIOD_MARKER`]. Lets callers holding daily stats switch periods
+/// without re-reading commits.
+#[must_use]
+pub fn aggregate_by_period(daily_stats: Vec<PeriodStats>, period: Period) -> Vec<PeriodStats> {
+    match period {
+        Period::Daily => daily_stats,
+        Period::Weekly => aggregate_by_week(daily_stats),
+        Period::Monthly => aggregate_by_month(daily_stats),
+        Period::Yearly => aggregate_by_year(daily_stats),
+    }
+}
 
-    for stat in daily_stats {
-        let week = stat.date.iso_week();
-        let key = (week.year(), week.week());
+/// Get (additions, deletions, files changed) for a commit, counting only files passing `filter`
+///
+/// Returns `None` when the commit should not be counted at all.
+fn filtered_counts(commit: &CommitInfo, filter: &FileFilter) -> Option<(u64, u64, u32)> {
+    if !filter.includes_commit(commit) {
+        return None;
+    }
 
-        let entry = weekly.entry(key).or_insert_with(|| {
-            PeriodStats::with_label(stat.date, format!("{}-W{:02}", week.year(), week.week()))
-        });
-        entry.merge(&stat);
+    if filter.is_active() {
+        let filtered: Vec<_> = commit
+            .diff
+            .files
+            .iter()
+            .filter(|f| filter.matches(f))
+            .collect();
+
+        Some((
+            filtered.iter().map(|f| f.additions).sum(),
+            filtered.iter().map(|f| f.deletions).sum(),
+            filtered.len() as u32,
+        ))
+    } else {
+        Some((
+            commit.diff.additions,
+            commit.diff.deletions,
+            commit.diff.files_changed,
+        ))
     }
+}
 
-    let mut result: Vec<_> = weekly.into_values().collect();
-    result.sort_by_key(|s| s.date);
-    result
+/// Paths changed by a commit that pass the filter
+///
+/// Only meaningful for commits [`filtered_counts`] keeps.
+fn filtered_paths<'a>(commit: &'a CommitInfo, filter: &FileFilter) -> Vec<&'a str> {
+    if filter.is_active() {
+        commit
+            .diff
+            .files
+            .iter()
+            .filter(|f| filter.matches(f))
+            .map(|f| f.path.as_str())
+            .collect()
+    } else {
+        commit.diff.changed_paths().collect()
+    }
 }
 
-/// Aggregate daily stats by month
-fn aggregate_by_month(daily_stats: Vec<PeriodStats>) -> Vec<PeriodStats> {
-    let mut monthly: HashMap<(i32, u32), PeriodStats> = HashMap::new();
+/// Collect total statistics from commits without period grouping
+///
+/// Applies the same file filtering as [`collect_stats`].
+#[must_use]
+pub fn collect_totals(commits: &[CommitInfo], filter: &FileFilter) -> TotalStats {
+    let mut total = TotalStats::default();
+    let mut paths = HashSet::new();
+    for commit in commits {
+        let Some((additions, deletions, files_changed)) = filtered_counts(commit, filter) else {
+            continue;
+        };
+        if commit.is_merge {
+            total.merge_commits += 1;
+        } else {
+            total.commits += 1;
+        }
+        total.additions += additions;
+        total.deletions += deletions;
+        total.files_changed += files_changed;
+        paths.extend(filtered_paths(commit, filter));
+    }
+    total.unique_files = paths.len() as u32;
+    total.net_lines = total.additions as i64 - total.deletions as i64;
+    total
+}
 
-    for stat in daily_stats {
-        let key = (stat.date.year(), stat.date.month());
+/// Appended to the label of a period only partly inside the analyzed range
+pub const PARTIAL_PERIOD_MARKER: &str = "*";
 
-        let entry = monthly.entry(key).or_insert_with(|| {
-            PeriodStats::with_label(
-                stat.date,
-                format!("{}-{:02}", stat.date.year(), stat.date.month()),
-            )
-        });
-        entry.merge(&stat);
-    }
+/// Aggregate daily stats by ISO week
+fn aggregate_by_week(daily_stats: Vec<PeriodStats>) -> Vec<PeriodStats> {
+    aggregate_buckets(daily_stats, Period::Weekly, |date| {
+        let week = date.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+    })
+}
 
-    let mut result: Vec<_> = monthly.into_values().collect();
-    result.sort_by_key(|s| s.date);
-    result
+/// Aggregate daily stats by month
+fn aggregate_by_month(daily_stats: Vec<PeriodStats>) -> Vec<PeriodStats> {
+    aggregate_buckets(daily_stats, Period::Monthly, |date| {
+        format!("{}-{:02}", date.year(), date.month())
+    })
 }
 
 /// Aggregate daily stats by year
 fn aggregate_by_year(daily_stats: Vec<PeriodStats>) -> Vec<PeriodStats> {
-    let mut yearly: HashMap<i32, PeriodStats> = HashMap::new();
+    aggregate_buckets(daily_stats, Period::Yearly, |date| date.year().to_string())
+}
 
-    for stat in daily_stats {
-        let year = stat.date.year();
+/// Merge daily stats into one bucket per `period`, labeled by `label`
+///
+/// Each bucket is dated by its first day in the range. Buckets with fewer days than
+/// their period, which only happens at the edges of the range, get
+/// [`PARTIAL_PERIOD_MARKER`] appended to their label.
+fn aggregate_buckets(
+    daily_stats: Vec<PeriodStats>,
+    period: Period,
+    label: impl Fn(NaiveDate) -> String,
+) -> Vec<PeriodStats> {
+    let mut buckets: BTreeMap<NaiveDate, (PeriodStats, i64)> = BTreeMap::new();
 
-        let entry = yearly
-            .entry(year)
-            .or_insert_with(|| PeriodStats::with_label(stat.date, year.to_string()));
+    for stat in daily_stats {
+        let (start, _) = period_bounds(stat.date, period);
+        let (entry, days) = buckets
+            .entry(start)
+            .or_insert_with(|| (PeriodStats::with_label(stat.date, label(stat.date)), 0));
         entry.merge(&stat);
+        *days += 1;
     }
 
-    let mut result: Vec<_> = yearly.into_values().collect();
-    result.sort_by_key(|s| s.date);
-    result
+    buckets
+        .into_iter()
+        .map(|(start, (mut stat, days))| {
+            let (_, end) = period_bounds(start, period);
+            if days < (end - start).num_days() + 1 {
+                stat.label.push_str(PARTIAL_PERIOD_MARKER);
+            }
+            stat
+        })
+        .collect()
 }
 
-/// Collect activity statistics (commits by weekday and hour) from commits
+/// Collect activity statistics (commits and changed lines by weekday and hour) from commits
 ///
-/// Groups commits by weekday (Mon-Sun) and hour (0-23) based on the selected timezone.
+/// Groups commits by weekday (Mon-Sun), hour (0-23), and weekday × hour
+/// based on the selected timezone, and sums their additions and deletions per
+/// weekday and hour. Applies the same commit and file filtering as [`collect_stats`].
 #[must_use]
-pub fn collect_activity_stats(commits: &[CommitInfo], timezone: &TimeZoneMode) -> ActivityStats {
+pub fn collect_activity_stats(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    timezone: &TimeZoneMode,
+) -> ActivityStats {
     let mut stats = ActivityStats::default();
 
     for commit in commits {
+        let Some((additions, deletions, _)) = filtered_counts(commit, filter) else {
+            continue;
+        };
         let local_time = timezone.datetime(commit.timestamp);
 
         // chrono::Weekday: Mon=0, Tue=1, ..., Sun=6
@@ -162,16 +246,308 @@ pub fn collect_activity_stats(commits: &[CommitInfo], timezone: &TimeZoneMode) -
 
         stats.weekday[weekday_index] += 1;
         stats.hourly[hour_index] += 1;
+        stats.heatmap[weekday_index][hour_index] += 1;
+        stats.weekday_lines[weekday_index] += additions + deletions;
+        stats.hourly_lines[hour_index] += additions + deletions;
+    }
+
+    stats
+}
+
+/// Split commits and their changed lines into working hours, weekday evenings, and weekends
+///
+/// Hours are read in `timezone`; a commit at `work_hours.end` already counts
+/// as an evening. Applies the same commit filtering as [`collect_stats`].
+#[must_use]
+pub fn collect_work_pattern(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    timezone: &TimeZoneMode,
+    work_hours: WorkHours,
+) -> WorkPatternStats {
+    let mut stats = WorkPatternStats {
+        work_hours,
+        ..WorkPatternStats::default()
+    };
+
+    for commit in commits {
+        let Some((additions, deletions, _)) = filtered_counts(commit, filter) else {
+            continue;
+        };
+        let local_time = timezone.datetime(commit.timestamp);
+        let bucket = if local_time.weekday().num_days_from_monday() >= 5 {
+            &mut stats.weekends
+        } else if work_hours.contains(local_time.hour()) {
+            &mut stats.business_hours
+        } else {
+            &mut stats.weekday_evenings
+        };
+        bucket.commits += 1;
+        bucket.additions += additions;
+        bucket.deletions += deletions;
     }
 
     stats
 }
 
+/// Extension label used for files without an extension
+pub const NO_EXTENSION: &str = "none";
+
+/// Collect per-extension statistics from commits
+///
+/// Files without an extension (including dotfiles like `.gitignore`) are grouped
+/// under [`NO_EXTENSION`]. Only files passing `filter` are counted.
+/// The result is sorted by additions (descending), then by extension name.
+#[must_use]
+pub fn collect_extension_stats(commits: &[CommitInfo], filter: &FileFilter) -> Vec<ExtensionStats> {
+    let mut by_ext: HashMap<&str, ExtensionStats> = HashMap::new();
+
+    for commit in commits {
+        let mut touched: Vec<&str> = Vec::new();
+
+        for file in &commit.diff.files {
+            if !filter.matches(file) {
+                continue;
+            }
+
+            let ext = file.extension().unwrap_or(NO_EXTENSION);
+            let entry = by_ext.entry(ext).or_insert_with(|| ExtensionStats {
+                extension: ext.to_string(),
+                ..Default::default()
+            });
+            entry.additions += file.additions;
+            entry.deletions += file.deletions;
+            entry.files += 1;
+
+            if !touched.contains(&ext) {
+                touched.push(ext);
+                entry.commits += 1;
+            }
+        }
+    }
+
+    let mut result: Vec<_> = by_ext.into_values().collect();
+    result.sort_by(|a, b| {
+        b.additions
+            .cmp(&a.additions)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+    result
+}
+
+/// Bucket label summing extensions outside the tracked top ones
+pub const OTHER_EXTENSIONS: &str = "other";
+
+/// Default number of extensions tracked per period
+pub const DEFAULT_PERIOD_EXTENSIONS: usize = 10;
+
+/// Collect lines changed per extension for each day, for [`PeriodStats::ext_stats`]
+///
+/// Only the `limit` extensions with the most lines changed (additions + deletions)
+/// over all commits are tracked by name; the rest are summed under
+/// [`OTHER_EXTENSIONS`], so memory stays bounded however many extensions appear.
+/// Commits and files are filtered the same way as in [`collect_stats`].
+#[must_use]
+pub fn collect_daily_extension_lines(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    timezone: &TimeZoneMode,
+    limit: usize,
+) -> HashMap<NaiveDate, BTreeMap<String, ExtensionLines>> {
+    let mut ranked: Vec<_> = collect_extension_stats(commits, filter)
+        .into_iter()
+        .map(|ext| (ext.additions + ext.deletions, ext.extension))
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    let tracked: HashSet<String> = ranked.into_iter().take(limit).map(|(_, ext)| ext).collect();
+
+    let mut by_day: HashMap<NaiveDate, BTreeMap<String, ExtensionLines>> = HashMap::new();
+    for commit in commits {
+        if !filter.includes_commit(commit) {
+            continue;
+        }
+
+        let day = by_day
+            .entry(timezone.date_naive(commit.timestamp))
+            .or_default();
+        for file in &commit.diff.files {
+            if !filter.matches(file) {
+                continue;
+            }
+
+            let ext = file.extension().unwrap_or(NO_EXTENSION);
+            let bucket = if tracked.contains(ext) {
+                ext
+            } else {
+                OTHER_EXTENSIONS
+            };
+            let entry = day.entry(bucket.to_string()).or_default();
+            entry.additions += file.additions;
+            entry.deletions += file.deletions;
+        }
+    }
+    by_day
+}
+
+/// Collect the `limit` largest commits by additions + deletions
+///
+/// Only files passing `filter` count towards a commit's size, and when the
+/// filter is active commits touching no matching files are left out. Ties are broken by
+/// newest first, then by commit id.
+#[must_use]
+pub fn collect_top_commits(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    limit: usize,
+    timezone: &TimeZoneMode,
+) -> Vec<TopCommit> {
+    let mut sized: Vec<_> = commits
+        .iter()
+        .filter_map(|commit| Some((commit, filtered_counts(commit, filter)?)))
+        .filter(|(_, (_, _, files))| !filter.is_active() || *files > 0)
+        .collect();
+
+    sized.sort_by(|(a, (a_add, a_del, _)), (b, (b_add, b_del, _))| {
+        (b_add + b_del)
+            .cmp(&(a_add + a_del))
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    sized
+        .into_iter()
+        .take(limit)
+        .map(|(commit, (additions, deletions, _))| TopCommit {
+            id: commit.id.clone(),
+            date: timezone.date_naive(commit.timestamp),
+            summary: commit.summary.clone(),
+            additions,
+            deletions,
+        })
+        .collect()
+}
+
+/// Collect the `limit` files with the most lines changed (additions + deletions)
+///
+/// Only files passing `filter` are counted. Files are keyed by the path
+/// recorded in each commit, so a renamed file appears under both its old and
+/// its new name. Ties are broken by path.
+#[must_use]
+pub fn collect_top_files(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    limit: usize,
+) -> Vec<FileChurn> {
+    collect_churn(commits, filter, limit, |path| path)
+}
+
+/// Collect the `limit` directories with the most lines changed
+///
+/// Files are grouped by the first two components of their directory
+/// (`src/cli/run.rs` and `src/cli/args/mod.rs` both count towards `src/cli`,
+/// `src/main.rs` towards `src`); files at the repository root are grouped
+/// under [`ROOT_DIR`]. Filtering and rename handling match [`collect_top_files`].
+#[must_use]
+pub fn collect_top_dirs(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    limit: usize,
+) -> Vec<FileChurn> {
+    collect_churn(commits, filter, limit, churn_dir)
+}
+
+/// Collect one entry per file changed by each commit, oldest commit first
+///
+/// Only files passing `filter` are listed, in the order the commit's diff
+/// reports them. Commits are dated in `timezone`.
+#[must_use]
+pub fn collect_file_details(
+    commits: &[CommitInfo],
+    filter: &FileFilter,
+    timezone: &TimeZoneMode,
+) -> Vec<FileDetail> {
+    let mut ordered: Vec<&CommitInfo> = commits.iter().collect();
+    ordered.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+
+    ordered
+        .into_iter()
+        .flat_map(|commit| {
+            let date = timezone.date_naive(commit.timestamp);
+            commit
+                .diff
+                .files
+                .iter()
+                .filter(|f| filter.matches(f))
+                .map(move |file| FileDetail {
+                    id: commit.id.clone(),
+                    date,
+                    path: file.path.clone(),
+                    additions: file.additions,
+                    deletions: file.deletions,
+                })
+        })
+        .collect()
+}
+
+/// Directory label used for files at the repository root
+pub const ROOT_DIR: &str = ".";
+
+/// Directory a file counts towards in the top-directories report
+fn churn_dir(path: &str) -> &str {
+    let Some((parent, _)) = path.rsplit_once('/') else {
+        return ROOT_DIR;
+    };
+    match parent.match_indices('/').nth(1) {
+        Some((index, _)) => &parent[..index],
+        None => parent,
+    }
+}
+
+/// Aggregate lines changed per key and keep the `limit` largest entries
+fn collect_churn<'a>(
+    commits: &'a [CommitInfo],
+    filter: &FileFilter,
+    limit: usize,
+    key: impl Fn(&'a str) -> &'a str,
+) -> Vec<FileChurn> {
+    let mut by_path: HashMap<&str, FileChurn> = HashMap::new();
+
+    for commit in commits {
+        let mut touched: Vec<&str> = Vec::new();
+
+        for file in commit.diff.files.iter().filter(|f| filter.matches(f)) {
+            let path = key(&file.path);
+            let entry = by_path.entry(path).or_insert_with(|| FileChurn {
+                path: path.to_string(),
+                ..Default::default()
+            });
+            entry.additions += file.additions;
+            entry.deletions += file.deletions;
+
+            if !touched.contains(&path) {
+                touched.push(path);
+                entry.commits += 1;
+            }
+        }
+    }
+
+    let mut result: Vec<_> = by_path.into_values().collect();
+    result.sort_by(|a, b| {
+        b.lines_changed()
+            .cmp(&a.lines_changed())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    result.truncate(limit);
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::git::{DiffStats, FileChange};
+    use crate::stats::filter::FileFilter;
     use crate::stats::timezone::TimeZoneMode;
+    use crate::stats::types::{Days, WorkPatternBucket};
     use chrono::{TimeZone, Utc};
 
     fn make_commit(date: NaiveDate, additions: u64, deletions: u64) -> CommitInfo {
@@ -180,6 +556,7 @@ mod tests {
             id: "abc1234".to_string(),
             timestamp,
             is_merge: false,
+            summary: String::new(),
             diff: DiffStats::new(additions, deletions, 1),
         }
     }
@@ -196,7 +573,7 @@ mod tests {
             vec![],
             range,
             Period::Daily,
-            None,
+            &FileFilter::default(),
             &TimeZoneMode::Local,
         );
 
@@ -205,6 +582,23 @@ mod tests {
         assert_eq!(result.total.commits, 0);
     }
 
+    #[test]
+    fn test_collect_stats_last_n_days_yields_exactly_n_periods() {
+        let range = DateRange::last_n_days(Days::new(7));
+
+        let result = collect_stats(
+            "test",
+            vec![],
+            range,
+            Period::Daily,
+            &FileFilter::default(),
+            &TimeZoneMode::Local,
+        );
+
+        assert_eq!(result.stats.len(), 7);
+        assert_eq!(result.stats.last().map(|s| s.date), Some(range.to));
+    }
+
     #[test]
     fn test_collect_stats_with_commits() {
         let date1 = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
@@ -222,7 +616,7 @@ mod tests {
             commits,
             range,
             Period::Daily,
-            None,
+            &FileFilter::default(),
             &TimeZoneMode::Local,
         );
 
@@ -246,24 +640,251 @@ mod tests {
             id: "abc1234".to_string(),
             timestamp,
             is_merge: false,
+            summary: String::new(),
             diff,
         };
 
+        let mut docs_diff = DiffStats::default();
+        docs_diff.add_file(FileChange::new("README.md".to_string(), 40, 4));
+        let docs_commit = CommitInfo::new("def5678".to_string(), timestamp, false, docs_diff);
+        let commits = vec![commit, docs_commit];
+
         let range = DateRange::new(date, date);
-        let extensions = vec!["rs".to_string()];
+        let filter = FileFilter::with_extensions(vec!["rs".to_string()]);
         let result = collect_stats(
             "test",
-            vec![commit],
+            commits.clone(),
             range,
             Period::Daily,
-            Some(&extensions),
+            &filter,
             &TimeZoneMode::Local,
         );
 
-        // Only .rs file should be counted
+        // Only .rs file should be counted, and the docs-only commit not at all
+        assert_eq!(result.total.commits, 1);
         assert_eq!(result.total.additions, 100);
         assert_eq!(result.total.deletions, 10);
         assert_eq!(result.total.files_changed, 1);
+
+        let activity = collect_activity_stats(&commits, &filter, &TimeZoneMode::Local);
+        assert_eq!(activity.weekday.iter().sum::<u32>(), 1);
+
+        // The escape hatch keeps counting commits without matching files
+        let filter = filter.with_count_all_commits(true);
+        let result = collect_stats(
+            "test",
+            commits,
+            range,
+            Period::Daily,
+            &filter,
+            &TimeZoneMode::Local,
+        );
+        assert_eq!(result.total.commits, 2);
+        assert_eq!(result.total.additions, 100);
+        assert_eq!(result.total.files_changed, 1);
+    }
+
+    #[test]
+    fn test_collect_stats_with_path_filters() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let timestamp = Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap());
+        let commit = |id: &str, files: &[(&str, u64)]| {
+            let mut diff = DiffStats::default();
+            for (path, additions) in files {
+                diff.add_file(FileChange::new((*path).to_string(), *additions, 1));
+            }
+            CommitInfo::new(id.to_string(), timestamp, false, diff)
+        };
+        let commits = vec![
+            commit(
+                "app",
+                &[
+                    ("src/cli/run.rs", 40),
+                    ("src/cli/generated/bindings.rs", 900),
+                    ("src/tui/widgets/heatmap.rs", 25),
+                    ("docs/guide/usage.md", 30),
+                ],
+            ),
+            commit("vendor", &[("vendor/lib/src/lib.rs", 500)]),
+        ];
+        let filter = FileFilter::new(
+            Some(vec!["rs".to_string()]),
+            &["src".to_string()],
+            &["**/generated/**".to_string()],
+        )
+        .unwrap();
+
+        let result = collect_stats(
+            "test",
+            commits,
+            DateRange::new(date, date),
+            Period::Daily,
+            &filter,
+            &TimeZoneMode::Utc,
+        );
+
+        // Only src/ .rs files outside generated/ count; the fully filtered
+        // commit is not counted at all
+        assert_eq!(result.total.commits, 1);
+        assert_eq!(result.total.additions, 65);
+        assert_eq!(result.total.deletions, 2);
+        assert_eq!(result.total.files_changed, 2);
+    }
+
+    #[test]
+    fn test_collect_extension_stats() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let timestamp = Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap());
+
+        let mut diff1 = DiffStats::default();
+        diff1.add_file(FileChange::new("src/main.rs".to_string(), 100, 10));
+        diff1.add_file(FileChange::new("src/lib.rs".to_string(), 20, 5));
+        diff1.add_file(FileChange::new(".gitignore".to_string(), 2, 0));
+
+        let mut diff2 = DiffStats::default();
+        diff2.add_file(FileChange::new("web/app.ts".to_string(), 300, 30));
+        diff2.add_file(FileChange::new("src/main.rs".to_string(), 1, 1));
+        diff2.add_file(FileChange::new("Makefile".to_string(), 3, 0));
+
+        let commits = vec![
+            CommitInfo {
+                id: "a".to_string(),
+                timestamp,
+                is_merge: false,
+                summary: String::new(),
+                diff: diff1,
+            },
+            CommitInfo {
+                id: "b".to_string(),
+                timestamp,
+                is_merge: false,
+                summary: String::new(),
+                diff: diff2,
+            },
+        ];
+
+        let stats = collect_extension_stats(&commits, &FileFilter::default());
+        let names: Vec<_> = stats.iter().map(|s| s.extension.as_str()).collect();
+        assert_eq!(names, ["ts", "rs", "none"]);
+
+        let rs = &stats[1];
+        assert_eq!(rs.commits, 2);
+        assert_eq!(rs.additions, 121);
+        assert_eq!(rs.deletions, 16);
+        assert_eq!(rs.files, 3);
+
+        // Dotfiles have no extension and are grouped with extensionless files
+        let none = &stats[2];
+        assert_eq!(none.commits, 2);
+        assert_eq!(none.additions, 5);
+        assert_eq!(none.files, 2);
+
+        let filtered = collect_extension_stats(
+            &commits,
+            &FileFilter::with_extensions(vec!["rs".to_string()]),
+        );
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].extension, "rs");
+    }
+
+    #[test]
+    fn test_collect_totals_matches_collect_stats() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let timestamp = Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap());
+
+        let mut diff = DiffStats::default();
+        diff.add_file(FileChange::new("src/main.rs".to_string(), 100, 10));
+        diff.add_file(FileChange::new("README.md".to_string(), 20, 2));
+        let commits = vec![
+            CommitInfo {
+                id: "a".to_string(),
+                timestamp,
+                is_merge: false,
+                summary: String::new(),
+                diff,
+            },
+            make_commit(date, 5, 50),
+        ];
+        let filter = FileFilter::with_extensions(vec!["rs".to_string()]);
+
+        let totals = collect_totals(&commits, &filter);
+        let result = collect_stats(
+            "test",
+            commits,
+            DateRange::new(date, date),
+            Period::Daily,
+            &filter,
+            &TimeZoneMode::Utc,
+        );
+
+        assert_eq!(totals.commits, result.total.commits);
+        assert_eq!(totals.additions, 100);
+        assert_eq!(totals.additions, result.total.additions);
+        assert_eq!(totals.deletions, result.total.deletions);
+        assert_eq!(totals.files_changed, result.total.files_changed);
+        assert_eq!(totals.net_lines, result.total.net_lines);
+    }
+
+    #[test]
+    fn test_collect_stats_counts_repeated_paths_once() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let commit = |date: NaiveDate, files: &[&str]| {
+            let mut diff = DiffStats::default();
+            for path in files {
+                diff.add_file(FileChange::new((*path).to_string(), 1, 0));
+            }
+            CommitInfo {
+                diff,
+                ..make_commit(date, 0, 0)
+            }
+        };
+        let commits = vec![
+            commit(monday, &["src/main.rs", "README.md"]),
+            commit(monday, &["src/main.rs"]),
+            commit(tuesday, &["src/main.rs", "src/lib.rs"]),
+            // Totals-only diffs still know which paths they touched
+            CommitInfo {
+                diff: DiffStats::new(3, 0, 1).with_paths(vec!["src/lib.rs".to_string()]),
+                ..make_commit(tuesday, 0, 0)
+            },
+        ];
+        let range = DateRange::new(monday, tuesday);
+        let collect = |period, filter: &FileFilter| {
+            collect_stats(
+                "test",
+                commits.clone(),
+                range,
+                period,
+                filter,
+                &TimeZoneMode::Utc,
+            )
+        };
+
+        let daily = collect(Period::Daily, &FileFilter::default());
+        let counts: Vec<_> = daily
+            .stats
+            .iter()
+            .map(|s| (s.files_changed, s.unique_files))
+            .collect();
+        assert_eq!(counts, [(3, 2), (3, 2)]);
+        assert_eq!(daily.total.files_changed, 6);
+        assert_eq!(daily.total.unique_files, 3);
+
+        // Merging days into a week counts files shared between them once
+        let weekly = collect(Period::Weekly, &FileFilter::default());
+        assert_eq!(weekly.stats.len(), 1);
+        assert_eq!(weekly.stats[0].files_changed, 6);
+        assert_eq!(weekly.stats[0].unique_files, 3);
+        assert_eq!(
+            collect_totals(&commits, &FileFilter::default()).unique_files,
+            3
+        );
+
+        let filter = FileFilter::with_extensions(vec!["rs".to_string()]);
+        let weekly = collect(Period::Weekly, &filter);
+        assert_eq!(weekly.stats[0].unique_files, 2);
+        assert_eq!(collect_totals(&commits, &filter).unique_files, 2);
     }
 
     #[test]
@@ -306,6 +927,56 @@ mod tests {
         assert_eq!(weekly[1].commits, 1);
     }
 
+    /// Aggregate an empty range of filled-in days by `period`
+    fn labels_for(from: NaiveDate, to: NaiveDate, period: Period) -> Vec<String> {
+        let result = collect_stats(
+            "test",
+            vec![],
+            DateRange::new(from, to),
+            period,
+            &FileFilter::default(),
+            &TimeZoneMode::Local,
+        );
+        result.stats.into_iter().map(|stat| stat.label).collect()
+    }
+
+    #[test]
+    fn test_weekly_marks_partial_weeks_at_range_edges() {
+        // Wednesday 2024-01-03 to Wednesday 2024-01-17
+        let labels = labels_for(
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 17).unwrap(),
+            Period::Weekly,
+        );
+        assert_eq!(labels, ["2024-W01*", "2024-W02", "2024-W03*"]);
+
+        // Monday to Sunday is one whole week
+        let labels = labels_for(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 7).unwrap(),
+            Period::Weekly,
+        );
+        assert_eq!(labels, ["2024-W01"]);
+    }
+
+    #[test]
+    fn test_monthly_marks_partial_months_at_range_edges() {
+        // Mid-January through the end of March, across a leap-year February
+        let labels = labels_for(
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            Period::Monthly,
+        );
+        assert_eq!(labels, ["2024-01*", "2024-02", "2024-03"]);
+
+        let labels = labels_for(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(),
+            Period::Monthly,
+        );
+        assert_eq!(labels, ["2024-01", "2024-02*"]);
+    }
+
     #[test]
     fn test_aggregate_by_month() {
         let jan = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
@@ -336,7 +1007,7 @@ mod tests {
     #[test]
     fn test_collect_activity_stats_empty() {
         let commits: Vec<CommitInfo> = vec![];
-        let stats = collect_activity_stats(&commits, &TimeZoneMode::Local);
+        let stats = collect_activity_stats(&commits, &FileFilter::default(), &TimeZoneMode::Local);
 
         assert_eq!(stats.weekday, [0; 7]);
         assert_eq!(stats.hourly, [0; 24]);
@@ -351,10 +1022,11 @@ mod tests {
             id: "abc1234".to_string(),
             timestamp,
             is_merge: false,
+            summary: String::new(),
             diff: DiffStats::default(),
         };
 
-        let stats = collect_activity_stats(&[commit], &TimeZoneMode::Local);
+        let stats = collect_activity_stats(&[commit], &FileFilter::default(), &TimeZoneMode::Local);
 
         // Verify exactly one commit is counted across all weekdays and hours
         let total_weekday: u32 = stats.weekday.iter().sum();
@@ -365,6 +1037,124 @@ mod tests {
         // The specific weekday/hour depends on local timezone, but exactly one slot should have 1
         assert_eq!(stats.weekday.iter().filter(|&&x| x == 1).count(), 1);
         assert_eq!(stats.hourly.iter().filter(|&&x| x == 1).count(), 1);
+        assert_eq!(
+            stats.heatmap.iter().flatten().filter(|&&x| x == 1).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_collect_work_pattern_hour_boundaries_and_weekend() {
+        // 2024-01-01 is a Monday, 2024-01-06 a Saturday
+        let commit = |day: u32, hour: u32| {
+            let date = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+            CommitInfo {
+                id: format!("{day}-{hour}"),
+                timestamp: Utc.from_utc_datetime(&date.and_hms_opt(hour, 0, 0).unwrap()),
+                is_merge: false,
+                summary: String::new(),
+                diff: DiffStats {
+                    additions: 10,
+                    deletions: 2,
+                    ..DiffStats::default()
+                },
+            }
+        };
+        let commits = [commit(1, 9), commit(1, 18), commit(1, 8), commit(6, 12)];
+
+        let stats = collect_work_pattern(
+            &commits,
+            &FileFilter::default(),
+            &TimeZoneMode::Utc,
+            WorkHours::default(),
+        );
+
+        // 09:00 opens working hours; 18:00 is already the evening
+        assert_eq!(
+            stats.business_hours,
+            WorkPatternBucket {
+                commits: 1,
+                additions: 10,
+                deletions: 2,
+            }
+        );
+        assert_eq!(stats.weekday_evenings.commits, 2);
+        assert_eq!(stats.weekends.commits, 1);
+        assert!((stats.commit_percent(&stats.weekday_evenings) - 50.0).abs() < f64::EPSILON);
+
+        let late = collect_work_pattern(
+            &commits,
+            &FileFilter::default(),
+            &TimeZoneMode::Utc,
+            "10-19".parse().unwrap(),
+        );
+        assert_eq!(late.business_hours.commits, 1);
+        assert_eq!(late.weekday_evenings.commits, 2);
+        assert_eq!(late.work_hours, WorkHours { start: 10, end: 19 });
+    }
+
+    #[test]
+    fn test_work_hours_parse() {
+        assert_eq!("9-18".parse(), Ok(WorkHours { start: 9, end: 18 }));
+        assert_eq!("0-24".parse(), Ok(WorkHours { start: 0, end: 24 }));
+        assert!("18-9".parse::<WorkHours>().is_err());
+        assert!("9-25".parse::<WorkHours>().is_err());
+        assert!("nine-five".parse::<WorkHours>().is_err());
+    }
+
+    #[test]
+    fn test_collect_activity_stats_heatmap_utc() {
+        // 2024-01-02 is a Tuesday
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let commits: Vec<CommitInfo> = [19, 19, 20]
+            .iter()
+            .map(|&hour| CommitInfo {
+                id: "a".to_string(),
+                timestamp: Utc.from_utc_datetime(&date.and_hms_opt(hour, 0, 0).unwrap()),
+                is_merge: false,
+                summary: String::new(),
+                diff: DiffStats::default(),
+            })
+            .collect();
+
+        let stats = collect_activity_stats(&commits, &FileFilter::default(), &TimeZoneMode::Utc);
+
+        assert_eq!(stats.heatmap[1][19], 2);
+        assert_eq!(stats.heatmap[1][20], 1);
+        assert_eq!(stats.heatmap.iter().flatten().sum::<u32>(), 3);
+    }
+
+    #[test]
+    fn test_collect_activity_stats_weights_by_changed_lines() {
+        // 2024-01-05 is a Friday, 2024-01-01 a Monday
+        let commit = |day: u32, hour: u32, additions: u64, deletions: u64| CommitInfo {
+            id: "a".to_string(),
+            timestamp: Utc.from_utc_datetime(
+                &NaiveDate::from_ymd_opt(2024, 1, day)
+                    .unwrap()
+                    .and_hms_opt(hour, 0, 0)
+                    .unwrap(),
+            ),
+            is_merge: false,
+            summary: String::new(),
+            diff: DiffStats {
+                additions,
+                deletions,
+                ..DiffStats::default()
+            },
+        };
+        let mut commits = vec![commit(5, 17, 4_000, 1_000)];
+        commits.extend((0..50).map(|_| commit(1, 9, 1, 1)));
+
+        let stats = collect_activity_stats(&commits, &FileFilter::default(), &TimeZoneMode::Utc);
+
+        assert_eq!(stats.weekday[0], 50);
+        assert_eq!(stats.weekday[4], 1);
+        assert_eq!(stats.weekday_lines[0], 100);
+        assert_eq!(stats.weekday_lines[4], 5_000);
+        assert_eq!(stats.hourly_lines[9], 100);
+        assert_eq!(stats.hourly_lines[17], 5_000);
+        assert_eq!(stats.hourly_lines.iter().sum::<u64>(), 5_100);
     }
 
     #[test]
@@ -378,6 +1168,7 @@ mod tests {
                     id: "a".to_string(),
                     timestamp,
                     is_merge: false,
+                    summary: String::new(),
                     diff: DiffStats::default(),
                 }
             },
@@ -388,6 +1179,7 @@ mod tests {
                     id: "b".to_string(),
                     timestamp,
                     is_merge: false,
+                    summary: String::new(),
                     diff: DiffStats::default(),
                 }
             },
@@ -399,6 +1191,7 @@ mod tests {
                     id: "c".to_string(),
                     timestamp,
                     is_merge: false,
+                    summary: String::new(),
                     diff: DiffStats::default(),
                 }
             },
@@ -410,12 +1203,13 @@ mod tests {
                     id: "d".to_string(),
                     timestamp,
                     is_merge: false,
+                    summary: String::new(),
                     diff: DiffStats::default(),
                 }
             },
         ];
 
-        let stats = collect_activity_stats(&commits, &TimeZoneMode::Local);
+        let stats = collect_activity_stats(&commits, &FileFilter::default(), &TimeZoneMode::Local);
 
         // Verify total commits are counted correctly
         let total_weekday: u32 = stats.weekday.iter().sum();
@@ -427,4 +1221,275 @@ mod tests {
         // (regardless of timezone, they should be in the same local hour)
         assert!(stats.hourly.contains(&2));
     }
+
+    #[test]
+    fn test_collect_top_commits_orders_by_size() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut small = make_commit(date, 5, 5);
+        small.id = "small".to_string();
+        let mut big = make_commit(date, 100, 50);
+        big.id = "big".to_string();
+        big.summary = "Big change".to_string();
+        let mut medium = make_commit(date, 30, 0);
+        medium.id = "medium".to_string();
+
+        let top = collect_top_commits(
+            &[small, big, medium],
+            &FileFilter::default(),
+            2,
+            &TimeZoneMode::Utc,
+        );
+
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].id, "big");
+        assert_eq!(top[0].summary, "Big change");
+        assert_eq!(top[0].additions, 100);
+        assert_eq!(top[0].deletions, 50);
+        assert_eq!(top[0].date, date);
+        assert_eq!(top[1].id, "medium");
+    }
+
+    #[test]
+    fn test_collect_top_commits_respects_extension_filter() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let timestamp = Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap());
+        let commit = |id: &str, files: &[(&str, u64)]| {
+            let mut diff = DiffStats::default();
+            for (path, additions) in files {
+                diff.add_file(FileChange::new((*path).to_string(), *additions, 0));
+            }
+            CommitInfo::new(id.to_string(), timestamp, false, diff)
+        };
+        let commits = vec![
+            commit("docs", &[("README.md", 500)]),
+            commit("mixed", &[("src/lib.rs", 10), ("CHANGELOG.md", 300)]),
+            commit("rust", &[("src/main.rs", 40)]),
+        ];
+        let filter = FileFilter::with_extensions(vec!["rs".to_string()]);
+
+        let top = collect_top_commits(&commits, &filter, 5, &TimeZoneMode::Utc);
+
+        let ids: Vec<_> = top.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["rust", "mixed"]);
+        assert_eq!(top[1].additions, 10);
+    }
+
+    fn commit_with_files(id: &str, files: &[(&str, u64, u64)]) -> CommitInfo {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let timestamp = Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap());
+        let mut diff = DiffStats::default();
+        for (path, additions, deletions) in files {
+            diff.add_file(FileChange::new((*path).to_string(), *additions, *deletions));
+        }
+        CommitInfo::new(id.to_string(), timestamp, false, diff)
+    }
+
+    fn lines(additions: u64, deletions: u64) -> ExtensionLines {
+        ExtensionLines {
+            additions,
+            deletions,
+        }
+    }
+
+    #[test]
+    fn test_collect_daily_extension_lines_buckets_other() {
+        let mut second_day = commit_with_files("b", &[("src/lib.rs", 5, 5), ("Makefile", 3, 0)]);
+        second_day.timestamp += chrono::Duration::days(1);
+        let commits = vec![
+            commit_with_files(
+                "a",
+                &[
+                    ("src/main.rs", 100, 10),
+                    ("web/app.ts", 50, 0),
+                    ("a.md", 2, 1),
+                ],
+            ),
+            second_day,
+        ];
+        let day1 = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let by_day =
+            collect_daily_extension_lines(&commits, &FileFilter::default(), &TimeZoneMode::Utc, 2);
+
+        // rs and ts are the top two; md and extensionless files fall into "other"
+        let expected_day1 = BTreeMap::from([
+            ("rs".to_string(), lines(100, 10)),
+            ("ts".to_string(), lines(50, 0)),
+            (OTHER_EXTENSIONS.to_string(), lines(2, 1)),
+        ]);
+        let expected_day2 = BTreeMap::from([
+            ("rs".to_string(), lines(5, 5)),
+            (OTHER_EXTENSIONS.to_string(), lines(3, 0)),
+        ]);
+        assert_eq!(by_day[&day1], expected_day1);
+        assert_eq!(by_day[&day2], expected_day2);
+
+        let filtered = collect_daily_extension_lines(
+            &commits,
+            &FileFilter::with_extensions(vec!["md".to_string()]),
+            &TimeZoneMode::Utc,
+            2,
+        );
+        assert_eq!(
+            filtered[&day1],
+            BTreeMap::from([("md".to_string(), lines(2, 1))])
+        );
+    }
+
+    #[test]
+    fn test_aggregate_by_week_merges_ext_stats() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let daily = vec![
+            PeriodStats {
+                date: monday,
+                commits: 1,
+                ext_stats: BTreeMap::from([
+                    ("rs".to_string(), lines(10, 1)),
+                    (OTHER_EXTENSIONS.to_string(), lines(4, 0)),
+                ]),
+                ..Default::default()
+            },
+            PeriodStats {
+                date: tuesday,
+                commits: 1,
+                ext_stats: BTreeMap::from([
+                    ("rs".to_string(), lines(5, 2)),
+                    ("ts".to_string(), lines(7, 0)),
+                ]),
+                ..Default::default()
+            },
+        ];
+
+        let weekly = aggregate_by_period(daily, Period::Weekly);
+
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(
+            weekly[0].ext_stats,
+            BTreeMap::from([
+                ("rs".to_string(), lines(15, 3)),
+                ("ts".to_string(), lines(7, 0)),
+                (OTHER_EXTENSIONS.to_string(), lines(4, 0)),
+            ])
+        );
+
+        let json = serde_json::to_value(&weekly[0]).unwrap();
+        assert_eq!(json["ext_stats"]["rs"]["additions"], 15);
+        let plain = serde_json::to_value(PeriodStats::default()).unwrap();
+        assert!(plain.get("ext_stats").is_none());
+    }
+
+    #[test]
+    fn test_collect_top_files_aggregates_across_commits() {
+        let commits = vec![
+            commit_with_files("a", &[("src/main.rs", 10, 5), ("README.md", 40, 0)]),
+            commit_with_files("b", &[("src/main.rs", 30, 10)]),
+            commit_with_files("c", &[("src/lib.rs", 1, 0)]),
+        ];
+
+        let top = collect_top_files(&commits, &FileFilter::default(), 2);
+
+        assert_eq!(
+            top,
+            [
+                FileChurn {
+                    path: "src/main.rs".to_string(),
+                    additions: 40,
+                    deletions: 15,
+                    commits: 2,
+                },
+                FileChurn {
+                    path: "README.md".to_string(),
+                    additions: 40,
+                    deletions: 0,
+                    commits: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_top_files_respects_filter() {
+        let commits = vec![commit_with_files(
+            "a",
+            &[("src/main.rs", 10, 0), ("README.md", 500, 0)],
+        )];
+        let filter = FileFilter::with_extensions(vec!["rs".to_string()]);
+
+        let top = collect_top_files(&commits, &filter, 10);
+
+        let paths: Vec<_> = top.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["src/main.rs"]);
+    }
+
+    #[test]
+    fn test_collect_top_dirs_groups_by_leading_components() {
+        let commits = vec![
+            commit_with_files(
+                "a",
+                &[
+                    ("src/cli/run.rs", 20, 0),
+                    ("src/cli/args/mod.rs", 10, 0),
+                    ("src/main.rs", 5, 0),
+                ],
+            ),
+            commit_with_files("b", &[("Cargo.toml", 2, 1), ("src/cli/run.rs", 0, 4)]),
+        ];
+
+        let top = collect_top_dirs(&commits, &FileFilter::default(), 10);
+
+        let dirs: Vec<_> = top
+            .iter()
+            .map(|d| (d.path.as_str(), d.lines_changed(), d.commits))
+            .collect();
+        assert_eq!(dirs, [("src/cli", 34, 2), ("src", 5, 1), (ROOT_DIR, 3, 1)]);
+    }
+
+    #[test]
+    fn test_collect_file_details_one_row_per_commit_and_file() {
+        let mut late = commit_with_files("b", &[("src/lib.rs", 3, 1)]);
+        late.timestamp += chrono::Duration::days(1);
+        let commits = vec![
+            late,
+            commit_with_files("a", &[("src/main.rs", 10, 5), ("README.md", 40, 0)]),
+        ];
+
+        let details = collect_file_details(&commits, &FileFilter::default(), &TimeZoneMode::Utc);
+
+        let rows: Vec<_> = details
+            .iter()
+            .map(|d| {
+                (
+                    d.id.as_str(),
+                    d.date.day(),
+                    d.path.as_str(),
+                    d.additions,
+                    d.deletions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("a", 1, "src/main.rs", 10, 5),
+                ("a", 1, "README.md", 40, 0),
+                ("b", 2, "src/lib.rs", 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_file_details_respects_filter() {
+        let commits = vec![
+            commit_with_files("a", &[("src/main.rs", 10, 0), ("README.md", 500, 0)]),
+            commit_with_files("b", &[("docs/guide.md", 7, 0)]),
+        ];
+        let filter = FileFilter::with_extensions(vec!["rs".to_string()]);
+
+        let details = collect_file_details(&commits, &filter, &TimeZoneMode::Utc);
+
+        let paths: Vec<_> = details.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, ["src/main.rs"]);
+    }
 }
@@ -0,0 +1,251 @@
+//! File filtering by extension and path glob
+
+use crate::error::{Error, Result};
+use crate::git::{CommitInfo, DiffStats, FileChange};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// Decides which changed files count towards statistics
+///
+/// A file is counted when it matches the extension list (if any), matches at
+/// least one include glob (if any), and matches no exclude glob. Exclude globs
+/// always win over include globs and extensions.
+///
+/// Globs are matched against the repository-relative path. `*` does not cross
+/// `/`; use `**` to match across directories. A glob that matches a directory
+/// also matches everything below it, so `--path src` behaves like `--path 'src/**'`.
+///
+/// A commit touching no matching files is not counted at all, unless
+/// [`FileFilter::with_count_all_commits`] is set.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    extensions: Option<Vec<String>>,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    count_all_commits: bool,
+}
+
+impl FileFilter {
+    /// Build a filter from extensions and include/exclude glob patterns
+    ///
+    /// Empty pattern lists disable the corresponding check.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any pattern is not a valid glob
+    pub fn new(
+        extensions: Option<Vec<String>>,
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Self> {
+        Ok(Self {
+            extensions,
+            include: build_glob_set(include)?,
+            exclude: build_glob_set(exclude)?,
+            count_all_commits: false,
+        })
+    }
+
+    /// Build a filter that only checks extensions
+    #[must_use]
+    pub fn with_extensions(extensions: Vec<String>) -> Self {
+        Self {
+            extensions: Some(extensions),
+            ..Self::default()
+        }
+    }
+
+    /// Keep counting commits whose files are all filtered out (they contribute no lines)
+    #[must_use]
+    pub const fn with_count_all_commits(mut self, count_all_commits: bool) -> Self {
+        self.count_all_commits = count_all_commits;
+        self
+    }
+
+    /// Whether the filter can reject any file
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.extensions.is_some() || self.include.is_some() || self.exclude.is_some()
+    }
+
+    /// Check whether a changed file passes the filter
+    #[must_use]
+    pub fn matches(&self, file: &FileChange) -> bool {
+        if self
+            .exclude
+            .as_ref()
+            .is_some_and(|set| set.is_match(&file.path))
+        {
+            return false;
+        }
+        if self
+            .include
+            .as_ref()
+            .is_some_and(|set| !set.is_match(&file.path))
+        {
+            return false;
+        }
+        self.extensions
+            .as_deref()
+            .is_none_or(|exts| file.matches_extensions(exts))
+    }
+
+    /// Check whether a commit counts towards statistics
+    ///
+    /// True when the filter is inactive, when at least one changed file passes
+    /// it, or when counting all commits was requested.
+    #[must_use]
+    pub fn includes_commit(&self, commit: &CommitInfo) -> bool {
+        !self.is_active()
+            || self.count_all_commits
+            || commit.diff.files.iter().any(|f| self.matches(f))
+    }
+
+    /// Drop the files rejected by the filter from each commit
+    ///
+    /// Commit totals are recomputed from the remaining files, and commits that
+    /// [`FileFilter::includes_commit`] rejects are removed. Used to apply one
+    /// repository's own filter before its commits are merged with others.
+    #[must_use]
+    pub fn narrow_commits(&self, commits: Vec<CommitInfo>) -> Vec<CommitInfo> {
+        if !self.is_active() {
+            return commits;
+        }
+        commits
+            .into_iter()
+            .filter(|commit| self.includes_commit(commit))
+            .map(|mut commit| {
+                let files = std::mem::take(&mut commit.diff.files);
+                commit.diff = DiffStats::default();
+                for file in files.into_iter().filter(|f| self.matches(f)) {
+                    commit.diff.add_file(file);
+                }
+                commit
+            })
+            .collect()
+    }
+}
+
+/// Compile patterns into a glob set, or `None` when there are no patterns
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_end_matches('/');
+        builder.add(compile(pattern)?);
+        // Also match everything below a matching directory
+        builder.add(compile(&format!("{pattern}/**"))?);
+    }
+    builder.build().map(Some).map_err(|e| Error::InvalidGlob {
+        pattern: patterns.join(", "),
+        message: e.kind().to_string(),
+    })
+}
+
+fn compile(pattern: &str) -> Result<Glob> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| Error::InvalidGlob {
+            pattern: pattern.to_string(),
+            message: e.kind().to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> FileChange {
+        FileChange::new(path.to_string(), 1, 0)
+    }
+
+    fn patterns(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_default_filter_matches_everything() {
+        let filter = FileFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches(&file("src/main.rs")));
+        assert!(filter.matches(&file(".gitignore")));
+    }
+
+    #[test]
+    fn test_include_globs_match_nested_paths() {
+        let filter = FileFilter::new(None, &patterns(&["src/**/*.rs"]), &[]).unwrap();
+
+        assert!(filter.matches(&file("src/main.rs")));
+        assert!(filter.matches(&file("src/git/diff/mod.rs")));
+        assert!(!filter.matches(&file("tests/main.rs")));
+        assert!(!filter.matches(&file("src/git/README.md")));
+    }
+
+    #[test]
+    fn test_star_does_not_cross_directories() {
+        let filter = FileFilter::new(None, &patterns(&["src/*.rs"]), &[]).unwrap();
+
+        assert!(filter.matches(&file("src/lib.rs")));
+        assert!(!filter.matches(&file("src/git/diff.rs")));
+    }
+
+    #[test]
+    fn test_directory_pattern_matches_contents() {
+        let filter = FileFilter::new(None, &patterns(&["docs/"]), &[]).unwrap();
+
+        assert!(filter.matches(&file("docs/guide.md")));
+        assert!(filter.matches(&file("docs/api/v1/index.md")));
+        assert!(!filter.matches(&file("src/docs.rs")));
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include_and_extensions() {
+        let filter = FileFilter::new(
+            Some(patterns(&["rs"])),
+            &patterns(&["src"]),
+            &patterns(&["src/generated"]),
+        )
+        .unwrap();
+
+        assert!(filter.matches(&file("src/cli/run.rs")));
+        assert!(!filter.matches(&file("src/generated/proto/types.rs")));
+        assert!(!filter.matches(&file("src/cli/help.txt")));
+        assert!(!filter.matches(&file("benches/git_stats.rs")));
+    }
+
+    #[test]
+    fn test_narrow_commits_recomputes_totals() {
+        let commit = |id: &str, paths: &[&str]| {
+            let mut diff = DiffStats::default();
+            for path in paths {
+                diff.add_file(FileChange::new((*path).to_string(), 3, 1));
+            }
+            CommitInfo::new(id.to_string(), chrono::Utc::now(), false, diff)
+        };
+        let commits = vec![
+            commit("a", &["README.md", "src/main.rs"]),
+            commit("b", &["src/lib.rs"]),
+        ];
+        let filter = FileFilter::with_extensions(patterns(&["md"]));
+
+        let narrowed = filter.narrow_commits(commits.clone());
+        assert_eq!(narrowed.len(), 1);
+        assert_eq!(narrowed[0].id, "a");
+        assert_eq!(narrowed[0].diff.additions, 3);
+        assert_eq!(narrowed[0].diff.deletions, 1);
+        assert_eq!(narrowed[0].diff.files_changed, 1);
+
+        let kept = filter.with_count_all_commits(true).narrow_commits(commits);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].diff.files_changed, 0);
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        let err = FileFilter::new(None, &patterns(&["src/[a-"]), &[]).unwrap_err();
+        assert!(matches!(err, Error::InvalidGlob { ref pattern, .. } if pattern == "src/[a-"));
+    }
+}
@@ -2,10 +2,25 @@
 
 pub mod aggregator;
 pub mod collector;
+pub mod filter;
 pub mod timezone;
 pub mod types;
 
-pub use aggregator::{filter_non_zero, merge_stats, running_totals};
-pub use collector::{collect_activity_stats, collect_stats};
+pub use aggregator::{
+    OUTLIER_MADS, compute_moving_average, default_min_gap, filter_non_zero, find_gaps, longest_gap,
+    mark_outliers, median, median_absolute_deviation, merge_stats, moving_averages, running_totals,
+};
+pub use collector::{
+    DEFAULT_PERIOD_EXTENSIONS, OTHER_EXTENSIONS, PARTIAL_PERIOD_MARKER, aggregate_by_period,
+    collect_activity_stats, collect_daily_extension_lines, collect_extension_stats,
+    collect_file_details, collect_stats, collect_top_commits, collect_top_dirs, collect_top_files,
+    collect_totals, collect_work_pattern,
+};
+pub use filter::FileFilter;
 pub use timezone::TimeZoneMode;
-pub use types::{ActivityStats, AnalysisResult, DateRange, Days, PeriodStats, TotalStats};
+pub use types::{
+    ActivityStats, AnalysisResult, ComparisonStats, DateRange, Days, ExtensionLines,
+    ExtensionStats, FileChurn, FileDetail, Gap, MetricDelta, MovingAverage, PeriodDelta,
+    PeriodStats, RepoStats, TIME_OF_DAY_BUCKETS, TopCommit, TotalStats, WorkHours,
+    WorkPatternBucket, WorkPatternStats, YearOverYear, period_bounds,
+};
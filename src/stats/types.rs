@@ -1,9 +1,12 @@
 //! Core statistics types
 
-#![allow(clippy::cast_possible_wrap)]
+#![allow(clippy::cast_possible_wrap, clippy::cast_precision_loss)]
 
-use chrono::{Local, NaiveDate};
-use serde::Serialize;
+use crate::cli::args::Period;
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
 
 /// Days count (non-negative)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,8 +47,16 @@ impl DateRange {
     /// Uses local timezone to determine "today"
     #[must_use]
     pub fn last_n_days(days: Days) -> Self {
-        let to = Local::now().date_naive();
-        let from = to - chrono::Duration::days(i64::from(days.0));
+        Self::ending_on(Local::now().date_naive(), days)
+    }
+
+    /// Create a date range covering exactly N days that ends on `to` (inclusive)
+    ///
+    /// A zero-day span is treated as a single day.
+    #[must_use]
+    pub fn ending_on(to: NaiveDate, days: Days) -> Self {
+        let span = i64::from(days.0.max(1)) - 1;
+        let from = to - chrono::Duration::days(span);
         Self { from, to }
     }
 
@@ -61,6 +72,57 @@ impl DateRange {
         date >= self.from && date <= self.to
     }
 
+    /// Number of days in the range (inclusive)
+    #[must_use]
+    pub fn num_days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+
+    /// The range of equal length ending the day before this one starts
+    #[must_use]
+    pub fn previous(&self) -> Self {
+        let to = self.from - chrono::Duration::days(1);
+        let from = to - chrono::Duration::days(self.num_days() - 1);
+        Self { from, to }
+    }
+
+    /// The same span one year earlier, for `--yoy`
+    ///
+    /// Weekly ranges keep each date's ISO week number and weekday, so weeks
+    /// line up by number. A date in week 53 of a year whose predecessor has
+    /// only 52 weeks maps to week 52 for the end of the range, and to week 1
+    /// of the following year for the start. Other periods shift the calendar
+    /// date back a year; February 29 becomes March 1 at the start of the
+    /// range and February 28 at its end, so the earlier range never covers a
+    /// day without a counterpart.
+    #[must_use]
+    pub fn year_earlier(&self, period: Period) -> Self {
+        Self {
+            from: year_earlier_date(self.from, period, true),
+            to: year_earlier_date(self.to, period, false),
+        }
+    }
+
+    /// The part of this range made of whole periods, or `None` if no period fits
+    ///
+    /// Weeks run Monday to Sunday, matching the ISO weeks of weekly stats.
+    #[must_use]
+    pub fn complete_periods(&self, period: Period) -> Option<Self> {
+        let (first_start, first_end) = period_bounds(self.from, period);
+        let from = if first_start == self.from {
+            self.from
+        } else {
+            first_end + chrono::Duration::days(1)
+        };
+        let (last_start, last_end) = period_bounds(self.to, period);
+        let to = if last_end == self.to {
+            self.to
+        } else {
+            last_start - chrono::Duration::days(1)
+        };
+        (from <= to).then_some(Self { from, to })
+    }
+
     /// Iterate over all dates in the range
     pub fn iter_days(&self) -> impl Iterator<Item = NaiveDate> {
         let from = self.from;
@@ -72,19 +134,79 @@ impl DateRange {
     }
 }
 
+/// `date` one year earlier, see [`DateRange::year_earlier`]
+fn year_earlier_date(date: NaiveDate, period: Period, start: bool) -> NaiveDate {
+    if period == Period::Weekly {
+        let week = date.iso_week();
+        let weekday = date.weekday();
+        return NaiveDate::from_isoywd_opt(week.year() - 1, week.week(), weekday)
+            .or_else(|| {
+                if start {
+                    NaiveDate::from_isoywd_opt(week.year(), 1, weekday)
+                } else {
+                    NaiveDate::from_isoywd_opt(week.year() - 1, 52, weekday)
+                }
+            })
+            .unwrap_or(date);
+    }
+    date.with_year(date.year() - 1)
+        .or_else(|| {
+            // February 29 of a leap year
+            if start {
+                NaiveDate::from_ymd_opt(date.year() - 1, 3, 1)
+            } else {
+                NaiveDate::from_ymd_opt(date.year() - 1, 2, 28)
+            }
+        })
+        .unwrap_or(date)
+}
+
+/// First and last day of the `period` containing `date`
+#[must_use]
+pub fn period_bounds(date: NaiveDate, period: Period) -> (NaiveDate, NaiveDate) {
+    let (start, next) = match period {
+        Period::Daily => (date, date.succ_opt()),
+        Period::Weekly => {
+            let start =
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()));
+            (start, Some(start + chrono::Duration::days(7)))
+        }
+        Period::Monthly => {
+            let start = date.with_day(1).unwrap_or(date);
+            (start, start.checked_add_months(chrono::Months::new(1)))
+        }
+        Period::Yearly => {
+            let start = date.with_ordinal(1).unwrap_or(date);
+            (start, start.with_year(start.year() + 1))
+        }
+    };
+    // Only the last representable period has no successor
+    let end = next
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(NaiveDate::MAX);
+    (start, end)
+}
+
 /// Statistics for a single time period
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PeriodStats {
     /// Period identifier (date, week, month, or year label)
     pub label: String,
 
     /// Start date of the period
-    #[serde(serialize_with = "serialize_date")]
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
     pub date: NaiveDate,
 
-    /// Number of commits
+    /// Number of commits, not counting merges
     pub commits: u32,
 
+    /// Number of merge commits, only collected with `--include-merges`
+    #[serde(default)]
+    pub merge_commits: u32,
+
     /// Lines added
     pub additions: u64,
 
@@ -94,19 +216,49 @@ pub struct PeriodStats {
     /// Net line change (additions - deletions)
     pub net_lines: i64,
 
-    /// Number of files changed
+    /// Number of files changed, counted once per commit that touched them
     pub files_changed: u32,
+
+    /// Number of distinct files changed
+    #[serde(default)]
+    pub unique_files: u32,
+
+    /// Paths behind `unique_files`, kept so that merged periods count each file once
+    ///
+    /// Not serialized: periods read back from JSON merge by summing `unique_files`.
+    #[serde(skip)]
+    pub paths: BTreeSet<String>,
+
+    /// Lines changed per file extension, tracked with `--by-extension`
+    ///
+    /// Holds the extensions with the most lines changed over the whole range,
+    /// plus an `other` bucket summing the rest.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ext_stats: BTreeMap<String, ExtensionLines>,
+
+    /// Changed lines are far above the typical period, see [`crate::stats::mark_outliers`]
+    #[serde(default)]
+    pub is_outlier: bool,
 }
 
 // serde's serialize_with requires `fn(&T, S)` signature
 #[allow(clippy::trivially_copy_pass_by_ref)]
-fn serialize_date<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_date<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
     serializer.serialize_str(&date.format("%Y-%m-%d").to_string())
 }
 
+/// Parse a `YYYY-MM-DD` date written by [`serialize_date`]
+pub(crate) fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(serde::de::Error::custom)
+}
+
 impl PeriodStats {
     /// Create a new `PeriodStats` for a given date
     #[must_use]
@@ -137,20 +289,53 @@ impl PeriodStats {
     /// Merge another period's stats into this one
     pub fn merge(&mut self, other: &Self) {
         self.commits += other.commits;
+        self.merge_commits += other.merge_commits;
         self.additions += other.additions;
         self.deletions += other.deletions;
         self.files_changed += other.files_changed;
+        // Stats read back from JSON have no paths; summing their counts is the
+        // best estimate then, where the empty path set would give zero
+        if self.paths.is_empty() && other.paths.is_empty() {
+            self.unique_files += other.unique_files;
+        } else {
+            self.paths.extend(other.paths.iter().cloned());
+            self.update_unique_files();
+        }
         self.net_lines = self.calculate_net_lines();
+        for (extension, lines) in &other.ext_stats {
+            let entry = self.ext_stats.entry(extension.clone()).or_default();
+            entry.additions += lines.additions;
+            entry.deletions += lines.deletions;
+        }
+    }
+
+    /// Whether nothing at all happened in the period: no commits, merge
+    /// commits, added or deleted lines, or changed files
+    ///
+    /// A period with changed files but no counted commits or lines (say, only
+    /// binary files under `--path`) is not empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.commits == 0
+            && self.merge_commits == 0
+            && self.additions == 0
+            && self.deletions == 0
+            && self.files_changed == 0
     }
 
     /// Update `net_lines` based on current additions/deletions
     pub fn update_net_lines(&mut self) {
         self.net_lines = self.calculate_net_lines();
     }
+
+    /// Update `unique_files` based on current paths
+    pub fn update_unique_files(&mut self) {
+        self.unique_files = u32::try_from(self.paths.len()).unwrap_or(u32::MAX);
+    }
 }
 
 /// Complete analysis result
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
     /// Repository name
     pub repository: String,
@@ -159,11 +344,17 @@ pub struct AnalysisResult {
     pub period: String,
 
     /// Start date of analysis
-    #[serde(serialize_with = "serialize_date")]
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
     pub from: NaiveDate,
 
     /// End date of analysis
-    #[serde(serialize_with = "serialize_date")]
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
     pub to: NaiveDate,
 
     /// Statistics per period
@@ -171,6 +362,54 @@ pub struct AnalysisResult {
 
     /// Total statistics across all periods
     pub total: TotalStats,
+
+    /// Per-extension breakdown (only present when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<ExtensionStats>>,
+
+    /// Largest commits by lines changed (only present when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_commits: Option<Vec<TopCommit>>,
+
+    /// Files with the most lines changed (only present when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_files: Option<Vec<FileChurn>>,
+
+    /// Directories with the most lines changed (only present when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_dirs: Option<Vec<FileChurn>>,
+
+    /// Lines changed per file by each commit (only present when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_details: Option<Vec<FileDetail>>,
+
+    /// Per-repository totals (only present when more than one repository is analyzed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repositories: Vec<RepoStats>,
+
+    /// Change relative to the preceding range (only present when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<ComparisonStats>,
+
+    /// Moving averages of the per-period series (only present when requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moving_average: Option<MovingAverage>,
+
+    /// Whether `--max-commits` or `--max-seconds` left commits in range uncounted
+    #[serde(default)]
+    pub truncated: bool,
+
+    /// Split of the activity into working hours, weekday evenings, and weekends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_pattern: Option<WorkPatternStats>,
+
+    /// Each period next to the same period a year earlier (only present when requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year_over_year: Option<YearOverYear>,
+
+    /// Stretches of days without commits, oldest first (only present when there are any)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gaps: Vec<Gap>,
 }
 
 impl AnalysisResult {
@@ -191,17 +430,527 @@ impl AnalysisResult {
             to,
             stats,
             total,
+            extensions: None,
+            top_commits: None,
+            top_files: None,
+            top_dirs: None,
+            file_details: None,
+            repositories: Vec::new(),
+            comparison: None,
+            moving_average: None,
+            truncated: false,
+            work_pattern: None,
+            year_over_year: None,
+            gaps: Vec::new(),
+        }
+    }
+}
+
+/// A run of consecutive days without commits
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Gap {
+    /// First day without commits
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
+    pub from: NaiveDate,
+
+    /// Last day without commits
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
+    pub to: NaiveDate,
+
+    /// Number of days in the run
+    pub length: u32,
+
+    /// Whether the run touches the start or end of the analyzed range, so it
+    /// may extend beyond it
+    #[serde(default)]
+    pub open_ended: bool,
+}
+
+impl std::fmt::Display for Gap {
+    /// Length and dates, like `6 days, Mar 10–15`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = if self.length == 1 { "day" } else { "days" };
+        write!(f, "{} {unit}, {}", self.length, self.from.format("%b %-d"))?;
+        if self.from.year() != self.to.year() {
+            write!(
+                f,
+                ", {}\u{2013}{}",
+                self.from.year(),
+                self.to.format("%b %-d, %Y")
+            )?;
+        } else if self.from.month() != self.to.month() {
+            write!(f, "\u{2013}{}", self.to.format("%b %-d"))?;
+        } else if self.from != self.to {
+            write!(f, "\u{2013}{}", self.to.day())?;
+        }
+        if self.open_ended {
+            f.write_str(" (open-ended)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Trailing moving averages over `window` periods, one value per entry of `stats`
+///
+/// The first periods average over however many periods exist so far, so the
+/// series starts at the first raw value instead of zero.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MovingAverage {
+    /// Number of periods averaged
+    pub window: usize,
+
+    /// Average commits per period
+    pub commits: Vec<f64>,
+
+    /// Average lines added per period
+    pub additions: Vec<f64>,
+
+    /// Average lines deleted per period
+    pub deletions: Vec<f64>,
+
+    /// Average net lines per period
+    pub net_lines: Vec<f64>,
+
+    /// Average files changed per period
+    pub files_changed: Vec<f64>,
+
+    /// Average distinct files changed per period
+    #[serde(default)]
+    pub unique_files: Vec<f64>,
+}
+
+/// Change of a single metric between the previous and current range
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MetricDelta {
+    /// Value in the analyzed range
+    pub current: i64,
+
+    /// Value in the previous range
+    pub previous: i64,
+
+    /// Absolute change (`current - previous`)
+    pub change: i64,
+
+    /// Percentage change relative to the previous value (`null` when it was zero)
+    pub percent: Option<f64>,
+}
+
+impl MetricDelta {
+    /// Compute the change from `previous` to `current`
+    #[must_use]
+    pub fn new(current: i64, previous: i64) -> Self {
+        let change = current - previous;
+        let percent =
+            (previous != 0).then(|| change as f64 / previous.unsigned_abs() as f64 * 100.0);
+        Self {
+            current,
+            previous,
+            change,
+            percent,
+        }
+    }
+
+    /// Trend indicator: `▲` for an increase, `▼` for a decrease, `=` for no change
+    #[must_use]
+    pub const fn indicator(&self) -> &'static str {
+        match self.change {
+            1.. => "▲",
+            0 => "=",
+            _ => "▼",
+        }
+    }
+
+    /// Signed percentage like `+12.5%`, or `n/a` when the previous value was zero
+    #[must_use]
+    pub fn percent_label(&self) -> String {
+        self.percent
+            .map_or_else(|| "n/a".to_string(), |percent| format!("{percent:+.1}%"))
+    }
+}
+
+/// Totals compared against the immediately preceding range of equal length
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComparisonStats {
+    /// Start date of the previous range
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
+    pub previous_from: NaiveDate,
+
+    /// End date of the previous range
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
+    pub previous_to: NaiveDate,
+
+    /// Change in commit count
+    pub commits: MetricDelta,
+
+    /// Change in lines added
+    pub additions: MetricDelta,
+
+    /// Change in lines deleted
+    pub deletions: MetricDelta,
+
+    /// Change in net lines
+    pub net_lines: MetricDelta,
+
+    /// Change in files changed
+    pub files_changed: MetricDelta,
+}
+
+impl ComparisonStats {
+    /// Compare current totals against the totals of `previous_range`
+    #[must_use]
+    pub fn new(previous_range: DateRange, current: &TotalStats, previous: &TotalStats) -> Self {
+        Self {
+            previous_from: previous_range.from,
+            previous_to: previous_range.to,
+            commits: MetricDelta::new(i64::from(current.commits), i64::from(previous.commits)),
+            additions: MetricDelta::new(current.additions as i64, previous.additions as i64),
+            deletions: MetricDelta::new(current.deletions as i64, previous.deletions as i64),
+            net_lines: MetricDelta::new(current.net_lines, previous.net_lines),
+            files_changed: MetricDelta::new(
+                i64::from(current.files_changed),
+                i64::from(previous.files_changed),
+            ),
+        }
+    }
+}
+
+/// Each period of the analyzed range next to the same period one year earlier
+///
+/// Entries of the three lists line up by index. Periods are matched by their
+/// start: the same month a year earlier, or the same ISO week number of the
+/// previous ISO year (see [`DateRange::year_earlier`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YearOverYear {
+    /// Periods of the analyzed range
+    pub current: Vec<PeriodStats>,
+
+    /// The matching periods a year earlier; `null` for ISO week 53 when the
+    /// previous year has no such week
+    pub previous: Vec<Option<PeriodStats>>,
+
+    /// Change of each period against its match
+    pub delta: Vec<PeriodDelta>,
+}
+
+impl YearOverYear {
+    /// Match each of `current` with its period in `previous`, both bucketed by `period`
+    #[must_use]
+    pub fn new(current: &[PeriodStats], previous: &[PeriodStats], period: Period) -> Self {
+        let by_start: HashMap<NaiveDate, &PeriodStats> = previous
+            .iter()
+            .map(|stat| (period_bounds(stat.date, period).0, stat))
+            .collect();
+        let previous: Vec<Option<PeriodStats>> = current
+            .iter()
+            .map(|stat| {
+                let (start, _) = period_bounds(stat.date, period);
+                period_year_earlier(start, period)
+                    .and_then(|start| by_start.get(&start))
+                    .map(|&stat| stat.clone())
+            })
+            .collect();
+        let delta = current
+            .iter()
+            .zip(&previous)
+            .map(|(current, previous)| PeriodDelta::new(current, previous.as_ref()))
+            .collect();
+        Self {
+            current: current.to_vec(),
+            previous,
+            delta,
+        }
+    }
+}
+
+/// Start of the period a year before the one starting on `start`
+///
+/// `None` for ISO week 53 when the previous ISO year has no week 53.
+fn period_year_earlier(start: NaiveDate, period: Period) -> Option<NaiveDate> {
+    if period == Period::Weekly {
+        let week = start.iso_week();
+        NaiveDate::from_isoywd_opt(week.year() - 1, week.week(), chrono::Weekday::Mon)
+    } else {
+        Some(year_earlier_date(start, period, true))
+    }
+}
+
+/// Change of one period against the same period a year earlier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeriodDelta {
+    /// Label of the current period
+    pub label: String,
+
+    /// Change in commit count
+    pub commits: MetricDelta,
+
+    /// Change in lines added
+    pub additions: MetricDelta,
+
+    /// Change in lines deleted
+    pub deletions: MetricDelta,
+
+    /// Change in net lines
+    pub net_lines: MetricDelta,
+
+    /// Change in files changed
+    pub files_changed: MetricDelta,
+}
+
+impl PeriodDelta {
+    /// Compare `current` against `previous`, counting a missing period as zero
+    #[must_use]
+    pub fn new(current: &PeriodStats, previous: Option<&PeriodStats>) -> Self {
+        let empty = PeriodStats::default();
+        let previous = previous.unwrap_or(&empty);
+        Self {
+            label: current.label.clone(),
+            commits: MetricDelta::new(i64::from(current.commits), i64::from(previous.commits)),
+            additions: MetricDelta::new(current.additions as i64, previous.additions as i64),
+            deletions: MetricDelta::new(current.deletions as i64, previous.deletions as i64),
+            net_lines: MetricDelta::new(current.net_lines, previous.net_lines),
+            files_changed: MetricDelta::new(
+                i64::from(current.files_changed),
+                i64::from(previous.files_changed),
+            ),
+        }
+    }
+}
+
+/// Totals for a single repository in a multi-repository analysis
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RepoStats {
+    /// Repository name
+    pub name: String,
+
+    /// Totals for this repository
+    #[serde(flatten)]
+    pub total: TotalStats,
+}
+
+/// Statistics for a single file extension
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ExtensionStats {
+    /// File extension without the leading dot (`none` for files without one)
+    pub extension: String,
+
+    /// Number of commits touching at least one file with this extension
+    pub commits: u32,
+
+    /// Lines added
+    pub additions: u64,
+
+    /// Lines deleted
+    pub deletions: u64,
+
+    /// Number of file changes with this extension
+    pub files: u32,
+}
+
+/// Lines changed in one extension bucket of a period
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ExtensionLines {
+    /// Lines added
+    pub additions: u64,
+
+    /// Lines deleted
+    pub deletions: u64,
+}
+
+/// A single commit in the largest-commits report
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TopCommit {
+    /// Short commit hash
+    pub id: String,
+
+    /// Commit date in the analysis timezone
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
+    pub date: NaiveDate,
+
+    /// First line of the commit message
+    pub summary: String,
+
+    /// Lines added
+    pub additions: u64,
+
+    /// Lines deleted
+    pub deletions: u64,
+}
+
+/// Lines changed in a single file or directory (churn report)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct FileChurn {
+    /// Repository-relative file or directory path
+    pub path: String,
+
+    /// Lines added
+    pub additions: u64,
+
+    /// Lines deleted
+    pub deletions: u64,
+
+    /// Number of commits touching this path
+    pub commits: u32,
+}
+
+/// Lines changed in a single file by a single commit (per-file detail report)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileDetail {
+    /// Short commit hash
+    pub id: String,
+
+    /// Commit date in the analysis timezone
+    #[serde(
+        serialize_with = "serialize_date",
+        deserialize_with = "deserialize_date"
+    )]
+    pub date: NaiveDate,
+
+    /// Repository-relative file path
+    pub path: String,
+
+    /// Lines added
+    pub additions: u64,
+
+    /// Lines deleted
+    pub deletions: u64,
+}
+
+impl FileChurn {
+    /// Total lines changed (additions + deletions)
+    #[must_use]
+    pub const fn lines_changed(&self) -> u64 {
+        self.additions + self.deletions
+    }
+}
+
+/// Working hours on weekdays, as the half-open hour range `[start, end)`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkHours {
+    /// First working hour (0-23)
+    pub start: u32,
+    /// Hour at which work ends (1-24), itself outside working hours
+    pub end: u32,
+}
+
+impl WorkHours {
+    /// Whether `hour` (0-23) falls within working hours
+    #[must_use]
+    pub const fn contains(&self, hour: u32) -> bool {
+        self.start <= hour && hour < self.end
+    }
+}
+
+impl Default for WorkHours {
+    /// 09:00 to 18:00
+    fn default() -> Self {
+        Self { start: 9, end: 18 }
+    }
+}
+
+impl std::fmt::Display for WorkHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl std::str::FromStr for WorkHours {
+    type Err = String;
+
+    /// Parse `START-END` in whole hours, e.g. `9-18`
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid work hours '{input}': expected START-END, e.g. 9-18");
+        let (start, end) = input.split_once('-').ok_or_else(invalid)?;
+        let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+        if start >= end || end > 24 {
+            return Err(format!(
+                "invalid work hours '{input}': need 0 <= START < END <= 24"
+            ));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Commits and changed lines in one part of the week
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct WorkPatternBucket {
+    /// Number of commits
+    pub commits: u32,
+    /// Lines added
+    pub additions: u64,
+    /// Lines deleted
+    pub deletions: u64,
+}
+
+/// Activity split by when it happened in the analysis timezone
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct WorkPatternStats {
+    /// Working hours the split is based on
+    pub work_hours: WorkHours,
+    /// Monday to Friday within working hours
+    pub business_hours: WorkPatternBucket,
+    /// Monday to Friday outside working hours, early mornings included
+    pub weekday_evenings: WorkPatternBucket,
+    /// Saturday and Sunday
+    pub weekends: WorkPatternBucket,
+}
+
+impl WorkPatternStats {
+    /// Labeled buckets in display order
+    #[must_use]
+    pub const fn buckets(&self) -> [(&'static str, &WorkPatternBucket); 3] {
+        [
+            ("Business hours", &self.business_hours),
+            ("Weekday evenings", &self.weekday_evenings),
+            ("Weekends", &self.weekends),
+        ]
+    }
+
+    /// Share of all commits in `bucket`, in percent (0 without commits)
+    #[must_use]
+    pub fn commit_percent(&self, bucket: &WorkPatternBucket) -> f64 {
+        let total =
+            self.business_hours.commits + self.weekday_evenings.commits + self.weekends.commits;
+        if total == 0 {
+            0.0
+        } else {
+            f64::from(bucket.commits) * 100.0 / f64::from(total)
         }
     }
 }
 
 /// Activity statistics by weekday and hour
+///
+/// Serializes `weekday`, `hourly`, and their `_lines` variants as maps keyed
+/// by their labels (`Mon`..`Sun`, `0`..`23`) in natural order, and `heatmap`
+/// as a nested array.
 #[derive(Debug, Clone, Default)]
 pub struct ActivityStats {
     /// Commits per weekday (0=Mon, 1=Tue, ..., 6=Sun)
     pub weekday: [u32; 7],
     /// Commits per hour (0-23)
     pub hourly: [u32; 24],
+    /// Commits per weekday and hour (`heatmap[weekday][hour]`, 0=Mon)
+    pub heatmap: [[u32; 24]; 7],
+    /// Changed lines (additions + deletions) per weekday (0=Mon)
+    pub weekday_lines: [u64; 7],
+    /// Changed lines (additions + deletions) per hour (0-23)
+    pub hourly_lines: [u64; 24],
 }
 
 impl ActivityStats {
@@ -216,14 +965,133 @@ impl ActivityStats {
     pub fn hour_labels() -> [String; 24] {
         std::array::from_fn(|i| i.to_string())
     }
+
+    /// Commits per part of the day, in [`TIME_OF_DAY_BUCKETS`] order
+    #[must_use]
+    pub fn time_of_day(&self) -> [(&'static str, u32); 4] {
+        by_time_of_day(&self.hourly)
+    }
+
+    /// Changed lines per part of the day, in [`TIME_OF_DAY_BUCKETS`] order
+    #[must_use]
+    pub fn time_of_day_lines(&self) -> [(&'static str, u64); 4] {
+        by_time_of_day(&self.hourly_lines)
+    }
+}
+
+/// Sum hourly values into [`TIME_OF_DAY_BUCKETS`]
+fn by_time_of_day<T: Copy + std::iter::Sum>(hourly: &[T; 24]) -> [(&'static str, T); 4] {
+    TIME_OF_DAY_BUCKETS.map(|(label, hours)| (label, hourly[hours].iter().copied().sum()))
+}
+
+/// Parts of the day for the time-of-day chart, with the hours each covers
+///
+/// Night 0-5, Morning 6-11, Afternoon 12-17, Evening 18-23.
+pub const TIME_OF_DAY_BUCKETS: [(&str, Range<usize>); 4] = [
+    ("Night", 0..6),
+    ("Morning", 6..12),
+    ("Afternoon", 12..18),
+    ("Evening", 18..24),
+];
+
+impl Serialize for ActivityStats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let hour_labels = Self::hour_labels();
+        let mut state = serializer.serialize_struct("ActivityStats", 5)?;
+        state.serialize_field(
+            "weekday",
+            &LabeledCounts(&Self::weekday_labels(), &self.weekday),
+        )?;
+        state.serialize_field("hourly", &LabeledCounts(&hour_labels, &self.hourly))?;
+        state.serialize_field("heatmap", &self.heatmap)?;
+        state.serialize_field(
+            "weekday_lines",
+            &LabeledCounts(&Self::weekday_labels(), &self.weekday_lines),
+        )?;
+        state.serialize_field(
+            "hourly_lines",
+            &LabeledCounts(&hour_labels, &self.hourly_lines),
+        )?;
+        state.end()
+    }
+}
+
+/// Counts serialized as a label → count map, preserving label order
+struct LabeledCounts<'a, L, T>(&'a [L], &'a [T]);
+
+impl<L: AsRef<str>, T: Serialize + Copy> Serialize for LabeledCounts<'_, L, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.0.iter().map(AsRef::as_ref).zip(self.1.iter().copied()))
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivityStats {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        /// The shape written by the `Serialize` impl
+        #[derive(Deserialize)]
+        struct Labeled {
+            weekday: HashMap<String, u32>,
+            hourly: HashMap<String, u32>,
+            heatmap: [[u32; 24]; 7],
+            #[serde(default)]
+            weekday_lines: HashMap<String, u64>,
+            #[serde(default)]
+            hourly_lines: HashMap<String, u64>,
+        }
+
+        let labeled = Labeled::deserialize(deserializer)?;
+        Ok(Self {
+            weekday: counts_by_label(&Self::weekday_labels(), labeled.weekday)?,
+            hourly: counts_by_label(&Self::hour_labels(), labeled.hourly)?,
+            heatmap: labeled.heatmap,
+            weekday_lines: counts_by_label(&Self::weekday_labels(), labeled.weekday_lines)?,
+            hourly_lines: counts_by_label(&Self::hour_labels(), labeled.hourly_lines)?,
+        })
+    }
+}
+
+/// Place each label → count entry at its label's index; missing labels count zero
+fn counts_by_label<L, T, E, const N: usize>(
+    labels: &[L; N],
+    counts: HashMap<String, T>,
+) -> Result<[T; N], E>
+where
+    L: AsRef<str>,
+    T: Copy + Default,
+    E: serde::de::Error,
+{
+    let mut ordered = [T::default(); N];
+    for (label, count) in counts {
+        let index = labels
+            .iter()
+            .position(|l| l.as_ref() == label)
+            .ok_or_else(|| E::custom(format!("unknown activity label '{label}'")))?;
+        ordered[index] = count;
+    }
+    Ok(ordered)
 }
 
 /// Aggregated total statistics
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TotalStats {
-    /// Total commits
+    /// Total commits, not counting merges
     pub commits: u32,
 
+    /// Total merge commits
+    #[serde(default)]
+    pub merge_commits: u32,
+
     /// Total lines added
     pub additions: u64,
 
@@ -235,6 +1103,10 @@ pub struct TotalStats {
 
     /// Total files changed
     pub files_changed: u32,
+
+    /// Distinct files changed over the whole range
+    #[serde(default)]
+    pub unique_files: u32,
 }
 
 impl TotalStats {
@@ -242,12 +1114,16 @@ impl TotalStats {
     #[must_use]
     pub fn from_periods(periods: &[PeriodStats]) -> Self {
         let mut total = Self::default();
+        let mut paths = BTreeSet::new();
         for p in periods {
             total.commits += p.commits;
+            total.merge_commits += p.merge_commits;
             total.additions += p.additions;
             total.deletions += p.deletions;
             total.files_changed += p.files_changed;
+            paths.extend(&p.paths);
         }
+        total.unique_files = u32::try_from(paths.len()).unwrap_or(u32::MAX);
         total.net_lines = total.additions as i64 - total.deletions as i64;
         total
     }
@@ -273,6 +1149,26 @@ mod tests {
 
         assert_eq!(range.to, today);
         assert!(range.from < range.to);
+        assert_eq!(range.iter_days().count(), 7);
+    }
+
+    #[test]
+    fn test_date_range_ending_on_zero_days_is_single_day() {
+        let to = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let range = DateRange::ending_on(to, Days::new(0));
+
+        assert_eq!(range.from, to);
+        assert_eq!(range.to, to);
+        assert_eq!(range.iter_days().count(), 1);
+    }
+
+    #[test]
+    fn test_date_range_ending_on() {
+        let to = NaiveDate::from_ymd_opt(2024, 1, 7).unwrap();
+        let range = DateRange::ending_on(to, Days::new(7));
+
+        assert_eq!(range.from, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(range.to, to);
     }
 
     #[test]
@@ -288,6 +1184,173 @@ mod tests {
         assert!(!range.contains(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()));
     }
 
+    #[test]
+    fn test_date_range_previous() {
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 7).unwrap(),
+        );
+
+        let previous = range.previous();
+        assert_eq!(previous.from, NaiveDate::from_ymd_opt(2024, 2, 23).unwrap());
+        assert_eq!(previous.to, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(previous.num_days(), range.num_days());
+    }
+
+    #[test]
+    fn test_date_range_year_earlier_monthly_leap_day() {
+        let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let range = DateRange::new(ymd(2024, 1, 15), ymd(2024, 6, 30));
+        let earlier = range.year_earlier(Period::Monthly);
+        assert_eq!(
+            (earlier.from, earlier.to),
+            (ymd(2023, 1, 15), ymd(2023, 6, 30))
+        );
+
+        // February 29 has no counterpart: the earlier range skips the day before it
+        let range = DateRange::new(ymd(2024, 2, 29), ymd(2024, 3, 31));
+        assert_eq!(range.year_earlier(Period::Monthly).from, ymd(2023, 3, 1));
+        let range = DateRange::new(ymd(2024, 1, 1), ymd(2024, 2, 29));
+        assert_eq!(range.year_earlier(Period::Monthly).to, ymd(2023, 2, 28));
+    }
+
+    #[test]
+    fn test_date_range_year_earlier_weekly_keeps_iso_week() {
+        let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        // Wednesday of 2024-W10 maps to Wednesday of 2023-W10
+        let range = DateRange::new(ymd(2024, 3, 6), ymd(2024, 3, 31));
+        let earlier = range.year_earlier(Period::Weekly);
+        assert_eq!(earlier.from, ymd(2023, 3, 8));
+        assert_eq!(earlier.to, ymd(2023, 4, 2));
+        assert_eq!(earlier.from.iso_week().week(), 10);
+
+        // 2020 has a week 53, 2019 does not
+        let w53_monday = ymd(2020, 12, 28);
+        let range = DateRange::new(w53_monday, w53_monday);
+        let earlier = range.year_earlier(Period::Weekly);
+        assert_eq!(earlier.from, ymd(2019, 12, 30)); // 2020-W01
+        assert_eq!(earlier.to, ymd(2019, 12, 23)); // 2019-W52
+    }
+
+    #[test]
+    fn test_year_over_year_aligns_months() {
+        let month = |y, m, commits| PeriodStats {
+            commits,
+            ..PeriodStats::with_label(
+                NaiveDate::from_ymd_opt(y, m, 1).unwrap(),
+                format!("{y}-{m:02}"),
+            )
+        };
+        let current = [month(2024, 1, 10), month(2024, 2, 6), month(2024, 3, 4)];
+        let previous = [month(2023, 1, 5), month(2023, 3, 4)];
+
+        let yoy = YearOverYear::new(&current, &previous, Period::Monthly);
+        assert_eq!(yoy.current.len(), 3);
+        let labels: Vec<Option<&str>> = yoy
+            .previous
+            .iter()
+            .map(|stat| stat.as_ref().map(|s| s.label.as_str()))
+            .collect();
+        assert_eq!(labels, [Some("2023-01"), None, Some("2023-03")]);
+        assert_eq!(yoy.delta[0].label, "2024-01");
+        assert_eq!(yoy.delta[0].commits.percent, Some(100.0));
+        assert_eq!(yoy.delta[1].commits.percent, None);
+        assert_eq!(yoy.delta[2].commits.change, 0);
+    }
+
+    #[test]
+    fn test_year_over_year_week_53_has_no_match() {
+        let week = |y, m, d, commits| PeriodStats {
+            commits,
+            ..PeriodStats::new(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+        };
+        // 2020-W52 and 2020-W53 against 2019-W52; 2019 has no week 53
+        let current = [week(2020, 12, 21, 3), week(2020, 12, 28, 2)];
+        let previous = [week(2019, 12, 23, 1), week(2019, 12, 30, 9)];
+
+        let yoy = YearOverYear::new(&current, &previous, Period::Weekly);
+        assert_eq!(yoy.previous[0].as_ref().map(|s| s.commits), Some(1));
+        assert!(yoy.previous[1].is_none());
+        assert_eq!(yoy.delta[1].commits.previous, 0);
+        assert_eq!(yoy.delta[1].commits.percent, None);
+
+        let json = serde_json::to_value(&yoy).unwrap();
+        assert!(json["previous"][1].is_null());
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 14).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 2, d).unwrap();
+
+        assert_eq!(period_bounds(date, Period::Daily), (date, date));
+        // 2024-02-14 is a Wednesday
+        assert_eq!(period_bounds(date, Period::Weekly), (day(12), day(18)));
+        assert_eq!(period_bounds(date, Period::Monthly), (day(1), day(29)));
+        assert_eq!(
+            period_bounds(date, Period::Yearly),
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_date_range_complete_periods_mid_week() {
+        // Wednesday 2024-01-03 to Wednesday 2024-01-24
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 24).unwrap(),
+        );
+
+        let weeks = range.complete_periods(Period::Weekly).unwrap();
+        assert_eq!(weeks.from, NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        assert_eq!(weeks.to, NaiveDate::from_ymd_opt(2024, 1, 21).unwrap());
+        assert_eq!(range.complete_periods(Period::Daily), Some(range));
+        assert_eq!(range.complete_periods(Period::Monthly), None);
+    }
+
+    #[test]
+    fn test_date_range_complete_periods_mid_month() {
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 4, 30).unwrap(),
+        );
+
+        let months = range.complete_periods(Period::Monthly).unwrap();
+        assert_eq!(months.from, NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        // April ends on the last day of the range, so it is kept
+        assert_eq!(months.to, range.to);
+    }
+
+    #[test]
+    fn test_metric_delta() {
+        let up = MetricDelta::new(15, 10);
+        assert_eq!(up.change, 5);
+        assert_eq!(up.indicator(), "▲");
+        assert_eq!(up.percent_label(), "+50.0%");
+
+        let down = MetricDelta::new(-30, -20);
+        assert_eq!(down.indicator(), "▼");
+        assert_eq!(down.percent_label(), "-50.0%");
+
+        assert_eq!(MetricDelta::new(4, 4).indicator(), "=");
+    }
+
+    #[test]
+    fn test_metric_delta_from_zero_is_not_available() {
+        let delta = MetricDelta::new(7, 0);
+        assert_eq!(delta.change, 7);
+        assert_eq!(delta.percent, None);
+        assert_eq!(delta.percent_label(), "n/a");
+
+        let json = serde_json::to_value(delta).unwrap();
+        assert!(json["percent"].is_null());
+    }
+
     #[test]
     fn test_date_range_iter_days() {
         let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
@@ -325,6 +1388,29 @@ mod tests {
         assert_eq!(stats1.net_lines, 120);
     }
 
+    #[test]
+    fn test_period_stats_merge_counts_shared_paths_once() {
+        let paths = |names: &[&str]| names.iter().map(ToString::to_string).collect();
+        let mut monday = PeriodStats {
+            files_changed: 3,
+            paths: paths(&["src/main.rs", "README.md"]),
+            ..Default::default()
+        };
+        monday.update_unique_files();
+        let tuesday = PeriodStats {
+            files_changed: 2,
+            unique_files: 2,
+            paths: paths(&["src/main.rs", "src/lib.rs"]),
+            ..Default::default()
+        };
+
+        monday.merge(&tuesday);
+
+        assert_eq!(monday.files_changed, 5);
+        assert_eq!(monday.unique_files, 3);
+        assert_eq!(TotalStats::from_periods(&[monday, tuesday]).unique_files, 3);
+    }
+
     #[test]
     fn test_total_stats_from_periods() {
         let periods = vec![
@@ -376,6 +1462,65 @@ mod tests {
         let stats = ActivityStats::default();
         assert_eq!(stats.weekday, [0; 7]);
         assert_eq!(stats.hourly, [0; 24]);
+        assert_eq!(stats.heatmap, [[0; 24]; 7]);
+    }
+
+    #[test]
+    fn test_activity_stats_serialization() {
+        let mut stats = ActivityStats::default();
+        stats.weekday[0] = 3;
+        stats.weekday[6] = 1;
+        stats.hourly[23] = 4;
+        stats.heatmap[6][23] = 1;
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.starts_with(r#"{"weekday":{"Mon":3,"Tue":0,"#));
+        assert!(json.contains(r#""Sun":1}"#));
+        assert!(json.contains(r#""hourly":{"0":0,"1":0,"#));
+        assert!(json.contains(r#""23":4}"#));
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["heatmap"][6][23], 1);
+        assert_eq!(value["heatmap"].as_array().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_activity_stats_deserialization_round_trip() {
+        let mut stats = ActivityStats::default();
+        stats.weekday[2] = 5;
+        stats.hourly[14] = 2;
+        stats.heatmap[2][14] = 2;
+        stats.weekday_lines[2] = 5_000;
+        stats.hourly_lines[14] = 40;
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let parsed: ActivityStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.weekday, stats.weekday);
+        assert_eq!(parsed.hourly, stats.hourly);
+        assert_eq!(parsed.heatmap, stats.heatmap);
+        assert_eq!(parsed.weekday_lines, stats.weekday_lines);
+        assert_eq!(parsed.hourly_lines, stats.hourly_lines);
+    }
+
+    #[test]
+    fn test_activity_stats_deserialization_rejects_unknown_label() {
+        let json = format!(
+            r#"{{"weekday":{{"Funday":1}},"hourly":{{}},"heatmap":{}}}"#,
+            serde_json::to_string(&[[0; 24]; 7]).unwrap()
+        );
+        let err = serde_json::from_str::<ActivityStats>(&json).unwrap_err();
+        assert!(err.to_string().contains("unknown activity label 'Funday'"));
+    }
+
+    #[test]
+    fn test_period_stats_date_deserialization() {
+        let json = r#"{"label":"2024-W03","date":"2024-01-15","commits":2,"additions":10,"deletions":4,"net_lines":6,"files_changed":3}"#;
+        let stats: PeriodStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.date, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(stats.label, "2024-W03");
+
+        let bad = json.replace("2024-01-15", "15/01/2024");
+        assert!(serde_json::from_str::<PeriodStats>(&bad).is_err());
     }
 
     #[test]
@@ -393,4 +1538,72 @@ mod tests {
         assert_eq!(labels[0], "0");
         assert_eq!(labels[23], "23");
     }
+
+    #[test]
+    fn test_activity_stats_time_of_day() {
+        let mut stats = ActivityStats::default();
+        stats.hourly[0] = 1;
+        stats.hourly[5] = 2;
+        stats.hourly[6] = 4;
+        stats.hourly[17] = 8;
+        stats.hourly[18] = 16;
+        stats.hourly[23] = 32;
+        assert_eq!(
+            stats.time_of_day(),
+            [
+                ("Night", 3),
+                ("Morning", 4),
+                ("Afternoon", 8),
+                ("Evening", 48)
+            ]
+        );
+
+        stats.hourly_lines[3] = 5_000;
+        stats.hourly_lines[12] = 7;
+        assert_eq!(
+            stats.time_of_day_lines(),
+            [
+                ("Night", 5_000),
+                ("Morning", 0),
+                ("Afternoon", 7),
+                ("Evening", 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_of_day_buckets_cover_each_hour_once() {
+        let hours: Vec<usize> = TIME_OF_DAY_BUCKETS
+            .iter()
+            .flat_map(|(_, hours)| hours.clone())
+            .collect();
+        assert_eq!(hours, (0..24).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_gap_display() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let gap = |from, to, length, open_ended| Gap {
+            from,
+            to,
+            length,
+            open_ended,
+        };
+        assert_eq!(
+            gap(date(2024, 3, 10), date(2024, 3, 15), 6, false).to_string(),
+            "6 days, Mar 10\u{2013}15"
+        );
+        assert_eq!(
+            gap(date(2024, 3, 30), date(2024, 4, 2), 4, true).to_string(),
+            "4 days, Mar 30\u{2013}Apr 2 (open-ended)"
+        );
+        assert_eq!(
+            gap(date(2023, 12, 30), date(2024, 1, 2), 4, false).to_string(),
+            "4 days, Dec 30, 2023\u{2013}Jan 2, 2024"
+        );
+        assert_eq!(
+            gap(date(2024, 3, 10), date(2024, 3, 10), 1, false).to_string(),
+            "1 day, Mar 10"
+        );
+    }
 }
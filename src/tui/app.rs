@@ -2,19 +2,52 @@
 
 #![allow(clippy::cast_possible_wrap)]
 
-use crate::error::Result;
-use crate::stats::{ActivityStats, AnalysisResult};
+use crate::cli::args::Period;
+use crate::error::{Error, Result};
+use crate::stats::{
+    ActivityStats, AnalysisResult, OTHER_EXTENSIONS, PeriodStats, TimeZoneMode,
+    aggregate_by_period, default_min_gap, find_gaps, mark_outliers, moving_averages,
+};
 use crate::tui::chart_type::ChartType;
-use crate::tui::event::{Event, EventHandler};
+use crate::tui::event::{DEFAULT_TICK_MS, Event, EventHandler};
+use crate::tui::export::export_view;
+use crate::tui::keymap::Keymap;
+use crate::tui::loader::{LoadProgress, Loader, Loading};
 use crate::tui::mvu::action::Action;
-use crate::tui::mvu::model::Model;
-use crate::tui::mvu::update::update;
+use crate::tui::mvu::model::{Model, StatusLevel};
+use crate::tui::mvu::update::{STATUS_TICKS, status_ticks, update};
+use crate::tui::panel::FocusedPanel;
+use crate::tui::terminal::{TerminalGuard, ViewportMode};
+use crate::tui::theme::Theme;
 use crate::tui::ui;
-use crossterm::ExecutableCommand;
-use crossterm::event::KeyEvent;
-use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crate::tui::widgets::line_chart_window;
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use clap::ValueEnum;
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::TerminalOptions;
 use ratatui::prelude::*;
+use std::collections::{HashMap, VecDeque};
 use std::io::stdout;
+use std::num::NonZeroU64;
+use std::path::PathBuf;
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Combined view and one view per repository (empty for a single repository)
+pub type RefreshOutcome = Result<(RepoView, Vec<RepoView>)>;
+
+/// Re-collects analysis data over the given number of days when the user asks for a refresh
+pub type Refresher = Arc<dyn Fn(u32) -> RefreshOutcome + Send + Sync>;
+
+/// Statistics for one repository tab
+#[derive(Debug, Clone)]
+pub struct RepoView {
+    pub result: AnalysisResult,
+    pub activity_stats: ActivityStats,
+    /// Per-day stats behind `result`, for switching periods (empty disables switching)
+    pub daily_stats: Vec<PeriodStats>,
+}
 
 /// Data point for additions/deletions diverging bar chart
 #[derive(Debug, Clone)]
@@ -22,6 +55,43 @@ pub struct AddDelDataPoint {
     pub label: String,
     pub additions: u64,
     pub deletions: u64,
+    /// The period changed unusually many lines ([`PeriodStats::is_outlier`])
+    pub is_outlier: bool,
+}
+
+/// Status message for a key that does nothing
+pub const UNKNOWN_KEY: &str = "Unknown key — press ? for help";
+
+/// Number of extensions drawn as their own segment in the Languages chart
+pub const LANGUAGE_SEGMENTS: usize = 5;
+
+/// Lines changed per extension for the Languages stacked bar chart
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageData {
+    /// Segment names, largest first, ending with `other` when any lines fall outside them
+    pub extensions: Vec<String>,
+    /// One point per period
+    pub points: Vec<LanguageDataPoint>,
+}
+
+/// Lines changed in one period, in the order of [`LanguageData::extensions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageDataPoint {
+    pub label: String,
+    pub lines: Vec<u64>,
+}
+
+/// Number of repositories drawn as their own line in the per-repository Commits chart
+pub const REPO_SERIES: usize = 5;
+
+/// Name of the line summing the repositories beyond [`REPO_SERIES`]
+pub const OTHER_REPOS: &str = "other";
+
+/// Commits per period of one repository, aligned with the combined view's periods
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSeries {
+    pub name: String,
+    pub commits: Vec<i64>,
 }
 
 /// Metric to display in charts
@@ -31,29 +101,11 @@ pub enum Metric {
     Commits,
     AdditionsAndDeletions,
     FilesChanged,
+    /// Merge commits, drawn dim under the Commits line rather than as a chart of their own
+    Merges,
 }
 
 impl Metric {
-    /// Get the next metric in the cycle
-    #[must_use]
-    pub fn next(self) -> Self {
-        match self {
-            Self::Commits => Self::AdditionsAndDeletions,
-            Self::AdditionsAndDeletions => Self::FilesChanged,
-            Self::FilesChanged => Self::Commits,
-        }
-    }
-
-    /// Get the previous metric in the cycle
-    #[must_use]
-    pub fn prev(self) -> Self {
-        match self {
-            Self::Commits => Self::FilesChanged,
-            Self::AdditionsAndDeletions => Self::Commits,
-            Self::FilesChanged => Self::AdditionsAndDeletions,
-        }
-    }
-
     /// Get display name
     #[must_use]
     pub fn name(self) -> &'static str {
@@ -61,18 +113,84 @@ impl Metric {
             Self::Commits => "Commits",
             Self::AdditionsAndDeletions => "Additions / Deletions",
             Self::FilesChanged => "Files Changed",
+            Self::Merges => "Merges",
         }
     }
 }
 
 /// Application state
 pub struct App {
-    /// Analysis result to display
+    /// Analysis result across all repositories
     pub result: AnalysisResult,
-    /// Activity statistics (commits by weekday and hour)
+    /// Activity statistics (commits by weekday and hour) across all repositories
     pub activity_stats: ActivityStats,
+    /// Per-day stats behind `result`, for switching periods (empty disables switching)
+    daily_stats: Vec<PeriodStats>,
+    /// Each repository on its own, selectable as tabs
+    repo_views: Vec<RepoView>,
+    /// Colors used for rendering
+    theme: Theme,
     /// MVU model for interactive UI state.
     pub(crate) model: Model,
+    /// Collects the data the app opens with, started by [`App::run`]
+    loader: Option<Loader>,
+    /// The initial data load running in the background
+    loading: Option<Loading>,
+    /// Why the initial data could not be loaded, returned from [`App::run`]
+    load_error: Option<Error>,
+    /// Notices from loading, printed once the TUI has closed
+    notices: Vec<String>,
+    /// Data provider for the refresh key (refresh is disabled without one)
+    refresher: Option<Refresher>,
+    /// Receives the result of an in-flight background refresh
+    pending_refresh: Option<mpsc::Receiver<RefreshOutcome>>,
+    /// Automatic refresh interval (`--watch`)
+    watch_interval: Option<Duration>,
+    /// When the last refresh finished (or the app started)
+    last_refresh: Instant,
+    /// Directory exports are written to
+    export_dir: PathBuf,
+    /// Keys rebound in the config
+    keymap: Keymap,
+    /// Milliseconds between ticks
+    tick_ms: NonZeroU64,
+    /// Something on screen changed since the last draw
+    needs_redraw: bool,
+    /// Alternate screen or inline rows
+    viewport: ViewportMode,
+    /// Date the charts mark as today
+    today: NaiveDate,
+    /// Timezone `today` is re-read in after a refresh (`None` keeps it fixed)
+    timezone: Option<TimeZoneMode>,
+    /// Days a gap must exceed to be reported (`None` follows the period)
+    min_gap: Option<u32>,
+}
+
+/// Check whether an automatic refresh should start
+///
+/// The interval counts from the end of the previous refresh, and no refresh
+/// starts while one is still running, so a collection slower than the
+/// interval never piles up.
+#[must_use]
+pub fn refresh_due(since_last: Duration, interval: Duration, refreshing: bool) -> bool {
+    !refreshing && since_last >= interval
+}
+
+/// Check whether handling `event` changed the screen from `before` to `after`
+///
+/// Keys and resizes always redraw. A tick only redraws while the loading
+/// gauge moves, or when it expired a status message or started a refresh.
+#[must_use]
+pub fn needs_redraw(event: &Event, before: &Model, after: &Model) -> bool {
+    !event.is_tick()
+        || after.loading
+        || before.refreshing != after.refreshing
+        || before.status() != after.status()
+}
+
+/// Number of days an analysis result covers
+fn range_days(result: &AnalysisResult) -> u32 {
+    u32::try_from((result.to - result.from).num_days() + 1).unwrap_or(1)
 }
 
 impl App {
@@ -84,39 +202,262 @@ impl App {
                 chart_type: ChartType::default(),
                 should_quit: false,
                 single_metric,
+                focused_panel: FocusedPanel::default(),
                 scroll_offset: 0,
+                window_offset: 0,
+                // Everything is visible until the first resize says otherwise
+                visible_periods: result.stats.len(),
+                page_rows: 1,
                 data_len: result.stats.len(),
+                days: range_days(&result),
+                period: Period::from_str(&result.period, true).unwrap_or_default(),
+                active_repo: 0,
+                repo_count: 0,
+                show_help: false,
+                show_summary: true,
+                activity_by_lines: false,
+                commits_by_repo: false,
+                selected_index: None,
+                refreshing: false,
+                refresh_error: None,
+                loading: false,
+                tick: 0,
+                status_ticks: STATUS_TICKS,
+                statuses: VecDeque::new(),
+                pending_count: None,
             },
             result,
             activity_stats,
+            daily_stats: Vec::new(),
+            repo_views: Vec::new(),
+            theme: Theme::default(),
+            loader: None,
+            loading: None,
+            load_error: None,
+            notices: Vec::new(),
+            refresher: None,
+            pending_refresh: None,
+            watch_interval: None,
+            last_refresh: Instant::now(),
+            export_dir: PathBuf::from("."),
+            keymap: Keymap::default(),
+            tick_ms: DEFAULT_TICK_MS,
+            needs_redraw: true,
+            viewport: ViewportMode::default(),
+            today: Local::now().date_naive(),
+            timezone: None,
+            min_gap: None,
         }
     }
 
+    /// Look up keys in `keymap` before the built-in ones
+    #[must_use]
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Render with `theme` instead of the default colors
+    #[must_use]
+    pub const fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Enable period switching, re-aggregating `daily_stats` instead of re-reading commits
+    #[must_use]
+    pub fn with_daily_stats(mut self, daily_stats: Vec<PeriodStats>) -> Self {
+        self.daily_stats = daily_stats;
+        self
+    }
+
+    /// Add per-repository tabs next to the combined view
+    #[must_use]
+    pub fn with_repo_views(mut self, repo_views: Vec<RepoView>) -> Self {
+        self.model.repo_count = repo_views.len();
+        self.repo_views = repo_views;
+        self
+    }
+
+    /// Open on a loading screen and collect the data with `loader` in the background
+    ///
+    /// The data the app was created with is a placeholder until the loader
+    /// finishes.
+    #[must_use]
+    pub fn with_loader(mut self, loader: Loader) -> Self {
+        self.loader = Some(loader);
+        self.model.loading = true;
+        self
+    }
+
+    /// Enable the refresh key, re-collecting data with `refresher`
+    #[must_use]
+    pub fn with_refresher(mut self, refresher: Refresher) -> Self {
+        self.refresher = Some(refresher);
+        self
+    }
+
+    /// Refresh automatically every `interval` (requires a refresher)
+    #[must_use]
+    pub fn with_watch_interval(mut self, interval: Duration) -> Self {
+        self.watch_interval = Some(interval);
+        self
+    }
+
+    /// Tick every `tick_ms` milliseconds instead of [`DEFAULT_TICK_MS`]
+    ///
+    /// Status messages stay on screen for the same time at any tick rate.
+    #[must_use]
+    pub fn with_tick_ms(mut self, tick_ms: NonZeroU64) -> Self {
+        self.tick_ms = tick_ms;
+        self.model.status_ticks = status_ticks(tick_ms);
+        self
+    }
+
+    /// Draw in `viewport` instead of the alternate screen
+    #[must_use]
+    pub const fn with_viewport(mut self, viewport: ViewportMode) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Write exports to `dir` instead of the current directory
+    #[must_use]
+    pub fn with_export_dir(mut self, dir: PathBuf) -> Self {
+        self.export_dir = dir;
+        self
+    }
+
+    /// Mark `today` on the charts instead of the local date
+    #[must_use]
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self.timezone = None;
+        self
+    }
+
+    /// Take today's date in `timezone`, the one commits are bucketed in,
+    /// and re-read it after every refresh
+    #[must_use]
+    pub fn with_timezone(mut self, timezone: TimeZoneMode) -> Self {
+        self.today = timezone.now_date_naive();
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Report gaps longer than `min_gap` days after a period switch, instead
+    /// of the default for the period
+    #[must_use]
+    pub const fn with_min_gap(mut self, min_gap: Option<u32>) -> Self {
+        self.min_gap = min_gap;
+        self
+    }
+
     /// Run the TUI application
     ///
     /// # Errors
     ///
-    /// Returns an error if terminal operations fail.
+    /// Returns an error if terminal operations fail or the initial data
+    /// could not be loaded.
     pub fn run(&mut self) -> Result<()> {
-        // Setup terminal
-        terminal::enable_raw_mode()?;
-        stdout().execute(EnterAlternateScreen)?;
+        // Setup terminal; the guard restores it on every way out, panics included
+        let guard = TerminalGuard::enter(self.viewport)?;
+        self.start_loading();
 
         let backend = CrosstermBackend::new(stdout());
-        let mut terminal = Terminal::new(backend)?;
+        let options = TerminalOptions {
+            viewport: self.viewport.viewport(),
+        };
+        let mut terminal = Terminal::with_options(backend, options)?;
         terminal.clear()?;
 
         // Create event handler
-        let event_handler = EventHandler::new(250);
+        let event_handler = EventHandler::new(self.tick_ms.get());
 
         // Main loop
         let result = self.main_loop(&mut terminal, &event_handler);
+        // Quitting mid-load cancels the loader
+        self.loading = None;
+
+        // An inline viewport stays on screen; continue below its last row
+        let inline = matches!(self.viewport, ViewportMode::Inline(_));
+        if inline {
+            let area = terminal.get_frame().area();
+            terminal.set_cursor_position((0, area.bottom().saturating_sub(1)))?;
+        }
 
         // Restore terminal
-        terminal::disable_raw_mode()?;
-        stdout().execute(LeaveAlternateScreen)?;
+        guard.restore()?;
+        if inline {
+            println!();
+        }
 
-        result
+        match self.load_error.take() {
+            Some(e) => Err(e),
+            None => result,
+        }
+    }
+
+    /// Start the loader given with [`App::with_loader`] on a background thread
+    ///
+    /// Called by [`App::run`]; public to drive the app without a terminal.
+    pub fn start_loading(&mut self) {
+        if let Some(loader) = self.loader.take() {
+            self.loading = Some(Loading::start(loader));
+        }
+    }
+
+    /// Apply loading progress, and the data once it arrived
+    ///
+    /// Called by [`App::run`] between frames; public to drive the app without
+    /// a terminal.
+    pub fn poll_loading(&mut self) {
+        let Some(loading) = &mut self.loading else {
+            return;
+        };
+        let Some(outcome) = loading.poll() else {
+            return;
+        };
+        self.notices.extend(loading.take_notices());
+        self.loading = None;
+        self.last_refresh = Instant::now();
+
+        let action = match outcome {
+            Ok((combined, repo_views)) => {
+                self.result = combined.result;
+                self.activity_stats = combined.activity_stats;
+                self.daily_stats = combined.daily_stats;
+                self.repo_views = repo_views;
+                Action::Loaded {
+                    data_len: self.result.stats.len(),
+                    repo_count: self.repo_views.len(),
+                    days: range_days(&self.result),
+                }
+            }
+            Err(e) => {
+                self.load_error = Some(e);
+                Action::LoadFailed
+            }
+        };
+        self.model = update(self.model.clone(), action);
+        self.needs_redraw = true;
+    }
+
+    /// Progress of the initial data load, once it started
+    #[must_use]
+    pub fn load_progress(&self) -> Option<&LoadProgress> {
+        self.loading.as_ref().map(Loading::progress)
+    }
+
+    /// Notices collected while loading, like skipped repositories
+    ///
+    /// Meant to be printed after [`App::run`] returned, when the terminal is
+    /// back to normal.
+    pub fn take_notices(&mut self) -> Vec<String> {
+        if let Some(loading) = &mut self.loading {
+            self.notices.extend(loading.take_notices());
+        }
+        std::mem::take(&mut self.notices)
     }
 
     fn main_loop<B: Backend>(
@@ -125,118 +466,520 @@ impl App {
         event_handler: &EventHandler,
     ) -> Result<()> {
         while !self.model.should_quit {
-            // Draw UI
-            terminal.draw(|frame| ui::render(frame, self))?;
+            self.poll_loading();
+            self.poll_refresh();
+            self.resize(self.viewport.area(terminal.size()?));
+
+            // Draw UI, unless nothing changed since the last time
+            if self.needs_redraw {
+                terminal.draw(|frame| ui::render(frame, self))?;
+                self.needs_redraw = false;
+            }
 
             // Handle events
-            match event_handler.next()? {
+            let event = event_handler.next()?;
+            let before = self.model.clone();
+            match event {
                 Event::Key(key) => self.handle_key(key),
-                Event::Tick => self.apply_action(Action::Tick),
+                Event::Tick => self.on_tick(),
                 Event::Resize(_, _) => {}
             }
+            self.needs_redraw |= needs_redraw(&event, &before, &self.model);
         }
 
         Ok(())
     }
 
+    /// Start a refresh when the watch interval has elapsed
+    fn on_tick(&mut self) {
+        let due = self.watch_interval.is_some_and(|interval| {
+            refresh_due(self.last_refresh.elapsed(), interval, self.model.refreshing)
+        });
+        let action = if due { Action::Refresh } else { Action::Tick };
+        self.dispatch(action);
+    }
+
     fn handle_key(&mut self, key: KeyEvent) {
-        let action = Action::from_key(key);
-        self.apply_action(action);
+        let action = if self.model.selected_index.is_some() {
+            Action::from_inspect_key(key, &self.keymap)
+        } else {
+            Action::from_key(key, self.model.single_metric, &self.keymap)
+        };
+        // Unmapped keys get a hint, unless they are closing the help overlay
+        let action = if action == Action::Noop
+            && key.kind == KeyEventKind::Press
+            && !self.model.show_help
+            && !self.model.loading
+        {
+            Action::ShowStatus {
+                text: UNKNOWN_KEY.to_string(),
+                level: StatusLevel::Warn,
+            }
+        } else {
+            action
+        };
+        self.dispatch(action);
     }
 
-    fn apply_action(&mut self, action: Action) {
-        if matches!(action, Action::Tick | Action::Noop) {
+    /// Apply `action` to the model through [`update`], then run what it
+    /// starts outside the model: a refresh, an export, or re-aggregating the
+    /// stats for a new period
+    ///
+    /// Keys, ticks, and loaded data all come through here.
+    pub fn dispatch(&mut self, action: Action) {
+        // Ticks only matter while a status message is on screen, and
+        // unmapped keys only while the help overlay is open
+        if (action == Action::Tick && self.model.statuses.is_empty())
+            || (action == Action::Noop && !self.model.show_help)
+        {
+            return;
+        }
+        if matches!(
+            action,
+            Action::Refresh | Action::GrowRange | Action::ShrinkRange
+        ) && self.refresher.is_none()
+        {
+            return;
+        }
+        if matches!(action, Action::SetPeriod(_)) && !self.can_switch_period() {
             return;
         }
-        self.model = update(self.model, action);
+
+        // With the help overlay open the key only closes it
+        let export = action == Action::Export && !self.model.show_help;
+        let was_refreshing = self.model.refreshing;
+        let previous_period = self.model.period;
+        self.model = update(self.model.clone(), action);
+        if self.model.refreshing && !was_refreshing {
+            self.start_refresh();
+        }
+        if self.model.period != previous_period {
+            self.aggregate_stats();
+            self.model = update(
+                self.model.clone(),
+                Action::Reaggregated {
+                    data_len: self.result.stats.len(),
+                },
+            );
+        }
+        if export {
+            self.export();
+        }
     }
 
-    /// Check if current view supports scrolling
-    #[cfg(test)]
-    fn can_scroll(&self) -> bool {
-        self.model.can_scroll()
+    /// Re-bucket the daily stats of every view into the selected period
+    fn aggregate_stats(&mut self) {
+        let period = self.model.period;
+        let views = std::iter::once((&mut self.result, &self.daily_stats)).chain(
+            self.repo_views
+                .iter_mut()
+                .map(|view| (&mut view.result, &view.daily_stats)),
+        );
+        for (result, daily_stats) in views {
+            if !daily_stats.is_empty() {
+                result.stats = aggregate_by_period(daily_stats.clone(), period);
+                result.period = period.to_string();
+                mark_outliers(&mut result.stats);
+                if let Some(average) = &mut result.moving_average {
+                    *average = moving_averages(&result.stats, average.window);
+                }
+                let min_gap = self.min_gap.unwrap_or_else(|| default_min_gap(period));
+                result.gaps = find_gaps(daily_stats, min_gap);
+            }
+        }
     }
 
-    /// Scroll up to see older data
-    #[cfg(test)]
-    fn scroll_up(&mut self) {
-        self.apply_action(Action::ScrollUp);
+    /// Export the current view, reporting the outcome in a status message
+    fn export(&mut self) {
+        let now = Local::now().naive_local();
+        let (text, level) = match export_view(self, &self.export_dir, now) {
+            Ok(path) => (format!("Exported to {}", path.display()), StatusLevel::Info),
+            Err(e) => (format!("Export failed: {e}"), StatusLevel::Error),
+        };
+        self.show_status(text, level);
     }
 
-    /// Scroll down to see newer data
-    #[cfg(test)]
-    fn scroll_down(&mut self) {
-        self.apply_action(Action::ScrollDown);
+    /// Run the refresher on a background thread so input stays responsive
+    fn start_refresh(&mut self) {
+        let Some(refresher) = self.refresher.clone() else {
+            return;
+        };
+        let days = self.model.days;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver is gone if the app quit mid-refresh; nothing to do then
+            let _ = sender.send(refresher(days));
+        });
+        self.pending_refresh = Some(receiver);
+    }
+
+    /// Apply the result of a finished background refresh, if any
+    fn poll_refresh(&mut self) {
+        let Some(receiver) = &self.pending_refresh else {
+            return;
+        };
+        let outcome = match receiver.try_recv() {
+            Ok(outcome) => outcome,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Err(std::io::Error::other("refresh thread exited unexpectedly").into())
+            }
+        };
+        self.pending_refresh = None;
+        self.last_refresh = Instant::now();
+
+        let error = match outcome {
+            Ok((combined, repo_views)) => {
+                self.result = combined.result;
+                self.activity_stats = combined.activity_stats;
+                self.daily_stats = combined.daily_stats;
+                self.repo_views = repo_views;
+                if let Some(timezone) = &self.timezone {
+                    self.today = timezone.now_date_naive();
+                }
+                // Keep the period picked in the TUI rather than the one collected with
+                self.aggregate_stats();
+                None
+            }
+            Err(e) => Some(e.to_string()),
+        };
+        self.model = update(
+            self.model.clone(),
+            Action::Refreshed {
+                data_len: self.result.stats.len(),
+                repo_count: self.repo_views.len(),
+                days: range_days(&self.result),
+                error,
+            },
+        );
+        self.needs_redraw = true;
     }
 
     /// Get values for a specific metric
     #[must_use]
     pub fn values_for_metric(&self, metric: Metric) -> Vec<(String, i64)> {
-        self.result
+        self.active_result()
             .stats
             .iter()
             .map(|s| {
                 let value = match metric {
                     Metric::Commits => i64::from(s.commits),
                     Metric::AdditionsAndDeletions => s.net_lines,
-                    Metric::FilesChanged => i64::from(s.files_changed),
+                    // Distinct files; the per-commit count is the table's Files column
+                    Metric::FilesChanged => i64::from(s.unique_files),
+                    Metric::Merges => i64::from(s.merge_commits),
                 };
                 (s.label.clone(), value)
             })
             .collect()
     }
 
-    /// Get all metrics
+    /// Total of a metric over the whole range
+    ///
+    /// Distinct files are counted once over the range, so their total is less
+    /// than the sum of the per-period values when periods share files.
+    #[must_use]
+    pub fn total_for_metric(&self, metric: Metric) -> i64 {
+        let total = &self.active_result().total;
+        match metric {
+            Metric::Commits => i64::from(total.commits),
+            Metric::AdditionsAndDeletions => total.net_lines,
+            Metric::FilesChanged => i64::from(total.unique_files),
+            Metric::Merges => i64::from(total.merge_commits),
+        }
+    }
+
+    /// Running net line total at the end of each period, the Cumulative chart's series
+    ///
+    /// The first period's value is its own net lines, not a change from zero.
+    #[must_use]
+    pub fn cumulative_net_lines(&self) -> Vec<(String, i64)> {
+        // A plain sum: running_totals would also carry every path, on every frame
+        let mut net_lines = 0;
+        self.active_result()
+            .stats
+            .iter()
+            .map(|s| {
+                net_lines += s.net_lines;
+                (s.label.clone(), net_lines)
+            })
+            .collect()
+    }
+
+    /// Moving average of a metric and its window in periods, when `--moving-average` is set
+    ///
+    /// Merges get none.
     #[must_use]
-    pub fn all_metrics() -> [Metric; 3] {
-        [
-            Metric::Commits,
-            Metric::AdditionsAndDeletions,
-            Metric::FilesChanged,
-        ]
+    pub fn moving_average_for_metric(&self, metric: Metric) -> Option<(usize, &[f64])> {
+        let average = self.active_result().moving_average.as_ref()?;
+        let values = match metric {
+            Metric::Commits => &average.commits,
+            Metric::AdditionsAndDeletions => &average.net_lines,
+            Metric::FilesChanged => &average.unique_files,
+            Metric::Merges => return None,
+        };
+        Some((average.window, values))
     }
 
     /// Get additions/deletions data for diverging bar chart
     #[must_use]
     pub fn additions_deletions_data(&self) -> Vec<AddDelDataPoint> {
-        self.result
+        self.active_result()
             .stats
             .iter()
             .map(|s| AddDelDataPoint {
                 label: s.label.clone(),
                 additions: s.additions,
                 deletions: s.deletions,
+                is_outlier: s.is_outlier,
             })
             .collect()
     }
 
+    /// Per-repository lines for the Commits chart, when they replace the combined line
+    ///
+    /// Empty unless the `p` toggle is on, the combined tab is selected, and
+    /// several repositories were analyzed. The [`REPO_SERIES`] repositories
+    /// with the most commits keep their own line, in tab order; the rest are
+    /// summed under [`OTHER_REPOS`]. Periods are matched by start date, so
+    /// every line has one value per period of the combined view.
     #[must_use]
-    pub fn chart_type(&self) -> ChartType {
-        self.model.chart_type
+    pub fn repo_series(&self) -> Vec<RepoSeries> {
+        if !self.model.commits_by_repo || self.active_view().is_some() || self.repo_views.len() < 2
+        {
+            return Vec::new();
+        }
+
+        let mut ranked: Vec<usize> = (0..self.repo_views.len()).collect();
+        ranked.sort_by_key(|&i| std::cmp::Reverse(self.repo_views[i].result.total.commits));
+        let mut own = vec![false; self.repo_views.len()];
+        for &i in ranked.iter().take(REPO_SERIES) {
+            own[i] = true;
+        }
+
+        let dates: Vec<NaiveDate> = self.result.stats.iter().map(|s| s.date).collect();
+        let commits_by_period = |view: &RepoView| -> Vec<i64> {
+            let by_date: HashMap<NaiveDate, u32> = view
+                .result
+                .stats
+                .iter()
+                .map(|s| (s.date, s.commits))
+                .collect();
+            dates
+                .iter()
+                .map(|date| i64::from(by_date.get(date).copied().unwrap_or(0)))
+                .collect()
+        };
+
+        let mut series = Vec::new();
+        let mut other: Option<Vec<i64>> = None;
+        for (view, own) in self.repo_views.iter().zip(own) {
+            let commits = commits_by_period(view);
+            if own {
+                series.push(RepoSeries {
+                    name: view.result.repository.clone(),
+                    commits,
+                });
+            } else {
+                let sum = other.get_or_insert_with(|| vec![0; dates.len()]);
+                for (total, value) in sum.iter_mut().zip(commits) {
+                    *total += value;
+                }
+            }
+        }
+        series.extend(other.map(|commits| RepoSeries {
+            name: OTHER_REPOS.to_string(),
+            commits,
+        }));
+        series
     }
 
+    /// Interactive state, changed only through [`Self::dispatch`]
     #[must_use]
-    pub fn single_metric(&self) -> bool {
-        self.model.single_metric
+    pub const fn model(&self) -> &Model {
+        &self.model
     }
 
-    /// Move to the next chart in single mode.
-    pub fn next_chart(&mut self) {
-        self.apply_action(Action::NextChart);
+    #[must_use]
+    pub const fn theme(&self) -> &Theme {
+        &self.theme
     }
 
+    /// Keys rebound in the config, for the help overlay
     #[must_use]
-    pub fn scroll_offset(&self) -> usize {
-        self.model.scroll_offset
+    pub const fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Date the charts mark as today
+    #[must_use]
+    pub const fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    /// Index of the period holding today, when periods are days
+    ///
+    /// Weekly and longer periods get no today marker.
+    #[must_use]
+    pub fn today_index(&self) -> Option<usize> {
+        if self.model.period != Period::Daily {
+            return None;
+        }
+        self.active_result()
+            .stats
+            .iter()
+            .position(|stat| stat.date == self.today)
+    }
+
+    /// Whether the period at `index` is a Saturday or Sunday, when periods are days
+    ///
+    /// Dates are the ones commits were bucketed by, so they already follow
+    /// the analysis timezone.
+    #[must_use]
+    pub fn is_weekend(&self, index: usize) -> bool {
+        self.model.period == Period::Daily
+            && self
+                .active_result()
+                .stats
+                .get(index)
+                .is_some_and(|stat| matches!(stat.date.weekday(), Weekday::Sat | Weekday::Sun))
+    }
+
+    /// Analysis result of the selected repository tab
+    #[must_use]
+    pub fn active_result(&self) -> &AnalysisResult {
+        self.active_view().map_or(&self.result, |view| &view.result)
+    }
+
+    /// Activity statistics of the selected repository tab
+    #[must_use]
+    pub fn active_activity_stats(&self) -> &ActivityStats {
+        self.active_view()
+            .map_or(&self.activity_stats, |view| &view.activity_stats)
+    }
+
+    fn active_view(&self) -> Option<&RepoView> {
+        self.model
+            .active_repo
+            .checked_sub(1)
+            .and_then(|index| self.repo_views.get(index))
+    }
+
+    /// Tab names: the combined view first, then each repository
+    #[must_use]
+    pub fn repo_tabs(&self) -> Vec<&str> {
+        if self.repo_views.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once("All")
+            .chain(
+                self.repo_views
+                    .iter()
+                    .map(|view| view.result.repository.as_str()),
+            )
+            .collect()
+    }
+
+    /// Whether daily stats are available to switch periods
+    #[must_use]
+    pub fn can_switch_period(&self) -> bool {
+        !self.daily_stats.is_empty()
+    }
+
+    /// Update how many periods fit in the charts for a terminal of `size`.
+    pub fn resize(&mut self, size: Size) {
+        let visible_periods = line_chart_window(size.width);
+        let page_rows = ui::add_del_rows(Rect::new(0, 0, size.width, size.height), self);
+        if visible_periods != self.model.visible_periods || page_rows != self.model.page_rows {
+            self.dispatch(Action::Resized {
+                visible_periods,
+                page_rows,
+            });
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Get per-extension lines for the Languages chart
+    ///
+    /// The [`LANGUAGE_SEGMENTS`] extensions with the most lines changed over all
+    /// periods keep their own segment; the rest are summed under `other`.
+    /// Empty when the analysis has no per-period extension stats.
+    #[must_use]
+    pub fn language_data(&self) -> LanguageData {
+        let stats = &self.active_result().stats;
+        let mut totals: HashMap<&str, u64> = HashMap::new();
+        for stat in stats {
+            for (extension, lines) in &stat.ext_stats {
+                *totals.entry(extension).or_default() += lines.additions + lines.deletions;
+            }
+        }
+        if totals.values().all(|&lines| lines == 0) {
+            return LanguageData::default();
+        }
+
+        let mut ranked: Vec<_> = totals
+            .iter()
+            .filter(|(extension, _)| **extension != OTHER_EXTENSIONS)
+            .map(|(&extension, &lines)| (lines, extension))
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        let mut extensions: Vec<String> = ranked
+            .iter()
+            .take(LANGUAGE_SEGMENTS)
+            .map(|(_, extension)| (*extension).to_string())
+            .collect();
+        let other_lines = totals.values().sum::<u64>()
+            - ranked
+                .iter()
+                .take(LANGUAGE_SEGMENTS)
+                .map(|(lines, _)| lines)
+                .sum::<u64>();
+        if other_lines > 0 {
+            extensions.push(OTHER_EXTENSIONS.to_string());
+        }
+
+        let points = stats
+            .iter()
+            .map(|stat| {
+                let mut lines = vec![0; extensions.len()];
+                for (extension, changed) in &stat.ext_stats {
+                    let index = extensions
+                        .iter()
+                        .position(|name| name == extension)
+                        .unwrap_or(extensions.len() - 1);
+                    lines[index] += changed.additions + changed.deletions;
+                }
+                LanguageDataPoint {
+                    label: stat.label.clone(),
+                    lines,
+                }
+            })
+            .collect();
+        LanguageData { extensions, points }
+    }
+
+    /// Whether the requested range differs from the data on screen (a range change is loading)
+    #[must_use]
+    pub fn is_loading_range(&self) -> bool {
+        self.model.refreshing && self.model.days != range_days(&self.result)
+    }
+
+    /// Queue a status message, shown after the ones already waiting
+    pub fn show_status(&mut self, text: impl Into<String>, level: StatusLevel) {
+        self.dispatch(Action::ShowStatus {
+            text: text.into(),
+            level,
+        });
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stats::{PeriodStats, TotalStats};
+    use crate::stats::{ExtensionLines, PeriodStats, TotalStats};
     use crate::tui::chart_type::ChartType;
     use chrono::NaiveDate;
+    use crossterm::event::KeyCode;
 
     fn make_result() -> AnalysisResult {
         AnalysisResult {
@@ -252,22 +995,57 @@ mod tests {
                 deletions: 20,
                 net_lines: 80,
                 files_changed: 10,
+                ..Default::default()
             }],
             total: TotalStats::default(),
+            extensions: None,
+            top_commits: None,
+            top_files: None,
+            top_dirs: None,
+            file_details: None,
+            repositories: Vec::new(),
+            comparison: None,
+            moving_average: None,
+            truncated: false,
+            work_pattern: None,
+            year_over_year: None,
+            gaps: Vec::new(),
         }
     }
 
     #[test]
-    fn test_metric_cycle() {
-        let metric = Metric::Commits;
-        assert_eq!(metric.next(), Metric::AdditionsAndDeletions);
-        assert_eq!(metric.prev(), Metric::FilesChanged);
-    }
+    fn test_keys_go_through_update() {
+        let mut app = App::new(
+            make_result_with_multiple_days(),
+            ActivityStats::default(),
+            false,
+        );
+        let keys = [
+            KeyCode::Tab,
+            KeyCode::Char('l'),
+            KeyCode::Char('m'),
+            KeyCode::Right,
+            KeyCode::Char('k'),
+            KeyCode::Enter,
+            KeyCode::Left,
+            KeyCode::Esc,
+            KeyCode::Char('?'),
+            KeyCode::Char('?'),
+            KeyCode::Char('q'),
+        ];
+        for code in keys {
+            let key = KeyEvent::from(code);
+            let action = if app.model().selected_index.is_some() {
+                Action::from_inspect_key(key, &Keymap::default())
+            } else {
+                Action::from_key(key, app.model().single_metric, &Keymap::default())
+            };
+            let expected = update(app.model().clone(), action);
 
-    #[test]
-    fn test_all_metrics() {
-        let metrics = App::all_metrics();
-        assert_eq!(metrics.len(), 3);
+            app.handle_key(key);
+            assert_eq!(app.model(), &expected, "{code:?}");
+        }
+        assert!(app.model().should_quit);
     }
 
     #[test]
@@ -280,6 +1058,12 @@ mod tests {
         assert_eq!(data[0].label, "2024-01-01");
         assert_eq!(data[0].additions, 100);
         assert_eq!(data[0].deletions, 20);
+        assert!(!data[0].is_outlier);
+
+        let mut result = make_result();
+        result.stats[0].is_outlier = true;
+        let app = App::new(result, ActivityStats::default(), false);
+        assert!(app.additions_deletions_data()[0].is_outlier);
     }
 
     fn make_result_with_multiple_days() -> AnalysisResult {
@@ -297,9 +1081,22 @@ mod tests {
                     deletions: u64::from(day) * 2,
                     net_lines: i64::from(day) * 8,
                     files_changed: day,
+                    ..Default::default()
                 })
                 .collect(),
             total: TotalStats::default(),
+            extensions: None,
+            top_commits: None,
+            top_files: None,
+            top_dirs: None,
+            file_details: None,
+            repositories: Vec::new(),
+            comparison: None,
+            moving_average: None,
+            truncated: false,
+            work_pattern: None,
+            year_over_year: None,
+            gaps: Vec::new(),
         }
     }
 
@@ -309,9 +1106,9 @@ mod tests {
         let mut app = App::new(result, ActivityStats::default(), false);
 
         assert_eq!(app.model.scroll_offset, 0);
-        app.scroll_up();
+        app.dispatch(Action::ScrollUp);
         assert_eq!(app.model.scroll_offset, 1);
-        app.scroll_up();
+        app.dispatch(Action::ScrollUp);
         assert_eq!(app.model.scroll_offset, 2);
     }
 
@@ -321,11 +1118,11 @@ mod tests {
         let mut app = App::new(result, ActivityStats::default(), false);
 
         app.model.scroll_offset = 3;
-        app.scroll_down();
+        app.dispatch(Action::ScrollDown);
         assert_eq!(app.model.scroll_offset, 2);
-        app.scroll_down();
+        app.dispatch(Action::ScrollDown);
         assert_eq!(app.model.scroll_offset, 1);
-        app.scroll_down();
+        app.dispatch(Action::ScrollDown);
         assert_eq!(app.model.scroll_offset, 0);
     }
 
@@ -335,7 +1132,7 @@ mod tests {
         let mut app = App::new(result, ActivityStats::default(), false);
 
         assert_eq!(app.model.scroll_offset, 0);
-        app.scroll_down();
+        app.dispatch(Action::ScrollDown);
         assert_eq!(app.model.scroll_offset, 0);
     }
 
@@ -346,7 +1143,7 @@ mod tests {
 
         // 5 items, max offset should be 4 (data_len - 1)
         for _ in 0..10 {
-            app.scroll_up();
+            app.dispatch(Action::ScrollUp);
         }
         assert_eq!(app.model.scroll_offset, 4);
     }
@@ -357,7 +1154,7 @@ mod tests {
         let mut app = App::new(result, ActivityStats::default(), false);
 
         app.model.scroll_offset = 3;
-        app.apply_action(Action::ToggleMetricView);
+        app.dispatch(Action::ToggleMetricView);
         assert_eq!(app.model.scroll_offset, 0);
     }
 
@@ -367,7 +1164,7 @@ mod tests {
         let app = App::new(result, ActivityStats::default(), false);
 
         // Split mode always supports scrolling
-        assert!(app.can_scroll());
+        assert!(app.model().can_scroll());
     }
 
     #[test]
@@ -377,19 +1174,188 @@ mod tests {
 
         // Only AddDel chart supports scrolling in single mode
         app.model.chart_type = ChartType::AddDel;
-        assert!(app.can_scroll());
+        assert!(app.model().can_scroll());
 
         app.model.chart_type = ChartType::Commits;
-        assert!(!app.can_scroll());
+        assert!(!app.model().can_scroll());
 
         app.model.chart_type = ChartType::FilesChanged;
-        assert!(!app.can_scroll());
+        assert!(!app.model().can_scroll());
 
         app.model.chart_type = ChartType::Weekday;
-        assert!(!app.can_scroll());
+        assert!(!app.model().can_scroll());
 
         app.model.chart_type = ChartType::Hour;
-        assert!(!app.can_scroll());
+        assert!(!app.model().can_scroll());
+
+        app.model.chart_type = ChartType::TimeOfDay;
+        assert!(!app.model().can_scroll());
+
+        app.model.chart_type = ChartType::Heatmap;
+        assert!(!app.model().can_scroll());
+
+        app.model.chart_type = ChartType::NetLines;
+        assert!(!app.model().can_scroll());
+
+        app.model.chart_type = ChartType::Cumulative;
+        assert!(!app.model().can_scroll());
+
+        app.model.chart_type = ChartType::TopFiles;
+        assert!(!app.model().can_scroll());
+
+        app.model.chart_type = ChartType::Languages;
+        assert!(!app.model().can_scroll());
+    }
+
+    #[test]
+    fn test_language_data_folds_small_extensions_into_other() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let lines = |additions| ExtensionLines {
+            additions,
+            deletions: 0,
+        };
+        let stats = vec![
+            PeriodStats {
+                ext_stats: [("rs", 60), ("md", 5), ("a", 1), ("b", 2), ("c", 3)]
+                    .into_iter()
+                    .map(|(extension, additions)| (extension.to_string(), lines(additions)))
+                    .collect(),
+                ..PeriodStats::new(day)
+            },
+            PeriodStats {
+                ext_stats: [("ts", 40), ("d", 4), (OTHER_EXTENSIONS, 7)]
+                    .into_iter()
+                    .map(|(extension, additions)| (extension.to_string(), lines(additions)))
+                    .collect(),
+                ..PeriodStats::new(day.succ_opt().unwrap())
+            },
+        ];
+        let mut result = make_result();
+        result.stats = stats;
+        let app = App::new(result, ActivityStats::default(), true);
+
+        let data = app.language_data();
+        assert_eq!(data.extensions, ["rs", "ts", "md", "d", "c", "other"]);
+        assert_eq!(data.points[0].lines, [60, 0, 5, 0, 3, 3]);
+        assert_eq!(data.points[1].lines, [0, 40, 0, 4, 0, 7]);
+
+        let app = App::new(make_result(), ActivityStats::default(), true);
+        assert_eq!(app.language_data(), LanguageData::default());
+    }
+
+    #[test]
+    fn test_cumulative_net_lines() {
+        let result = make_result();
+        let app = App::new(result.clone(), ActivityStats::default(), false);
+
+        let cumulative = app.cumulative_net_lines();
+        assert_eq!(cumulative.len(), result.stats.len());
+
+        let mut running = 0;
+        for ((label, value), stat) in cumulative.iter().zip(&result.stats) {
+            running += stat.net_lines;
+            assert_eq!(label, &stat.label);
+            assert_eq!(*value, running);
+        }
+    }
+
+    #[test]
+    fn test_repo_tabs_switch_data_and_reset_scroll() {
+        let mut docs = make_result_with_multiple_days();
+        docs.repository = "docs".to_string();
+        docs.stats[4].commits = 9;
+        let mut app = App::new(
+            make_result_with_multiple_days(),
+            ActivityStats::default(),
+            false,
+        )
+        .with_repo_views(vec![RepoView {
+            result: docs,
+            activity_stats: ActivityStats::default(),
+            daily_stats: Vec::new(),
+        }]);
+        assert_eq!(app.repo_tabs(), ["All", "docs"]);
+
+        app.dispatch(Action::ScrollUp);
+        app.dispatch(Action::SelectRepo(1));
+        assert_eq!(app.model().active_repo, 1);
+        assert_eq!(app.model().scroll_offset, 0);
+        assert_eq!(app.values_for_metric(Metric::Commits)[4].1, 9);
+
+        app.dispatch(Action::SelectRepo(0));
+        assert_eq!(app.values_for_metric(Metric::Commits)[4].1, 5);
+    }
+
+    #[test]
+    fn test_repo_series_folds_small_repositories_into_other() {
+        // Seven repositories with 1..=7 commits on January 1st
+        let views: Vec<RepoView> = (1..=7u32)
+            .map(|commits| {
+                let mut result = make_result_with_multiple_days();
+                result.repository = format!("repo{commits}");
+                result.stats = vec![PeriodStats {
+                    commits,
+                    ..PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+                }];
+                result.total.commits = commits;
+                RepoView {
+                    result,
+                    activity_stats: ActivityStats::default(),
+                    daily_stats: Vec::new(),
+                }
+            })
+            .collect();
+        let mut app = App::new(
+            make_result_with_multiple_days(),
+            ActivityStats::default(),
+            false,
+        )
+        .with_repo_views(views);
+        assert!(app.repo_series().is_empty());
+
+        app.dispatch(Action::ToggleCommitsByRepo);
+        let series = app.repo_series();
+        let names: Vec<&str> = series.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["repo3", "repo4", "repo5", "repo6", "repo7", OTHER_REPOS]
+        );
+        // Every line covers the five periods of the combined view
+        assert_eq!(series[0].commits, [3, 0, 0, 0, 0]);
+        assert_eq!(series[5].commits, [3, 0, 0, 0, 0]);
+
+        app.dispatch(Action::SelectRepo(1));
+        assert!(app.repo_series().is_empty());
+    }
+
+    #[test]
+    fn test_repo_series_aligns_periods_by_date() {
+        // Weekly buckets of a repository are matched to the combined view by start date
+        let week = |day, commits| PeriodStats {
+            commits,
+            ..PeriodStats::new(NaiveDate::from_ymd_opt(2024, 1, day).unwrap())
+        };
+        let mut combined = make_result_with_multiple_days();
+        combined.stats = vec![week(1, 3), week(8, 4), week(15, 2)];
+        let view = |name: &str, stats| {
+            let mut result = make_result_with_multiple_days();
+            result.repository = name.to_string();
+            result.stats = stats;
+            RepoView {
+                result,
+                activity_stats: ActivityStats::default(),
+                daily_stats: Vec::new(),
+            }
+        };
+        let mut app = App::new(combined, ActivityStats::default(), false).with_repo_views(vec![
+            view("kodo", vec![week(1, 3), week(8, 1), week(15, 2)]),
+            view("docs", vec![week(8, 3)]),
+        ]);
+        app.dispatch(Action::ToggleCommitsByRepo);
+
+        let series = app.repo_series();
+        assert_eq!(series[0].commits, [3, 1, 2]);
+        assert_eq!(series[1].commits, [0, 3, 0]);
     }
 
     #[test]
@@ -397,6 +1363,310 @@ mod tests {
         let result = make_result();
         let app = App::new(result, ActivityStats::default(), false);
 
-        assert_eq!(app.chart_type(), ChartType::default());
+        assert_eq!(app.model().chart_type, ChartType::default());
+    }
+
+    /// Poll until the background refresh has been applied
+    fn wait_for_refresh(app: &mut App) {
+        for _ in 0..200 {
+            app.poll_refresh();
+            if !app.model().refreshing {
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        panic!("refresh did not finish");
+    }
+
+    #[test]
+    fn test_refresh_replaces_data_and_keeps_scroll_offset() {
+        let mut app = App::new(
+            make_result_with_multiple_days(),
+            ActivityStats::default(),
+            false,
+        )
+        .with_refresher(Arc::new(|_| {
+            let mut result = make_result_with_multiple_days();
+            result.stats[4].commits = 42;
+            Ok((
+                RepoView {
+                    result,
+                    activity_stats: ActivityStats::default(),
+                    daily_stats: Vec::new(),
+                },
+                Vec::new(),
+            ))
+        }));
+        app.dispatch(Action::ScrollUp);
+        app.dispatch(Action::ScrollUp);
+
+        app.dispatch(Action::Refresh);
+        assert!(app.model().refreshing);
+        wait_for_refresh(&mut app);
+
+        assert_eq!(app.result.stats[4].commits, 42);
+        assert_eq!(app.model().scroll_offset, 2);
+        assert!(app.model().refresh_error.is_none());
+    }
+
+    #[test]
+    fn test_refresh_failure_keeps_old_data() {
+        let mut app = App::new(make_result(), ActivityStats::default(), false)
+            .with_refresher(Arc::new(|_| Err(crate::error::Error::NoRepositories)));
+
+        app.dispatch(Action::Refresh);
+        wait_for_refresh(&mut app);
+
+        assert_eq!(app.result.stats[0].commits, 5);
+        assert_eq!(
+            app.model().refresh_error.as_deref(),
+            Some("No repositories to analyze")
+        );
+    }
+
+    #[test]
+    fn test_refresh_ignored_without_refresher() {
+        let mut app = App::new(make_result(), ActivityStats::default(), false);
+
+        app.dispatch(Action::Refresh);
+        assert!(!app.model().refreshing);
+    }
+
+    #[test]
+    fn test_refresh_due() {
+        let interval = Duration::from_mins(5);
+
+        assert!(!refresh_due(
+            Duration::from_secs(4 * 60 + 59),
+            interval,
+            false
+        ));
+        assert!(refresh_due(interval, interval, false));
+        assert!(refresh_due(Duration::from_mins(15), interval, false));
+        // Never overlap a refresh that is still running
+        assert!(!refresh_due(Duration::from_mins(15), interval, true));
+    }
+
+    #[test]
+    fn test_needs_redraw() {
+        let app = App::new(make_result(), ActivityStats::default(), false);
+        let idle = app.model.clone();
+        let key = Event::Key(KeyEvent::from(KeyCode::Char('x')));
+
+        // Input always redraws, an idle tick never does
+        assert!(needs_redraw(&key, &idle, &idle));
+        assert!(needs_redraw(&Event::Resize(80, 24), &idle, &idle));
+        assert!(!needs_redraw(&Event::Tick, &idle, &idle));
+
+        // A tick counting down a status message redraws only once it expires
+        let shown = update(
+            idle.clone(),
+            Action::ShowStatus {
+                text: "Exported".to_string(),
+                level: StatusLevel::Info,
+            },
+        );
+        let counting = update(shown.clone(), Action::Tick);
+        assert!(!needs_redraw(&Event::Tick, &shown, &counting));
+        let mut expiring = shown.clone();
+        for _ in 0..STATUS_TICKS {
+            expiring = update(expiring, Action::Tick);
+        }
+        assert!(needs_redraw(&Event::Tick, &shown, &expiring));
+
+        // Ticks starting a watch refresh or moving the loading gauge redraw
+        let refreshing = update(idle.clone(), Action::Refresh);
+        assert!(needs_redraw(&Event::Tick, &idle, &refreshing));
+        let loading = Model {
+            loading: true,
+            ..idle
+        };
+        assert!(needs_redraw(&Event::Tick, &loading, &loading));
+    }
+
+    #[test]
+    fn test_tick_ms_keeps_status_duration() {
+        let mut app = App::new(make_result(), ActivityStats::default(), false)
+            .with_tick_ms(NonZeroU64::new(1000).unwrap());
+        app.show_status("Exported", StatusLevel::Info);
+
+        for _ in 0..2 {
+            app.dispatch(Action::Tick);
+        }
+        assert!(app.model().status().is_some());
+        app.dispatch(Action::Tick);
+        assert!(app.model().status().is_none());
+    }
+
+    #[test]
+    fn test_tick_starts_refresh_when_watch_interval_elapsed() {
+        let mut app = App::new(make_result(), ActivityStats::default(), false).with_refresher(
+            Arc::new(|_| {
+                Ok((
+                    RepoView {
+                        result: make_result(),
+                        activity_stats: ActivityStats::default(),
+                        daily_stats: Vec::new(),
+                    },
+                    Vec::new(),
+                ))
+            }),
+        );
+
+        // Without --watch, ticks never refresh
+        app.on_tick();
+        assert!(!app.model().refreshing);
+
+        let mut app = app.with_watch_interval(Duration::ZERO);
+        app.on_tick();
+        assert!(app.model().refreshing);
+        wait_for_refresh(&mut app);
+        assert!(app.model().refresh_error.is_none());
+    }
+
+    #[test]
+    fn test_export_writes_view_and_shows_status() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(make_result(), ActivityStats::default(), false)
+            .with_export_dir(dir.path().to_path_buf());
+
+        app.dispatch(Action::Export);
+
+        let status = app.model().status().unwrap();
+        assert!(status.text.starts_with("Exported to "), "{}", status.text);
+        let path = std::fs::read_dir(dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert_eq!(path.extension().unwrap(), "json");
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(json["repository"], "test");
+
+        // The message clears once its ticks run out
+        for _ in 0..STATUS_TICKS {
+            app.dispatch(Action::Tick);
+        }
+        assert!(app.model().status().is_none());
+    }
+
+    #[test]
+    fn test_unknown_key_shows_hint() {
+        let mut app = App::new(make_result(), ActivityStats::default(), false);
+        let key = KeyEvent::from(KeyCode::Char('x'));
+
+        app.handle_key(key);
+        let status = app.model().status().unwrap();
+        assert_eq!(status.text, UNKNOWN_KEY);
+        assert_eq!(status.level, StatusLevel::Warn);
+
+        // With the help overlay open the key only closes it
+        let mut app = App::new(make_result(), ActivityStats::default(), false);
+        app.dispatch(Action::ToggleHelp);
+        app.handle_key(key);
+        assert!(!app.model().show_help);
+        assert!(app.model().status().is_none());
+    }
+
+    #[test]
+    fn test_export_single_mode_writes_chart_csv() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(make_result(), ActivityStats::default(), true)
+            .with_export_dir(dir.path().to_path_buf());
+        app.model.chart_type = ChartType::AddDel;
+
+        app.dispatch(Action::Export);
+
+        let path = std::fs::read_dir(dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert_eq!(path.extension().unwrap(), "csv");
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "period,additions,deletions\n2024-01-01,100,20\n"
+        );
+    }
+
+    #[test]
+    fn test_export_failure_shows_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(make_result(), ActivityStats::default(), false)
+            .with_export_dir(dir.path().join("missing"));
+
+        app.dispatch(Action::Export);
+
+        let status = app.model().status().unwrap();
+        assert!(
+            status.text.starts_with("Export failed: "),
+            "{}",
+            status.text
+        );
+    }
+
+    #[test]
+    fn test_grow_range_refreshes_with_doubled_days() {
+        let mut app = App::new(
+            make_result_with_multiple_days(),
+            ActivityStats::default(),
+            false,
+        )
+        .with_refresher(Arc::new(|days| {
+            let mut result = make_result_with_multiple_days();
+            result.from = result.to - chrono::Duration::days(i64::from(days) - 1);
+            Ok((
+                RepoView {
+                    result,
+                    activity_stats: ActivityStats::default(),
+                    daily_stats: Vec::new(),
+                },
+                Vec::new(),
+            ))
+        }));
+        assert_eq!(app.model().days, 5);
+
+        app.dispatch(Action::GrowRange);
+        assert!(app.is_loading_range());
+        wait_for_refresh(&mut app);
+
+        assert_eq!(app.model().days, 10);
+        assert_eq!(
+            app.result.from,
+            NaiveDate::from_ymd_opt(2023, 12, 27).unwrap()
+        );
+        assert!(!app.is_loading_range());
+    }
+
+    #[test]
+    fn test_set_period_reaggregates_daily_stats() {
+        let daily = make_result_with_multiple_days();
+        let daily_stats = daily.stats.clone();
+        let mut app = App::new(daily, ActivityStats::default(), true).with_daily_stats(daily_stats);
+        app.model.chart_type = ChartType::AddDel;
+        app.dispatch(Action::ScrollUp);
+
+        app.dispatch(Action::SetPeriod(Period::Monthly));
+        assert_eq!(app.result.period, "monthly");
+        assert_eq!(app.result.stats.len(), 1);
+        assert_eq!(app.result.stats[0].commits, 15);
+        assert_eq!(app.model().scroll_offset, 0);
+        assert_eq!(app.model.data_len, 1);
+
+        app.dispatch(Action::SetPeriod(Period::Daily));
+        assert_eq!(app.result.stats.len(), 5);
+        assert_eq!(app.model.data_len, 5);
+    }
+
+    #[test]
+    fn test_set_period_ignored_without_daily_stats() {
+        let mut app = App::new(make_result(), ActivityStats::default(), false);
+
+        app.dispatch(Action::SetPeriod(Period::Weekly));
+        assert_eq!(app.model.period, Period::Daily);
+        assert_eq!(app.result.period, "daily");
     }
 }
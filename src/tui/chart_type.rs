@@ -7,6 +7,12 @@ pub enum ChartType {
     AddDel,
     Weekday,
     Hour,
+    TimeOfDay,
+    Heatmap,
+    NetLines,
+    Cumulative,
+    TopFiles,
+    Languages,
 }
 
 impl ChartType {
@@ -18,7 +24,13 @@ impl ChartType {
             Self::FilesChanged => Self::AddDel,
             Self::AddDel => Self::Weekday,
             Self::Weekday => Self::Hour,
-            Self::Hour => Self::Commits,
+            Self::Hour => Self::TimeOfDay,
+            Self::TimeOfDay => Self::Heatmap,
+            Self::Heatmap => Self::NetLines,
+            Self::NetLines => Self::Cumulative,
+            Self::Cumulative => Self::TopFiles,
+            Self::TopFiles => Self::Languages,
+            Self::Languages => Self::Commits,
         }
     }
 
@@ -26,11 +38,17 @@ impl ChartType {
     #[must_use]
     pub fn prev(self) -> Self {
         match self {
-            Self::Commits => Self::Hour,
+            Self::Commits => Self::Languages,
             Self::FilesChanged => Self::Commits,
             Self::AddDel => Self::FilesChanged,
             Self::Weekday => Self::AddDel,
             Self::Hour => Self::Weekday,
+            Self::TimeOfDay => Self::Hour,
+            Self::Heatmap => Self::TimeOfDay,
+            Self::NetLines => Self::Heatmap,
+            Self::Cumulative => Self::NetLines,
+            Self::TopFiles => Self::Cumulative,
+            Self::Languages => Self::TopFiles,
         }
     }
 
@@ -43,6 +61,12 @@ impl ChartType {
             Self::AddDel => "Add/Del",
             Self::Weekday => "Weekday",
             Self::Hour => "Hour",
+            Self::TimeOfDay => "Time of Day",
+            Self::Heatmap => "Heatmap",
+            Self::NetLines => "Net Lines",
+            Self::Cumulative => "Cumulative",
+            Self::TopFiles => "Top Files",
+            Self::Languages => "Languages",
         }
     }
 }
@@ -53,22 +77,46 @@ mod tests {
 
     #[test]
     fn chart_type_cycle() {
-        let chart = ChartType::Commits;
-        assert_eq!(chart.next(), ChartType::FilesChanged);
-        assert_eq!(chart.next().next(), ChartType::AddDel);
-        assert_eq!(chart.next().next().next(), ChartType::Weekday);
-        assert_eq!(chart.next().next().next().next(), ChartType::Hour);
-        assert_eq!(chart.next().next().next().next().next(), ChartType::Commits);
+        let expected = [
+            ChartType::FilesChanged,
+            ChartType::AddDel,
+            ChartType::Weekday,
+            ChartType::Hour,
+            ChartType::TimeOfDay,
+            ChartType::Heatmap,
+            ChartType::NetLines,
+            ChartType::Cumulative,
+            ChartType::TopFiles,
+            ChartType::Languages,
+            ChartType::Commits,
+        ];
+        let mut chart = ChartType::Commits;
+        for next in expected {
+            chart = chart.next();
+            assert_eq!(chart, next);
+        }
     }
 
     #[test]
     fn chart_type_prev_cycle() {
-        let chart = ChartType::Commits;
-        assert_eq!(chart.prev(), ChartType::Hour);
-        assert_eq!(chart.prev().prev(), ChartType::Weekday);
-        assert_eq!(chart.prev().prev().prev(), ChartType::AddDel);
-        assert_eq!(chart.prev().prev().prev().prev(), ChartType::FilesChanged);
-        assert_eq!(chart.prev().prev().prev().prev().prev(), ChartType::Commits);
+        let expected = [
+            ChartType::Languages,
+            ChartType::TopFiles,
+            ChartType::Cumulative,
+            ChartType::NetLines,
+            ChartType::Heatmap,
+            ChartType::TimeOfDay,
+            ChartType::Hour,
+            ChartType::Weekday,
+            ChartType::AddDel,
+            ChartType::FilesChanged,
+            ChartType::Commits,
+        ];
+        let mut chart = ChartType::Commits;
+        for prev in expected {
+            chart = chart.prev();
+            assert_eq!(chart, prev);
+        }
     }
 
     #[test]
@@ -78,6 +126,12 @@ mod tests {
         assert_eq!(ChartType::AddDel.name(), "Add/Del");
         assert_eq!(ChartType::Weekday.name(), "Weekday");
         assert_eq!(ChartType::Hour.name(), "Hour");
+        assert_eq!(ChartType::TimeOfDay.name(), "Time of Day");
+        assert_eq!(ChartType::Heatmap.name(), "Heatmap");
+        assert_eq!(ChartType::NetLines.name(), "Net Lines");
+        assert_eq!(ChartType::Cumulative.name(), "Cumulative");
+        assert_eq!(ChartType::TopFiles.name(), "Top Files");
+        assert_eq!(ChartType::Languages.name(), "Languages");
     }
 
     #[test]
@@ -2,10 +2,14 @@
 
 use crate::error::Result;
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent};
+use std::num::NonZeroU64;
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
+/// Milliseconds between ticks unless `--tick-ms` or `defaults.tick_ms` say otherwise
+pub const DEFAULT_TICK_MS: NonZeroU64 = NonZeroU64::new(250).unwrap();
+
 /// Terminal events
 #[derive(Debug)]
 pub enum Event {
@@ -17,6 +21,14 @@ pub enum Event {
     Tick,
 }
 
+impl Event {
+    /// Whether this is a tick rather than input from the terminal
+    #[must_use]
+    pub const fn is_tick(&self) -> bool {
+        matches!(self, Self::Tick)
+    }
+}
+
 /// Event handler that runs in a separate thread
 pub struct EventHandler {
     /// Event receiver
@@ -0,0 +1,198 @@
+//! Export the data behind the current TUI view
+
+use crate::error::Result;
+use crate::stats::{ActivityStats, FileChurn};
+use crate::tui::app::{App, Metric};
+use crate::tui::chart_type::ChartType;
+use chrono::NaiveDateTime;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// File name for an export made at `now`, e.g. `kodo-export-20240601-1530.json`
+#[must_use]
+pub fn export_file_name(now: NaiveDateTime, extension: &str) -> String {
+    format!("kodo-export-{}.{extension}", now.format("%Y%m%d-%H%M"))
+}
+
+/// Write the data behind the current view into `dir`
+///
+/// Split mode exports the full analysis result as JSON; single mode exports
+/// the series of the visible chart as CSV.
+///
+/// # Errors
+///
+/// Returns an error if the result cannot be serialized or the file cannot be written
+pub fn export_view(app: &App, dir: &Path, now: NaiveDateTime) -> Result<PathBuf> {
+    let (contents, extension) = if app.model().single_metric {
+        (chart_csv(app), "csv")
+    } else {
+        (serde_json::to_string_pretty(app.active_result())?, "json")
+    };
+
+    let path = dir.join(export_file_name(now, extension));
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// CSV of the series shown by the current single-mode chart
+#[must_use]
+pub fn chart_csv(app: &App) -> String {
+    let mut csv = String::new();
+    match app.model().chart_type {
+        ChartType::Commits => {
+            write_series(&mut csv, "commits", &app.values_for_metric(Metric::Commits));
+        }
+        ChartType::FilesChanged => {
+            let values = app.values_for_metric(Metric::FilesChanged);
+            write_series(&mut csv, "unique_files", &values);
+        }
+        ChartType::NetLines => {
+            let values = app.values_for_metric(Metric::AdditionsAndDeletions);
+            write_series(&mut csv, "net_lines", &values);
+        }
+        ChartType::Cumulative => {
+            write_series(
+                &mut csv,
+                "cumulative_net_lines",
+                &app.cumulative_net_lines(),
+            );
+        }
+        ChartType::AddDel => {
+            csv.push_str("period,additions,deletions\n");
+            for point in app.additions_deletions_data() {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{}",
+                    csv_field(&point.label),
+                    point.additions,
+                    point.deletions
+                );
+            }
+        }
+        ChartType::Weekday => {
+            csv.push_str("weekday,commits,lines\n");
+            let stats = app.active_activity_stats();
+            let labels = ActivityStats::weekday_labels();
+            for ((label, commits), lines) in
+                labels.iter().zip(stats.weekday).zip(stats.weekday_lines)
+            {
+                let _ = writeln!(csv, "{label},{commits},{lines}");
+            }
+        }
+        ChartType::Hour => {
+            csv.push_str("hour,commits,lines\n");
+            let stats = app.active_activity_stats();
+            for (hour, (commits, lines)) in stats.hourly.iter().zip(stats.hourly_lines).enumerate()
+            {
+                let _ = writeln!(csv, "{hour},{commits},{lines}");
+            }
+        }
+        ChartType::TimeOfDay => {
+            csv.push_str("time_of_day,commits,lines\n");
+            let stats = app.active_activity_stats();
+            for ((label, commits), (_, lines)) in stats
+                .time_of_day()
+                .into_iter()
+                .zip(stats.time_of_day_lines())
+            {
+                let _ = writeln!(csv, "{label},{commits},{lines}");
+            }
+        }
+        ChartType::Heatmap => {
+            csv.push_str("weekday");
+            for hour in 0..24 {
+                let _ = write!(csv, ",{hour}");
+            }
+            csv.push('\n');
+            let stats = app.active_activity_stats();
+            for (label, row) in ActivityStats::weekday_labels().iter().zip(stats.heatmap) {
+                csv.push_str(label);
+                for commits in row {
+                    let _ = write!(csv, ",{commits}");
+                }
+                csv.push('\n');
+            }
+        }
+        ChartType::TopFiles => {
+            csv.push_str("kind,path,commits,additions,deletions\n");
+            let result = app.active_result();
+            write_churn(&mut csv, "file", result.top_files.as_deref());
+            write_churn(&mut csv, "dir", result.top_dirs.as_deref());
+        }
+        ChartType::Languages => {
+            let data = app.language_data();
+            csv.push_str("period");
+            for extension in &data.extensions {
+                let _ = write!(csv, ",{}", csv_field(extension));
+            }
+            csv.push('\n');
+            for point in &data.points {
+                csv.push_str(&csv_field(&point.label));
+                for lines in &point.lines {
+                    let _ = write!(csv, ",{lines}");
+                }
+                csv.push('\n');
+            }
+        }
+    }
+    csv
+}
+
+fn write_series(csv: &mut String, column: &str, values: &[(String, i64)]) {
+    let _ = writeln!(csv, "period,{column}");
+    for (label, value) in values {
+        let _ = writeln!(csv, "{},{value}", csv_field(label));
+    }
+}
+
+fn write_churn(csv: &mut String, kind: &str, entries: Option<&[FileChurn]>) {
+    for entry in entries.unwrap_or_default() {
+        let _ = writeln!(
+            csv,
+            "{kind},{},{},{},{}",
+            csv_field(&entry.path),
+            entry.commits,
+            entry.additions,
+            entry.deletions
+        );
+    }
+}
+
+/// Quote a field when it contains a separator, quote, or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_export_file_name() {
+        let now = NaiveDate::from_ymd_opt(2024, 6, 1)
+            .unwrap()
+            .and_hms_opt(15, 30, 59)
+            .unwrap();
+
+        assert_eq!(
+            export_file_name(now, "json"),
+            "kodo-export-20240601-1530.json"
+        );
+        assert_eq!(
+            export_file_name(now, "csv"),
+            "kodo-export-20240601-1530.csv"
+        );
+    }
+
+    #[test]
+    fn test_csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("src/main.rs"), "src/main.rs");
+        assert_eq!(csv_field("a,b.rs"), "\"a,b.rs\"");
+        assert_eq!(csv_field("say \"hi\".md"), "\"say \"\"hi\"\".md\"");
+    }
+}
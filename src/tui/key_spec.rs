@@ -0,0 +1,320 @@
+//! Key specs like `ctrl+n`, `F5`, or `space`, as written in `defaults.keybindings`
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+use std::str::FromStr;
+
+/// Modifier prefixes, long and short, in lowercase
+const MODIFIERS: [(&str, KeyModifiers); 9] = [
+    ("ctrl+", KeyModifiers::CONTROL),
+    ("control+", KeyModifiers::CONTROL),
+    ("c-", KeyModifiers::CONTROL),
+    ("alt+", KeyModifiers::ALT),
+    ("meta+", KeyModifiers::ALT),
+    ("a-", KeyModifiers::ALT),
+    ("m-", KeyModifiers::ALT),
+    ("shift+", KeyModifiers::SHIFT),
+    ("s-", KeyModifiers::SHIFT),
+];
+
+/// Named keys in lowercase, with the aliases people commonly write
+const NAMED_KEYS: [(&str, KeyCode); 24] = [
+    ("space", KeyCode::Char(' ')),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("enter", KeyCode::Enter),
+    ("return", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("escape", KeyCode::Esc),
+    ("backspace", KeyCode::Backspace),
+    ("del", KeyCode::Delete),
+    ("delete", KeyCode::Delete),
+    ("ins", KeyCode::Insert),
+    ("insert", KeyCode::Insert),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pgup", KeyCode::PageUp),
+    ("pageup", KeyCode::PageUp),
+    ("pgdn", KeyCode::PageDown),
+    ("pagedown", KeyCode::PageDown),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("plus", KeyCode::Char('+')),
+    ("minus", KeyCode::Char('-')),
+];
+
+/// A key with its modifiers, compared the way terminals report key presses
+///
+/// Shift is part of the character for character keys (`G` rather than
+/// `shift+g`), and letters with Ctrl are lowercase since terminals cannot
+/// tell `ctrl+n` from `ctrl+N`. Shift+Tab is `BackTab`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    /// Spec for `code` with `modifiers`, normalized like a parsed spec
+    #[must_use]
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) => {
+                let shifted = modifiers.contains(KeyModifiers::SHIFT);
+                modifiers.remove(KeyModifiers::SHIFT);
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else if shifted {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Spec of a key press
+    #[must_use]
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeySpec {
+    type Err = String;
+
+    /// Parse `ctrl+n`, `alt+x`, `shift+tab`, `F5`, `space`, `G`, or the
+    /// short forms shown in the help overlay (`C-u`, `S-Tab`, `↑`)
+    ///
+    /// Modifier and key names are case-insensitive; a single character is
+    /// taken as is, so `g` and `G` are different keys.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let spec = input.trim();
+        if spec.is_empty() {
+            return Err("empty key".to_string());
+        }
+
+        let mut rest = spec;
+        let mut modifiers = KeyModifiers::NONE;
+        'prefixes: loop {
+            for (prefix, modifier) in MODIFIERS {
+                // A lone `-` or `+` after a prefix is the key itself, not another prefix
+                if rest.len() > prefix.len()
+                    && rest.is_char_boundary(prefix.len())
+                    && rest[..prefix.len()].eq_ignore_ascii_case(prefix)
+                {
+                    modifiers |= modifier;
+                    rest = &rest[prefix.len()..];
+                    continue 'prefixes;
+                }
+            }
+            break;
+        }
+
+        let code = parse_code(rest).ok_or_else(|| {
+            format!(
+                "unknown key '{spec}'; use a character, a name like space, enter, up, \
+                 pagedown, or f5, optionally after ctrl+, alt+, or shift+"
+            )
+        })?;
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+/// Key code of a single character, a named key, a function key, or an arrow
+fn parse_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(match c {
+            '↑' => KeyCode::Up,
+            '↓' => KeyCode::Down,
+            '←' => KeyCode::Left,
+            '→' => KeyCode::Right,
+            c => KeyCode::Char(c),
+        });
+    }
+    let lower = name.to_ascii_lowercase();
+    if let Some((_, code)) = NAMED_KEYS.iter().find(|(key, _)| *key == lower) {
+        return Some(*code);
+    }
+    lower
+        .strip_prefix('f')
+        .and_then(|number| number.parse::<u8>().ok())
+        .filter(|number| (1..=24).contains(number))
+        .map(KeyCode::F)
+}
+
+impl fmt::Display for KeySpec {
+    /// Short form used in the help overlay, like `C-n`, `S-Tab`, `PgUp`, or `↓`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("C-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("A-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("S-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("S-Tab"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Insert => f.write_str("Ins"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(spec: &str) -> KeySpec {
+        spec.parse().unwrap()
+    }
+
+    fn spec(code: KeyCode, modifiers: KeyModifiers) -> KeySpec {
+        KeySpec { code, modifiers }
+    }
+
+    #[test]
+    fn test_parse_characters_keep_their_case() {
+        assert_eq!(key("g"), spec(KeyCode::Char('g'), KeyModifiers::NONE));
+        assert_eq!(key("G"), spec(KeyCode::Char('G'), KeyModifiers::NONE));
+        assert_eq!(key("shift+g"), key("G"));
+        assert_eq!(key("["), spec(KeyCode::Char('['), KeyModifiers::NONE));
+        assert_eq!(key(" j "), key("j"));
+    }
+
+    #[test]
+    fn test_parse_plus_and_minus_keys() {
+        assert_eq!(key("+"), spec(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!(key("-"), spec(KeyCode::Char('-'), KeyModifiers::NONE));
+        assert_eq!(key("plus"), key("+"));
+        assert_eq!(
+            key("ctrl++"),
+            spec(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(key("alt+-"), spec(KeyCode::Char('-'), KeyModifiers::ALT));
+        // `c-` is only a prefix when a key follows it
+        assert!("c-".parse::<KeySpec>().is_err());
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        assert_eq!(
+            key("ctrl+n"),
+            spec(KeyCode::Char('n'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(key("control+n"), key("ctrl+n"));
+        assert_eq!(key("C-n"), key("ctrl+n"));
+        assert_eq!(key("alt+x"), spec(KeyCode::Char('x'), KeyModifiers::ALT));
+        assert_eq!(key("meta+x"), key("alt+x"));
+        assert_eq!(
+            key("ctrl+alt+down"),
+            spec(KeyCode::Down, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(key("shift+up"), spec(KeyCode::Up, KeyModifiers::SHIFT));
+    }
+
+    #[test]
+    fn test_parse_is_case_insensitive_for_names() {
+        assert_eq!(key("CTRL+n"), key("ctrl+n"));
+        assert_eq!(key("Ctrl+N"), key("ctrl+n"));
+        assert_eq!(key("SPACE"), spec(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(key("PageDown"), spec(KeyCode::PageDown, KeyModifiers::NONE));
+        assert_eq!(key("pgdn"), key("PAGEDOWN"));
+        assert_eq!(key("Escape"), key("esc"));
+        assert_eq!(key("RETURN"), key("enter"));
+    }
+
+    #[test]
+    fn test_parse_function_keys() {
+        assert_eq!(key("F5"), spec(KeyCode::F(5), KeyModifiers::NONE));
+        assert_eq!(key("f12"), spec(KeyCode::F(12), KeyModifiers::NONE));
+        assert_eq!(key("shift+F1"), spec(KeyCode::F(1), KeyModifiers::SHIFT));
+        assert!("f0".parse::<KeySpec>().is_err());
+        assert!("f25".parse::<KeySpec>().is_err());
+        // A lone `f` is the letter
+        assert_eq!(key("f"), spec(KeyCode::Char('f'), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_parse_shift_tab_is_backtab() {
+        let backtab = spec(KeyCode::BackTab, KeyModifiers::NONE);
+        assert_eq!(key("shift+tab"), backtab);
+        assert_eq!(key("S-Tab"), backtab);
+        assert_eq!(key("backtab"), backtab);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys() {
+        for input in ["", "  ", "hyper+x", "ctrl+", "pagesideways", "ctrl+ab"] {
+            let err = input.parse::<KeySpec>().unwrap_err();
+            assert!(
+                err.contains("empty key") || err.contains("unknown key"),
+                "{input}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_event_matches_parsed_spec() {
+        // Terminals report Shift on uppercase letters and on BackTab
+        let upper = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeySpec::from_event(upper), key("G"));
+        let backtab = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(KeySpec::from_event(backtab), key("shift+tab"));
+        let ctrl = KeyEvent::new(
+            KeyCode::Char('N'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        );
+        assert_eq!(KeySpec::from_event(ctrl), key("ctrl+n"));
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for (input, shown) in [
+            ("ctrl+n", "C-n"),
+            ("shift+tab", "S-Tab"),
+            ("pageup", "PgUp"),
+            ("down", "↓"),
+            ("space", "Space"),
+            ("alt+F5", "A-F5"),
+            ("G", "G"),
+        ] {
+            let parsed = key(input);
+            assert_eq!(parsed.to_string(), shown);
+            assert_eq!(key(shown), parsed);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Keys rebound with `defaults.keybindings`
+
+use crate::cli::args::Period;
+use crate::tui::key_spec::KeySpec;
+use crate::tui::mvu::action::Action;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+
+/// Action names accepted in `defaults.keybindings`
+///
+/// The `inspect_*` and `exit_inspect` actions apply in inspect mode, all
+/// others in the normal view.
+pub const ACTION_NAMES: [&str; 32] = [
+    "quit",
+    "next_chart",
+    "prev_chart",
+    "next_panel",
+    "prev_panel",
+    "scroll_up",
+    "scroll_down",
+    "page_up",
+    "page_down",
+    "scroll_to_oldest",
+    "scroll_to_newest",
+    "pan_left",
+    "pan_right",
+    "next_repo",
+    "prev_repo",
+    "toggle_metric_view",
+    "toggle_help",
+    "toggle_summary",
+    "toggle_activity_weight",
+    "toggle_commits_by_repo",
+    "enter_inspect",
+    "refresh",
+    "export",
+    "period_daily",
+    "period_weekly",
+    "period_monthly",
+    "period_yearly",
+    "grow_range",
+    "shrink_range",
+    "inspect_prev",
+    "inspect_next",
+    "exit_inspect",
+];
+
+/// Where a bound action applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    Inspect,
+}
+
+/// Action and mode for a name in [`ACTION_NAMES`]
+fn named_action(name: &str) -> Option<(Mode, Action)> {
+    let action = match name {
+        "quit" => Action::Quit,
+        "next_chart" => Action::NextChart,
+        "prev_chart" => Action::PrevChart,
+        "next_panel" => Action::NextPanel,
+        "prev_panel" => Action::PrevPanel,
+        "scroll_up" => Action::ScrollUp,
+        "scroll_down" => Action::ScrollDown,
+        "page_up" => Action::PageUp,
+        "page_down" => Action::PageDown,
+        "scroll_to_oldest" => Action::ScrollToOldest,
+        "scroll_to_newest" => Action::ScrollToNewest,
+        "pan_left" => Action::PanLeft,
+        "pan_right" => Action::PanRight,
+        "next_repo" => Action::NextRepo,
+        "prev_repo" => Action::PrevRepo,
+        "toggle_metric_view" => Action::ToggleMetricView,
+        "toggle_help" => Action::ToggleHelp,
+        "toggle_summary" => Action::ToggleSummary,
+        "toggle_activity_weight" => Action::ToggleActivityWeight,
+        "toggle_commits_by_repo" => Action::ToggleCommitsByRepo,
+        "enter_inspect" => Action::EnterInspect,
+        "refresh" => Action::Refresh,
+        "export" => Action::Export,
+        "period_daily" => Action::SetPeriod(Period::Daily),
+        "period_weekly" => Action::SetPeriod(Period::Weekly),
+        "period_monthly" => Action::SetPeriod(Period::Monthly),
+        "period_yearly" => Action::SetPeriod(Period::Yearly),
+        "grow_range" => Action::GrowRange,
+        "shrink_range" => Action::ShrinkRange,
+        "inspect_prev" => return Some((Mode::Inspect, Action::MoveSelection(-1))),
+        "inspect_next" => return Some((Mode::Inspect, Action::MoveSelection(1))),
+        "exit_inspect" => return Some((Mode::Inspect, Action::ExitInspect)),
+        _ => return None,
+    };
+    Some((Mode::Normal, action))
+}
+
+/// Keys from the config, looked up before the built-in ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keymap {
+    normal: HashMap<KeySpec, Action>,
+    inspect: HashMap<KeySpec, Action>,
+    /// Keys per action name, in config order, for the help overlay
+    keys: BTreeMap<String, Vec<KeySpec>>,
+}
+
+impl Keymap {
+    /// Build the lookup from `defaults.keybindings`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown action name (listing the valid ones),
+    /// an unparseable key, Ctrl+C (which always force-quits), or a key bound
+    /// to two actions of the same mode.
+    pub fn from_config(bindings: &BTreeMap<String, Vec<String>>) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        for (name, specs) in bindings {
+            let (mode, action) = named_action(name).ok_or_else(|| {
+                format!(
+                    "unknown action '{name}' in defaults.keybindings; valid actions: {}",
+                    ACTION_NAMES.join(", ")
+                )
+            })?;
+            for spec in specs {
+                let key = spec.parse::<KeySpec>().map_err(|message| {
+                    format!("invalid key '{spec}' for defaults.keybindings.{name}: {message}")
+                })?;
+                if key == KeySpec::new(KeyCode::Char('c'), KeyModifiers::CONTROL) {
+                    return Err(format!(
+                        "defaults.keybindings.{name} cannot use ctrl+c, which always force-quits"
+                    ));
+                }
+                let bound = match mode {
+                    Mode::Normal => &mut keymap.normal,
+                    Mode::Inspect => &mut keymap.inspect,
+                };
+                if let Some(previous) = bound.insert(key, action.clone())
+                    && previous != action
+                {
+                    let previous = keymap
+                        .keys
+                        .iter()
+                        .find(|(other, keys)| {
+                            keys.contains(&key)
+                                && named_action(other)
+                                    .is_some_and(|(other_mode, _)| other_mode == mode)
+                        })
+                        .map_or("another action", |(other, _)| other.as_str());
+                    return Err(format!(
+                        "key '{spec}' is bound to both {previous} and {name} in defaults.keybindings"
+                    ));
+                }
+                let keys = keymap.keys.entry(name.clone()).or_default();
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// Configured action for a key press in the normal view
+    #[must_use]
+    pub fn normal(&self, key: KeyEvent) -> Option<Action> {
+        self.normal.get(&KeySpec::from_event(key)).cloned()
+    }
+
+    /// Configured action for a key press in inspect mode
+    #[must_use]
+    pub fn inspect(&self, key: KeyEvent) -> Option<Action> {
+        self.inspect.get(&KeySpec::from_event(key)).cloned()
+    }
+
+    /// Keys configured for the action `name`, in config order
+    #[must_use]
+    pub fn keys_for(&self, name: &str) -> &[KeySpec] {
+        self.keys.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether `key` is configured for some action of the normal view,
+    /// hiding its built-in meaning there
+    #[must_use]
+    pub fn rebinds(&self, key: KeySpec) -> bool {
+        self.normal.contains_key(&key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, keys)| {
+                (
+                    (*name).to_string(),
+                    keys.iter().map(|key| (*key).to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_every_action_name_is_bindable() {
+        for name in ACTION_NAMES {
+            assert!(named_action(name).is_some(), "{name}");
+        }
+        assert!(named_action("force_quit").is_none());
+    }
+
+    #[test]
+    fn test_from_config_maps_keys_per_mode() {
+        let keymap = Keymap::from_config(&bindings(&[
+            ("scroll_down", &["n", "ctrl+n"]),
+            ("inspect_next", &["n"]),
+        ]))
+        .unwrap();
+
+        let n = press(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(keymap.normal(n), Some(Action::ScrollDown));
+        assert_eq!(keymap.inspect(n), Some(Action::MoveSelection(1)));
+        assert_eq!(
+            keymap.normal(press(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(
+            keymap.normal(press(KeyCode::Char('j'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap
+                .keys_for("scroll_down")
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["n", "C-n"]
+        );
+        assert!(keymap.keys_for("scroll_up").is_empty());
+    }
+
+    #[test]
+    fn test_from_config_rejects_unknown_action_listing_valid_ones() {
+        let err = Keymap::from_config(&bindings(&[("scroll_sideways", &["n"])])).unwrap_err();
+        assert!(err.contains("unknown action 'scroll_sideways'"), "{err}");
+        assert!(err.contains("scroll_down, page_up"), "{err}");
+    }
+
+    #[test]
+    fn test_from_config_rejects_bad_keys() {
+        let err = Keymap::from_config(&bindings(&[("scroll_down", &["hyper+n"])])).unwrap_err();
+        assert!(
+            err.contains("invalid key 'hyper+n' for defaults.keybindings.scroll_down"),
+            "{err}"
+        );
+
+        let err = Keymap::from_config(&bindings(&[("quit", &["Ctrl+C"])])).unwrap_err();
+        assert!(err.contains("always force-quits"), "{err}");
+    }
+
+    #[test]
+    fn test_from_config_rejects_a_key_for_two_actions() {
+        let err = Keymap::from_config(&bindings(&[
+            ("scroll_down", &["n"]),
+            ("scroll_up", &["N", "n"]),
+        ]))
+        .unwrap_err();
+        assert!(
+            err.contains("key 'n' is bound to both scroll_down and scroll_up"),
+            "{err}"
+        );
+
+        // The same key twice for one action is harmless
+        assert!(Keymap::from_config(&bindings(&[("quit", &["x", "X", "x"])])).is_ok());
+    }
+}
@@ -0,0 +1,236 @@
+//! Background loading of the data the TUI opens with
+//!
+//! The TUI starts on a loading screen while a [`Loader`] collects commits on
+//! its own thread. Progress and the final result come back over a channel
+//! that the event loop drains between frames, so keys (`q` to cancel) keep
+//! working during a long collection.
+
+use crate::tui::app::RefreshOutcome;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Collects the initial data, reporting progress through the context
+pub type Loader = Box<dyn FnOnce(&LoadContext) -> RefreshOutcome + Send>;
+
+/// Message from the loader thread
+#[derive(Debug)]
+pub enum LoadEvent {
+    /// Collection from `total` repositories started
+    Started { total: usize },
+    /// A repository finished with `commits` commits in range
+    RepoLoaded { repo: String, commits: usize },
+    /// All commits are in and statistics are being computed
+    Summarizing,
+    /// A note for the user once the TUI has closed, like a skipped repository
+    Notice(String),
+}
+
+/// Handed to a [`Loader`] to report progress and notice cancellation
+pub struct LoadContext {
+    sender: mpsc::Sender<Message>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LoadContext {
+    /// Report progress to the loading screen
+    pub fn send(&self, event: LoadEvent) {
+        // The receiver is gone once the TUI quit; the result no longer matters then
+        let _ = self.sender.send(Message::Event(event));
+    }
+
+    /// Whether the user quit, so the loader should stop as soon as it can
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress shown on the loading screen
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// Repositories being collected (0 until collection starts)
+    pub total: usize,
+    /// Repositories finished so far
+    pub loaded: usize,
+    /// Commits found in the finished repositories
+    pub commits: usize,
+    /// The repository that finished last
+    pub last_repo: Option<String>,
+    /// Commits are collected and statistics are being computed
+    pub summarizing: bool,
+}
+
+impl LoadProgress {
+    /// Fraction of repositories finished, from 0.0 to 1.0
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        if self.summarizing {
+            return 1.0;
+        }
+        if self.total == 0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = self.loaded as f64 / self.total as f64;
+        ratio.min(1.0)
+    }
+
+    fn apply(&mut self, event: LoadEvent, notices: &mut Vec<String>) {
+        match event {
+            LoadEvent::Started { total } => self.total = total,
+            LoadEvent::RepoLoaded { repo, commits } => {
+                self.loaded += 1;
+                self.commits += commits;
+                self.last_repo = Some(repo);
+            }
+            LoadEvent::Summarizing => self.summarizing = true,
+            LoadEvent::Notice(notice) => notices.push(notice),
+        }
+    }
+}
+
+/// What travels over the channel: progress, then exactly one result
+enum Message {
+    Event(LoadEvent),
+    Finished(Box<RefreshOutcome>),
+}
+
+/// A [`Loader`] running on a background thread
+///
+/// Dropping it cancels the loader, so quitting the TUI mid-load stops the
+/// collection at the next repository instead of finishing it for nothing.
+pub struct Loading {
+    receiver: mpsc::Receiver<Message>,
+    cancelled: Arc<AtomicBool>,
+    progress: LoadProgress,
+    notices: Vec<String>,
+}
+
+impl Loading {
+    /// Run `loader` on a new thread
+    #[must_use]
+    pub fn start(loader: Loader) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let context = LoadContext {
+            sender,
+            cancelled: Arc::clone(&cancelled),
+        };
+        thread::spawn(move || {
+            let outcome = loader(&context);
+            let _ = context.sender.send(Message::Finished(Box::new(outcome)));
+        });
+        Self {
+            receiver,
+            cancelled,
+            progress: LoadProgress::default(),
+            notices: Vec::new(),
+        }
+    }
+
+    /// Apply the progress received so far, returning the result once it arrived
+    pub fn poll(&mut self) -> Option<RefreshOutcome> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Message::Event(event)) => self.progress.apply(event, &mut self.notices),
+                Ok(Message::Finished(outcome)) => return Some(*outcome),
+                Err(mpsc::TryRecvError::Empty) => return None,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    return Some(Err(std::io::Error::other(
+                        "loading thread exited unexpectedly",
+                    )
+                    .into()));
+                }
+            }
+        }
+    }
+
+    /// Progress received so far
+    #[must_use]
+    pub const fn progress(&self) -> &LoadProgress {
+        &self.progress
+    }
+
+    /// Notices the loader sent, to print after the TUI closed
+    pub fn take_notices(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.notices)
+    }
+}
+
+impl Drop for Loading {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::time::Duration;
+
+    /// Poll until the loader's result arrives
+    fn wait(loading: &mut Loading) -> RefreshOutcome {
+        for _ in 0..500 {
+            if let Some(outcome) = loading.poll() {
+                return outcome;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("loader did not finish");
+    }
+
+    #[test]
+    fn test_loading_tracks_progress_and_notices() {
+        let mut loading = Loading::start(Box::new(|context| {
+            context.send(LoadEvent::Started { total: 2 });
+            context.send(LoadEvent::RepoLoaded {
+                repo: "kodo".to_string(),
+                commits: 12,
+            });
+            context.send(LoadEvent::Notice("Warning: skipped docs".to_string()));
+            context.send(LoadEvent::RepoLoaded {
+                repo: "web".to_string(),
+                commits: 3,
+            });
+            Err(Error::Cancelled)
+        }));
+
+        assert!(matches!(wait(&mut loading), Err(Error::Cancelled)));
+        let progress = loading.progress();
+        assert_eq!(progress.total, 2);
+        assert_eq!(progress.loaded, 2);
+        assert_eq!(progress.commits, 15);
+        assert_eq!(progress.last_repo.as_deref(), Some("web"));
+        assert_eq!(loading.take_notices(), ["Warning: skipped docs"]);
+        assert!(loading.take_notices().is_empty());
+    }
+
+    #[test]
+    fn test_dropping_loading_cancels_the_loader() {
+        let (stopped_sender, stopped) = mpsc::channel();
+        let loading = Loading::start(Box::new(move |context| {
+            while !context.is_cancelled() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            stopped_sender.send(()).unwrap();
+            Err(Error::Cancelled)
+        }));
+
+        drop(loading);
+        assert!(stopped.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn test_load_progress_ratio() {
+        let mut progress = LoadProgress::default();
+        assert!(progress.ratio().abs() < f64::EPSILON);
+        progress.total = 4;
+        progress.loaded = 1;
+        assert!((progress.ratio() - 0.25).abs() < f64::EPSILON);
+        progress.summarizing = true;
+        assert!((progress.ratio() - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -3,9 +3,19 @@
 pub mod app;
 pub mod chart_type;
 pub mod event;
+pub mod export;
+pub mod key_spec;
+pub mod keymap;
+pub mod loader;
 pub mod mvu;
+pub mod panel;
+pub mod terminal;
+pub mod theme;
 pub mod ui;
 pub mod widgets;
 
-pub use app::{App, Metric};
+pub use app::{App, Metric, Refresher, RepoView};
 pub use chart_type::ChartType;
+pub use keymap::Keymap;
+pub use panel::FocusedPanel;
+pub use theme::Theme;
@@ -1,30 +1,184 @@
+use crate::cli::args::Period;
+use crate::tui::keymap::Keymap;
+use crate::tui::mvu::model::StatusLevel;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 /// User intent represented as messages for the update function.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     Quit,
     ForceQuit,
+    /// Esc: drop a pending count, or quit when none is pending
+    Escape,
     NextChart,
     PrevChart,
+    /// Focus the next split-mode panel
+    NextPanel,
+    /// Focus the previous split-mode panel
+    PrevPanel,
     ScrollUp,
     ScrollDown,
+    /// Scroll the Add/Del chart one page towards older periods
+    PageUp,
+    /// Scroll the Add/Del chart one page towards newer periods
+    PageDown,
+    /// Scroll the Add/Del chart to the oldest period
+    ScrollToOldest,
+    /// Scroll the Add/Del chart back to the newest period
+    ScrollToNewest,
+    /// Pan the line chart window towards older periods
+    PanLeft,
+    /// Pan the line chart window towards newer periods
+    PanRight,
     ToggleMetricView,
+    ToggleHelp,
+    /// Show or hide the summary cards in split mode
+    ToggleSummary,
+    /// Switch the weekday/hour charts between commit counts and changed lines
+    ToggleActivityWeight,
+    /// Switch the Commits line chart between the combined line and one line per repository
+    ToggleCommitsByRepo,
+    /// Start highlighting one period, weekday, or hour of the current chart
+    EnterInspect,
+    /// Move the inspect highlight by this many elements, negative towards the start
+    MoveSelection(isize),
+    /// Leave inspect mode, restoring the usual keys
+    ExitInspect,
+    /// Show all repositories (0) or the n-th repository on its own
+    SelectRepo(usize),
+    /// A digit key: a repository tab when there are tabs, otherwise part of
+    /// a count repeating the next motion
+    Digit(u8),
+    /// Switch to the next repository tab
+    NextRepo,
+    /// Switch to the previous repository tab
+    PrevRepo,
+    /// Re-collect data in the background
+    Refresh,
+    /// Write the data behind the current view to a file
+    Export,
+    /// Re-aggregate the collected data by another period
+    SetPeriod(Period),
+    /// The stats were re-aggregated into `data_len` periods
+    Reaggregated {
+        data_len: usize,
+    },
+    /// Double the analysis range and re-collect
+    GrowRange,
+    /// Halve the analysis range and re-collect
+    ShrinkRange,
+    /// A background refresh finished; the data now has `data_len` periods over
+    /// `days` days and `repo_count` repository tabs, or kept the old data and
+    /// failed with `error`
+    Refreshed {
+        data_len: usize,
+        repo_count: usize,
+        days: u32,
+        error: Option<String>,
+    },
+    /// The initial data finished loading, with the same counts as [`Action::Refreshed`]
+    Loaded {
+        data_len: usize,
+        repo_count: usize,
+        days: u32,
+    },
+    /// The initial data could not be loaded; the app quits with the error
+    LoadFailed,
+    /// The terminal was resized; `visible_periods` fit in a line chart and
+    /// `page_rows` in the Add/Del chart
+    Resized {
+        visible_periods: usize,
+        page_rows: usize,
+    },
+    /// Queue a transient message, shown once the ones before it expire
+    ShowStatus {
+        text: String,
+        level: StatusLevel,
+    },
     Tick,
     Noop,
 }
 
 impl Action {
+    /// Map a key press, which depends on the view for a few keys
+    ///
+    /// Keys configured in `keymap` come first. Of the built-in keys, Tab
+    /// switches charts in single mode and moves the panel focus in split mode.
     #[must_use]
-    pub fn from_key(key: KeyEvent) -> Self {
+    pub fn from_key(key: KeyEvent, single_metric: bool, keymap: &Keymap) -> Self {
+        if let Some(action) = keymap.normal(key) {
+            return action;
+        }
         match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => Self::Quit,
+            KeyCode::Tab if !single_metric => Self::NextPanel,
+            KeyCode::BackTab if !single_metric => Self::PrevPanel,
+            KeyCode::Char('q') => Self::Quit,
+            KeyCode::Esc => Self::Escape,
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Self::ForceQuit,
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => Self::PageUp,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => Self::PageDown,
+            KeyCode::PageUp => Self::PageUp,
+            KeyCode::PageDown => Self::PageDown,
+            KeyCode::Char('g') => Self::ScrollToOldest,
+            KeyCode::Char('G') => Self::ScrollToNewest,
             KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => Self::NextChart,
             KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => Self::PrevChart,
             KeyCode::Up | KeyCode::Char('k') => Self::ScrollUp,
             KeyCode::Down | KeyCode::Char('j') => Self::ScrollDown,
+            KeyCode::Char('[') => Self::PanLeft,
+            KeyCode::Char(']') => Self::PanRight,
+            KeyCode::Char(c @ '0'..='9') => Self::Digit(c as u8 - b'0'),
+            KeyCode::Char('}') => Self::NextRepo,
+            KeyCode::Char('{') => Self::PrevRepo,
             KeyCode::Char('m') => Self::ToggleMetricView,
+            KeyCode::Char('?') => Self::ToggleHelp,
+            KeyCode::Char('s') => Self::ToggleSummary,
+            KeyCode::Char('W') => Self::ToggleActivityWeight,
+            KeyCode::Char('p') => Self::ToggleCommitsByRepo,
+            KeyCode::Char('i') | KeyCode::Enter => Self::EnterInspect,
+            KeyCode::Char('r') => Self::Refresh,
+            KeyCode::Char('e') => Self::Export,
+            KeyCode::Char('d') => Self::SetPeriod(Period::Daily),
+            KeyCode::Char('w') => Self::SetPeriod(Period::Weekly),
+            KeyCode::Char('M') => Self::SetPeriod(Period::Monthly),
+            KeyCode::Char('y') => Self::SetPeriod(Period::Yearly),
+            KeyCode::Char('+' | '=') => Self::GrowRange,
+            KeyCode::Char('-') => Self::ShrinkRange,
+            _ => Self::Noop,
+        }
+    }
+
+    /// Whether a count typed before this action repeats it, like `10k`
+    #[must_use]
+    pub const fn is_motion(&self) -> bool {
+        matches!(
+            self,
+            Self::NextChart
+                | Self::PrevChart
+                | Self::NextPanel
+                | Self::PrevPanel
+                | Self::ScrollUp
+                | Self::ScrollDown
+                | Self::PageUp
+                | Self::PageDown
+                | Self::PanLeft
+                | Self::PanRight
+        )
+    }
+
+    /// Map a key press in inspect mode, where only moving and leaving work
+    ///
+    /// Keys configured in `keymap` come first, like in [`Action::from_key`].
+    #[must_use]
+    pub fn from_inspect_key(key: KeyEvent, keymap: &Keymap) -> Self {
+        if let Some(action) = keymap.inspect(key) {
+            return action;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Self::ForceQuit,
+            KeyCode::Left | KeyCode::Up | KeyCode::Char('h' | 'k') => Self::MoveSelection(-1),
+            KeyCode::Right | KeyCode::Down | KeyCode::Char('l' | 'j') => Self::MoveSelection(1),
+            KeyCode::Esc | KeyCode::Char('i' | 'q') | KeyCode::Enter => Self::ExitInspect,
             _ => Self::Noop,
         }
     }
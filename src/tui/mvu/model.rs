@@ -1,22 +1,178 @@
+use crate::cli::args::Period;
+use crate::stats::TIME_OF_DAY_BUCKETS;
 use crate::tui::chart_type::ChartType;
+use crate::tui::panel::FocusedPanel;
+use std::collections::VecDeque;
 
 /// UI state for MVU update function.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// Independent UI flags; not a state machine
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Model {
     pub chart_type: ChartType,
     pub should_quit: bool,
     pub single_metric: bool,
+    /// Split-mode panel the scroll keys act on
+    pub focused_panel: FocusedPanel,
     pub scroll_offset: usize,
+    /// Periods the Commits/Files line charts are panned back from the newest
+    pub window_offset: usize,
+    /// Periods that fit in a single-mode line chart at the current width
+    pub visible_periods: usize,
+    /// Add/Del rows visible at the current terminal size, scrolled by a page
+    pub page_rows: usize,
     pub data_len: usize,
+    /// Aggregation period of the displayed stats
+    pub period: Period,
+    /// Length of the analysis range in days, changed with `+` / `-`
+    pub days: u32,
+    /// Selected repository tab: 0 for all repositories, `n` for the n-th one
+    pub active_repo: usize,
+    /// Number of per-repository tabs (0 when a single repository is analyzed)
+    pub repo_count: usize,
+    pub show_help: bool,
+    /// Summary cards above the split-view charts, toggled with `s`
+    pub show_summary: bool,
+    /// Weekday/hour charts count changed lines instead of commits, toggled with `W`
+    pub activity_by_lines: bool,
+    /// The Commits line chart draws one line per repository, toggled with `p`
+    pub commits_by_repo: bool,
+    /// Element highlighted in inspect mode, an index into [`Model::inspect_target`];
+    /// `None` outside inspect mode
+    pub selected_index: Option<usize>,
+    pub refreshing: bool,
+    /// Error message from the last failed refresh, cleared when the next one starts
+    pub refresh_error: Option<String>,
+    /// The initial data is still loading in the background; only quitting works
+    pub loading: bool,
+    /// Ticks counted while status messages are on screen, their expiry clock
+    pub tick: u64,
+    /// Ticks a status message stays on screen, about three seconds at the tick rate
+    pub status_ticks: u64,
+    /// Status messages still to show, the one on screen first
+    pub statuses: VecDeque<StatusMessage>,
+    /// Count typed so far for the next motion, like the `10` of `10k`
+    pub pending_count: Option<usize>,
 }
 
 impl Model {
     #[must_use]
-    pub fn can_scroll(self) -> bool {
+    pub fn can_scroll(&self) -> bool {
         if self.single_metric {
             matches!(self.chart_type, ChartType::AddDel)
         } else {
-            true
+            matches!(self.focused_panel, FocusedPanel::AddDel)
+        }
+    }
+
+    /// Whether the current view is a line chart that can be panned
+    #[must_use]
+    pub fn can_pan(&self) -> bool {
+        self.single_metric
+            && matches!(
+                self.chart_type,
+                ChartType::Commits | ChartType::FilesChanged | ChartType::NetLines
+            )
+    }
+
+    /// What inspect mode steps through in the current view, if it has anything to inspect
+    ///
+    /// The split-mode Hour panel inspects hours even when it is drawn as a
+    /// time-of-day chart.
+    #[must_use]
+    pub fn inspect_target(&self) -> Option<InspectTarget> {
+        if self.single_metric {
+            match self.chart_type {
+                ChartType::Commits
+                | ChartType::FilesChanged
+                | ChartType::AddDel
+                | ChartType::NetLines => Some(InspectTarget::Periods),
+                ChartType::Weekday => Some(InspectTarget::Weekday),
+                ChartType::Hour => Some(InspectTarget::Hour),
+                ChartType::TimeOfDay => Some(InspectTarget::TimeOfDay),
+                ChartType::Heatmap
+                | ChartType::Cumulative
+                | ChartType::TopFiles
+                | ChartType::Languages => None,
+            }
+        } else {
+            Some(match self.focused_panel {
+                FocusedPanel::Commits | FocusedPanel::FilesChanged | FocusedPanel::AddDel => {
+                    InspectTarget::Periods
+                }
+                FocusedPanel::Weekday => InspectTarget::Weekday,
+                FocusedPanel::Hour => InspectTarget::Hour,
+            })
         }
     }
+
+    /// What inspect mode highlights in the current view and the index of the
+    /// highlighted element, while inspecting
+    #[must_use]
+    pub fn selection(&self) -> Option<(InspectTarget, usize)> {
+        self.inspect_target().zip(self.selected_index)
+    }
+
+    /// Highlighted period while inspecting a period chart
+    #[must_use]
+    pub fn selected_period(&self) -> Option<usize> {
+        match self.selection() {
+            Some((InspectTarget::Periods, index)) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Number of elements inspect mode can select in the current view
+    #[must_use]
+    pub fn inspect_len(&self) -> usize {
+        match self.inspect_target() {
+            Some(InspectTarget::Periods) => self.data_len,
+            Some(InspectTarget::Weekday) => 7,
+            Some(InspectTarget::Hour) => 24,
+            Some(InspectTarget::TimeOfDay) => TIME_OF_DAY_BUCKETS.len(),
+            None => 0,
+        }
+    }
+
+    /// Status message on screen, if any
+    #[must_use]
+    pub fn status(&self) -> Option<&StatusMessage> {
+        self.statuses.front()
+    }
+
+    /// Largest window offset that still fills the visible window
+    #[must_use]
+    pub fn max_window_offset(&self) -> usize {
+        self.data_len.saturating_sub(self.visible_periods.max(1))
+    }
+}
+
+/// Elements inspect mode steps through, depending on the chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectTarget {
+    /// Periods of the line charts and the Add/Del chart
+    Periods,
+    /// Days of the week, Monday first
+    Weekday,
+    /// Hours of the day
+    Hour,
+    /// Parts of the day ([`TIME_OF_DAY_BUCKETS`])
+    TimeOfDay,
+}
+
+/// How a status message is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+/// A transient message shown over the bottom of the charts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    pub text: String,
+    pub level: StatusLevel,
+    /// [`Model::tick`] at which the message leaves the screen
+    pub expires_at_tick: u64,
 }
@@ -1,56 +1,461 @@
+use crate::tui::event::DEFAULT_TICK_MS;
 use crate::tui::mvu::action::Action;
-use crate::tui::mvu::model::Model;
+use crate::tui::mvu::model::{InspectTarget, Model, StatusLevel, StatusMessage};
+use std::num::NonZeroU64;
+
+/// Milliseconds a status message stays on screen
+pub const STATUS_MS: u64 = 3000;
+
+/// Ticks a status message stays on screen at the default tick rate
+pub const STATUS_TICKS: u64 = status_ticks(DEFAULT_TICK_MS);
+
+/// Ticks a status message stays on screen when ticks come every `tick_ms`
+#[must_use]
+pub const fn status_ticks(tick_ms: NonZeroU64) -> u64 {
+    STATUS_MS.div_ceil(tick_ms.get())
+}
+
+/// Largest count a motion can be repeated by
+pub const MAX_COUNT: usize = 999;
+
+/// Longest analysis range `+` grows to (about ten years)
+pub const MAX_RANGE_DAYS: u32 = 3650;
 
 /// Pure transition function for UI state.
 #[must_use]
-pub fn update(mut model: Model, action: Action) -> Model {
+pub fn update(model: Model, action: Action) -> Model {
+    let inspected = model.inspect_target();
+    keep_selection(update_view(model, action), inspected)
+}
+
+/// Apply `action`, leaving the inspect highlight to [`keep_selection`]
+fn update_view(mut model: Model, action: Action) -> Model {
+    if update_background(&mut model, &action) || update_blocked(&mut model, &action) {
+        return model;
+    }
+
+    // A count typed before a motion repeats it; any other key drops the count
+    let count = model.pending_count.take();
+    if action.is_motion() {
+        for _ in 1..count.unwrap_or(1) {
+            model = update_view(model, action.clone());
+        }
+    }
+
     match action {
         Action::Quit | Action::ForceQuit => {
             model.should_quit = true;
         }
-        Action::NextChart => {
-            if model.single_metric {
-                model.chart_type = model.chart_type.next();
+        Action::Escape => {
+            if count.is_none() {
+                model.should_quit = true;
             }
         }
-        Action::PrevChart => {
-            if model.single_metric {
-                model.chart_type = model.chart_type.prev();
-            }
+        Action::Digit(digit) => model = type_digit(model, count, usize::from(digit)),
+        Action::NextChart | Action::PrevChart | Action::NextPanel | Action::PrevPanel => {
+            model = cycle(model, action);
         }
-        Action::ScrollUp => {
-            if model.can_scroll() && model.data_len > 0 {
-                let max_offset = model.data_len.saturating_sub(1);
-                model.scroll_offset = (model.scroll_offset + 1).min(max_offset);
+        Action::ScrollUp
+        | Action::ScrollDown
+        | Action::PageUp
+        | Action::PageDown
+        | Action::ScrollToOldest
+        | Action::ScrollToNewest => model = scroll(model, &action),
+        Action::PanLeft => {
+            if model.can_pan() {
+                model.window_offset = (model.window_offset + 1).min(model.max_window_offset());
             }
         }
-        Action::ScrollDown => {
-            if model.can_scroll() {
-                model.scroll_offset = model.scroll_offset.saturating_sub(1);
+        Action::PanRight => {
+            if model.can_pan() {
+                model.window_offset = model.window_offset.saturating_sub(1);
             }
         }
+        Action::SelectRepo(_) | Action::NextRepo | Action::PrevRepo => {
+            model = switch_repo(model, &action);
+        }
         Action::ToggleMetricView => {
             model.single_metric = !model.single_metric;
             model.scroll_offset = 0;
+            model.window_offset = 0;
+        }
+        Action::ToggleHelp => {
+            model.show_help = true;
+        }
+        Action::ToggleSummary => {
+            if !model.single_metric {
+                model.show_summary = !model.show_summary;
+            }
+        }
+        Action::ToggleActivityWeight => {
+            model.activity_by_lines = !model.activity_by_lines;
+        }
+        Action::ToggleCommitsByRepo => {
+            model.commits_by_repo = !model.commits_by_repo;
+        }
+        Action::EnterInspect => model = enter_inspect(model),
+        Action::MoveSelection(delta) => model = move_selection(model, delta),
+        Action::ExitInspect => model.selected_index = None,
+        Action::Refresh => {
+            model.refreshing = true;
+            model.refresh_error = None;
+        }
+        Action::SetPeriod(period) => {
+            if period != model.period {
+                model.period = period;
+                model.scroll_offset = 0;
+                model.window_offset = 0;
+            }
+        }
+        Action::GrowRange => {
+            let days = model.days.saturating_mul(2).min(MAX_RANGE_DAYS);
+            model = change_range(model, days);
+        }
+        Action::ShrinkRange => {
+            let days = (model.days / 2).max(1);
+            model = change_range(model, days);
+        }
+        // The app writes the file and reports back with a status message
+        Action::Export
+        | Action::Refreshed { .. }
+        | Action::Loaded { .. }
+        | Action::LoadFailed
+        | Action::Reaggregated { .. }
+        | Action::Resized { .. }
+        | Action::ShowStatus { .. }
+        | Action::Tick
+        | Action::Noop => {}
+    }
+
+    model
+}
+
+/// Switch to a repository tab by number or to the next or previous one
+fn switch_repo(model: Model, action: &Action) -> Model {
+    let tabs = model.repo_count + 1;
+    let repo = match *action {
+        Action::SelectRepo(repo) if repo < tabs => repo,
+        Action::NextRepo if tabs > 1 => (model.active_repo + 1) % tabs,
+        Action::PrevRepo if tabs > 1 => (model.active_repo + tabs - 1) % tabs,
+        _ => return model,
+    };
+    select_repo(model, repo)
+}
+
+/// Switch to repository tab `digit` when the view has tabs, otherwise add
+/// `digit` to the `count` typed so far
+///
+/// Digits keep their tab meaning wherever there are tabs to switch, so counts
+/// work in single-repository views. A leading zero starts no count.
+fn type_digit(mut model: Model, count: Option<usize>, digit: usize) -> Model {
+    if model.repo_count > 0 {
+        return switch_repo(model, &Action::SelectRepo(digit));
+    }
+    model.pending_count = match count {
+        Some(count) => Some(
+            count
+                .saturating_mul(10)
+                .saturating_add(digit)
+                .min(MAX_COUNT),
+        ),
+        None => (digit > 0).then_some(digit),
+    };
+    model
+}
+
+/// Start inspect mode on the newest period in view, or on the first weekday or hour
+fn enter_inspect(mut model: Model) -> Model {
+    let len = model.inspect_len();
+    if len == 0 {
+        return model;
+    }
+    let index = if model.inspect_target() == Some(InspectTarget::Periods) {
+        let offset = if model.can_pan() {
+            model.window_offset
+        } else if model.can_scroll() {
+            model.scroll_offset
+        } else {
+            0
+        };
+        len.saturating_sub(offset + 1)
+    } else {
+        0
+    };
+    model.selected_index = Some(index);
+    model
+}
+
+/// Move the inspect highlight by `delta`, stopping at either end
+///
+/// A single-mode line chart pans and the Add/Del chart scrolls so the
+/// highlighted period stays in view.
+fn move_selection(mut model: Model, delta: isize) -> Model {
+    let Some(index) = model.selected_index else {
+        return model;
+    };
+    let index = index
+        .saturating_add_signed(delta)
+        .min(model.inspect_len().saturating_sub(1));
+    model.selected_index = Some(index);
+    if model.can_pan() {
+        model.window_offset = offset_showing(
+            index,
+            model.data_len,
+            model.window_offset,
+            model.visible_periods,
+        );
+    } else if model.can_scroll() {
+        model.scroll_offset =
+            offset_showing(index, model.data_len, model.scroll_offset, model.page_rows);
+    }
+    model
+}
+
+/// Offset back from the newest of `len` periods that keeps `index` within a
+/// view of `window` periods, moving `offset` as little as possible
+fn offset_showing(index: usize, len: usize, offset: usize, window: usize) -> usize {
+    let end = len.saturating_sub(offset);
+    if index >= end {
+        len.saturating_sub(index + 1)
+    } else if index + window.max(1) < end {
+        len.saturating_sub(index + window.max(1))
+    } else {
+        offset
+    }
+}
+
+/// Keep the inspect highlight within the data, leaving inspect mode when the
+/// view changed from what `inspected` was or has nothing left to inspect
+fn keep_selection(mut model: Model, inspected: Option<InspectTarget>) -> Model {
+    if let Some(index) = model.selected_index {
+        let len = model.inspect_len();
+        model.selected_index =
+            (model.inspect_target() == inspected && len > 0).then(|| index.min(len - 1));
+    }
+    model
+}
+
+/// Switch the single-mode chart or move the split-mode panel focus
+///
+/// Chart actions only apply in single mode and panel actions only in split mode.
+fn cycle(mut model: Model, action: Action) -> Model {
+    match (action, model.single_metric) {
+        (Action::NextChart, true) => model.chart_type = model.chart_type.next(),
+        (Action::PrevChart, true) => model.chart_type = model.chart_type.prev(),
+        (Action::NextPanel, false) => model.focused_panel = model.focused_panel.next(),
+        (Action::PrevPanel, false) => model.focused_panel = model.focused_panel.prev(),
+        _ => {}
+    }
+    model
+}
+
+/// Handle keys while the loading screen or help overlay hides the charts
+///
+/// Returns `false` when neither is shown and the action applies normally.
+fn update_blocked(model: &mut Model, action: &Action) -> bool {
+    // Nothing is on screen to act on until the data has loaded
+    if model.loading {
+        if matches!(action, Action::Quit | Action::ForceQuit) {
+            model.should_quit = true;
+        }
+        return true;
+    }
+
+    // While the help overlay is open, any key closes it
+    if model.show_help {
+        match action {
+            Action::ForceQuit => model.should_quit = true,
+            _ => model.show_help = false,
+        }
+        return true;
+    }
+
+    false
+}
+
+/// Apply actions that arrive regardless of what the user is doing
+///
+/// Returns `false` for actions driven by user input.
+fn update_background(model: &mut Model, action: &Action) -> bool {
+    match *action {
+        Action::Refreshed {
+            data_len,
+            repo_count,
+            days,
+            ref error,
+        } => {
+            refreshed(model, data_len, repo_count, days);
+            model.refresh_error.clone_from(error);
+        }
+        Action::Loaded {
+            data_len,
+            repo_count,
+            days,
+        } => {
+            model.loading = false;
+            refreshed(model, data_len, repo_count, days);
+        }
+        Action::LoadFailed => {
+            model.loading = false;
+            model.should_quit = true;
+        }
+        // Re-aggregation follows a period switch; the offsets were already reset
+        Action::Reaggregated { data_len } => {
+            model.data_len = data_len;
+            model.scroll_offset = model.scroll_offset.min(data_len.saturating_sub(1));
+            model.window_offset = model.window_offset.min(model.max_window_offset());
+        }
+        // Resizing changes how much fits, never what the user is doing
+        Action::Resized {
+            visible_periods,
+            page_rows,
+        } => {
+            model.visible_periods = visible_periods;
+            model.page_rows = page_rows;
+            model.window_offset = model.window_offset.min(model.max_window_offset());
         }
-        Action::Tick | Action::Noop => {}
+        Action::ShowStatus { ref text, level } => show_status(model, text, level),
+        // Status messages expire on their own, whatever else is on screen
+        Action::Tick => {
+            model.tick += 1;
+            while model
+                .status()
+                .is_some_and(|status| status.expires_at_tick <= model.tick)
+            {
+                model.statuses.pop_front();
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Queue `text` to show for [`Model::status_ticks`] once the messages before it expire
+///
+/// A message repeating the last one in the queue is dropped, so a key pressed
+/// over and over does not keep its message on screen for long after.
+fn show_status(model: &mut Model, text: &str, level: StatusLevel) {
+    if model
+        .statuses
+        .back()
+        .is_some_and(|last| last.text == text && last.level == level)
+    {
+        return;
+    }
+    let start = model
+        .statuses
+        .back()
+        .map_or(model.tick, |last| last.expires_at_tick);
+    model.statuses.push_back(StatusMessage {
+        text: text.to_string(),
+        level,
+        expires_at_tick: start + model.status_ticks,
+    });
+}
+
+/// Scroll the Add/Del chart by a row, a page, or to either end
+fn scroll(model: Model, action: &Action) -> Model {
+    let page = model.page_rows.max(1);
+    let (data_len, scroll_offset) = (model.data_len, model.scroll_offset);
+    match action {
+        Action::ScrollUp => scroll_up(model, 1),
+        Action::ScrollDown => scroll_down(model, 1),
+        Action::PageUp => scroll_up(model, page),
+        Action::PageDown => scroll_down(model, page),
+        Action::ScrollToOldest => scroll_up(model, data_len),
+        Action::ScrollToNewest => scroll_down(model, scroll_offset),
+        _ => model,
+    }
+}
+
+/// Scroll the Add/Del chart `rows` periods towards older data, stopping at the oldest
+fn scroll_up(mut model: Model, rows: usize) -> Model {
+    if model.can_scroll() && model.data_len > 0 {
+        let max_offset = model.data_len.saturating_sub(1);
+        model.scroll_offset = model.scroll_offset.saturating_add(rows).min(max_offset);
+    }
+    model
+}
+
+/// Scroll the Add/Del chart `rows` periods towards newer data, stopping at the newest
+fn scroll_down(mut model: Model, rows: usize) -> Model {
+    if model.can_scroll() {
+        model.scroll_offset = model.scroll_offset.saturating_sub(rows);
+    }
+    model
+}
+
+/// Take in the shape of freshly collected data, keeping the view where possible
+fn refreshed(model: &mut Model, data_len: usize, repo_count: usize, days: u32) {
+    model.refreshing = false;
+    model.data_len = data_len;
+    // A failed refresh keeps the old data, and with it the old range
+    model.days = days;
+    model.repo_count = repo_count;
+    // A repository that disappeared falls back to the combined view
+    if model.active_repo > repo_count {
+        model.active_repo = 0;
+    }
+    // Keep the scroll position unless the data shrank below it
+    model.scroll_offset = model.scroll_offset.min(data_len.saturating_sub(1));
+    model.window_offset = model.window_offset.min(model.max_window_offset());
+}
+
+/// Re-collect over `days` days, unless that is the current range or a refresh is running
+fn change_range(mut model: Model, days: u32) -> Model {
+    if days != model.days && !model.refreshing {
+        model.days = days;
+        model.refreshing = true;
+        model.refresh_error = None;
     }
+    model
+}
 
+/// Switch the repository tab, starting the new one at the latest periods
+fn select_repo(mut model: Model, repo: usize) -> Model {
+    if repo != model.active_repo {
+        model.active_repo = repo;
+        model.scroll_offset = 0;
+        model.window_offset = 0;
+    }
     model
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::args::Period;
     use crate::tui::chart_type::ChartType;
+    use crate::tui::panel::FocusedPanel;
+    use std::collections::VecDeque;
 
     fn model() -> Model {
         Model {
             chart_type: ChartType::Commits,
             should_quit: false,
             single_metric: false,
+            focused_panel: FocusedPanel::AddDel,
             scroll_offset: 0,
+            window_offset: 0,
+            visible_periods: 5,
+            page_rows: 3,
             data_len: 5,
+            days: 7,
+            period: Period::Daily,
+            active_repo: 0,
+            repo_count: 0,
+            show_help: false,
+            show_summary: true,
+            activity_by_lines: false,
+            commits_by_repo: false,
+            selected_index: None,
+            refreshing: false,
+            refresh_error: None,
+            loading: false,
+            tick: 0,
+            status_ticks: STATUS_TICKS,
+            statuses: VecDeque::new(),
+            pending_count: None,
         }
     }
 
@@ -77,6 +482,38 @@ mod tests {
         assert_eq!(next.chart_type, ChartType::FilesChanged);
     }
 
+    #[test]
+    fn update_panel_focus_cycles_in_split_mode() {
+        let next = update(model(), Action::NextPanel);
+        assert_eq!(next.focused_panel, FocusedPanel::Weekday);
+        let wrapped = update(update(next, Action::NextPanel), Action::NextPanel);
+        assert_eq!(wrapped.focused_panel, FocusedPanel::Commits);
+        let back = update(wrapped, Action::PrevPanel);
+        assert_eq!(back.focused_panel, FocusedPanel::Hour);
+
+        let mut single = model();
+        single.single_metric = true;
+        let unchanged = update(single, Action::NextPanel);
+        assert_eq!(unchanged.focused_panel, FocusedPanel::AddDel);
+    }
+
+    #[test]
+    fn update_scroll_follows_split_focus() {
+        let scrolled = update(model(), Action::ScrollUp);
+        assert_eq!(scrolled.scroll_offset, 1);
+
+        // The line charts show the whole range in split mode, so there is nothing to scroll
+        let commits = update(update(model(), Action::PrevPanel), Action::PrevPanel);
+        assert_eq!(commits.focused_panel, FocusedPanel::Commits);
+        let unchanged = update(commits, Action::ScrollUp);
+        assert_eq!(unchanged.scroll_offset, 0);
+        assert_eq!(update(unchanged.clone(), Action::PageUp).scroll_offset, 0);
+
+        // Focus stays on its panel while scrolled, and returning to Add/Del scrolls again
+        let add_del = update(update(unchanged, Action::NextPanel), Action::NextPanel);
+        assert_eq!(update(add_del, Action::ScrollUp).scroll_offset, 1);
+    }
+
     #[test]
     fn update_prev_chart_only_in_single_mode() {
         let split = update(model(), Action::PrevChart);
@@ -85,7 +522,7 @@ mod tests {
         let mut single = model();
         single.single_metric = true;
         let next = update(single, Action::PrevChart);
-        assert_eq!(next.chart_type, ChartType::Hour);
+        assert_eq!(next.chart_type, ChartType::Languages);
     }
 
     #[test]
@@ -112,13 +549,805 @@ mod tests {
         assert_eq!(next.scroll_offset, 0);
     }
 
+    #[test]
+    fn update_page_up_scrolls_by_page_rows_up_to_oldest() {
+        let mut m = model();
+        m.data_len = 10;
+
+        let next = update(m, Action::PageUp);
+        assert_eq!(next.scroll_offset, 3);
+        let next = update(
+            update(update(next, Action::PageUp), Action::PageUp),
+            Action::PageUp,
+        );
+        assert_eq!(next.scroll_offset, 9);
+    }
+
+    #[test]
+    fn update_page_down_scrolls_by_page_rows_down_to_newest() {
+        let mut m = model();
+        m.data_len = 10;
+        m.scroll_offset = 4;
+
+        let next = update(m, Action::PageDown);
+        assert_eq!(next.scroll_offset, 1);
+        assert_eq!(update(next, Action::PageDown).scroll_offset, 0);
+    }
+
+    #[test]
+    fn update_page_larger_than_data_clamps() {
+        let mut m = model();
+        m.page_rows = 20;
+
+        assert_eq!(update(m.clone(), Action::PageUp).scroll_offset, 4);
+        m.scroll_offset = 2;
+        assert_eq!(update(m.clone(), Action::PageDown).scroll_offset, 0);
+
+        // Before the first resize a page is still at least one row
+        m.page_rows = 0;
+        assert_eq!(update(m.clone(), Action::PageUp).scroll_offset, 3);
+        assert_eq!(update(m.clone(), Action::PageDown).scroll_offset, 1);
+
+        m.data_len = 0;
+        m.scroll_offset = 0;
+        assert_eq!(update(m, Action::PageUp).scroll_offset, 0);
+    }
+
+    #[test]
+    fn update_jump_to_oldest_and_newest() {
+        let next = update(model(), Action::ScrollToOldest);
+        assert_eq!(next.scroll_offset, 4);
+        assert_eq!(update(next, Action::ScrollToNewest).scroll_offset, 0);
+
+        let mut empty = model();
+        empty.data_len = 0;
+        assert_eq!(update(empty, Action::ScrollToOldest).scroll_offset, 0);
+    }
+
+    #[test]
+    fn update_page_and_jump_ignored_when_single_mode_non_add_del() {
+        let mut m = model();
+        m.single_metric = true;
+        m.scroll_offset = 2;
+
+        for action in [
+            Action::PageUp,
+            Action::PageDown,
+            Action::ScrollToOldest,
+            Action::ScrollToNewest,
+        ] {
+            assert_eq!(update(m.clone(), action).scroll_offset, 2);
+        }
+    }
+
+    fn panning_model() -> Model {
+        let mut m = model();
+        m.single_metric = true;
+        m.data_len = 10;
+        m.visible_periods = 4;
+        m
+    }
+
+    #[test]
+    fn update_pan_left_stops_at_oldest_full_window() {
+        let mut m = panning_model();
+        for _ in 0..10 {
+            m = update(m, Action::PanLeft);
+        }
+        assert_eq!(m.window_offset, 6);
+
+        let next = update(m, Action::PanRight);
+        assert_eq!(next.window_offset, 5);
+    }
+
+    #[test]
+    fn update_pan_right_saturates_at_newest() {
+        let next = update(panning_model(), Action::PanRight);
+        assert_eq!(next.window_offset, 0);
+    }
+
+    #[test]
+    fn update_pan_only_for_single_mode_line_charts() {
+        let mut m = panning_model();
+        m.chart_type = ChartType::FilesChanged;
+        assert_eq!(update(m.clone(), Action::PanLeft).window_offset, 1);
+        m.chart_type = ChartType::NetLines;
+        assert_eq!(update(m.clone(), Action::PanLeft).window_offset, 1);
+
+        for chart_type in [ChartType::AddDel, ChartType::Cumulative, ChartType::Heatmap] {
+            m.chart_type = chart_type;
+            assert_eq!(update(m.clone(), Action::PanLeft).window_offset, 0);
+        }
+
+        let mut split = panning_model();
+        split.single_metric = false;
+        assert_eq!(update(split, Action::PanLeft).window_offset, 0);
+    }
+
+    #[test]
+    fn update_pan_ignored_when_all_periods_fit() {
+        let mut m = panning_model();
+        m.visible_periods = 20;
+        assert_eq!(update(m, Action::PanLeft).window_offset, 0);
+    }
+
+    #[test]
+    fn update_resized_clamps_window_offset() {
+        let mut m = panning_model();
+        m.window_offset = 6;
+        m.show_help = true;
+
+        let next = update(
+            m,
+            Action::Resized {
+                visible_periods: 8,
+                page_rows: 4,
+            },
+        );
+        assert_eq!(next.visible_periods, 8);
+        assert_eq!(next.page_rows, 4);
+        assert_eq!(next.window_offset, 2);
+        assert!(next.show_help);
+    }
+
+    #[test]
+    fn update_refreshed_clamps_window_offset() {
+        let mut m = panning_model();
+        m.window_offset = 6;
+
+        let next = update(
+            m,
+            Action::Refreshed {
+                data_len: 7,
+                repo_count: 0,
+                days: 7,
+                error: None,
+            },
+        );
+        assert_eq!(next.window_offset, 3);
+    }
+
+    fn multi_repo_model() -> Model {
+        let mut m = model();
+        m.repo_count = 2;
+        m
+    }
+
+    #[test]
+    fn update_select_repo_resets_offsets() {
+        let mut m = multi_repo_model();
+        m.scroll_offset = 3;
+        m.window_offset = 2;
+
+        let next = update(m, Action::SelectRepo(2));
+        assert_eq!(next.active_repo, 2);
+        assert_eq!(next.scroll_offset, 0);
+        assert_eq!(next.window_offset, 0);
+
+        // Reselecting the active tab keeps the position
+        let mut same = next;
+        same.scroll_offset = 1;
+        assert_eq!(update(same, Action::SelectRepo(2)).scroll_offset, 1);
+    }
+
+    #[test]
+    fn update_select_repo_ignores_missing_tabs() {
+        assert_eq!(
+            update(multi_repo_model(), Action::SelectRepo(3)).active_repo,
+            0
+        );
+        // A single repository has no tabs to switch to
+        assert_eq!(update(model(), Action::SelectRepo(1)).active_repo, 0);
+        assert_eq!(update(model(), Action::NextRepo).active_repo, 0);
+    }
+
+    #[test]
+    fn update_next_and_prev_repo_wrap_around() {
+        let m = multi_repo_model();
+        let next = update(m.clone(), Action::NextRepo);
+        assert_eq!(next.active_repo, 1);
+        let next = update(update(next, Action::NextRepo), Action::NextRepo);
+        assert_eq!(next.active_repo, 0);
+
+        assert_eq!(update(m, Action::PrevRepo).active_repo, 2);
+    }
+
+    #[test]
+    fn update_refreshed_falls_back_to_all_when_repo_disappears() {
+        let mut m = multi_repo_model();
+        m.active_repo = 2;
+
+        let next = update(
+            m,
+            Action::Refreshed {
+                data_len: 5,
+                repo_count: 1,
+                days: 7,
+                error: None,
+            },
+        );
+        assert_eq!(next.repo_count, 1);
+        assert_eq!(next.active_repo, 0);
+    }
+
     #[test]
     fn update_toggle_metric_view_resets_scroll_offset() {
         let mut m = model();
         m.scroll_offset = 3;
+        m.window_offset = 2;
 
         let next = update(m, Action::ToggleMetricView);
         assert!(next.single_metric);
         assert_eq!(next.scroll_offset, 0);
+        assert_eq!(next.window_offset, 0);
+    }
+
+    #[test]
+    fn update_toggle_help_opens_overlay() {
+        let next = update(model(), Action::ToggleHelp);
+        assert!(next.show_help);
+    }
+
+    #[test]
+    fn update_any_key_closes_help_without_acting() {
+        let mut m = model();
+        m.show_help = true;
+        m.single_metric = true;
+
+        for action in [Action::Noop, Action::Quit, Action::ToggleHelp] {
+            let next = update(m.clone(), action);
+            assert!(!next.show_help);
+            assert!(!next.should_quit);
+        }
+
+        let next = update(m, Action::NextChart);
+        assert!(!next.show_help);
+        assert_eq!(next.chart_type, ChartType::Commits);
+    }
+
+    #[test]
+    fn update_toggle_summary_only_in_split_mode() {
+        let next = update(model(), Action::ToggleSummary);
+        assert!(!next.show_summary);
+        assert!(update(next, Action::ToggleSummary).show_summary);
+
+        let mut single = model();
+        single.single_metric = true;
+        assert!(update(single, Action::ToggleSummary).show_summary);
+    }
+
+    #[test]
+    fn update_toggle_activity_weight_in_both_modes() {
+        let next = update(model(), Action::ToggleActivityWeight);
+        assert!(next.activity_by_lines);
+        assert!(!update(next, Action::ToggleActivityWeight).activity_by_lines);
+
+        let mut single = model();
+        single.single_metric = true;
+        single.chart_type = ChartType::Hour;
+        let next = update(single, Action::ToggleActivityWeight);
+        assert!(next.activity_by_lines);
+        assert_eq!(next.chart_type, ChartType::Hour);
+    }
+
+    #[test]
+    fn update_toggle_commits_by_repo_keeps_view() {
+        let mut single = model();
+        single.single_metric = true;
+        single.window_offset = 3;
+        let next = update(single, Action::ToggleCommitsByRepo);
+        assert!(next.commits_by_repo);
+        assert_eq!(next.window_offset, 3);
+        assert!(!update(next, Action::ToggleCommitsByRepo).commits_by_repo);
+    }
+
+    #[test]
+    fn update_inspect_starts_on_newest_visible_period() {
+        let mut m = panning_model();
+        m.window_offset = 2;
+        let next = update(m, Action::EnterInspect);
+        assert_eq!(next.selected_index, Some(7));
+
+        // Add/Del starts on the newest row in view
+        let mut add_del = model();
+        add_del.scroll_offset = 1;
+        assert_eq!(
+            update(add_del, Action::EnterInspect).selected_index,
+            Some(3)
+        );
+
+        // Activity charts start on their first bar
+        let mut weekday = model();
+        weekday.focused_panel = FocusedPanel::Weekday;
+        assert_eq!(
+            update(weekday, Action::EnterInspect).selected_index,
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn update_inspect_needs_something_to_inspect() {
+        let mut heatmap = panning_model();
+        heatmap.chart_type = ChartType::Heatmap;
+        assert_eq!(update(heatmap, Action::EnterInspect).selected_index, None);
+
+        let mut empty = panning_model();
+        empty.data_len = 0;
+        assert_eq!(update(empty, Action::EnterInspect).selected_index, None);
+    }
+
+    #[test]
+    fn update_move_selection_clamps_and_pans_into_view() {
+        // Ten periods, the newest four visible
+        let mut m = update(panning_model(), Action::EnterInspect);
+        assert_eq!(m.selected_index, Some(9));
+        m = update(m, Action::MoveSelection(1));
+        assert_eq!(m.selected_index, Some(9));
+
+        for _ in 0..4 {
+            m = update(m, Action::MoveSelection(-1));
+        }
+        assert_eq!(m.selected_index, Some(5));
+        assert_eq!(m.window_offset, 1);
+
+        m = update(m, Action::MoveSelection(-20));
+        assert_eq!(m.selected_index, Some(0));
+        assert_eq!(m.window_offset, 6);
+
+        // Moving back right only pans once the highlight leaves the window
+        m = update(m, Action::MoveSelection(3));
+        assert_eq!(m.window_offset, 6);
+        m = update(m, Action::MoveSelection(1));
+        assert_eq!(m.selected_index, Some(4));
+        assert_eq!(m.window_offset, 5);
+    }
+
+    #[test]
+    fn update_move_selection_scrolls_add_del() {
+        // Five periods, three rows visible
+        let mut m = update(model(), Action::EnterInspect);
+        for _ in 0..3 {
+            m = update(m, Action::MoveSelection(-1));
+        }
+        assert_eq!(m.selected_index, Some(1));
+        assert_eq!(m.scroll_offset, 1);
+    }
+
+    #[test]
+    fn update_move_selection_within_activity_bars() {
+        let mut hour = model();
+        hour.focused_panel = FocusedPanel::Hour;
+        let m = update(hour, Action::EnterInspect);
+        let m = update(m, Action::MoveSelection(30));
+        assert_eq!(m.selected_index, Some(23));
+        assert_eq!(m.scroll_offset, 0);
+    }
+
+    #[test]
+    fn update_inspect_ends_when_the_view_changes() {
+        let inspecting = update(panning_model(), Action::EnterInspect);
+        assert_eq!(
+            update(inspecting.clone(), Action::ExitInspect).selected_index,
+            None
+        );
+        assert_eq!(
+            update(inspecting.clone(), Action::PrevChart).selected_index,
+            None
+        );
+
+        // Another chart over the same periods keeps the highlight
+        let files = update(inspecting.clone(), Action::NextChart);
+        assert_eq!(files.chart_type, ChartType::FilesChanged);
+        assert_eq!(files.selected_index, Some(9));
+
+        // Fewer periods after re-aggregation pull the highlight in
+        let shrunk = update(inspecting.clone(), Action::Reaggregated { data_len: 3 });
+        assert_eq!(shrunk.selected_index, Some(2));
+        let emptied = update(inspecting, Action::Reaggregated { data_len: 0 });
+        assert_eq!(emptied.selected_index, None);
+    }
+
+    #[test]
+    fn update_while_loading_only_quits() {
+        let mut loading = model();
+        loading.loading = true;
+        loading.single_metric = true;
+
+        for action in [
+            Action::NextChart,
+            Action::Refresh,
+            Action::GrowRange,
+            Action::SetPeriod(Period::Weekly),
+            Action::ToggleHelp,
+            Action::Export,
+        ] {
+            assert_eq!(
+                update(loading.clone(), action.clone()),
+                loading,
+                "{action:?}"
+            );
+        }
+        assert!(update(loading.clone(), Action::Quit).should_quit);
+        assert!(update(loading, Action::ForceQuit).should_quit);
+    }
+
+    #[test]
+    fn update_loaded_leaves_loading_state() {
+        let mut loading = model();
+        loading.loading = true;
+
+        let next = update(
+            loading.clone(),
+            Action::Loaded {
+                data_len: 30,
+                repo_count: 2,
+                days: 30,
+            },
+        );
+        assert!(!next.loading);
+        assert_eq!(next.data_len, 30);
+        assert_eq!(next.repo_count, 2);
+        assert_eq!(next.days, 30);
+
+        let failed = update(loading, Action::LoadFailed);
+        assert!(!failed.loading);
+        assert!(failed.should_quit);
+    }
+
+    #[test]
+    fn update_refresh_marks_refreshing() {
+        let next = update(model(), Action::Refresh);
+        assert!(next.refreshing);
+
+        // A second press while refreshing changes nothing
+        assert_eq!(update(next.clone(), Action::Refresh), next);
+    }
+
+    #[test]
+    fn update_refresh_error_lasts_until_the_next_refresh() {
+        let mut m = model();
+        m.refreshing = true;
+        let failed = update(
+            m,
+            Action::Refreshed {
+                data_len: 7,
+                repo_count: 0,
+                days: 7,
+                error: Some("No repositories to analyze".to_string()),
+            },
+        );
+        assert!(!failed.refreshing);
+        assert_eq!(
+            failed.refresh_error.as_deref(),
+            Some("No repositories to analyze")
+        );
+        assert_eq!(
+            update(failed.clone(), Action::Tick).refresh_error,
+            failed.refresh_error
+        );
+
+        let retry = update(failed.clone(), Action::Refresh);
+        assert!(retry.refresh_error.is_none());
+        assert!(update(failed, Action::GrowRange).refresh_error.is_none());
+    }
+
+    #[test]
+    fn update_refreshed_preserves_scroll_offset_when_data_grows() {
+        let mut m = model();
+        m.refreshing = true;
+        m.scroll_offset = 3;
+
+        let next = update(
+            m,
+            Action::Refreshed {
+                data_len: 8,
+                repo_count: 0,
+                days: 7,
+                error: None,
+            },
+        );
+        assert!(!next.refreshing);
+        assert_eq!(next.data_len, 8);
+        assert_eq!(next.scroll_offset, 3);
+    }
+
+    #[test]
+    fn update_refreshed_clamps_scroll_offset_when_data_shrinks() {
+        let mut m = model();
+        m.refreshing = true;
+        m.scroll_offset = 4;
+
+        assert_eq!(
+            update(
+                m.clone(),
+                Action::Refreshed {
+                    data_len: 2,
+                    repo_count: 0,
+                    days: 7,
+                    error: None,
+                }
+            )
+            .scroll_offset,
+            1
+        );
+        assert_eq!(
+            update(
+                m,
+                Action::Refreshed {
+                    data_len: 0,
+                    repo_count: 0,
+                    days: 7,
+                    error: None,
+                }
+            )
+            .scroll_offset,
+            0
+        );
+    }
+
+    #[test]
+    fn update_refreshed_applies_while_help_is_open() {
+        let mut m = model();
+        m.refreshing = true;
+        m.show_help = true;
+
+        let next = update(
+            m,
+            Action::Refreshed {
+                data_len: 5,
+                repo_count: 0,
+                days: 7,
+                error: None,
+            },
+        );
+        assert!(!next.refreshing);
+        assert!(next.show_help);
+    }
+
+    #[test]
+    fn update_help_stays_open_on_tick_and_force_quit_still_quits() {
+        let mut m = model();
+        m.show_help = true;
+
+        assert!(update(m.clone(), Action::Tick).show_help);
+        assert!(update(m, Action::ForceQuit).should_quit);
+    }
+
+    fn show(text: &str, level: StatusLevel) -> Action {
+        Action::ShowStatus {
+            text: text.to_string(),
+            level,
+        }
+    }
+
+    fn status_text(model: &Model) -> Option<&str> {
+        model.status().map(|status| status.text.as_str())
+    }
+
+    #[test]
+    fn update_status_shows_until_it_expires() {
+        let mut next = update(model(), show("Exported", StatusLevel::Info));
+        assert_eq!(status_text(&next), Some("Exported"));
+
+        for _ in 0..STATUS_TICKS - 1 {
+            next = update(next, Action::Tick);
+        }
+        assert_eq!(status_text(&next), Some("Exported"));
+        next = update(next, Action::Tick);
+        assert_eq!(status_text(&next), None);
+        // Extra ticks keep it cleared
+        assert!(update(next, Action::Tick).statuses.is_empty());
+    }
+
+    #[test]
+    fn status_ticks_follow_tick_rate() {
+        let ticks = |ms| status_ticks(NonZeroU64::new(ms).unwrap());
+        assert_eq!(STATUS_TICKS, 12);
+        assert_eq!(ticks(100), 30);
+        assert_eq!(ticks(700), 5);
+        // Ticks slower than the duration still show the message for one tick
+        assert_eq!(ticks(5000), 1);
+    }
+
+    #[test]
+    fn update_statuses_queue_and_show_in_turn() {
+        let mut next = update(model(), show("first", StatusLevel::Info));
+        for _ in 0..2 {
+            next = update(next, Action::Tick);
+        }
+        next = update(next, show("second", StatusLevel::Error));
+        assert_eq!(status_text(&next), Some("first"));
+
+        // The second message gets its full time once the first one is gone
+        for _ in 2..STATUS_TICKS {
+            next = update(next, Action::Tick);
+        }
+        assert_eq!(status_text(&next), Some("second"));
+        assert_eq!(next.status().unwrap().level, StatusLevel::Error);
+        for _ in 0..STATUS_TICKS - 1 {
+            next = update(next, Action::Tick);
+        }
+        assert_eq!(status_text(&next), Some("second"));
+        next = update(next, Action::Tick);
+        assert_eq!(status_text(&next), None);
+    }
+
+    #[test]
+    fn update_status_repeating_the_last_one_is_dropped() {
+        let mut next = model();
+        for _ in 0..3 {
+            next = update(next, show("Unknown key", StatusLevel::Warn));
+        }
+        assert_eq!(next.statuses.len(), 1);
+
+        // The same text at another level is a different message
+        next = update(next, show("Unknown key", StatusLevel::Error));
+        assert_eq!(next.statuses.len(), 2);
+    }
+
+    #[test]
+    fn update_status_shows_and_expires_while_help_is_open() {
+        let mut m = model();
+        m.show_help = true;
+
+        let mut next = update(m, show("Exported", StatusLevel::Info));
+        assert!(next.show_help);
+        assert_eq!(status_text(&next), Some("Exported"));
+        for _ in 0..STATUS_TICKS {
+            next = update(next, Action::Tick);
+        }
+        assert_eq!(status_text(&next), None);
+        assert!(next.show_help);
+    }
+
+    #[test]
+    fn update_export_only_closes_help_when_open() {
+        let mut m = model();
+        m.show_help = true;
+
+        let next = update(m, Action::Export);
+        assert!(!next.show_help);
+        assert!(next.statuses.is_empty());
+    }
+
+    #[test]
+    fn update_grow_range_doubles_days_up_to_cap() {
+        let next = update(model(), Action::GrowRange);
+        assert_eq!(next.days, 14);
+        assert!(next.refreshing);
+
+        let mut m = model();
+        m.days = 3000;
+        assert_eq!(update(m.clone(), Action::GrowRange).days, MAX_RANGE_DAYS);
+
+        // Already at the cap: nothing to re-collect
+        m.days = MAX_RANGE_DAYS;
+        let next = update(m, Action::GrowRange);
+        assert_eq!(next.days, MAX_RANGE_DAYS);
+        assert!(!next.refreshing);
+    }
+
+    #[test]
+    fn update_shrink_range_halves_days_down_to_one() {
+        let next = update(model(), Action::ShrinkRange);
+        assert_eq!(next.days, 3);
+        assert!(next.refreshing);
+
+        let mut m = model();
+        m.days = 1;
+        let next = update(m, Action::ShrinkRange);
+        assert_eq!(next.days, 1);
+        assert!(!next.refreshing);
+    }
+
+    #[test]
+    fn update_range_change_waits_for_running_refresh() {
+        let mut m = model();
+        m.refreshing = true;
+
+        assert_eq!(update(m.clone(), Action::GrowRange).days, 7);
+        assert_eq!(update(m, Action::ShrinkRange).days, 7);
+    }
+
+    #[test]
+    fn update_refreshed_restores_range_of_displayed_data() {
+        let mut m = update(model(), Action::GrowRange);
+        assert_eq!(m.days, 14);
+
+        // The refresh failed, so the old 7-day data is still shown
+        m = update(
+            m,
+            Action::Refreshed {
+                data_len: 5,
+                repo_count: 0,
+                days: 7,
+                error: None,
+            },
+        );
+        assert_eq!(m.days, 7);
+        assert!(!m.refreshing);
+    }
+
+    #[test]
+    fn update_set_period_resets_offsets_only_on_change() {
+        let mut m = model();
+        m.scroll_offset = 2;
+        m.window_offset = 1;
+
+        let same = update(m.clone(), Action::SetPeriod(Period::Daily));
+        assert_eq!(same.scroll_offset, 2);
+
+        let next = update(m, Action::SetPeriod(Period::Weekly));
+        assert_eq!(next.period, Period::Weekly);
+        assert_eq!(next.scroll_offset, 0);
+        assert_eq!(next.window_offset, 0);
+
+        let next = update(next, Action::Reaggregated { data_len: 1 });
+        assert_eq!(next.data_len, 1);
+        assert_eq!(next.max_window_offset(), 0);
+    }
+
+    fn type_keys(model: Model, actions: impl IntoIterator<Item = Action>) -> Model {
+        actions.into_iter().fold(model, update)
+    }
+
+    #[test]
+    fn update_count_repeats_scroll() {
+        // 12j
+        let mut m = model();
+        m.data_len = 20;
+        m.scroll_offset = 15;
+        let m = type_keys(m, [Action::Digit(1), Action::Digit(2)]);
+        assert_eq!(m.pending_count, Some(12));
+
+        let next = update(m, Action::ScrollDown);
+        assert_eq!(next.scroll_offset, 3);
+        assert_eq!(next.pending_count, None);
+    }
+
+    #[test]
+    fn update_count_repeats_tab() {
+        // 3Tab, in single mode and in split mode
+        let mut single = model();
+        single.single_metric = true;
+        let expected = type_keys(single.clone(), std::iter::repeat_n(Action::NextChart, 3));
+        let next = type_keys(single, [Action::Digit(3), Action::NextChart]);
+        assert_eq!(next.chart_type, expected.chart_type);
+        assert_ne!(next.chart_type, ChartType::Commits);
+
+        let expected = type_keys(model(), std::iter::repeat_n(Action::NextPanel, 3));
+        let next = type_keys(model(), [Action::Digit(3), Action::NextPanel]);
+        assert_eq!(next.focused_panel, expected.focused_panel);
+        assert_eq!(next.pending_count, None);
+    }
+
+    #[test]
+    fn update_escape_clears_count_before_quitting() {
+        let m = type_keys(model(), [Action::Digit(4), Action::Escape]);
+        assert_eq!(m.pending_count, None);
+        assert!(!m.should_quit);
+
+        assert!(update(m, Action::Escape).should_quit);
+    }
+
+    #[test]
+    fn update_count_dropped_by_other_keys_and_kept_by_background() {
+        let m = type_keys(model(), [Action::Digit(2), Action::Tick]);
+        assert_eq!(m.pending_count, Some(2));
+        let m = update(m, Action::ToggleSummary);
+        assert_eq!(m.pending_count, None);
+
+        // A leading zero is no count; counts stop at MAX_COUNT
+        assert_eq!(update(model(), Action::Digit(0)).pending_count, None);
+        let m = type_keys(model(), std::iter::repeat_n(Action::Digit(9), 5));
+        assert_eq!(m.pending_count, Some(MAX_COUNT));
+    }
+
+    #[test]
+    fn update_digits_switch_tabs_when_there_are_tabs() {
+        let next = update(multi_repo_model(), Action::Digit(2));
+        assert_eq!(next.active_repo, 2);
+        assert_eq!(next.pending_count, None);
+        assert_eq!(update(next, Action::Digit(5)).active_repo, 2);
     }
 }
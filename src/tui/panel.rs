@@ -0,0 +1,86 @@
+/// Split-mode panel that scroll keys act on, cycled with Tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusedPanel {
+    Commits,
+    FilesChanged,
+    /// Focused by default, so the arrow keys scroll it as before
+    #[default]
+    AddDel,
+    Weekday,
+    Hour,
+}
+
+impl FocusedPanel {
+    /// Get the next panel in the cycle.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Commits => Self::FilesChanged,
+            Self::FilesChanged => Self::AddDel,
+            Self::AddDel => Self::Weekday,
+            Self::Weekday => Self::Hour,
+            Self::Hour => Self::Commits,
+        }
+    }
+
+    /// Get the previous panel in the cycle.
+    #[must_use]
+    pub fn prev(self) -> Self {
+        match self {
+            Self::Commits => Self::Hour,
+            Self::FilesChanged => Self::Commits,
+            Self::AddDel => Self::FilesChanged,
+            Self::Weekday => Self::AddDel,
+            Self::Hour => Self::Weekday,
+        }
+    }
+
+    /// Get display name.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Commits => "Commits",
+            Self::FilesChanged => "Files Changed",
+            Self::AddDel => "Add/Del",
+            Self::Weekday => "Weekday",
+            Self::Hour => "Hour",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FocusedPanel;
+
+    #[test]
+    fn focused_panel_cycle() {
+        let expected = [
+            FocusedPanel::Weekday,
+            FocusedPanel::Hour,
+            FocusedPanel::Commits,
+            FocusedPanel::FilesChanged,
+            FocusedPanel::AddDel,
+        ];
+        let mut panel = FocusedPanel::AddDel;
+        for next in expected {
+            panel = panel.next();
+            assert_eq!(panel, next);
+        }
+    }
+
+    #[test]
+    fn focused_panel_prev_undoes_next() {
+        let mut panel = FocusedPanel::default();
+        for _ in 0..5 {
+            assert_eq!(panel.next().prev(), panel);
+            panel = panel.next();
+        }
+        assert_eq!(FocusedPanel::Commits.prev(), FocusedPanel::Hour);
+    }
+
+    #[test]
+    fn focused_panel_default() {
+        assert_eq!(FocusedPanel::default(), FocusedPanel::AddDel);
+        assert_eq!(FocusedPanel::default().name(), "Add/Del");
+    }
+}
@@ -0,0 +1,204 @@
+//! Terminal setup and teardown for the TUI
+//!
+//! Raw mode and the alternate screen (when [`ViewportMode::Fullscreen`] uses
+//! it) are undone by [`TerminalGuard`] however
+//! the TUI ends: a normal quit, an error, or a panic unwinding through
+//! [`App::run`](crate::tui::App::run). A panic hook additionally restores the
+//! terminal before the panic message is printed, so the message lands on the
+//! normal screen instead of being wiped with the alternate one.
+
+use crate::error::Result;
+use crossterm::ExecutableCommand;
+use crossterm::cursor::Show;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::Viewport;
+use ratatui::layout::Size;
+use std::io::{self, stdout};
+use std::panic;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether raw mode is on and the terminal still needs restoring
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the alternate screen was entered and still needs leaving
+static ALTERNATE: AtomicBool = AtomicBool::new(false);
+
+/// Guards installing the panic hook once per process
+static PANIC_HOOK: Once = Once::new();
+
+/// Where the TUI draws: the whole alternate screen, or rows below the prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewportMode {
+    /// The alternate screen, which vanishes on quit
+    #[default]
+    Fullscreen,
+    /// This many rows below the cursor, left in the scrollback on quit (`--inline`)
+    Inline(u16),
+}
+
+impl ViewportMode {
+    /// The ratatui viewport for this mode
+    #[must_use]
+    pub const fn viewport(self) -> Viewport {
+        match self {
+            Self::Fullscreen => Viewport::Fullscreen,
+            Self::Inline(height) => Viewport::Inline(height),
+        }
+    }
+
+    /// Size the TUI gets in a terminal of `size`
+    ///
+    /// An inline viewport taller than the terminal is clamped to it.
+    #[must_use]
+    pub fn area(self, size: Size) -> Size {
+        match self {
+            Self::Fullscreen => size,
+            Self::Inline(height) => Size::new(size.width, height.min(size.height)),
+        }
+    }
+}
+
+/// Teardown used by [`TerminalGuard::enter`]
+type Teardown = fn() -> io::Result<()>;
+
+/// Runs a teardown exactly once, on [`restore`](Self::restore) or when dropped
+///
+/// Dropping also covers early returns and panics, but ignores teardown errors;
+/// call `restore` on the normal exit path to get them.
+pub struct TerminalGuard<F: FnMut() -> io::Result<()> = Teardown> {
+    teardown: Option<F>,
+}
+
+impl TerminalGuard {
+    /// Switch the terminal to raw mode, and to the alternate screen for
+    /// [`ViewportMode::Fullscreen`]
+    ///
+    /// If entering the alternate screen fails, raw mode is switched off again
+    /// before the error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be set up.
+    pub fn enter(mode: ViewportMode) -> Result<Self> {
+        install_panic_hook();
+        terminal::enable_raw_mode()?;
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        // From here on, dropping the guard undoes whatever setup succeeded
+        let guard = Self::new(restore_terminal as Teardown);
+        if mode == ViewportMode::Fullscreen {
+            ALTERNATE.store(true, Ordering::SeqCst);
+            stdout().execute(EnterAlternateScreen)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl<F: FnMut() -> io::Result<()>> TerminalGuard<F> {
+    /// Guard that runs `teardown` once
+    pub const fn new(teardown: F) -> Self {
+        Self {
+            teardown: Some(teardown),
+        }
+    }
+
+    /// Run the teardown now instead of on drop
+    ///
+    /// # Errors
+    ///
+    /// Returns the teardown's error.
+    pub fn restore(mut self) -> io::Result<()> {
+        self.teardown
+            .take()
+            .map_or(Ok(()), |mut teardown| teardown())
+    }
+}
+
+impl<F: FnMut() -> io::Result<()>> Drop for TerminalGuard<F> {
+    fn drop(&mut self) {
+        if let Some(mut teardown) = self.teardown.take() {
+            let _ = teardown();
+        }
+    }
+}
+
+/// Leave the alternate screen and raw mode, unless that already happened
+///
+/// Both steps are attempted even if one fails. The alternate screen is only
+/// left when it was entered, since leaving it also restores a saved cursor
+/// position that would move the cursor into an inline viewport's output.
+fn restore_terminal() -> io::Result<()> {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let mut out = stdout();
+    let screen = if ALTERNATE.swap(false, Ordering::SeqCst) {
+        out.execute(LeaveAlternateScreen).map(|_| ())
+    } else {
+        Ok(())
+    };
+    let cursor = out.execute(Show).map(|_| ());
+    let raw = terminal::disable_raw_mode();
+    screen.and(cursor).and(raw)
+}
+
+/// Restore the terminal before the default panic output is printed
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    #[test]
+    fn test_guard_tears_down_when_a_panic_unwinds() {
+        let calls = Cell::new(0);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = TerminalGuard::new(|| {
+                calls.set(calls.get() + 1);
+                Ok(())
+            });
+            panic!("widget index out of bounds");
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_guard_restore_runs_teardown_once() {
+        let calls = Cell::new(0);
+        let guard = TerminalGuard::new(|| {
+            calls.set(calls.get() + 1);
+            Err(io::Error::other("not a terminal"))
+        });
+
+        // The error reaches the caller, and dropping afterwards does not repeat it
+        assert!(guard.restore().is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_inline_viewport_is_clamped_to_the_terminal() {
+        let terminal = Size::new(80, 24);
+        assert_eq!(ViewportMode::Fullscreen.area(terminal), terminal);
+        assert_eq!(ViewportMode::Inline(20).area(terminal), Size::new(80, 20));
+        assert_eq!(ViewportMode::Inline(40).area(terminal), terminal);
+    }
+
+    #[test]
+    fn test_restore_terminal_is_a_no_op_when_inactive() {
+        assert!(!ACTIVE.load(Ordering::SeqCst));
+        assert!(restore_terminal().is_ok());
+    }
+}
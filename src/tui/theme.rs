@@ -0,0 +1,233 @@
+//! Color themes for the TUI
+
+use crate::cli::args::ThemeName;
+use crate::config::ThemeColors;
+use ratatui::style::Color;
+use std::str::FromStr;
+
+/// Colors used across the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Header text, border, active repository tab, and focused panel border
+    pub header: Color,
+    /// Chart borders
+    pub border: Color,
+    /// Chart titles
+    pub title: Color,
+    /// Additions in the diverging chart
+    pub addition: Color,
+    /// Deletions in the diverging chart
+    pub deletion: Color,
+    /// Line charts, weekday bars, and top files
+    pub bar_primary: Color,
+    /// Hour bars and top directories
+    pub bar_secondary: Color,
+    /// Labels, axes, and the footer
+    pub text_dim: Color,
+    /// Heatmap intensity ramp from empty to busiest
+    pub heatmap: [Color; 5],
+    /// Palette the Languages chart picks extension colors from
+    pub languages: [Color; 6],
+}
+
+impl Theme {
+    /// Built-in theme for a preset name
+    #[must_use]
+    pub const fn preset(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self {
+                header: Color::Cyan,
+                border: Color::White,
+                title: Color::Yellow,
+                addition: Color::Green,
+                deletion: Color::Red,
+                bar_primary: Color::Cyan,
+                bar_secondary: Color::Magenta,
+                text_dim: Color::DarkGray,
+                heatmap: [
+                    Color::DarkGray,
+                    Color::Indexed(22),
+                    Color::Indexed(28),
+                    Color::Indexed(34),
+                    Color::Indexed(40),
+                ],
+                languages: [
+                    Color::Cyan,
+                    Color::Magenta,
+                    Color::Yellow,
+                    Color::Green,
+                    Color::Blue,
+                    Color::LightRed,
+                ],
+            },
+            // Shades of gray only; the chart glyphs still tell series apart
+            ThemeName::Monochrome => Self {
+                header: Color::White,
+                border: Color::Gray,
+                title: Color::White,
+                addition: Color::White,
+                deletion: Color::Gray,
+                bar_primary: Color::White,
+                bar_secondary: Color::Gray,
+                text_dim: Color::DarkGray,
+                heatmap: [
+                    Color::DarkGray,
+                    Color::Indexed(240),
+                    Color::Indexed(246),
+                    Color::Indexed(252),
+                    Color::White,
+                ],
+                languages: [
+                    Color::White,
+                    Color::Indexed(252),
+                    Color::Gray,
+                    Color::Indexed(246),
+                    Color::Indexed(243),
+                    Color::Indexed(240),
+                ],
+            },
+            // Blue/orange instead of green/red, distinguishable with red-green color blindness
+            ThemeName::Colorblind => Self {
+                header: Color::Indexed(39),
+                border: Color::White,
+                title: Color::Indexed(220),
+                addition: Color::Indexed(33),
+                deletion: Color::Indexed(208),
+                bar_primary: Color::Indexed(39),
+                bar_secondary: Color::Indexed(208),
+                text_dim: Color::DarkGray,
+                heatmap: [
+                    Color::DarkGray,
+                    Color::Indexed(17),
+                    Color::Indexed(19),
+                    Color::Indexed(27),
+                    Color::Indexed(39),
+                ],
+                // Okabe-Ito inspired, avoiding red/green pairs
+                languages: [
+                    Color::Indexed(39),
+                    Color::Indexed(208),
+                    Color::Indexed(220),
+                    Color::Indexed(117),
+                    Color::Indexed(166),
+                    Color::Indexed(134),
+                ],
+            },
+        }
+    }
+
+    /// This theme with panel borders in the header color when `focused`
+    #[must_use]
+    pub const fn for_panel(self, focused: bool) -> Self {
+        if focused {
+            Self {
+                border: self.header,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Apply color overrides from the config on top of this theme
+    ///
+    /// Colors are ratatui color names (`red`, `lightblue`), `#rrggbb`, or a
+    /// 256-color index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first override that is not a valid color.
+    pub fn with_overrides(mut self, colors: &ThemeColors) -> Result<Self, String> {
+        let slots = [
+            ("header", &colors.header, &mut self.header),
+            ("border", &colors.border, &mut self.border),
+            ("title", &colors.title, &mut self.title),
+            ("addition", &colors.addition, &mut self.addition),
+            ("deletion", &colors.deletion, &mut self.deletion),
+            ("bar_primary", &colors.bar_primary, &mut self.bar_primary),
+            (
+                "bar_secondary",
+                &colors.bar_secondary,
+                &mut self.bar_secondary,
+            ),
+            ("text_dim", &colors.text_dim, &mut self.text_dim),
+        ];
+        for (field, value, slot) in slots {
+            if let Some(value) = value {
+                *slot = Color::from_str(value.trim()).map_err(|_| {
+                    format!(
+                        "invalid color '{value}' for defaults.colors.{field}; \
+                         use a name like lightblue, #rrggbb, or 0-255"
+                    )
+                })?;
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ThemeName::Default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_preset_matches_default() {
+        assert_eq!(Theme::default(), Theme::preset(ThemeName::Default));
+        assert_eq!(Theme::default().addition, Color::Green);
+    }
+
+    #[test]
+    fn test_colorblind_avoids_red_green_pair() {
+        let theme = Theme::preset(ThemeName::Colorblind);
+        for color in [theme.addition, theme.deletion] {
+            assert!(!matches!(
+                color,
+                Color::Red | Color::LightRed | Color::Green | Color::LightGreen
+            ));
+        }
+        assert_ne!(theme.addition, theme.deletion);
+    }
+
+    #[test]
+    fn test_for_panel_highlights_only_focused_border() {
+        let theme = Theme::default();
+        assert_eq!(theme.for_panel(false), theme);
+        let focused = theme.for_panel(true);
+        assert_eq!(focused.border, theme.header);
+        assert_eq!(focused.title, theme.title);
+    }
+
+    #[test]
+    fn test_overrides_replace_only_given_colors() {
+        let colors = ThemeColors {
+            addition: Some("lightblue".to_string()),
+            deletion: Some("#ff8800".to_string()),
+            text_dim: Some("244".to_string()),
+            ..ThemeColors::default()
+        };
+
+        let theme = Theme::default().with_overrides(&colors).unwrap();
+        assert_eq!(theme.addition, Color::LightBlue);
+        assert_eq!(theme.deletion, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.text_dim, Color::Indexed(244));
+        assert_eq!(theme.header, Theme::default().header);
+    }
+
+    #[test]
+    fn test_invalid_override_names_the_field() {
+        let colors = ThemeColors {
+            border: Some("blurple".to_string()),
+            ..ThemeColors::default()
+        };
+
+        let err = Theme::default().with_overrides(&colors).unwrap_err();
+        assert!(err.contains("'blurple'"));
+        assert!(err.contains("defaults.colors.border"));
+    }
+}
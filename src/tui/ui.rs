@@ -1,198 +1,1135 @@
 //! UI rendering
 
-use crate::stats::ActivityStats;
+use crate::stats::{ActivityStats, FileChurn, TIME_OF_DAY_BUCKETS, longest_gap};
 use crate::tui::app::{App, Metric};
 use crate::tui::chart_type::ChartType;
+use crate::tui::key_spec::KeySpec;
+use crate::tui::keymap::Keymap;
+use crate::tui::loader::LoadProgress;
+use crate::tui::mvu::model::{InspectTarget, StatusLevel};
+use crate::tui::panel::FocusedPanel;
+use crate::tui::theme::Theme;
 use crate::tui::widgets::{
-    chart_width, render_diverging_bar_chart, render_line_chart_for_metric,
-    render_vertical_bar_chart,
+    BarDataPoint, DIVERGING_BAR_CHART_MIN_SIZE, HEATMAP_HEIGHT, HEATMAP_WIDTH, LINE_CHART_MIN_SIZE,
+    SUMMARY_CARDS_HEIGHT, add_del_window, bar_width_for, chart_width, chart_width_for,
+    render_cumulative_chart, render_diverging_bar_chart, render_heatmap,
+    render_horizontal_bar_chart, render_line_chart_for_metric, render_net_lines_chart,
+    render_stacked_bar_chart, render_summary_cards, render_vertical_bar_chart, rows_label,
+    scroll_edge, vertical_bar_chart_min_size,
 };
 use ratatui::layout::Flex;
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Gauge, Padding, Paragraph};
+use std::fmt::Write;
 
 /// Render the entire UI
 pub fn render(frame: &mut Frame, app: &App) {
     let area = frame.area();
+    let [header, main, footer] = frame_layout(area, app);
 
-    // Create layout: header, main content, footer
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Min(10),   // Main content
-            Constraint::Length(3), // Footer
-        ])
-        .split(area);
-
-    render_header(frame, chunks[0], app);
+    render_header(frame, header, app);
 
-    if app.single_metric() {
-        render_single_chart(frame, chunks[1], app);
+    if app.model().loading {
+        let progress = app.load_progress().cloned().unwrap_or_default();
+        render_loading(frame, main, &progress, app.theme());
+    } else if app.model().single_metric {
+        render_single_chart(frame, main, app);
     } else {
-        render_split_charts(frame, chunks[1], app);
+        render_split_charts(frame, main, app);
+    }
+
+    render_footer(frame, footer, app, add_del_rows(area, app));
+    render_status(frame, main, app);
+
+    if app.model().show_help {
+        render_help_overlay(frame, area, app.theme(), app.keymap());
     }
+}
+
+/// Draw the status message on screen, if any, over the bottom border of `area`
+fn render_status(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(status) = app.model().status() else {
+        return;
+    };
+    let theme = app.theme();
+    // Yellow and red in the default theme, kept apart from chart colors in the others
+    let color = match status.level {
+        StatusLevel::Info => theme.text_dim,
+        StatusLevel::Warn => theme.title,
+        StatusLevel::Error => theme.deletion,
+    };
+    let text = format!(" {} ", status.text);
+    let width = u16::try_from(text.chars().count())
+        .unwrap_or(u16::MAX)
+        .min(area.width);
+    let row = Rect::new(
+        area.x,
+        area.bottom().saturating_sub(1),
+        area.width,
+        1.min(area.height),
+    );
+    let [line] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(row);
+    frame.render_widget(Clear, line);
+    frame.render_widget(
+        Paragraph::new(text).style(Style::default().fg(color).bold()),
+        line,
+    );
+}
 
-    render_footer(frame, chunks[2], app);
+/// Split the frame into header, main content and footer
+fn frame_layout(area: Rect, app: &App) -> [Rect; 3] {
+    // Footer: help and summary, plus the longest gap when there is one
+    let footer = 4 + u16::from(gap_line(app).is_some());
+    Layout::vertical([
+        Constraint::Length(3), // Header
+        Constraint::Min(10),   // Main content
+        Constraint::Length(footer),
+    ])
+    .areas(area)
+}
+
+/// Number of Add/Del rows visible in a frame of `area`, i.e. one page of scrolling
+#[must_use]
+pub fn add_del_rows(area: Rect, app: &App) -> usize {
+    let [_, main, _] = frame_layout(area, app);
+    let chart = if app.model().single_metric {
+        main
+    } else {
+        split_layout(main, app.model().show_summary)
+            .add_del
+            .unwrap_or_default()
+    };
+    // The chart block draws a border above and below the rows
+    usize::from(chart.height.saturating_sub(2))
 }
 
 fn render_header(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = app.theme();
+    let result = app.active_result();
     let title = format!(
         " {} | {} | {} ",
-        app.result.repository,
-        app.result.period,
-        format_date_range(&app.result.from.to_string(), &app.result.to.to_string())
+        result.repository,
+        result.period,
+        format_date_range(&result.from.to_string(), &result.to.to_string())
     );
 
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.header));
+    let tabs = app.repo_tabs();
+    if !tabs.is_empty() {
+        block = block.title(repo_tabs_line(&tabs, app.model().active_repo, theme));
+    }
+
     let header = Paragraph::new(title)
-        .style(Style::default().fg(Color::Cyan).bold())
+        .style(Style::default().fg(theme.header).bold())
         .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
+        .block(block);
 
     frame.render_widget(header, area);
 }
 
+/// Repository tabs as `0 All  [1 kodo]  2 docs`, with the active tab highlighted
+fn repo_tabs_line<'a>(tabs: &[&'a str], active: usize, theme: &Theme) -> Line<'a> {
+    let mut spans = vec![Span::raw(" ")];
+    for (i, name) in tabs.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+        }
+        if i == active {
+            spans.push(Span::styled(
+                format!("[{i} {name}]"),
+                Style::default().fg(Color::Black).bg(theme.header).bold(),
+            ));
+        } else {
+            spans.push(Span::styled(
+                format!("{i} {name}"),
+                Style::default().fg(theme.text_dim),
+            ));
+        }
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
 fn render_single_chart(frame: &mut Frame, area: Rect, app: &App) {
-    match app.chart_type() {
-        ChartType::Commits => render_line_chart_for_metric(frame, area, app, Metric::Commits),
+    let theme = app.theme();
+    match app.model().chart_type {
+        ChartType::Commits => {
+            render_line_chart_for_metric(frame, area, app, Metric::Commits, false);
+        }
         ChartType::FilesChanged => {
-            render_line_chart_for_metric(frame, area, app, Metric::FilesChanged);
+            render_line_chart_for_metric(frame, area, app, Metric::FilesChanged, false);
         }
-        ChartType::AddDel => render_diverging_bar_chart(frame, area, app),
+        ChartType::AddDel => render_diverging_bar_chart(frame, area, app, false),
+        ChartType::NetLines => render_net_lines_chart(frame, area, app),
+        ChartType::Cumulative => render_cumulative_chart(frame, area, app),
         ChartType::Weekday => {
             let centered = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([Constraint::Max(chart_width(7))])
                 .flex(Flex::Center)
                 .split(area)[0];
-            render_weekday_chart(frame, centered, &app.activity_stats);
+            let stats = app.active_activity_stats();
+            let selected = selected_in(app, InspectTarget::Weekday);
+            render_weekday_chart(
+                frame,
+                centered,
+                stats,
+                theme,
+                false,
+                app.model().activity_by_lines,
+                selected,
+            );
         }
         ChartType::Hour => {
+            let width = match hour_layout(area.width) {
+                HourLayout::Bars(hours) => hour_chart_width(hours),
+                HourLayout::Rows => area.width,
+            };
+            let centered = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Max(width)])
+                .flex(Flex::Center)
+                .split(area)[0];
+            let stats = app.active_activity_stats();
+            let selected = selected_in(app, InspectTarget::Hour);
+            render_hourly_chart(
+                frame,
+                centered,
+                stats,
+                theme,
+                false,
+                app.model().activity_by_lines,
+                selected,
+            );
+        }
+        ChartType::TimeOfDay => {
+            let centered = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Max(time_of_day_chart_width())])
+                .flex(Flex::Center)
+                .split(area)[0];
+            let stats = app.active_activity_stats();
+            let by_lines = app.model().activity_by_lines;
+            let selected = selected_in(app, InspectTarget::TimeOfDay);
+            render_time_of_day_chart(frame, centered, stats, theme, false, by_lines, selected);
+        }
+        ChartType::Heatmap => {
             let centered = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Max(chart_width(24))])
+                .constraints([Constraint::Max(HEATMAP_WIDTH)])
                 .flex(Flex::Center)
                 .split(area)[0];
-            render_hourly_chart(frame, centered, &app.activity_stats);
+            render_heatmap_chart(frame, centered, app.active_activity_stats(), theme);
         }
+        ChartType::TopFiles => render_churn_charts(frame, area, app),
+        ChartType::Languages => render_stacked_bar_chart(frame, area, app),
     }
 }
 
-fn render_split_charts(frame: &mut Frame, area: Rect, app: &App) {
-    // Split into top and bottom rows (3:1)
-    let rows = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Ratio(3, 4), Constraint::Ratio(1, 4)])
+/// Render top files and top directories side by side (`--top-files` / `--top-dirs`)
+fn render_churn_charts(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = app.theme();
+    let panels: Vec<(&str, &[FileChurn], Color)> = [
+        (
+            "Top Files",
+            app.active_result().top_files.as_deref(),
+            theme.bar_primary,
+        ),
+        (
+            "Top Directories",
+            app.active_result().top_dirs.as_deref(),
+            theme.bar_secondary,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(title, entries, color)| entries.map(|entries| (title, entries, color)))
+    .collect();
+
+    if panels.is_empty() {
+        let hint = Paragraph::new("Run with --top-files N or --top-dirs N")
+            .alignment(Alignment::Center)
+            .block(Block::default().title(" Top Files ").borders(Borders::ALL));
+        frame.render_widget(hint, area);
+        return;
+    }
+
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Fill(1); panels.len()])
         .split(area);
 
+    for ((title, entries, color), col) in panels.into_iter().zip(cols.iter()) {
+        let data: Vec<BarDataPoint> = entries
+            .iter()
+            .map(|entry| {
+                let lines = u32::try_from(entry.lines_changed()).unwrap_or(u32::MAX);
+                BarDataPoint::new(entry.path.clone(), lines)
+            })
+            .collect();
+        render_horizontal_bar_chart(frame, *col, title, &data, color, theme);
+    }
+}
+
+/// Minimum main-area height at which split mode adds the heatmap row
+const SPLIT_HEATMAP_MIN_HEIGHT: u16 = 30;
+
+/// Minimum main-area height at which split mode adds the summary cards
+const SPLIT_SUMMARY_MIN_HEIGHT: u16 = 20;
+
+/// Where each split-mode chart goes within the main area
+///
+/// Panels too small for their widget are left out, their space going to the
+/// panels next to them.
+struct SplitLayout {
+    summary: Option<Rect>,
+    commits: Rect,
+    files_changed: Option<Rect>,
+    add_del: Option<Rect>,
+    weekday: Option<Rect>,
+    hour: Option<Rect>,
+    heatmap: Option<Rect>,
+}
+
+fn split_layout(area: Rect, show_summary: bool) -> SplitLayout {
+    // Summary cards take the top rows when enabled and the terminal is tall enough
+    let (summary, area) = if show_summary && area.height >= SPLIT_SUMMARY_MIN_HEIGHT {
+        let [summary, rest] =
+            Layout::vertical([Constraint::Length(SUMMARY_CARDS_HEIGHT), Constraint::Min(0)])
+                .areas(area);
+        (Some(summary), rest)
+    } else {
+        (None, area)
+    };
+
+    // Reserve a heatmap row at the bottom when the terminal is tall enough
+    let (area, heatmap) = if area.height >= SPLIT_HEATMAP_MIN_HEIGHT {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(HEATMAP_HEIGHT)])
+            .split(area);
+        let centered = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Max(HEATMAP_WIDTH)])
+            .flex(Flex::Center)
+            .split(parts[1])[0];
+        (parts[0], Some(centered))
+    } else {
+        (area, None)
+    };
+
+    // Split into top and bottom rows (3:1), the bottom one only when its bars fit
+    let [top, bottom] =
+        Layout::vertical([Constraint::Ratio(3, 4), Constraint::Ratio(1, 4)]).areas(area);
+    let (top, (weekday, hour)) = if bottom.height >= weekday_chart_min_size().height {
+        (top, activity_layout(bottom))
+    } else {
+        (area, (None, None))
+    };
+
     // Top row: Commits + Files Changed (left) | Addition/Deletions (right) - 3:1
-    let top_cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Ratio(3, 4), Constraint::Ratio(1, 4)])
-        .split(rows[0]);
+    let [left, right] =
+        Layout::horizontal([Constraint::Ratio(3, 4), Constraint::Ratio(1, 4)]).areas(top);
+    let (left, add_del) = if right.width >= DIVERGING_BAR_CHART_MIN_SIZE.width {
+        (left, Some(right))
+    } else {
+        (top, None)
+    };
 
     // Left side of top row: Commits + Files Changed stacked
-    let top_left_rows = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(top_cols[0]);
+    let [commits, files_changed] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(left);
+    let (commits, files_changed) =
+        if commits.height.min(files_changed.height) >= LINE_CHART_MIN_SIZE.height {
+            (commits, Some(files_changed))
+        } else {
+            (left, None)
+        };
+
+    SplitLayout {
+        summary,
+        commits,
+        files_changed,
+        add_del,
+        weekday,
+        hour,
+        heatmap,
+    }
+}
 
-    render_line_chart_for_metric(frame, top_left_rows[0], app, Metric::Commits);
-    render_line_chart_for_metric(frame, top_left_rows[1], app, Metric::FilesChanged);
+/// Weekday (at least a fifth of the width) and Hour panels of the split
+/// view's bottom row
+///
+/// When both do not fit, the Hour panel takes the row, or the Weekday panel
+/// when only that one fits.
+fn activity_layout(area: Rect) -> (Option<Rect>, Option<Rect>) {
+    let weekday_width = weekday_chart_min_size().width;
+    let hour_width = time_of_day_chart_width();
+    if area.width >= weekday_width + hour_width {
+        let weekday_width = (area.width / 5).max(weekday_width);
+        let [weekday, hour] =
+            Layout::horizontal([Constraint::Length(weekday_width), Constraint::Min(0)]).areas(area);
+        (Some(weekday), Some(hour))
+    } else if area.width < hour_width && area.width >= weekday_width {
+        (Some(area), None)
+    } else {
+        (None, Some(area))
+    }
+}
 
-    // Right side of top row: Addition/Deletions
-    render_diverging_bar_chart(frame, top_cols[1], app);
+/// Render the split-mode panels, highlighting the focused one
+///
+/// A focused panel left out by [`split_layout`] shows again once the
+/// terminal is large enough.
+fn render_split_charts(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = app.theme();
+    let layout = split_layout(area, app.model().show_summary);
+    let focused = app.model().focused_panel;
+    let stats = app.active_activity_stats();
 
-    // Bottom row: Weekdays (1/5) | Hour (4/5)
-    let bottom_cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Ratio(1, 5), Constraint::Ratio(4, 5)])
-        .split(rows[1]);
+    if let Some(summary) = layout.summary {
+        render_summary_cards(frame, summary, &app.active_result().total, theme);
+    }
+    if let Some(heatmap) = layout.heatmap {
+        render_heatmap_chart(frame, heatmap, app.active_activity_stats(), theme);
+    }
+    render_line_chart_for_metric(
+        frame,
+        layout.commits,
+        app,
+        Metric::Commits,
+        focused == FocusedPanel::Commits,
+    );
+    if let Some(files_changed) = layout.files_changed {
+        render_line_chart_for_metric(
+            frame,
+            files_changed,
+            app,
+            Metric::FilesChanged,
+            focused == FocusedPanel::FilesChanged,
+        );
+    }
+    if let Some(add_del) = layout.add_del {
+        render_diverging_bar_chart(frame, add_del, app, focused == FocusedPanel::AddDel);
+    }
+    let by_lines = app.model().activity_by_lines;
+    if let Some(weekday) = layout.weekday {
+        render_weekday_chart(
+            frame,
+            weekday,
+            stats,
+            theme,
+            focused == FocusedPanel::Weekday,
+            by_lines,
+            selected_in(app, InspectTarget::Weekday),
+        );
+    }
+    let Some(hour) = layout.hour else {
+        return;
+    };
+    let hour_focused = focused == FocusedPanel::Hour;
+    let selected_hour = selected_in(app, InspectTarget::Hour);
+    if hour.width < hour_chart_width(1) {
+        // The inspected hour highlights the part of the day it falls in
+        let selected = selected_hour.and_then(|hour| {
+            TIME_OF_DAY_BUCKETS
+                .iter()
+                .position(|(_, hours)| hours.contains(&hour))
+        });
+        render_time_of_day_chart(frame, hour, stats, theme, hour_focused, by_lines, selected);
+    } else {
+        render_hourly_chart(
+            frame,
+            hour,
+            stats,
+            theme,
+            hour_focused,
+            by_lines,
+            selected_hour,
+        );
+    }
+}
+
+/// Index of the element highlighted in inspect mode, while stepping through `target`
+fn selected_in(app: &App, target: InspectTarget) -> Option<usize> {
+    app.model()
+        .selection()
+        .filter(|(inspected, _)| *inspected == target)
+        .map(|(_, index)| index)
+}
+
+/// Title of an activity chart, marked when it counts changed lines instead of commits
+fn activity_title(name: &str, by_lines: bool) -> String {
+    if by_lines {
+        format!("{name} Lines")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Smallest area the weekday chart renders in
+fn weekday_chart_min_size() -> Size {
+    vertical_bar_chart_min_size(&ActivityStats::weekday_labels())
+}
+
+/// Render commits, or changed lines when `by_lines`, per weekday, highlighting
+/// the `selected` day
+fn render_weekday_chart(
+    frame: &mut Frame,
+    area: Rect,
+    stats: &ActivityStats,
+    theme: &Theme,
+    focused: bool,
+    by_lines: bool,
+    selected: Option<usize>,
+) {
+    let labels = ActivityStats::weekday_labels();
+    let values = if by_lines {
+        stats.weekday_lines
+    } else {
+        stats.weekday.map(u64::from)
+    };
+    let theme = &theme.for_panel(focused);
+    render_vertical_bar_chart(
+        frame,
+        area,
+        &activity_title("Weekday", by_lines),
+        &labels,
+        &values,
+        theme.bar_primary,
+        selected,
+        theme,
+    );
+}
+
+/// Hours per vertical bar tried for the hour chart, most detailed first
+const HOUR_BAR_SIZES: [usize; 3] = [1, 2, 3];
+
+/// Hours per row when the hour chart falls back to horizontal bars
+const HOUR_ROW_SIZE: usize = 4;
+
+/// How the hour chart fits the available width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HourLayout {
+    /// Vertical bars of this many hours each
+    Bars(usize),
+    /// Horizontal bars of [`HOUR_ROW_SIZE`] hours each
+    Rows,
+}
+
+/// The most detailed hour chart layout that fits in `width` columns
+fn hour_layout(width: u16) -> HourLayout {
+    HOUR_BAR_SIZES
+        .into_iter()
+        .find(|&hours| hour_chart_width(hours) <= width)
+        .map_or(HourLayout::Rows, HourLayout::Bars)
+}
+
+/// Width of the vertical hour chart with `hours` hours per bar
+fn hour_chart_width(hours: usize) -> u16 {
+    let buckets = hour_buckets(&[0; 24], hours);
+    let labels: Vec<&str> = buckets.iter().map(|(label, _)| label.as_str()).collect();
+    let count = u16::try_from(labels.len()).unwrap_or(u16::MAX);
+    chart_width_for(count, bar_width_for(&labels))
+}
+
+/// Hourly values summed per `hours`-hour bucket, labeled `0`..`23` for
+/// single hours and `0-1`, `2-3`, ... otherwise
+fn hour_buckets(hourly: &[u64; 24], hours: usize) -> Vec<(String, u64)> {
+    hourly
+        .chunks(hours.max(1))
+        .enumerate()
+        .map(|(i, chunk)| {
+            let start = i * hours.max(1);
+            let end = start + chunk.len() - 1;
+            let label = if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            };
+            (label, chunk.iter().sum())
+        })
+        .collect()
+}
+
+/// Render commits, or changed lines when `by_lines`, per hour, bucketing
+/// hours when all 24 bars do not fit
+///
+/// Narrow areas get two- or three-hour bars, and below that a horizontal
+/// chart of four-hour rows. A `focused` chart gets a highlighted border, and
+/// the bar holding `selected_hour` is highlighted unless rows are drawn.
+fn render_hourly_chart(
+    frame: &mut Frame,
+    area: Rect,
+    stats: &ActivityStats,
+    theme: &Theme,
+    focused: bool,
+    by_lines: bool,
+    selected_hour: Option<usize>,
+) {
+    let theme = &theme.for_panel(focused);
+    let hourly = if by_lines {
+        stats.hourly_lines
+    } else {
+        stats.hourly.map(u64::from)
+    };
+    let title = activity_title("Hour", by_lines);
+    match hour_layout(area.width) {
+        HourLayout::Bars(hours) => {
+            let buckets = hour_buckets(&hourly, hours);
+            let labels: Vec<&str> = buckets.iter().map(|(label, _)| label.as_str()).collect();
+            let values: Vec<u64> = buckets.iter().map(|(_, value)| *value).collect();
+            render_vertical_bar_chart(
+                frame,
+                area,
+                &title,
+                &labels,
+                &values,
+                theme.bar_secondary,
+                selected_hour.map(|hour| hour / hours),
+                theme,
+            );
+        }
+        HourLayout::Rows => {
+            let data: Vec<BarDataPoint> = hour_buckets(&hourly, HOUR_ROW_SIZE)
+                .into_iter()
+                .map(|(label, value)| {
+                    BarDataPoint::new(label, u32::try_from(value).unwrap_or(u32::MAX))
+                })
+                .collect();
+            render_horizontal_bar_chart(frame, area, &title, &data, theme.bar_secondary, theme);
+        }
+    }
+}
 
-    render_weekday_chart(frame, bottom_cols[0], &app.activity_stats);
-    render_hourly_chart(frame, bottom_cols[1], &app.activity_stats);
+/// Width of the time-of-day chart, with bars wide enough for its labels
+fn time_of_day_chart_width() -> u16 {
+    let labels = TIME_OF_DAY_BUCKETS.map(|(label, _)| label);
+    let count = u16::try_from(labels.len()).unwrap_or(u16::MAX);
+    chart_width_for(count, bar_width_for(&labels))
 }
 
-fn render_weekday_chart(frame: &mut Frame, area: Rect, stats: &ActivityStats) {
+/// Render commits, or changed lines when `by_lines`, per part of the day
+/// ([`TIME_OF_DAY_BUCKETS`]) as four bars
+///
+/// A `focused` chart gets a highlighted border and the `selected` bucket is
+/// highlighted.
+fn render_time_of_day_chart(
+    frame: &mut Frame,
+    area: Rect,
+    stats: &ActivityStats,
+    theme: &Theme,
+    focused: bool,
+    by_lines: bool,
+    selected: Option<usize>,
+) {
+    let theme = &theme.for_panel(focused);
+    let buckets = if by_lines {
+        stats.time_of_day_lines()
+    } else {
+        stats
+            .time_of_day()
+            .map(|(label, commits)| (label, u64::from(commits)))
+    };
+    let labels = buckets.map(|(label, _)| label);
+    let values = buckets.map(|(_, value)| value);
+    render_vertical_bar_chart(
+        frame,
+        area,
+        &activity_title("Time of Day", by_lines),
+        &labels,
+        &values,
+        theme.bar_secondary,
+        selected,
+        theme,
+    );
+}
+
+fn render_heatmap_chart(frame: &mut Frame, area: Rect, stats: &ActivityStats, theme: &Theme) {
     let labels = ActivityStats::weekday_labels();
-    render_vertical_bar_chart(frame, area, "Weekday", &labels, &stats.weekday, Color::Cyan);
-}
-
-fn render_hourly_chart(frame: &mut Frame, area: Rect, stats: &ActivityStats) {
-    // Use shorter labels for hours to fit
-    let labels: Vec<&str> = (0..24).map(hour_label).collect();
-    render_vertical_bar_chart(frame, area, "Hour", &labels, &stats.hourly, Color::Magenta);
-}
-
-fn hour_label(hour: usize) -> &'static str {
-    match hour {
-        0 => "0",
-        1 => "1",
-        2 => "2",
-        3 => "3",
-        4 => "4",
-        5 => "5",
-        6 => "6",
-        7 => "7",
-        8 => "8",
-        9 => "9",
-        10 => "10",
-        11 => "11",
-        12 => "12",
-        13 => "13",
-        14 => "14",
-        15 => "15",
-        16 => "16",
-        17 => "17",
-        18 => "18",
-        19 => "19",
-        20 => "20",
-        21 => "21",
-        22 => "22",
-        23 => "23",
-        _ => "",
-    }
-}
-
-fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
-    let mode_indicator = if app.single_metric() {
-        format!("Single: {}", app.chart_type().name())
+    render_heatmap(
+        frame,
+        area,
+        "Weekday × Hour",
+        &labels,
+        &stats.heatmap,
+        theme,
+    );
+}
+
+/// Width of the loading panel, narrower on small terminals
+const LOADING_WIDTH: u16 = 60;
+
+/// Render the loading panel shown until the initial data arrives
+fn render_loading(frame: &mut Frame, area: Rect, progress: &LoadProgress, theme: &Theme) {
+    let popup = centered_rect(area, LOADING_WIDTH.min(area.width), 7);
+    let block = Block::default()
+        .title(" Loading ")
+        .title_style(Style::default().fg(theme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let [status, gauge, _, detail] = Layout::vertical([Constraint::Length(1); 4]).areas(inner);
+    let dim = Style::default().fg(theme.text_dim);
+
+    let message = if progress.summarizing {
+        "Calculating statistics…".to_string()
+    } else if progress.total == 0 {
+        "Collecting commits…".to_string()
+    } else {
+        let noun = if progress.total == 1 {
+            "repository"
+        } else {
+            "repositories"
+        };
+        format!("Collecting commits from {} {noun}…", progress.total)
+    };
+    frame.render_widget(Paragraph::new(message), status);
+
+    let label = format!("{}/{}", progress.loaded, progress.total);
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(Style::default().fg(theme.bar_primary))
+            .ratio(progress.ratio())
+            .label(label),
+        gauge,
+    );
+
+    let found = format!("{} commits found", progress.commits);
+    let detail_text = match &progress.last_repo {
+        Some(repo) => format!("{found}, last from {repo}"),
+        None => found,
+    };
+    frame.render_widget(Paragraph::new(detail_text).style(dim), detail);
+}
+
+/// Key hints (or the inspected values) over the summary stats
+///
+/// `add_del_rows` is the height of the Add/Del chart in this frame.
+fn render_footer(frame: &mut Frame, area: Rect, app: &App, add_del_rows: usize) {
+    if app.model().loading {
+        let text_dim = app.theme().text_dim;
+        let footer = Paragraph::new(" Loading… | [q] Cancel ")
+            .style(Style::default().fg(text_dim))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(text_dim)),
+            );
+        frame.render_widget(footer, area);
+        return;
+    }
+
+    let mode_indicator = if app.model().single_metric {
+        format!("Single: {}", app.model().chart_type.name())
     } else {
         "Split".to_string()
     };
 
-    let nav_hint = if app.single_metric() {
-        "[Tab] Switch | "
+    let nav_hint = if app.model().single_metric {
+        "[Tab] Switch | ".to_string()
+    } else {
+        format!("[Tab] Focus: {} | ", app.model().focused_panel.name())
+    };
+    let mut help_text = format!(" {nav_hint}[m] Mode: {mode_indicator} | ");
+    if app.can_switch_period() {
+        help_text.push_str("[d/w/M/y] Period | ");
+    }
+    let repo_count = app.result.repositories.len();
+    if repo_count > 1 {
+        let _ = write!(help_text, "Repos: {repo_count} | ");
+    }
+    if let Some(count) = app.model().pending_count {
+        let _ = write!(help_text, "Count: {count} | ");
+    }
+    if app.is_loading_range() {
+        let _ = write!(help_text, "Loading {} days… | ", app.model().days);
+    } else if app.model().refreshing {
+        help_text.push_str("Refreshing… | ");
+    } else if let Some(error) = &app.model().refresh_error {
+        let _ = write!(help_text, "Refresh failed: {error} | ");
+    }
+    help_text.push_str("[?] Help | [q] Quit ");
+    // Inspect mode replaces the key hints with the highlighted element's
+    // values, scrolling back through the Add/Del chart with its position
+    let help_line = if let Some(values) = inspect_line(app) {
+        Line::from(format!(" {values} | [Esc] Exit "))
+    } else if let Some(position) = scroll_position_line(app, add_del_rows) {
+        position
     } else {
-        ""
+        Line::from(help_text)
     };
-    let help_text = format!(" {nav_hint}[m] Mode: {mode_indicator} | [q] Quit ");
 
     // Summary stats
-    let total = &app.result.total;
-    let summary = format!(
+    let result = app.active_result();
+    let total = &result.total;
+    let mut summary = format!(
         "Total: {} commits | +{} -{} | {} files",
         total.commits, total.additions, total.deletions, total.files_changed
     );
+    if let Some(comparison) = &result.comparison {
+        let commits = &comparison.commits;
+        let net = &comparison.net_lines;
+        let _ = write!(
+            summary,
+            " | vs prev: {} {:+} commits ({}), {} {:+} net ({})",
+            commits.indicator(),
+            commits.change,
+            commits.percent_label(),
+            net.indicator(),
+            net.change,
+            net.percent_label()
+        );
+    }
+
+    if let Some(work_pattern) = &result.work_pattern {
+        let _ = write!(
+            summary,
+            " | Work hrs {:.0}% / eve {:.0}% / wknd {:.0}%",
+            work_pattern.commit_percent(&work_pattern.business_hours),
+            work_pattern.commit_percent(&work_pattern.weekday_evenings),
+            work_pattern.commit_percent(&work_pattern.weekends)
+        );
+    }
+
+    if result.truncated {
+        summary.push_str(" | Truncated by --max-commits/--max-seconds");
+    }
 
-    let footer_text = format!("{help_text}\n{summary}");
+    let mut footer_lines = vec![help_line, Line::from(summary)];
+    footer_lines.extend(gap_line(app).map(Line::from));
+    let footer_text = Text::from(footer_lines);
 
+    let text_dim = app.theme().text_dim;
     let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(text_dim))
         .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray)),
+                .border_style(Style::default().fg(text_dim)),
         );
 
     frame.render_widget(footer, area);
 }
 
+/// Footer line with the longest stretch without commits, if there was one
+fn gap_line(app: &App) -> Option<String> {
+    if app.model().loading {
+        return None;
+    }
+    longest_gap(&app.active_result().gaps).map(|gap| format!("Longest gap: {gap}"))
+}
+
+/// Rows of the Add/Del chart on screen, like
+/// `2024-03-03 – 2024-03-20 of 2024-01-01 – 2024-03-31 (rows 42–60/90) TOP`,
+/// while the scroll keys act on it and it is scrolled back from the newest
+fn scroll_position_line(app: &App, add_del_rows: usize) -> Option<Line<'static>> {
+    if !app.model().can_scroll() || app.model().scroll_offset == 0 {
+        return None;
+    }
+    let stats = &app.active_result().stats;
+    let total = stats.len();
+    let window = add_del_window(total, app.model().scroll_offset, add_del_rows);
+    if window.is_empty() || window.len() >= total {
+        return None;
+    }
+    let label = |index: usize| stats[index].label.as_str();
+    let mut line = Line::from(format!(
+        " {} \u{2013} {} of {} \u{2013} {} ({}) ",
+        label(window.start),
+        label(window.end - 1),
+        label(0),
+        label(total - 1),
+        rows_label(&window, total)
+    ));
+    if let Some(edge) = scroll_edge(&window, total) {
+        line.push_span(Span::styled(
+            edge,
+            Style::default().fg(app.theme().title).bold(),
+        ));
+        line.push_span(Span::raw(" "));
+    }
+    Some(line)
+}
+
+/// Exact values of the element highlighted in inspect mode, like
+/// `2024-01-03: 4 commits | +120 -30 (net +90) | 6 files`
+fn inspect_line(app: &App) -> Option<String> {
+    let (target, index) = app.model().selection()?;
+    let stats = app.active_activity_stats();
+    let (label, commits, lines) = match target {
+        InspectTarget::Periods => {
+            let stat = app.active_result().stats.get(index)?;
+            return Some(format!(
+                "{}: {} commits | +{} -{} (net {:+}) | {} files",
+                stat.label,
+                stat.commits,
+                stat.additions,
+                stat.deletions,
+                stat.net_lines,
+                stat.files_changed
+            ));
+        }
+        InspectTarget::Weekday => (
+            ActivityStats::weekday_labels().get(index)?.to_string(),
+            *stats.weekday.get(index)?,
+            *stats.weekday_lines.get(index)?,
+        ),
+        InspectTarget::Hour => (
+            format!("{index}:00-{index}:59"),
+            *stats.hourly.get(index)?,
+            *stats.hourly_lines.get(index)?,
+        ),
+        InspectTarget::TimeOfDay => {
+            let (name, hours) = TIME_OF_DAY_BUCKETS.get(index)?;
+            (
+                format!("{name} ({}:00-{}:59)", hours.start, hours.end - 1),
+                stats.time_of_day().get(index)?.1,
+                stats.time_of_day_lines().get(index)?.1,
+            )
+        }
+    };
+    Some(format!(
+        "{label}: {commits} commits | {lines} lines changed"
+    ))
+}
+
+/// A help overlay row: keys as (action name, built-in key) pairs, and what they do
+///
+/// An empty action name marks a key that cannot be rebound.
+type HelpRow = (&'static [(&'static str, &'static str)], &'static str);
+
+/// Keybindings shown in the help overlay, grouped by context
+const HELP_SECTIONS: [(&str, &[HelpRow]); 3] = [
+    (
+        "Global",
+        &[
+            (&[("toggle_help", "?")], "Show / hide this help"),
+            (&[("toggle_metric_view", "m")], "Toggle single / split mode"),
+            (&[("refresh", "r")], "Refresh data"),
+            (
+                &[("grow_range", "+"), ("shrink_range", "-")],
+                "Double / halve the date range",
+            ),
+            (
+                &[
+                    ("period_daily", "d"),
+                    ("period_weekly", "w"),
+                    ("period_monthly", "M"),
+                    ("period_yearly", "y"),
+                ],
+                "Daily / weekly / monthly / yearly periods",
+            ),
+            (&[("export", "e")], "Export current view (JSON / chart CSV)"),
+            (
+                &[("toggle_activity_weight", "W")],
+                "Activity charts by commits / lines",
+            ),
+            (
+                &[("toggle_commits_by_repo", "p")],
+                "Commits chart per repository / combined",
+            ),
+            (
+                &[("enter_inspect", "i"), ("enter_inspect", "Enter")],
+                "Inspect chart values (←/→ move, Esc exits)",
+            ),
+            (
+                &[("", "0-9"), ("prev_repo", "{"), ("next_repo", "}")],
+                "Switch repository tab (0 = all)",
+            ),
+            (&[("", "1-9")], "Repeat the next move, like 10k"),
+            (
+                &[("quit", "q"), ("quit", "Esc")],
+                "Quit (Esc drops a count)",
+            ),
+            (&[("", "Ctrl+C")], "Force quit"),
+        ],
+    ),
+    (
+        "Single mode",
+        &[
+            (
+                &[
+                    ("next_chart", "Tab"),
+                    ("next_chart", "→"),
+                    ("next_chart", "l"),
+                ],
+                "Next chart",
+            ),
+            (
+                &[
+                    ("prev_chart", "S-Tab"),
+                    ("prev_chart", "←"),
+                    ("prev_chart", "h"),
+                ],
+                "Previous chart",
+            ),
+            (
+                &[("pan_left", "["), ("pan_right", "]")],
+                "Pan line charts to older / newer periods",
+            ),
+            (
+                &[("scroll_up", "↑"), ("scroll_up", "k")],
+                "Scroll Add/Del to older periods",
+            ),
+            (
+                &[("scroll_down", "↓"), ("scroll_down", "j")],
+                "Scroll Add/Del to newer periods",
+            ),
+            (
+                &[("page_up", "PgUp"), ("page_up", "C-u")],
+                "Scroll Add/Del a page older",
+            ),
+            (
+                &[("page_down", "PgDn"), ("page_down", "C-d")],
+                "Scroll Add/Del a page newer",
+            ),
+            (
+                &[("scroll_to_oldest", "g"), ("scroll_to_newest", "G")],
+                "Jump Add/Del to oldest / newest",
+            ),
+        ],
+    ),
+    (
+        "Split mode",
+        &[
+            (
+                &[("next_panel", "Tab"), ("prev_panel", "S-Tab")],
+                "Focus next / previous panel",
+            ),
+            (
+                &[("scroll_up", "↑"), ("scroll_up", "k")],
+                "Scroll focused Add/Del to older periods",
+            ),
+            (
+                &[("scroll_down", "↓"), ("scroll_down", "j")],
+                "Scroll focused Add/Del to newer periods",
+            ),
+            (
+                &[("page_up", "PgUp"), ("page_up", "C-u")],
+                "Scroll focused Add/Del a page older",
+            ),
+            (
+                &[("page_down", "PgDn"), ("page_down", "C-d")],
+                "Scroll focused Add/Del a page newer",
+            ),
+            (
+                &[("scroll_to_oldest", "g"), ("scroll_to_newest", "G")],
+                "Jump focused Add/Del to oldest / newest",
+            ),
+            (&[("toggle_summary", "s")], "Show / hide summary cards"),
+        ],
+    ),
+];
+
+/// Narrowest key column in the help overlay
+const HELP_KEY_WIDTH: usize = 13;
+
+/// Effective keys of a help row: configured keys first, then the built-in
+/// keys that the config has not taken for another action
+fn help_keys(keys: &[(&str, &str)], keymap: &Keymap) -> String {
+    let mut shown: Vec<String> = Vec::new();
+    let mut listed: Vec<&str> = Vec::new();
+    for &(name, builtin) in keys {
+        if !name.is_empty() && !listed.contains(&name) {
+            listed.push(name);
+            shown.extend(keymap.keys_for(name).iter().map(ToString::to_string));
+        }
+        let rebound = !name.is_empty()
+            && builtin
+                .parse::<KeySpec>()
+                .is_ok_and(|key| keymap.rebinds(key));
+        if !rebound {
+            shown.push(builtin.to_string());
+        }
+    }
+    shown.join(", ")
+}
+
+/// Render the keybinding help as a centered popup over the current view
+fn render_help_overlay(frame: &mut Frame, area: Rect, theme: &Theme, keymap: &Keymap) {
+    let sections: Vec<(&str, Vec<(String, &str)>)> = HELP_SECTIONS
+        .iter()
+        .map(|(section, rows)| {
+            let rows = rows
+                .iter()
+                .map(|(keys, description)| (help_keys(keys, keymap), *description))
+                .collect();
+            (*section, rows)
+        })
+        .collect();
+    let key_width = sections
+        .iter()
+        .flat_map(|(_, rows)| rows.iter().map(|(keys, _)| keys.chars().count() + 1))
+        .max()
+        .unwrap_or(0)
+        .max(HELP_KEY_WIDTH);
+
+    let mut lines = Vec::new();
+    for (i, (section, rows)) in sections.into_iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::default());
+        }
+        lines.push(Line::styled(
+            section,
+            Style::default().fg(theme.title).bold(),
+        ));
+        for (keys, description) in rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {keys:<key_width$}"),
+                    Style::default().fg(theme.header),
+                ),
+                Span::raw(description),
+            ]));
+        }
+    }
+    lines.push(Line::default());
+    lines.push(Line::styled(
+        "Press any key to close",
+        Style::default().fg(theme.text_dim),
+    ));
+
+    let content_width = lines.iter().map(Line::width).max().unwrap_or(0);
+    let width = u16::try_from(content_width + 4).unwrap_or(u16::MAX);
+    let height = u16::try_from(lines.len() + 2).unwrap_or(u16::MAX);
+    let popup = centered_rect(area, width, height);
+
+    let block = Block::default()
+        .title(" Keybindings ")
+        .title_style(Style::default().fg(theme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .padding(Padding::horizontal(1));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().fg(theme.border)),
+        popup,
+    );
+}
+
+/// Compute a rectangle of the given size centered within `area`, clamped to fit
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let [vertical] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [centered] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(vertical);
+    centered
+}
+
 fn format_date_range(from: &str, to: &str) -> String {
     format!("{from} → {to}")
 }
@@ -201,6 +1138,43 @@ fn format_date_range(from: &str, to: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hour_layout_width_boundaries() {
+        assert_eq!(hour_chart_width(1), chart_width(24));
+        assert_eq!(hour_layout(chart_width(24)), HourLayout::Bars(1));
+        assert_eq!(hour_layout(chart_width(24) - 1), HourLayout::Bars(2));
+        // Twelve bars widened to fit "10-11"
+        assert_eq!(hour_chart_width(2), 73);
+        assert_eq!(hour_layout(73), HourLayout::Bars(2));
+        assert_eq!(hour_layout(72), HourLayout::Bars(3));
+        assert_eq!(hour_chart_width(3), 49);
+        assert_eq!(hour_layout(49), HourLayout::Bars(3));
+        assert_eq!(hour_layout(48), HourLayout::Rows);
+        assert_eq!(hour_layout(0), HourLayout::Rows);
+    }
+
+    #[test]
+    fn test_hour_buckets_sum_and_label() {
+        let hourly: [u64; 24] = std::array::from_fn(|hour| hour as u64);
+
+        let single = hour_buckets(&hourly, 1);
+        assert_eq!(single.len(), 24);
+        assert_eq!(single[23], ("23".to_string(), 23));
+
+        let pairs = hour_buckets(&hourly, 2);
+        assert_eq!(pairs.len(), 12);
+        assert_eq!(pairs[0], ("0-1".to_string(), 1));
+        assert_eq!(pairs[5], ("10-11".to_string(), 21));
+
+        let rows = hour_buckets(&hourly, HOUR_ROW_SIZE);
+        let labels: Vec<_> = rows.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, ["0-3", "4-7", "8-11", "12-15", "16-19", "20-23"]);
+        assert_eq!(
+            rows.iter().map(|(_, n)| n).sum::<u64>(),
+            hourly.iter().sum::<u64>()
+        );
+    }
+
     #[test]
     fn test_format_date_range() {
         assert_eq!(
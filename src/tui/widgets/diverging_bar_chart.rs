@@ -8,27 +8,65 @@
     clippy::too_many_lines
 )]
 
-use crate::tui::app::App;
+use super::label::{format_number, pad_start, truncate_tail};
+use super::min_size::render_min_size_hint;
+use crate::tui::app::{AddDelDataPoint, App};
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
 
-/// Minimum width required to render the chart
-const MIN_WIDTH: u16 = 20;
+/// Smallest area the chart renders in: room for a label and a short bar
+pub const DIVERGING_BAR_CHART_MIN_SIZE: Size = Size::new(20, 3);
+
+/// Narrowest bar half (in cells) that still leaves room for value annotations
+const MIN_ANNOTATED_BAR: u16 = 6;
+
+/// Periods of `total` on screen in `rows` rows, scrolled `scroll_offset`
+/// periods back from the newest
+///
+/// The offset is clamped so the oldest period always stays on screen.
+#[must_use]
+pub fn add_del_window(total: usize, scroll_offset: usize, rows: usize) -> Range<usize> {
+    let end = total - scroll_offset.min(total.saturating_sub(1));
+    end.saturating_sub(rows)..end
+}
+
+/// `END` when `window` reaches the newest of `total` periods and `TOP` when
+/// it reaches the oldest; `None` in between or when every period fits
+#[must_use]
+pub fn scroll_edge(window: &Range<usize>, total: usize) -> Option<&'static str> {
+    if window.len() >= total {
+        None
+    } else if window.end == total {
+        Some("END")
+    } else if window.start == 0 {
+        Some("TOP")
+    } else {
+        None
+    }
+}
+
+/// Position like `rows 42–60/90` of `window` among `total` periods
+#[must_use]
+pub fn rows_label(window: &Range<usize>, total: usize) -> String {
+    format!("rows {}\u{2013}{}/{total}", window.start + 1, window.end)
+}
 
 /// Render a diverging bar chart for additions/deletions
-pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
+///
+/// A `focused` chart gets a highlighted border.
+pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App, focused: bool) {
     let data = app.additions_deletions_data();
+    let theme = &app.theme().for_panel(focused);
 
-    // Check minimum width
-    if area.width < MIN_WIDTH {
-        let msg = Paragraph::new("Too narrow")
-            .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .title(" Additions / Deletions ")
-                    .borders(Borders::ALL),
-            );
-        frame.render_widget(msg, area);
+    if render_min_size_hint(
+        frame,
+        area,
+        "Additions / Deletions",
+        DIVERGING_BAR_CHART_MIN_SIZE,
+        theme,
+    ) {
         return;
     }
 
@@ -54,30 +92,29 @@ pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
     );
 
     // Create block
-    let block = Block::default()
+    let mut block = Block::default()
         .title(title)
-        .title_style(Style::default().fg(Color::Yellow).bold())
+        .title_style(Style::default().fg(theme.title).bold())
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::White));
+        .border_style(Style::default().fg(theme.border));
 
     let inner = block.inner(area);
-    frame.render_widget(block, area);
-
-    if inner.height < 1 || inner.width < 10 {
-        return;
-    }
-
-    // Determine how many rows we can display
-    let available_rows = inner.height as usize;
 
-    // Calculate display range with scroll offset
-    // offset=0 means show latest data (end of array)
-    // offset>0 means scroll up to see older data
+    // offset=0 shows the latest data, offset>0 scrolls back to older data
     let total = data.len();
-    let scroll_offset = app.scroll_offset().min(total.saturating_sub(1));
-    let end = total.saturating_sub(scroll_offset);
-    let start = end.saturating_sub(available_rows);
-    let display_data: Vec<_> = data[start..end].iter().collect();
+    let window = add_del_window(total, app.model().scroll_offset, inner.height as usize);
+    let start = window.start;
+    let display_data: Vec<_> = data[window.clone()].iter().collect();
+
+    // Where the rows sit in the data, when they do not show all of it
+    if window.len() < total {
+        let mut position = vec![Span::raw(format!(" {} ", rows_label(&window, total)))];
+        if let Some(edge) = scroll_edge(&window, total) {
+            position.push(Span::styled(edge, Style::default().fg(theme.title).bold()));
+            position.push(Span::raw(" "));
+        }
+        block = block.title(Line::from(position).right_aligned());
+    }
 
     // Find max value for unified scale
     let max_value = display_data
@@ -87,10 +124,10 @@ pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
         .unwrap_or(1)
         .max(1);
 
-    // Calculate label width (date labels)
+    // Calculate label width in cells (date labels)
     let label_width = display_data
         .iter()
-        .map(|d| d.label.chars().count())
+        .map(|d| d.label.width())
         .max()
         .unwrap_or(10)
         .min(12) as u16;
@@ -99,19 +136,47 @@ pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
     let bar_area_width = inner.width.saturating_sub(label_width + 3); // +3 for " | "
     let half_bar_width = bar_area_width / 2;
 
+    // Bars scale into what is left of each half after the annotation gutter,
+    // so the center line stays put whether or not values are shown
+    let gutter = annotation_gutter(&display_data, half_bar_width);
+    let bar_scale_width = half_bar_width - gutter;
+
+    // Scale legend on the bottom border, when it fits
+    if let Some(legend) = scale_legend(max_value, bar_scale_width)
+        && legend.chars().count() + 2 <= usize::from(inner.width)
+    {
+        block = block.title_bottom(
+            Line::styled(legend, Style::default().fg(theme.text_dim)).right_aligned(),
+        );
+    }
+    frame.render_widget(block, area);
+
     // Render each row
+    let today = app.today_index();
     for (i, point) in display_data.iter().enumerate() {
         let y = inner.y + i as u16;
         if y >= inner.y + inner.height {
             break;
         }
 
-        // Render label (right-aligned, truncated if needed)
+        // Render label (right-aligned, truncated if needed); outlier periods
+        // stand out in the title color, yellow in the default theme, and the
+        // period highlighted in inspect mode is drawn reversed; today's day
+        // is bold and underlined
         let label = truncate_tail(&point.label, label_width as usize);
-        let label_span = Span::styled(
-            format!("{:>width$}", label, width = label_width as usize),
-            Style::default().fg(Color::DarkGray),
-        );
+        let label_color = if point.is_outlier {
+            theme.title
+        } else {
+            theme.text_dim
+        };
+        let mut label_style = Style::default().fg(label_color);
+        if today == Some(start + i) {
+            label_style = label_style.bold().underlined();
+        }
+        if app.model().selected_period() == Some(start + i) {
+            label_style = label_style.fg(theme.title).reversed();
+        }
+        let label_span = Span::styled(pad_start(&label, label_width as usize), label_style);
         frame.render_widget(
             Paragraph::new(label_span),
             Rect::new(inner.x, y, label_width, 1),
@@ -119,12 +184,12 @@ pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
 
         // Calculate bar lengths
         let del_bar_len = if max_value > 0 {
-            ((point.deletions as f64 / max_value as f64) * half_bar_width as f64) as u16
+            ((point.deletions as f64 / max_value as f64) * bar_scale_width as f64) as u16
         } else {
             0
         };
         let add_bar_len = if max_value > 0 {
-            ((point.additions as f64 / max_value as f64) * half_bar_width as f64) as u16
+            ((point.additions as f64 / max_value as f64) * bar_scale_width as f64) as u16
         } else {
             0
         };
@@ -133,12 +198,12 @@ pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
         let bar_start_x = inner.x + label_width + 1;
         let center_x = bar_start_x + half_bar_width;
 
-        // Render deletion bar (red, going left from center)
+        // Render deletion bar (going left from center)
         if del_bar_len > 0 {
             let del_start = center_x.saturating_sub(del_bar_len);
             let del_bar = Span::styled(
                 "\u{2588}".repeat(del_bar_len as usize),
-                Style::default().fg(Color::Red),
+                Style::default().fg(theme.deletion),
             );
             frame.render_widget(
                 Paragraph::new(del_bar),
@@ -146,47 +211,82 @@ pub fn render_diverging_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
             );
         }
 
-        // Render center line
-        let center_span = Span::styled("|", Style::default().fg(Color::DarkGray));
+        // Render deletion value left of its bar, leaving one blank cell
+        if gutter > 0 && point.deletions > 0 {
+            let text = format!("-{}", format_number(point.deletions));
+            let width = text.len() as u16;
+            let x = center_x - del_bar_len - 1 - width;
+            frame.render_widget(
+                Paragraph::new(Span::styled(text, Style::default().fg(theme.text_dim))),
+                Rect::new(x, y, width, 1),
+            );
+        }
+
+        // Render center line, broken on weekend days
+        let center = if app.is_weekend(start + i) {
+            "\u{a6}"
+        } else {
+            "|"
+        };
+        let center_span = Span::styled(center, Style::default().fg(theme.text_dim));
         frame.render_widget(Paragraph::new(center_span), Rect::new(center_x, y, 1, 1));
 
-        // Render addition bar (green, going right from center)
+        // Render addition bar (going right from center)
         if add_bar_len > 0 {
             let add_bar = Span::styled(
                 "\u{2588}".repeat(add_bar_len as usize),
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.addition),
             );
             frame.render_widget(
                 Paragraph::new(add_bar),
                 Rect::new(center_x + 1, y, add_bar_len, 1),
             );
         }
+
+        // Render addition value right of its bar, leaving one blank cell
+        if gutter > 0 && point.additions > 0 {
+            let text = format!("+{}", format_number(point.additions));
+            let width = text.len() as u16;
+            frame.render_widget(
+                Paragraph::new(Span::styled(text, Style::default().fg(theme.text_dim))),
+                Rect::new(center_x + add_bar_len + 2, y, width, 1),
+            );
+        }
     }
 }
 
-/// Truncate a string to the last `max_chars` characters (safe for multi-byte UTF-8)
-fn truncate_tail(label: &str, max_chars: usize) -> String {
-    let count = label.chars().count();
-    if count <= max_chars {
-        return label.to_string();
+/// Legend like ` scale: █ = 25 lines ` for bars `bar_scale_width` cells long at `max_value`
+///
+/// `None` when the bars have no room at all.
+fn scale_legend(max_value: u64, bar_scale_width: u16) -> Option<String> {
+    if bar_scale_width == 0 {
+        return None;
     }
-    label
-        .chars()
-        .rev()
-        .take(max_chars)
-        .collect::<Vec<_>>()
-        .into_iter()
-        .rev()
-        .collect()
+    let per_cell = max_value.div_ceil(u64::from(bar_scale_width));
+    let unit = if per_cell == 1 { "line" } else { "lines" };
+    Some(format!(
+        " scale: \u{2588} = {} {unit} ",
+        format_number(per_cell)
+    ))
 }
 
-fn format_number(value: u64) -> String {
-    if value >= 1_000_000 {
-        format!("{:.1}M", value as f64 / 1_000_000.0)
-    } else if value >= 1_000 {
-        format!("{:.1}K", value as f64 / 1_000.0)
+/// Cells reserved at the outer end of each bar half for value annotations
+///
+/// Sized for the widest signed value in the visible rows plus a blank cell,
+/// or 0 when the bars would get narrower than [`MIN_ANNOTATED_BAR`].
+fn annotation_gutter(rows: &[&AddDelDataPoint], half_bar_width: u16) -> u16 {
+    let widest = rows
+        .iter()
+        .flat_map(|point| [point.additions, point.deletions])
+        .filter(|&value| value > 0)
+        .map(|value| format_number(value).len() as u16 + 1)
+        .max()
+        .unwrap_or(0);
+    let gutter = widest + 1;
+    if widest > 0 && half_bar_width >= gutter + MIN_ANNOTATED_BAR {
+        gutter
     } else {
-        value.to_string()
+        0
     }
 }
 
@@ -195,33 +295,60 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_format_number() {
-        assert_eq!(format_number(100), "100");
-        assert_eq!(format_number(2500), "2.5K");
-        assert_eq!(format_number(2_500_000), "2.5M");
-    }
+    fn test_add_del_window_and_edges() {
+        assert_eq!(add_del_window(90, 0, 19), 71..90);
+        assert_eq!(add_del_window(90, 30, 19), 41..60);
+        // The oldest period stays on screen however far back the offset goes
+        assert_eq!(add_del_window(90, 200, 19), 0..1);
+        assert_eq!(add_del_window(0, 3, 19), 0..0);
 
-    #[test]
-    fn test_truncate_tail_ascii() {
-        // ASCII string that needs truncation
-        assert_eq!(truncate_tail("2024-01-15", 8), "24-01-15");
-        assert_eq!(truncate_tail("abcdefghij", 5), "fghij");
+        assert_eq!(scroll_edge(&(71..90), 90), Some("END"));
+        assert_eq!(scroll_edge(&(41..60), 90), None);
+        assert_eq!(scroll_edge(&(0..19), 90), Some("TOP"));
+        assert_eq!(scroll_edge(&(0..7), 7), None);
+        assert_eq!(rows_label(&(41..60), 90), "rows 42\u{2013}60/90");
     }
 
     #[test]
-    fn test_truncate_tail_no_truncation_needed() {
-        // String shorter than or equal to max_chars
-        assert_eq!(truncate_tail("hello", 10), "hello");
-        assert_eq!(truncate_tail("hello", 5), "hello");
-        assert_eq!(truncate_tail("", 5), "");
+    fn test_scale_legend() {
+        assert_eq!(
+            scale_legend(220, 30).as_deref(),
+            Some(" scale: \u{2588} = 8 lines ")
+        );
+        assert_eq!(
+            scale_legend(12, 40).as_deref(),
+            Some(" scale: \u{2588} = 1 line ")
+        );
+        assert_eq!(
+            scale_legend(250_000, 10).as_deref(),
+            Some(" scale: \u{2588} = 25.0K lines ")
+        );
+        assert_eq!(scale_legend(220, 0), None);
     }
 
     #[test]
-    fn test_truncate_tail_non_ascii() {
-        // Japanese characters (multi-byte UTF-8)
-        assert_eq!(truncate_tail("こんにちは", 3), "にちは");
-        assert_eq!(truncate_tail("日本語テスト", 4), "語テスト");
-        // Mixed ASCII and non-ASCII
-        assert_eq!(truncate_tail("Hello世界", 4), "lo世界");
+    fn test_annotation_gutter_fits_widest_value_or_drops() {
+        let points = [
+            AddDelDataPoint {
+                label: "2024-01-01".to_string(),
+                additions: 3_400,
+                deletions: 12,
+                is_outlier: false,
+            },
+            AddDelDataPoint {
+                label: "2024-01-02".to_string(),
+                additions: 0,
+                deletions: 0,
+                is_outlier: false,
+            },
+        ];
+        let rows: Vec<_> = points.iter().collect();
+
+        // "+3.4K" plus a blank cell
+        assert_eq!(annotation_gutter(&rows, 30), 6);
+        assert_eq!(annotation_gutter(&rows, 6 + MIN_ANNOTATED_BAR), 6);
+        assert_eq!(annotation_gutter(&rows, 5 + MIN_ANNOTATED_BAR), 0);
+        // Nothing to annotate
+        assert_eq!(annotation_gutter(&rows[1..], 30), 0);
     }
 }
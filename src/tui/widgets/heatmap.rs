@@ -0,0 +1,117 @@
+//! Weekday × hour heatmap widget for activity statistics
+
+#![allow(clippy::cast_possible_truncation)]
+
+use crate::tui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use std::fmt::Write;
+
+/// Number of hour columns
+const HOURS: usize = 24;
+/// Row label width ("Mon") plus a separating space
+const LABEL_WIDTH: u16 = 4;
+/// Border width (left + right / top + bottom)
+const BORDER_WIDTH: u16 = 2;
+
+/// Width needed to render the heatmap with two-character cells
+pub const HEATMAP_WIDTH: u16 = LABEL_WIDTH + 2 * HOURS as u16 + BORDER_WIDTH;
+/// Height needed to render the heatmap (hour header + 7 weekday rows)
+pub const HEATMAP_HEIGHT: u16 = 1 + 7 + BORDER_WIDTH;
+
+/// Intensity ramp glyphs from empty to busiest (colors come from the theme)
+const RAMP: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+
+/// Map a value to a ramp index (0 = no activity, 4 = max activity)
+fn intensity(value: u32, max: u32) -> usize {
+    if value == 0 || max == 0 {
+        return 0;
+    }
+    // Scale 1..=max onto 1..=4, rounding up so any activity is visible
+    let step = (u64::from(value) * 4).div_ceil(u64::from(max));
+    step.clamp(1, 4) as usize
+}
+
+/// Render a weekday × hour heatmap
+pub fn render_heatmap(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    row_labels: &[&str],
+    matrix: &[[u32; HOURS]],
+    theme: &Theme,
+) {
+    let max = matrix.iter().flatten().copied().max().unwrap_or(0);
+    let total: u32 = matrix.iter().flatten().sum();
+
+    let block = Block::default()
+        .title(format!(" {title} ({total}) "))
+        .title_style(Style::default().fg(theme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if inner.width < LABEL_WIDTH + HOURS as u16 || inner.height < 2 {
+        return;
+    }
+
+    // Use two-character cells when there is room, otherwise one
+    let cell_width = if inner.width >= LABEL_WIDTH + 2 * HOURS as u16 {
+        2
+    } else {
+        1
+    };
+
+    let mut lines = Vec::with_capacity(row_labels.len() + 1);
+    lines.push(hour_header(cell_width, theme.text_dim));
+
+    for (label, row) in row_labels.iter().zip(matrix) {
+        let mut spans = vec![Span::styled(
+            format!("{label:>3} "),
+            Style::default().fg(theme.text_dim),
+        )];
+        for &value in row {
+            let level = intensity(value, max);
+            spans.push(Span::styled(
+                RAMP[level].repeat(cell_width),
+                Style::default().fg(theme.heatmap[level]),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Build the hour header row with a label every six hours
+fn hour_header(cell_width: usize, color: Color) -> Line<'static> {
+    let mut header = " ".repeat(LABEL_WIDTH as usize);
+    let slot = cell_width * 6;
+    for hour in (0..HOURS).step_by(6) {
+        let _ = write!(header, "{hour:<slot$}");
+    }
+    Line::styled(header, Style::default().fg(color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intensity_levels() {
+        assert_eq!(intensity(0, 10), 0);
+        assert_eq!(intensity(1, 10), 1);
+        assert_eq!(intensity(5, 10), 2);
+        assert_eq!(intensity(8, 10), 4);
+        assert_eq!(intensity(10, 10), 4);
+        assert_eq!(intensity(3, 0), 0);
+    }
+
+    #[test]
+    fn test_heatmap_dimensions() {
+        assert_eq!(HEATMAP_WIDTH, 54);
+        assert_eq!(HEATMAP_HEIGHT, 10);
+    }
+}
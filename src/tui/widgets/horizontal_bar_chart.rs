@@ -7,11 +7,15 @@
     clippy::cast_lossless
 )]
 
+use super::label::{pad_start, truncate_tail};
+use super::min_size::render_min_size_hint;
+use crate::tui::theme::Theme;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
+use unicode_width::UnicodeWidthStr;
 
-/// Minimum width required to render the chart
-const MIN_WIDTH: u16 = 15;
+/// Smallest area the chart renders in: room for a label and a short bar
+pub const HORIZONTAL_BAR_CHART_MIN_SIZE: Size = Size::new(15, 3);
 
 /// Data point for horizontal bar chart
 #[derive(Debug, Clone)]
@@ -38,13 +42,9 @@ pub fn render_horizontal_bar_chart(
     title: &str,
     data: &[BarDataPoint],
     color: Color,
+    theme: &Theme,
 ) {
-    // Check minimum width
-    if area.width < MIN_WIDTH {
-        let msg = Paragraph::new("Too narrow")
-            .alignment(Alignment::Center)
-            .block(Block::default().title(title).borders(Borders::ALL));
-        frame.render_widget(msg, area);
+    if render_min_size_hint(frame, area, title, HORIZONTAL_BAR_CHART_MIN_SIZE, theme) {
         return;
     }
 
@@ -65,15 +65,11 @@ pub fn render_horizontal_bar_chart(
         .title(title_with_total)
         .title_style(Style::default().fg(color).bold())
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::White));
+        .border_style(Style::default().fg(theme.border));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    if inner.height < 1 || inner.width < 5 {
-        return;
-    }
-
     // Determine how many rows we can display
     let available_rows = inner.height as usize;
     let display_data: Vec<_> = data.iter().take(available_rows).collect();
@@ -86,13 +82,13 @@ pub fn render_horizontal_bar_chart(
         .unwrap_or(1)
         .max(1);
 
-    // Calculate label width
+    // Calculate label width (long labels such as file paths get up to half the width)
     let label_width = display_data
         .iter()
-        .map(|d| d.label.chars().count())
+        .map(|d| d.label.width())
         .max()
         .unwrap_or(3)
-        .min(5) as u16;
+        .min(usize::from(inner.width / 2)) as u16;
 
     // Calculate bar area width (excluding label and space)
     let bar_area_width = inner.width.saturating_sub(label_width + 2); // +2 for " " separator
@@ -106,8 +102,11 @@ pub fn render_horizontal_bar_chart(
 
         // Render label (right-aligned)
         let label_span = Span::styled(
-            format!("{:>width$}", point.label, width = label_width as usize),
-            Style::default().fg(Color::DarkGray),
+            pad_start(
+                &truncate_tail(&point.label, label_width as usize),
+                label_width as usize,
+            ),
+            Style::default().fg(theme.text_dim),
         );
         frame.render_widget(
             Paragraph::new(label_span),
@@ -0,0 +1,122 @@
+//! Label fitting by terminal display width
+//!
+//! Double-width characters (CJK) take two cells, so labels are measured with
+//! `unicode-width` rather than by `char` count.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Marker put in front of a label whose start was cut off
+const ELLIPSIS: char = '\u{2026}';
+
+/// Shorten a label to at most `width` cells, keeping its end
+///
+/// The end is what tells labels apart (the file name for paths, the day for
+/// dates), so the start is replaced by `…` when the label does not fit. A
+/// double-width character that would straddle the limit is dropped, so the
+/// result can be one cell narrower than `width`.
+pub fn truncate_tail(label: &str, width: usize) -> String {
+    if label.width() <= width {
+        return label.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    // The ellipsis takes one cell
+    let mut cells = 1;
+    let mut start = label.len();
+    for (i, c) in label.char_indices().rev() {
+        cells += c.width().unwrap_or(0);
+        if cells > width {
+            break;
+        }
+        start = i;
+    }
+    format!("{ELLIPSIS}{}", &label[start..])
+}
+
+/// Right-align `text` in `width` cells, padding with spaces on the left
+pub fn pad_start(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!("{}{text}", " ".repeat(padding))
+}
+
+/// Format a count compactly: `950`, `2.5K`, `1.2M`
+// Display precision only
+#[allow(clippy::cast_precision_loss)]
+pub fn format_number(value: u64) -> String {
+    if value >= 1_000_000 {
+        format!("{:.1}M", value as f64 / 1_000_000.0)
+    } else if value >= 1_000 {
+        format!("{:.1}K", value as f64 / 1_000.0)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format a signed value like [`format_number`]: `-950`, `12.4K`, `-1.2M`
+pub fn format_signed(value: i64) -> String {
+    let magnitude = format_number(value.unsigned_abs());
+    if value < 0 {
+        format!("-{magnitude}")
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(100), "100");
+        assert_eq!(format_number(2500), "2.5K");
+        assert_eq!(format_number(2_500_000), "2.5M");
+    }
+
+    #[test]
+    fn test_format_signed() {
+        assert_eq!(format_signed(0), "0");
+        assert_eq!(format_signed(950), "950");
+        assert_eq!(format_signed(-2500), "-2.5K");
+        assert_eq!(format_signed(-1_200_000), "-1.2M");
+    }
+
+    #[test]
+    fn test_truncate_tail_ascii() {
+        assert_eq!(truncate_tail("2024-01-15", 8), "\u{2026}4-01-15");
+        assert_eq!(truncate_tail("src/cli/run.rs", 10), "\u{2026}li/run.rs");
+        assert_eq!(truncate_tail("abcdefghij", 5).width(), 5);
+    }
+
+    #[test]
+    fn test_truncate_tail_no_truncation_needed() {
+        assert_eq!(truncate_tail("hello", 10), "hello");
+        assert_eq!(truncate_tail("hello", 5), "hello");
+        assert_eq!(truncate_tail("", 5), "");
+        assert_eq!(truncate_tail("src", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_tail_double_width() {
+        // Each Japanese character takes two cells
+        assert_eq!(truncate_tail("日本語", 6), "日本語");
+        assert_eq!(truncate_tail("日本語テスト", 7), "\u{2026}テスト");
+        assert_eq!(truncate_tail("日本語テスト", 7).width(), 7);
+        // A character straddling the limit is dropped rather than split
+        assert_eq!(truncate_tail("日本語テスト", 8), "\u{2026}テスト");
+        assert_eq!(truncate_tail("日本語テスト", 8).width(), 7);
+        // Mixed ASCII and double-width
+        assert_eq!(truncate_tail("Hello世界", 6), "\u{2026}o世界");
+        assert_eq!(truncate_tail("世界", 1), "\u{2026}");
+    }
+
+    #[test]
+    fn test_pad_start_counts_cells() {
+        assert_eq!(pad_start("Mon", 5), "  Mon");
+        assert_eq!(pad_start("日本", 5), " 日本");
+        assert_eq!(pad_start("日本", 5).width(), 5);
+        assert_eq!(pad_start("日本語", 4), "日本語");
+    }
+}
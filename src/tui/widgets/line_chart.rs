@@ -2,97 +2,825 @@
 
 #![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
 
-use crate::tui::app::{App, Metric};
+use super::label::{format_signed, pad_start};
+use super::min_size::render_min_size_hint;
+use crate::cli::args::Period;
+use crate::tui::app::{App, Metric, OTHER_REPOS, RepoSeries};
+use crate::tui::theme::Theme;
 use ratatui::prelude::*;
 use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns taken by the borders and Y axis labels of a line chart
+const LINE_CHART_CHROME: u16 = 8;
+
+/// Smallest area a line chart renders in: a dozen periods over a few rows,
+/// with room for the date labels below
+pub const LINE_CHART_MIN_SIZE: Size = Size::new(LINE_CHART_CHROME + 12, 6);
+
+/// Number of periods shown at once in a line chart `width` columns wide
+///
+/// Each period gets at least one terminal column so the line stays readable.
+#[must_use]
+pub fn line_chart_window(width: u16) -> usize {
+    usize::from(width.saturating_sub(LINE_CHART_CHROME).max(2))
+}
+
+/// Index range of the periods visible with `window` periods shown, panned
+/// `offset` periods back from the newest
+///
+/// The offset is clamped so the window is always full when there is enough data.
+#[must_use]
+pub fn visible_window(total: usize, offset: usize, window: usize) -> Range<usize> {
+    let window = window.min(total);
+    let end = total - offset.min(total - window);
+    end - window..end
+}
 
 /// Render a line chart for a specific metric
-pub fn render_line_chart_for_metric(frame: &mut Frame, area: Rect, app: &App, metric: Metric) {
-    let values = app.values_for_metric(metric);
+///
+/// In single mode only the periods that fit the width are drawn, starting at
+/// the app's window offset; split mode always shows the whole range. With
+/// `--moving-average`, the smoothed series is drawn over it in a dimmer color.
+/// Negative values (net lines) are drawn below a dim zero baseline. A
+/// `focused` chart gets a highlighted border. With the per-repository toggle
+/// on, the Commits chart draws [`App::repo_series`] instead. Daily periods
+/// mark today with a vertical line and weekend days with dots on the bottom
+/// edge, as the other line charts do.
+pub fn render_line_chart_for_metric(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    metric: Metric,
+    focused: bool,
+) {
+    let all_values = app.values_for_metric(metric);
+    let theme = &app.theme().for_panel(focused);
 
-    if values.is_empty() {
-        let empty = Paragraph::new("No data to display")
-            .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .title(format!(" {} ", metric.name()))
-                    .borders(Borders::ALL),
-            );
-        frame.render_widget(empty, area);
+    if render_min_size_hint(frame, area, metric.name(), LINE_CHART_MIN_SIZE, theme) {
         return;
     }
+    if all_values.is_empty() {
+        render_empty(frame, area, metric.name());
+        return;
+    }
+    if metric == Metric::Commits {
+        let series = app.repo_series();
+        if !series.is_empty() {
+            render_repo_series_chart(frame, area, app, &all_values, &series, theme);
+            return;
+        }
+    }
 
-    // Convert to chart data points (use absolute values for consistency)
-    let data_points: Vec<(f64, f64)> = values
-        .iter()
+    let range = if app.model().single_metric {
+        visible_window(
+            all_values.len(),
+            app.model().window_offset,
+            line_chart_window(area.width),
+        )
+    } else {
+        0..all_values.len()
+    };
+    let average = app.moving_average_for_metric(metric);
+    let average_points: Vec<(f64, f64)> = average
+        .map(|(_, averages)| averages[range.clone()].iter())
+        .into_iter()
+        .flatten()
         .enumerate()
-        .map(|(i, (_, v))| (i as f64, v.abs() as f64))
+        .map(|(i, v)| (i as f64, *v))
         .collect();
+    let values = &all_values[range.clone()];
 
-    // Calculate bounds
-    let max_y = values.iter().map(|(_, v)| v.abs()).max().unwrap_or(1) as f64;
-    let y_max = max_y * 1.1;
+    let data_points = plot_points(values);
 
-    // Calculate total for title
-    let total: i64 = values.iter().map(|(_, v)| *v).sum();
-    let title = format!(" {} (Total: {}) ", metric.name(), format_number(total));
+    let merges = merge_series(app, metric, &range);
+    let merge_points = merges.as_ref().map_or(&[][..], |(points, _)| points);
+    let overlays: Vec<(f64, f64)> = average_points.iter().chain(merge_points).copied().collect();
+    let (min_y, max_y) = metric_bounds(values, &overlays);
+    let y_bounds = [min_y as f64, max_y.max(min_y + 1) as f64];
+    let x_max = (values.len() - 1).max(1) as f64;
 
-    // Create dataset (no name to avoid legend display)
-    let dataset = Dataset::default()
-        .marker(symbols::Marker::Braille)
-        .graph_type(GraphType::Line)
-        .style(Style::default().fg(Color::Cyan))
-        .data(&data_points);
+    // The title total always covers the whole range, not just the visible window
+    let title = line_chart_title(
+        metric,
+        app.total_for_metric(metric),
+        merges
+            .is_some()
+            .then(|| app.total_for_metric(Metric::Merges)),
+        average.map(|(window, _)| window),
+        app.model().period,
+    );
+
+    // Create datasets (no name to avoid legend display); the average goes on top
+    let baseline = [(0.0, 0.0), (x_max, 0.0)];
+    let marker = selection_marker(app, &range, y_bounds);
+    let (today, weekends) = day_markers(app, &range, y_bounds);
+    let mut datasets = Vec::with_capacity(6);
+    datasets.push(ticks(&weekends, theme.text_dim));
+    if min_y < 0 {
+        datasets.push(line(&baseline, theme.text_dim));
+    }
+    if let Some(today) = &today {
+        datasets.push(line(today, theme.header));
+    }
+    if let Some(marker) = &marker {
+        datasets.push(line(marker, theme.title));
+    }
+    if merges.is_some() {
+        datasets.push(line(merge_points, theme.text_dim));
+    }
+    datasets.push(line(&data_points, theme.bar_primary));
+    if average.is_some() {
+        datasets.push(line(&average_points, theme.text_dim));
+    }
+
+    // Split mode is too short to spare the rows
+    let x_labels = edge_labels(values, app.model().single_metric);
+
+    let gutter = metric_gutter(
+        &all_values,
+        merges.as_ref().map(|(_, max)| *max),
+        average.map(|(_, averages)| averages),
+    );
+    let y_labels = y_axis_labels(min_y, max_y, plot_rows(area, &x_labels), gutter);
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.title).bold())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([0.0, x_max])
+                .labels(x_labels)
+                .labels_alignment(Alignment::Right),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds(y_bounds)
+                .labels(y_labels),
+        );
+
+    frame.render_widget(chart, area);
+}
+
+/// Y label width for a metric chart, sized for the whole range of `values`,
+/// the most merges and the moving average, so panning never moves the plot
+fn metric_gutter(values: &[(String, i64)], merges: Option<i64>, average: Option<&[f64]>) -> usize {
+    label_gutter(
+        values.iter().map(|(_, v)| *v).chain(merges).chain(
+            average
+                .into_iter()
+                .flatten()
+                .flat_map(|v| [v.floor() as i64, v.ceil() as i64]),
+        ),
+    )
+}
+
+/// Merge commits per period in `range` and the most in any period of the
+/// whole range, drawn dim under the Commits line
+///
+/// `None` for other metrics and when the range has no merges, which is
+/// always the case without `--include-merges`.
+fn merge_series(app: &App, metric: Metric, range: &Range<usize>) -> Option<(Vec<(f64, f64)>, i64)> {
+    if metric != Metric::Commits || app.total_for_metric(Metric::Merges) == 0 {
+        return None;
+    }
+    let merges = app.values_for_metric(Metric::Merges);
+    let max = merges.iter().map(|(_, v)| *v).max().unwrap_or(0);
+    Some((plot_points(&merges[range.clone()]), max))
+}
+
+/// Render commits per period with one line per repository and a color legend below
+///
+/// `labels` are the combined view's periods, which every series is aligned
+/// with. The visible window follows the same rules as the combined line; the
+/// moving average is not drawn.
+fn render_repo_series_chart(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    labels: &[(String, i64)],
+    series: &[RepoSeries],
+    theme: &Theme,
+) {
+    let range = if app.model().single_metric {
+        visible_window(
+            labels.len(),
+            app.model().window_offset,
+            line_chart_window(area.width),
+        )
+    } else {
+        0..labels.len()
+    };
+    let points: Vec<Vec<(f64, f64)>> = series
+        .iter()
+        .map(|repo| {
+            repo.commits[range.clone()]
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i as f64, *v as f64))
+                .collect()
+        })
+        .collect();
+    let labels = &labels[range.clone()];
+    let max_y = series
+        .iter()
+        .flat_map(|repo| repo.commits[range.clone()].iter().copied())
+        .max()
+        .unwrap_or(0);
+    let x_max = (labels.len() - 1).max(1) as f64;
+
+    let dim = Style::default().fg(theme.text_dim);
+    let title = format!(
+        " Commits by Repository (Total: {}) ",
+        format_signed(app.total_for_metric(Metric::Commits))
+    );
+    let block = Block::default()
+        .title(title)
+        .title_style(Style::default().fg(theme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if inner.height < 2 {
+        return;
+    }
+    let [chart_area, legend_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+
+    let colors = repo_colors(series, theme);
+    let y_bounds = [0.0, max_y.max(1) as f64];
+    let marker = selection_marker(app, &range, y_bounds);
+    let (today, weekends) = day_markers(app, &range, y_bounds);
+    let datasets = std::iter::once(ticks(&weekends, theme.text_dim))
+        .chain(today.iter().map(|today| line(today, theme.header)))
+        .chain(marker.iter().map(|marker| line(marker, theme.title)))
+        .chain(
+            points
+                .iter()
+                .zip(&colors)
+                .map(|(data, color)| line(data, *color)),
+        )
+        .collect();
+
+    let x_labels = edge_labels(labels, app.model().single_metric);
+    let gutter = label_gutter(series.iter().flat_map(|repo| repo.commits.iter().copied()));
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .style(dim)
+                .bounds([0.0, x_max])
+                .labels(x_labels)
+                .labels_alignment(Alignment::Right),
+        )
+        .y_axis(
+            Axis::default()
+                .style(dim)
+                .bounds(y_bounds)
+                .labels(y_axis_labels(0, max_y, 0, gutter)),
+        );
+    frame.render_widget(chart, chart_area);
+
+    let mut legend = Vec::new();
+    for (i, (repo, color)) in series.iter().zip(&colors).enumerate() {
+        if i > 0 {
+            legend.push(Span::raw("  "));
+        }
+        legend.push(Span::styled("\u{25a0} ", Style::default().fg(*color)));
+        legend.push(Span::styled(repo.name.as_str(), dim));
+    }
+    frame.render_widget(Paragraph::new(Line::from(legend)), legend_area);
+}
+
+/// Color for each repository line: the theme palette in order, with `other` dim
+///
+/// The palette has a slot for every line [`App::repo_series`] draws on its own.
+fn repo_colors(series: &[RepoSeries], theme: &Theme) -> Vec<Color> {
+    let palette = &theme.languages;
+    series
+        .iter()
+        .enumerate()
+        .map(|(i, repo)| {
+            if repo.name == OTHER_REPOS {
+                theme.text_dim
+            } else {
+                palette[i % palette.len()]
+            }
+        })
+        .collect()
+}
+
+/// Render the running total of net lines over the analysis range
+///
+/// Unlike [`render_line_chart_for_metric`], values keep their sign and the
+/// Y axis extends below zero when the running total goes negative.
+pub fn render_cumulative_chart(frame: &mut Frame, area: Rect, app: &App) {
+    const NAME: &str = "Cumulative Net Lines";
+    let values = app.cumulative_net_lines();
+    let theme = app.theme();
+
+    if render_min_size_hint(frame, area, NAME, LINE_CHART_MIN_SIZE, theme) {
+        return;
+    }
+    if values.is_empty() {
+        render_empty(frame, area, NAME);
+        return;
+    }
+
+    let data_points = plot_points(&values);
+
+    let [y_min, y_max] = zero_based_bounds(&values);
+    let x_max = (values.len() - 1).max(1) as f64;
+
+    let final_total = values.last().map_or(0, |(_, v)| *v);
+    let title = format!(" {NAME} (Total: {}) ", format_signed(final_total));
+
+    let baseline = [(0.0, 0.0), (x_max, 0.0)];
+    let (today, weekends) = day_markers(app, &(0..values.len()), [y_min, y_max]);
+    let mut datasets = Vec::with_capacity(4);
+    datasets.push(ticks(&weekends, theme.text_dim));
+    if y_min < 0.0 {
+        datasets.push(line(&baseline, theme.text_dim));
+    }
+    if let Some(today) = &today {
+        datasets.push(line(today, theme.header));
+    }
+    datasets.push(line(&data_points, theme.bar_primary));
 
-    // Simple Y-axis labels
     let y_labels = vec![
-        Span::raw("0"),
-        Span::raw(format_number((y_max / 2.0) as i64)),
-        Span::raw(format_number(y_max as i64)),
+        Span::raw(format_signed(y_min as i64)),
+        Span::raw(format_signed(f64::midpoint(y_min, y_max) as i64)),
+        Span::raw(format_signed(y_max as i64)),
     ];
 
-    let chart = Chart::new(vec![dataset])
+    let chart = Chart::new(datasets)
         .block(
             Block::default()
                 .title(title)
-                .title_style(Style::default().fg(Color::Yellow).bold())
+                .title_style(Style::default().fg(theme.title).bold())
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::White)),
+                .border_style(Style::default().fg(theme.border)),
         )
         .x_axis(
             Axis::default()
-                .style(Style::default().fg(Color::DarkGray))
-                .bounds([0.0, (values.len() - 1).max(1) as f64]),
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([0.0, x_max]),
         )
         .y_axis(
             Axis::default()
-                .style(Style::default().fg(Color::Gray))
-                .bounds([0.0, y_max])
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([y_min, y_max])
                 .labels(y_labels),
         );
 
     frame.render_widget(chart, area);
 }
 
-fn format_number(value: i64) -> String {
-    if value.abs() >= 1_000_000 {
-        format!("{:.1}M", value as f64 / 1_000_000.0)
-    } else if value.abs() >= 1_000 {
-        format!("{:.1}K", value as f64 / 1_000.0)
+/// Render net lines (additions minus deletions) per period
+///
+/// Values keep their sign around a zero baseline: the line takes the
+/// addition color above zero and the deletion color below it. Like the
+/// Commits chart, only the periods that fit are drawn, panned by the app's
+/// window offset.
+pub fn render_net_lines_chart(frame: &mut Frame, area: Rect, app: &App) {
+    const NAME: &str = "Net Lines";
+    let all_values = app.values_for_metric(Metric::AdditionsAndDeletions);
+    let theme = app.theme();
+
+    if render_min_size_hint(frame, area, NAME, LINE_CHART_MIN_SIZE, theme) {
+        return;
+    }
+    if all_values.is_empty() {
+        render_empty(frame, area, NAME);
+        return;
+    }
+
+    let range = visible_window(
+        all_values.len(),
+        app.model().window_offset,
+        line_chart_window(area.width),
+    );
+    let values = &all_values[range.clone()];
+
+    let data_points = plot_points(values);
+
+    let [y_min, y_max] = zero_based_bounds(values);
+    let x_max = (values.len() - 1).max(1) as f64;
+
+    // The title total always covers the whole range, not just the visible window
+    let total: i64 = all_values.iter().map(|(_, v)| *v).sum();
+    let title = format!(" {NAME} (Total: {}) ", format_signed(total));
+
+    let baseline = [(0.0, 0.0), (x_max, 0.0)];
+    let (above, below) = split_at_zero(&data_points);
+    let marker = selection_marker(app, &range, [y_min, y_max]);
+    let (today, weekends) = day_markers(app, &range, [y_min, y_max]);
+    let mut datasets = vec![
+        ticks(&weekends, theme.text_dim),
+        line(&baseline, theme.text_dim),
+    ];
+    datasets.extend(today.iter().map(|today| line(today, theme.header)));
+    datasets.extend(marker.iter().map(|marker| line(marker, theme.title)));
+    for (runs, color) in [(&above, theme.addition), (&below, theme.deletion)] {
+        datasets.extend(runs.iter().map(|run| line(run, color)));
+    }
+
+    let y_labels = vec![
+        Span::raw(format_signed(y_min as i64)),
+        Span::raw(format_signed(f64::midpoint(y_min, y_max) as i64)),
+        Span::raw(format_signed(y_max as i64)),
+    ];
+
+    let x_labels = edge_labels(values, true);
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.title).bold())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([0.0, x_max])
+                .labels(x_labels)
+                .labels_alignment(Alignment::Right),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([y_min, y_max])
+                .labels(y_labels),
+        );
+
+    frame.render_widget(chart, area);
+}
+
+/// `values` as chart points, one per period from x = 0
+fn plot_points(values: &[(String, i64)]) -> Vec<(f64, f64)> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, (_, v))| (i as f64, *v as f64))
+        .collect()
+}
+
+/// Braille line through `data` in `color`, without a legend name
+fn line(data: &[(f64, f64)], color: Color) -> Dataset<'_> {
+    Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data)
+}
+
+/// Vertical line across `y_bounds` at the period highlighted in inspect mode,
+/// when it is among the periods in `range`
+fn selection_marker(
+    app: &App,
+    range: &Range<usize>,
+    y_bounds: [f64; 2],
+) -> Option<[(f64, f64); 2]> {
+    let x = app
+        .model()
+        .selected_period()
+        .filter(|index| range.contains(index))?
+        .checked_sub(range.start)? as f64;
+    Some([(x, y_bounds[0]), (x, y_bounds[1])])
+}
+
+/// The today line, if any, and the weekend points of a line chart
+type DayMarkers = (Option<[(f64, f64); 2]>, Vec<(f64, f64)>);
+
+/// Markers for daily periods in `range`: a vertical line across `y_bounds`
+/// at today, and a point on the bottom edge under each weekend day
+///
+/// Both are empty for weekly and longer periods.
+fn day_markers(app: &App, range: &Range<usize>, y_bounds: [f64; 2]) -> DayMarkers {
+    let today = app
+        .today_index()
+        .filter(|index| range.contains(index))
+        .map(|index| {
+            let x = (index - range.start) as f64;
+            [(x, y_bounds[0]), (x, y_bounds[1])]
+        });
+    let weekends = range
+        .clone()
+        .filter(|&index| app.is_weekend(index))
+        .map(|index| ((index - range.start) as f64, y_bounds[0]))
+        .collect();
+    (today, weekends)
+}
+
+/// Dim dots at `data`, drawn under the lines as weekend ticks
+fn ticks(data: &[(f64, f64)], color: Color) -> Dataset<'_> {
+    Dataset::default()
+        .marker(symbols::Marker::Dot)
+        .graph_type(GraphType::Scatter)
+        .style(Style::default().fg(color))
+        .data(data)
+}
+
+/// X axis labels naming the first and last period, or none when `shown` is false
+///
+/// A single period gets no labels, since both would name it.
+fn edge_labels(values: &[(String, i64)], shown: bool) -> Vec<Span<'static>> {
+    match (values.first(), values.last()) {
+        (Some((first, _)), Some((last, _))) if shown && values.len() > 1 => {
+            vec![Span::raw(first.clone()), Span::raw(last.clone())]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Columns for the Y axis labels: the widest label any value in `values`
+/// (or zero) can get
+fn label_gutter(values: impl IntoIterator<Item = i64>) -> usize {
+    values
+        .into_iter()
+        .chain([0])
+        .map(|value| format_signed(value).width())
+        .max()
+        .unwrap_or(1)
+}
+
+/// Rows of the plot inside a bordered chart `area`, below which `x_labels`
+/// take a row for themselves and one for the axis line
+fn plot_rows(area: Rect, x_labels: &[Span]) -> u16 {
+    let rows = area.height.saturating_sub(2);
+    if x_labels.is_empty() {
+        rows
+    } else {
+        rows.saturating_sub(2)
+    }
+}
+
+/// Y axis labels reading `min` at the bottom and `max` at the top,
+/// right-aligned in `gutter` columns
+///
+/// When the axis spans both signs, a `0` label goes on the row of the
+/// `rows` tall plot where the zero baseline is drawn, with blank labels on
+/// the rows between. A zero within a row of either end shares that row with
+/// its label, which keeps the value.
+fn y_axis_labels(min: i64, max: i64, rows: u16, gutter: usize) -> Vec<Span<'static>> {
+    let label = |value: i64| Span::raw(pad_start(&format_signed(value), gutter));
+    let rows = usize::from(rows);
+    if min < 0 && max > 0 && rows >= 3 {
+        // Braille dots from the top, rounded down like the chart's canvas;
+        // max and max - min are both positive here
+        let dots = (rows * 4 - 1) as f64;
+        #[allow(clippy::cast_sign_loss)]
+        let zero_row = (max as f64 * dots / (max - min) as f64) as usize / 4;
+        if (1..rows - 1).contains(&zero_row) {
+            // Ratatui spreads the labels evenly, so one per row from the bottom
+            let mut labels = vec![Span::raw(""); rows];
+            labels[0] = label(min);
+            labels[rows - 1 - zero_row] = label(0);
+            labels[rows - 1] = label(max);
+            return labels;
+        }
+    }
+    vec![label(min), label(max)]
+}
+
+/// Y axis min and max for the generic metric chart
+///
+/// The axis spans exactly the visible values so its labels read as min and
+/// max, widened to whole numbers around an average carried in from before
+/// the window. When anything is negative the axis also reaches zero, so the
+/// baseline is visible.
+fn metric_bounds(values: &[(String, i64)], average: &[(f64, f64)]) -> (i64, i64) {
+    let average_min = average.iter().map(|(_, v)| v.floor() as i64);
+    let average_max = average.iter().map(|(_, v)| v.ceil() as i64);
+    let min = values
+        .iter()
+        .map(|(_, v)| *v)
+        .chain(average_min)
+        .min()
+        .unwrap_or(0);
+    let max = values
+        .iter()
+        .map(|(_, v)| *v)
+        .chain(average_max)
+        .max()
+        .unwrap_or(0);
+    if min < 0 {
+        (min, max.max(0))
     } else {
-        value.to_string()
+        (min, max)
+    }
+}
+
+/// Y bounds spanning `values` and zero, padded on the sides that have data
+///
+/// Always at least one unit tall, so all-zero data still gets a valid axis.
+fn zero_based_bounds(values: &[(String, i64)]) -> [f64; 2] {
+    let min = values.iter().map(|(_, v)| *v).min().unwrap_or(0).min(0) as f64;
+    let max = values.iter().map(|(_, v)| *v).max().unwrap_or(0).max(0) as f64;
+    let padding = ((max - min) * 0.1).max(1.0);
+    let y_min = if min < 0.0 { min - padding } else { 0.0 };
+    let y_max = if max > 0.0 { max + padding } else { 0.0 }.max(y_min + 1.0);
+    [y_min, y_max]
+}
+
+/// A line split into runs at or above zero and runs below it
+type SignedRuns = (Vec<Vec<(f64, f64)>>, Vec<Vec<(f64, f64)>>);
+
+/// Split a line where it crosses zero, into runs at or above zero and runs below
+///
+/// The crossing point ends one run and starts the next so the runs join up.
+fn split_at_zero(points: &[(f64, f64)]) -> SignedRuns {
+    let mut above = Vec::new();
+    let mut below = Vec::new();
+    let mut run: Vec<(f64, f64)> = Vec::new();
+    let mut run_below = false;
+
+    for &point in points {
+        let is_below = point.1 < 0.0;
+        if let Some(&last) = run.last()
+            && is_below != run_below
+        {
+            // The signs differ, so the denominator is never zero
+            let x = last.0 + (point.0 - last.0) * last.1 / (last.1 - point.1);
+            run.push((x, 0.0));
+            let finished = std::mem::replace(&mut run, vec![(x, 0.0)]);
+            if run_below {
+                below.push(finished);
+            } else {
+                above.push(finished);
+            }
+        }
+        run_below = is_below;
+        run.push(point);
+    }
+
+    if !run.is_empty() {
+        if run_below {
+            below.push(run);
+        } else {
+            above.push(run);
+        }
+    }
+    (above, below)
+}
+
+/// Title like ` Commits (Total: 20) `, naming the moving average when one is drawn
+fn line_chart_title(
+    metric: Metric,
+    total: i64,
+    merges: Option<i64>,
+    window: Option<usize>,
+    period: Period,
+) -> String {
+    let merges = merges
+        .map(|merges| format!(", Merges: {}", format_signed(merges)))
+        .unwrap_or_default();
+    let totals = format!("Total: {}{merges}", format_signed(total));
+    match window {
+        Some(window) => format!(
+            " {} ({}, {totals}) ",
+            metric.name(),
+            average_label(window, period),
+        ),
+        None => format!(" {} ({totals}) ", metric.name()),
     }
 }
 
+/// Title label for a moving average, like `7d avg` or `4w avg`
+fn average_label(window: usize, period: Period) -> String {
+    let unit = match period {
+        Period::Daily => "d",
+        Period::Weekly => "w",
+        Period::Monthly => "mo",
+        Period::Yearly => "y",
+    };
+    format!("{window}{unit} avg")
+}
+
+/// Render a bordered placeholder when there is nothing to plot
+fn render_empty(frame: &mut Frame, area: Rect, name: &str) {
+    let empty = Paragraph::new("No data to display")
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .title(format!(" {name} "))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(empty, area);
+}
+
 #[cfg(test)]
+// Expected bounds and crossings are exact in f64
+#[allow(clippy::float_cmp)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_format_number() {
-        assert_eq!(format_number(100), "100");
-        assert_eq!(format_number(2500), "2.5K");
-        assert_eq!(format_number(2_500_000), "2.5M");
-        assert_eq!(format_number(-2500), "-2.5K");
+    fn test_line_chart_window() {
+        assert_eq!(line_chart_window(80), 72);
+        assert_eq!(line_chart_window(5), 2);
+    }
+
+    #[test]
+    fn test_visible_window() {
+        // Newest periods by default
+        assert_eq!(visible_window(10, 0, 4), 6..10);
+        assert_eq!(visible_window(10, 2, 4), 4..8);
+        // Offsets past the oldest full window are clamped
+        assert_eq!(visible_window(10, 9, 4), 0..4);
+        // Everything fits
+        assert_eq!(visible_window(3, 2, 4), 0..3);
+        assert_eq!(visible_window(0, 0, 4), 0..0);
+    }
+
+    #[test]
+    fn test_average_label_uses_period_unit() {
+        assert_eq!(average_label(7, Period::Daily), "7d avg");
+        assert_eq!(average_label(4, Period::Weekly), "4w avg");
+        assert_eq!(average_label(3, Period::Monthly), "3mo avg");
+        assert_eq!(average_label(2, Period::Yearly), "2y avg");
+    }
+
+    fn labeled(values: &[i64]) -> Vec<(String, i64)> {
+        values.iter().map(|v| (String::new(), *v)).collect()
+    }
+
+    #[test]
+    fn test_metric_bounds_keep_sign() {
+        assert_eq!(metric_bounds(&labeled(&[3, 8, 5]), &[]), (3, 8));
+        // A negative period pulls the axis below zero instead of being mirrored
+        assert_eq!(metric_bounds(&labeled(&[120, -500, 40]), &[]), (-500, 120));
+        assert_eq!(metric_bounds(&labeled(&[-500, -20]), &[]), (-500, 0));
+        // A moving average widens the axis to whole numbers
+        let average = [(0.0, -2.5), (1.0, 9.5)];
+        assert_eq!(metric_bounds(&labeled(&[1, 2]), &average), (-3, 10));
+        assert_eq!(metric_bounds(&[], &[]), (0, 0));
+    }
+
+    #[test]
+    fn test_y_axis_labels() {
+        let text = |labels: Vec<Span>| {
+            labels
+                .iter()
+                .map(|label| label.content.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(text(y_axis_labels(950, 12_400, 6, 5)), ["  950", "12.4K"]);
+        // Zero on the baseline's row, counted from the bottom
+        assert_eq!(
+            text(y_axis_labels(-100, 100, 5, 4)),
+            ["-100", "", "   0", "", " 100"]
+        );
+        // A zero next to an end keeps that end's label
+        assert_eq!(text(y_axis_labels(-1, 1_200_000, 8, 4)), ["  -1", "1.2M"]);
+    }
+
+    #[test]
+    fn test_zero_based_bounds() {
+        assert_eq!(zero_based_bounds(&labeled(&[0, 50, 100])), [0.0, 110.0]);
+        assert_eq!(zero_based_bounds(&labeled(&[-100, -50])), [-110.0, 0.0]);
+        assert_eq!(zero_based_bounds(&labeled(&[-50, 50])), [-60.0, 60.0]);
+        // Flat zero data still gets a one-unit axis
+        assert_eq!(zero_based_bounds(&labeled(&[0, 0])), [0.0, 1.0]);
+        assert_eq!(zero_based_bounds(&[]), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_split_at_zero_interpolates_crossings() {
+        let (above, below) = split_at_zero(&[(0.0, 30.0), (1.0, -10.0), (2.0, -20.0), (3.0, 20.0)]);
+        assert_eq!(
+            above,
+            [
+                vec![(0.0, 30.0), (0.75, 0.0)],
+                vec![(2.5, 0.0), (3.0, 20.0)]
+            ]
+        );
+        assert_eq!(
+            below,
+            [vec![(0.75, 0.0), (1.0, -10.0), (2.0, -20.0), (2.5, 0.0)]]
+        );
+    }
+
+    #[test]
+    fn test_split_at_zero_single_sign() {
+        let (above, below) = split_at_zero(&[(0.0, -5.0), (1.0, -1.0)]);
+        assert!(above.is_empty());
+        assert_eq!(below, [vec![(0.0, -5.0), (1.0, -1.0)]]);
+
+        // Zero counts as above
+        let (above, below) = split_at_zero(&[(0.0, 0.0), (1.0, 0.0)]);
+        assert_eq!(above, [vec![(0.0, 0.0), (1.0, 0.0)]]);
+        assert!(below.is_empty());
+
+        assert_eq!(split_at_zero(&[]), (Vec::new(), Vec::new()));
     }
 }
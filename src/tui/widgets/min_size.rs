@@ -0,0 +1,95 @@
+//! Hint drawn in place of a widget whose area is smaller than it needs
+
+use crate::tui::theme::Theme;
+use ratatui::layout::Flex;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use unicode_width::UnicodeWidthStr;
+
+/// Whether `area` is at least `min` in both directions
+#[must_use]
+pub const fn fits(area: Rect, min: Size) -> bool {
+    area.width >= min.width && area.height >= min.height
+}
+
+/// Render the bordered `name` block with a centered hint like
+/// `Needs ≥ 40×6 (have 28×4)` when `area` is smaller than `min`
+///
+/// The hint breaks before `(have …)` when it does not fit on one line.
+/// Returns whether the hint was drawn, in which case the widget should not be.
+pub fn render_min_size_hint(
+    frame: &mut Frame,
+    area: Rect,
+    name: &str,
+    min: Size,
+    theme: &Theme,
+) -> bool {
+    if fits(area, min) {
+        return false;
+    }
+    let block = Block::default()
+        .title(format!(" {name} "))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let needs = format!("Needs ≥ {}×{}", min.width, min.height);
+    let have = format!("(have {}×{})", area.width, area.height);
+    let lines = if needs.width() + 1 + have.width() <= usize::from(inner.width) {
+        vec![Line::from(format!("{needs} {have}"))]
+    } else {
+        vec![Line::from(needs), Line::from(have)]
+    };
+    let height = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+    let [row] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(inner);
+    let hint = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(theme.text_dim));
+    frame.render_widget(hint, row);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn render(width: u16, height: u16, min: Size) -> (bool, String) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let mut drawn = false;
+        terminal
+            .draw(|frame| {
+                drawn = render_min_size_hint(frame, frame.area(), "Chart", min, &Theme::default());
+            })
+            .unwrap();
+        (drawn, terminal.backend().to_string())
+    }
+
+    #[test]
+    fn test_fits() {
+        let min = Size::new(20, 5);
+        assert!(fits(Rect::new(3, 3, 20, 5), min));
+        assert!(!fits(Rect::new(0, 0, 19, 40), min));
+        assert!(!fits(Rect::new(0, 0, 80, 4), min));
+    }
+
+    #[test]
+    fn test_hint_only_when_too_small() {
+        assert!(!render(20, 5, Size::new(20, 5)).0);
+
+        let (drawn, screen) = render(40, 5, Size::new(40, 6));
+        assert!(drawn);
+        assert!(screen.contains("Needs ≥ 40×6 (have 40×5)"), "{screen}");
+    }
+
+    #[test]
+    fn test_hint_breaks_when_narrow() {
+        let (_, screen) = render(16, 6, Size::new(24, 6));
+        assert!(screen.contains("Needs ≥ 24×6"), "{screen}");
+        assert!(screen.contains("(have 16×6)"), "{screen}");
+    }
+}
@@ -1,11 +1,30 @@
 //! Custom widgets for TUI
 
 mod diverging_bar_chart;
+mod heatmap;
 mod horizontal_bar_chart;
+mod label;
 mod line_chart;
+mod min_size;
+mod stacked_bar_chart;
+mod summary_cards;
 mod vertical_bar_chart;
 
-pub use diverging_bar_chart::render_diverging_bar_chart;
-pub use horizontal_bar_chart::{BarDataPoint, render_horizontal_bar_chart};
-pub use line_chart::render_line_chart_for_metric;
-pub use vertical_bar_chart::{chart_width, render_vertical_bar_chart};
+pub use diverging_bar_chart::{
+    DIVERGING_BAR_CHART_MIN_SIZE, add_del_window, render_diverging_bar_chart, rows_label,
+    scroll_edge,
+};
+pub use heatmap::{HEATMAP_HEIGHT, HEATMAP_WIDTH, render_heatmap};
+pub use horizontal_bar_chart::{
+    BarDataPoint, HORIZONTAL_BAR_CHART_MIN_SIZE, render_horizontal_bar_chart,
+};
+pub use line_chart::{
+    LINE_CHART_MIN_SIZE, line_chart_window, render_cumulative_chart, render_line_chart_for_metric,
+    render_net_lines_chart, visible_window,
+};
+pub use stacked_bar_chart::render_stacked_bar_chart;
+pub use summary_cards::{SUMMARY_CARDS_HEIGHT, render_summary_cards};
+pub use vertical_bar_chart::{
+    bar_width_for, chart_width, chart_width_for, render_vertical_bar_chart,
+    vertical_bar_chart_min_size,
+};
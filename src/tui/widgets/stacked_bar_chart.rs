@@ -0,0 +1,197 @@
+//! Stacked bar chart widget for lines changed per extension
+
+use super::vertical_bar_chart::{BAR_GAP, BAR_WIDTH};
+use crate::stats::OTHER_EXTENSIONS;
+use crate::tui::app::{App, LanguageDataPoint};
+use crate::tui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+/// Render one stacked bar per period, one segment per extension, with a color legend below
+///
+/// Bars keep their full width; when the area is too narrow for every period,
+/// only the most recent periods that fit are drawn.
+pub fn render_stacked_bar_chart(frame: &mut Frame, area: Rect, app: &App) {
+    let data = app.language_data();
+    let theme = app.theme();
+
+    if data.extensions.is_empty() {
+        let hint = Paragraph::new("Run with --by-extension")
+            .alignment(Alignment::Center)
+            .block(Block::default().title(" Languages ").borders(Borders::ALL));
+        frame.render_widget(hint, area);
+        return;
+    }
+
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    let visible = visible_bars(inner.width).min(data.points.len());
+    let shown = &data.points[data.points.len() - visible..];
+    let max_lines = shown
+        .iter()
+        .map(|point| point.lines.iter().sum::<u64>())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let dim = Style::default().fg(theme.text_dim);
+    let mut block = Block::default()
+        .title(" Languages ")
+        .title_style(Style::default().fg(theme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let max_label = format!(" max: {max_lines} lines ");
+    if " Languages ".len() + max_label.len() + 2 <= usize::from(area.width) {
+        block = block.title(Line::styled(max_label, dim).right_aligned());
+    }
+    // First and last visible period on the bottom border, the first only when both fit
+    if let (Some(first), Some(last)) = (shown.first(), shown.last()) {
+        let first = format!(" {} ", first.label);
+        let last = format!(" {} ", last.label);
+        if first.chars().count() + last.chars().count() + 2 <= usize::from(area.width) {
+            block = block.title_bottom(Line::styled(first, dim));
+        }
+        block = block.title_bottom(Line::styled(last, dim).right_aligned());
+    }
+    frame.render_widget(block, area);
+
+    if inner.height < 2 {
+        return;
+    }
+    let [bars_area, legend_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+
+    let colors = extension_colors(&data.extensions, theme);
+    let buf = frame.buffer_mut();
+    for (x, point) in (bars_area.x..)
+        .step_by(usize::from(BAR_WIDTH + BAR_GAP))
+        .zip(shown)
+    {
+        render_bar(buf, x, bars_area, point, &colors, max_lines);
+    }
+
+    let mut legend = Vec::new();
+    for (i, (extension, color)) in data.extensions.iter().zip(&colors).enumerate() {
+        if i > 0 {
+            legend.push(Span::raw("  "));
+        }
+        legend.push(Span::styled("\u{25a0} ", Style::default().fg(*color)));
+        legend.push(Span::styled(extension.as_str(), dim));
+    }
+    frame.render_widget(Paragraph::new(Line::from(legend)), legend_area);
+}
+
+/// Draw one bar bottom-up, segment by segment
+///
+/// Segment tops are scaled from running totals, so rounding never makes the
+/// stack taller or shorter than the bar's total.
+fn render_bar(
+    buf: &mut Buffer,
+    x: u16,
+    area: Rect,
+    point: &LanguageDataPoint,
+    colors: &[Color],
+    max_lines: u64,
+) {
+    let mut running = 0;
+    let mut filled = 0;
+    for (lines, color) in point.lines.iter().zip(colors) {
+        running += lines;
+        let top = scaled_height(running, max_lines, area.height);
+        for row in filled..top {
+            let y = area.bottom() - 1 - row;
+            for dx in 0..BAR_WIDTH {
+                buf[(x + dx, y)].set_symbol("\u{2588}").set_fg(*color);
+            }
+        }
+        filled = top;
+    }
+}
+
+/// Number of full-width bars that fit in `width` cells
+fn visible_bars(width: u16) -> usize {
+    usize::from((width + BAR_GAP) / (BAR_WIDTH + BAR_GAP))
+}
+
+/// Height in cells of `lines` on a bar `height` cells tall at `max_lines`, rounded to nearest
+fn scaled_height(lines: u64, max_lines: u64, height: u16) -> u16 {
+    let max_lines = u128::from(max_lines);
+    let cells = (u128::from(lines) * u128::from(height) + max_lines / 2) / max_lines;
+    u16::try_from(cells).unwrap_or(height).min(height)
+}
+
+/// Color for each extension, hashed into the theme palette
+///
+/// The color depends only on the extension name, so it stays the same across
+/// periods and refreshes. When a larger extension already took the hashed
+/// color, the next free palette slot is used instead. `other` is always dim.
+fn extension_colors(extensions: &[String], theme: &Theme) -> Vec<Color> {
+    let palette = &theme.languages;
+    let mut used = vec![false; palette.len()];
+    extensions
+        .iter()
+        .map(|extension| {
+            if extension == OTHER_EXTENSIONS {
+                return theme.text_dim;
+            }
+            let start = fnv1a(extension) % palette.len();
+            let slot = (0..palette.len())
+                .map(|offset| (start + offset) % palette.len())
+                .find(|&slot| !used[slot])
+                .unwrap_or(start);
+            used[slot] = true;
+            palette[slot]
+        })
+        .collect()
+}
+
+/// 32-bit FNV-1a hash, stable across Rust versions unlike `DefaultHasher`
+fn fnv1a(text: &str) -> usize {
+    let hash = text.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    hash as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_bars_never_shrink_below_bar_width() {
+        assert_eq!(visible_bars(0), 0);
+        assert_eq!(visible_bars(BAR_WIDTH - 1), 0);
+        assert_eq!(visible_bars(BAR_WIDTH), 1);
+        assert_eq!(visible_bars(2 * BAR_WIDTH + BAR_GAP), 2);
+        assert_eq!(visible_bars(2 * BAR_WIDTH + BAR_GAP - 1), 1);
+    }
+
+    #[test]
+    fn test_scaled_height_rounds_and_clamps() {
+        assert_eq!(scaled_height(0, 100, 10), 0);
+        assert_eq!(scaled_height(100, 100, 10), 10);
+        assert_eq!(scaled_height(44, 100, 10), 4);
+        assert_eq!(scaled_height(45, 100, 10), 5);
+        assert_eq!(scaled_height(u64::MAX / 20, u64::MAX / 20, 10), 10);
+    }
+
+    #[test]
+    fn test_extension_colors_are_stable_and_distinct() {
+        let theme = Theme::default();
+        let extensions: Vec<String> = ["rs", "ts", "md", "toml", "json", OTHER_EXTENSIONS]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let colors = extension_colors(&extensions, &theme);
+        assert_eq!(colors, extension_colors(&extensions, &theme));
+        assert_eq!(colors[5], theme.text_dim);
+        for (i, color) in colors[..5].iter().enumerate() {
+            assert!(theme.languages.contains(color));
+            assert!(!colors[..i].contains(color), "{colors:?}");
+        }
+
+        // The largest extension keeps its hashed color whatever else is shown
+        let alone = extension_colors(&extensions[..1], &theme);
+        assert_eq!(alone[0], colors[0]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Summary cards widget showing the totals of the analysis range
+
+use crate::output::table::{format_with_commas_i64, format_with_commas_u64};
+use crate::stats::TotalStats;
+use crate::tui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+/// Height of the card row: the number and the metric name inside a border
+pub const SUMMARY_CARDS_HEIGHT: u16 = 4;
+
+/// Narrower than this, only the Commits, Net, and Files cards are shown
+const NARROW_WIDTH: u16 = 60;
+
+/// One card: a big number above the metric name
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Card {
+    name: &'static str,
+    value: String,
+    color: Color,
+}
+
+/// Cards for `total`, dropping +Lines and -Lines when `narrow`
+fn cards(total: &TotalStats, theme: &Theme, narrow: bool) -> Vec<Card> {
+    let net_color = match total.net_lines.signum() {
+        1 => theme.addition,
+        -1 => theme.deletion,
+        _ => theme.border,
+    };
+    let net = if total.net_lines > 0 {
+        format!("+{}", format_with_commas_i64(total.net_lines))
+    } else {
+        format_with_commas_i64(total.net_lines)
+    };
+
+    let mut cards = vec![Card {
+        name: "Commits",
+        value: format_with_commas_u64(u64::from(total.commits)),
+        color: theme.bar_primary,
+    }];
+    if !narrow {
+        cards.push(Card {
+            name: "+Lines",
+            value: format_with_commas_u64(total.additions),
+            color: theme.addition,
+        });
+        cards.push(Card {
+            name: "-Lines",
+            value: format_with_commas_u64(total.deletions),
+            color: theme.deletion,
+        });
+    }
+    cards.push(Card {
+        name: "Net",
+        value: net,
+        color: net_color,
+    });
+    cards.push(Card {
+        name: "Files",
+        value: format_with_commas_u64(u64::from(total.files_changed)),
+        color: theme.bar_secondary,
+    });
+    cards
+}
+
+/// Render a row of bordered cards with the totals, sharing `area` evenly
+pub fn render_summary_cards(frame: &mut Frame, area: Rect, total: &TotalStats, theme: &Theme) {
+    let cards = cards(total, theme, area.width < NARROW_WIDTH);
+    let cols = Layout::horizontal(vec![Constraint::Fill(1); cards.len()]).split(area);
+
+    for (card, col) in cards.into_iter().zip(cols.iter()) {
+        let lines = vec![
+            Line::styled(card.value, Style::default().fg(card.color).bold()),
+            Line::styled(card.name, Style::default().fg(theme.text_dim)),
+        ];
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .block(block),
+            *col,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(net_lines: i64) -> TotalStats {
+        TotalStats {
+            commits: 1_234,
+            merge_commits: 0,
+            additions: 56_789,
+            deletions: 4_321,
+            net_lines,
+            files_changed: 98,
+            unique_files: 98,
+        }
+    }
+
+    #[test]
+    fn test_cards_format_with_commas() {
+        let theme = Theme::default();
+        let cards = cards(&total(52_468), &theme, false);
+
+        let values: Vec<_> = cards.iter().map(|c| c.value.as_str()).collect();
+        assert_eq!(values, ["1,234", "56,789", "4,321", "+52,468", "98"]);
+        assert_eq!(cards[3].color, theme.addition);
+    }
+
+    #[test]
+    fn test_cards_color_net_by_sign() {
+        let theme = Theme::default();
+
+        let negative = cards(&total(-1_500), &theme, false);
+        assert_eq!(negative[3].value, "-1,500");
+        assert_eq!(negative[3].color, theme.deletion);
+
+        let zero = cards(&total(0), &theme, false);
+        assert_eq!(zero[3].value, "0");
+        assert_eq!(zero[3].color, theme.border);
+    }
+
+    #[test]
+    fn test_cards_collapse_when_narrow() {
+        let names: Vec<_> = cards(&total(0), &Theme::default(), true)
+            .iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, ["Commits", "Net", "Files"]);
+    }
+}
@@ -1,5 +1,8 @@
 //! Vertical bar chart widget for activity statistics
 
+use super::label::format_number;
+use super::min_size::render_min_size_hint;
+use crate::tui::theme::Theme;
 use ratatui::prelude::*;
 use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders};
 
@@ -9,59 +12,135 @@ pub const BAR_WIDTH: u16 = 3;
 pub const BAR_GAP: u16 = 1;
 /// Border width (left + right)
 const BORDER_WIDTH: u16 = 2;
+/// Rows taken by the borders, the labels, and the values at the foot of the bars
+const MIN_HEIGHT: u16 = 4;
 
 /// Calculate the minimum width needed to display a vertical bar chart
 #[must_use]
 pub const fn chart_width(bar_count: u16) -> u16 {
+    chart_width_for(bar_count, BAR_WIDTH)
+}
+
+/// Minimum width of a vertical bar chart whose bars are `bar_width` cells wide
+#[must_use]
+pub const fn chart_width_for(bar_count: u16, bar_width: u16) -> u16 {
     if bar_count == 0 {
         return BORDER_WIDTH;
     }
-    (BAR_WIDTH + BAR_GAP) * bar_count - BAR_GAP + BORDER_WIDTH
+    (bar_width + BAR_GAP) * bar_count - BAR_GAP + BORDER_WIDTH
+}
+
+/// Width of the bars for `labels`: [`BAR_WIDTH`], or wider when a label needs it
+#[must_use]
+pub fn bar_width_for(labels: &[&str]) -> u16 {
+    let widest = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+    u16::try_from(widest).unwrap_or(u16::MAX).max(BAR_WIDTH)
+}
+
+/// Smallest area a vertical bar chart of `labels` renders in without cutting off bars
+#[must_use]
+pub fn vertical_bar_chart_min_size(labels: &[&str]) -> Size {
+    let count = u16::try_from(labels.len()).unwrap_or(u16::MAX);
+    Size::new(chart_width_for(count, bar_width_for(labels)), MIN_HEIGHT)
 }
 
 /// Render a vertical bar chart
+///
+/// Bars are [`BAR_WIDTH`] cells wide, widened to fit the longest label.
+/// Values, the total, and the maximum are shown as `2.5K` / `1.2M` once they
+/// reach a thousand. The `selected` bar, highlighted in inspect mode, takes
+/// the title color and a reversed label. An area smaller than
+/// [`vertical_bar_chart_min_size`] gets a hint instead of a cut-off chart.
+#[allow(clippy::too_many_arguments)]
 pub fn render_vertical_bar_chart(
     frame: &mut Frame,
     area: Rect,
     title: &str,
     labels: &[&str],
-    values: &[u32],
+    values: &[u64],
     color: Color,
+    selected: Option<usize>,
+    theme: &Theme,
 ) {
+    let min = vertical_bar_chart_min_size(labels);
+    if render_min_size_hint(frame, area, title, min, theme) {
+        return;
+    }
     let max_value = *values.iter().max().unwrap_or(&1).max(&1);
 
     let bars: Vec<Bar> = labels
         .iter()
         .zip(values.iter())
-        .map(|(label, &value)| {
-            Bar::default()
-                .value(u64::from(value))
-                .label(Line::from(*label))
-                .style(Style::default().fg(color))
+        .enumerate()
+        .map(|(i, (label, &value))| {
+            let bar = Bar::default().value(value).text_value(format_number(value));
+            if selected == Some(i) {
+                bar.label(Line::styled(*label, Style::default().reversed()))
+                    .style(Style::default().fg(theme.title))
+            } else {
+                bar.label(Line::from(*label))
+                    .style(Style::default().fg(color))
+            }
         })
         .collect();
 
-    let total: u32 = values.iter().sum();
-    let title_with_total = format!(" {title} ({total}) ");
+    let total: u64 = values.iter().sum();
+    let title_with_total = format!(" {title} ({}) ", format_number(total));
+
+    // Bar heights are relative, so show what a full-height bar means when there is room
+    let max_label = format!(" max: {} ", format_number(max_value));
+    let mut block = Block::default()
+        .title(title_with_total.as_str())
+        .title_style(Style::default().fg(color).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    if title_with_total.chars().count() + max_label.len() + BORDER_WIDTH as usize
+        <= usize::from(area.width)
+    {
+        block = block
+            .title(Line::styled(max_label, Style::default().fg(theme.text_dim)).right_aligned());
+    }
 
     let chart = BarChart::default()
-        .block(
-            Block::default()
-                .title(title_with_total)
-                .title_style(Style::default().fg(color).bold())
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::White)),
-        )
+        .block(block)
         .data(BarGroup::default().bars(&bars))
-        .bar_width(BAR_WIDTH)
+        .bar_width(bar_width_for(labels))
         .bar_gap(BAR_GAP)
-        .max(u64::from(max_value));
+        .max(max_value);
 
     frame.render_widget(chart, area);
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_width_for_bar_width() {
+        assert_eq!(chart_width(24), 97);
+        assert_eq!(chart_width_for(24, BAR_WIDTH), chart_width(24));
+        assert_eq!(chart_width_for(12, 5), 73);
+        assert_eq!(chart_width_for(0, 5), BORDER_WIDTH);
+    }
+
+    #[test]
+    fn test_vertical_bar_chart_min_size() {
+        let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+        assert_eq!(vertical_bar_chart_min_size(&weekdays), Size::new(29, 4));
+        assert_eq!(
+            vertical_bar_chart_min_size(&["0-1", "10-11"]),
+            Size::new(13, 4)
+        );
+    }
+
+    #[test]
+    fn test_bar_width_for_widest_label() {
+        assert_eq!(bar_width_for(&["Mon", "Tue"]), BAR_WIDTH);
+        assert_eq!(bar_width_for(&["0", "23"]), BAR_WIDTH);
+        assert_eq!(bar_width_for(&["0-1", "10-11"]), 5);
+        assert_eq!(bar_width_for(&[]), BAR_WIDTH);
+    }
+
     #[test]
     fn test_vertical_bar_chart_creation() {
         // Basic smoke test - actual rendering tested via integration
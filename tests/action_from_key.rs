@@ -1,70 +1,296 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kodo::cli::args::Period;
+use kodo::tui::Keymap;
 use kodo::tui::mvu::action::Action;
+use std::collections::BTreeMap;
+
+fn from_key(key: KeyEvent, single_metric: bool) -> Action {
+    Action::from_key(key, single_metric, &Keymap::default())
+}
+
+fn from_inspect_key(key: KeyEvent) -> Action {
+    Action::from_inspect_key(key, &Keymap::default())
+}
 
 #[test]
 fn maps_navigation_and_mode_keys() {
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), true),
         Action::Quit
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
-        Action::Quit
+        from_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), true),
+        Action::Escape
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('7'), KeyModifiers::NONE), true),
+        Action::Digit(7)
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE), true),
         Action::NextChart
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE), true),
         Action::NextChart
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE), true),
         Action::NextChart
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)),
+        from_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT), true),
         Action::PrevChart
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE), true),
         Action::PrevChart
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE), true),
         Action::PrevChart
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE), true),
         Action::ScrollUp
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE), true),
         Action::ScrollUp
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE), true),
         Action::ScrollDown
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE), true),
         Action::ScrollDown
     );
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE)),
+        from_key(KeyEvent::new(KeyCode::Char('['), KeyModifiers::NONE), true),
+        Action::PanLeft
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char(']'), KeyModifiers::NONE), true),
+        Action::PanRight
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE), true),
+        Action::Refresh
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE), true),
+        Action::Export
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::SHIFT), true),
+        Action::GrowRange
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('='), KeyModifiers::NONE), true),
+        Action::GrowRange
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('-'), KeyModifiers::NONE), true),
+        Action::ShrinkRange
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE), true),
+        Action::SetPeriod(Period::Weekly)
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('M'), KeyModifiers::SHIFT), true),
+        Action::SetPeriod(Period::Monthly)
+    );
+}
+
+#[test]
+fn maps_toggle_keys() {
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE), true),
         Action::ToggleMetricView
     );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT), true),
+        Action::ToggleHelp
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE), true),
+        Action::ToggleSummary
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('W'), KeyModifiers::SHIFT), true),
+        Action::ToggleActivityWeight
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE), false),
+        Action::ToggleCommitsByRepo
+    );
+}
+
+#[test]
+fn maps_tab_to_panel_focus_in_split_mode() {
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE), false),
+        Action::NextPanel
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT), false),
+        Action::PrevPanel
+    );
+    // Arrow keys keep their meaning in both views
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE), false),
+        Action::NextChart
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE), false),
+        Action::ScrollUp
+    );
+}
+
+#[test]
+fn maps_page_and_jump_keys() {
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE), true),
+        Action::PageUp
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE), true),
+        Action::PageDown
+    );
+    assert_eq!(
+        from_key(
+            KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL),
+            true
+        ),
+        Action::PageUp
+    );
+    assert_eq!(
+        from_key(
+            KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            true
+        ),
+        Action::PageDown
+    );
+    // Without Ctrl, d still switches to daily periods
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE), true),
+        Action::SetPeriod(Period::Daily)
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE), true),
+        Action::ScrollToOldest
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT), true),
+        Action::ScrollToNewest
+    );
 }
 
 #[test]
 fn maps_force_quit_and_noop() {
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        from_key(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            true
+        ),
+        Action::ForceQuit
+    );
+    assert_eq!(
+        from_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE), true),
+        Action::Noop
+    );
+}
+
+#[test]
+fn maps_inspect_keys() {
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        from_key(key(KeyCode::Char('i')), true),
+        Action::EnterInspect
+    );
+    assert_eq!(from_key(key(KeyCode::Enter), false), Action::EnterInspect);
+
+    // While inspecting, arrows move the highlight instead of switching charts
+    for code in [
+        KeyCode::Left,
+        KeyCode::Up,
+        KeyCode::Char('h'),
+        KeyCode::Char('k'),
+    ] {
+        assert_eq!(from_inspect_key(key(code)), Action::MoveSelection(-1));
+    }
+    for code in [
+        KeyCode::Right,
+        KeyCode::Down,
+        KeyCode::Char('l'),
+        KeyCode::Char('j'),
+    ] {
+        assert_eq!(from_inspect_key(key(code)), Action::MoveSelection(1));
+    }
+    for code in [
+        KeyCode::Esc,
+        KeyCode::Enter,
+        KeyCode::Char('i'),
+        KeyCode::Char('q'),
+    ] {
+        assert_eq!(from_inspect_key(key(code)), Action::ExitInspect);
+    }
+    assert_eq!(from_inspect_key(key(KeyCode::Tab)), Action::Noop);
+    assert_eq!(
+        from_inspect_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
         Action::ForceQuit
     );
+}
+
+#[test]
+fn configured_keys_come_before_built_in_ones() {
+    let bindings: BTreeMap<String, Vec<String>> = [
+        ("scroll_down", vec!["n"]),
+        ("quit", vec!["j"]),
+        ("inspect_next", vec!["space"]),
+    ]
+    .into_iter()
+    .map(|(name, keys)| {
+        (
+            name.to_string(),
+            keys.into_iter().map(String::from).collect(),
+        )
+    })
+    .collect();
+    let keymap = Keymap::from_config(&bindings).unwrap();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+    assert_eq!(
+        Action::from_key(key(KeyCode::Char('n')), true, &keymap),
+        Action::ScrollDown
+    );
+    // A rebound built-in key takes the configured action; the others keep theirs
+    assert_eq!(
+        Action::from_key(key(KeyCode::Char('j')), true, &keymap),
+        Action::Quit
+    );
+    assert_eq!(
+        Action::from_key(key(KeyCode::Down), true, &keymap),
+        Action::ScrollDown
+    );
+    // Inspect-mode actions only apply while inspecting
     assert_eq!(
-        Action::from_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+        Action::from_key(key(KeyCode::Char(' ')), true, &keymap),
         Action::Noop
     );
+    assert_eq!(
+        Action::from_inspect_key(key(KeyCode::Char(' ')), &keymap),
+        Action::MoveSelection(1)
+    );
+    // Ctrl+C cannot be configured away
+    assert_eq!(
+        Action::from_key(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            true,
+            &keymap
+        ),
+        Action::ForceQuit
+    );
 }
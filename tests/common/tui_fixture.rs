@@ -1,16 +1,49 @@
 use chrono::NaiveDate;
-use kodo::stats::{ActivityStats, AnalysisResult, PeriodStats};
-use kodo::tui::App;
+use kodo::cli::args::Period;
+use kodo::error::Error;
+use kodo::stats::{
+    ActivityStats, AnalysisResult, ComparisonStats, DateRange, ExtensionLines, FileChurn,
+    PeriodStats, RepoStats, TotalStats, default_min_gap, find_gaps, moving_averages,
+};
+use kodo::tui::loader::LoadEvent;
+use kodo::tui::{App, RepoView};
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
 
 pub fn fixed_analysis_result() -> AnalysisResult {
+    // Nine extensions, so the Languages chart folds the smallest four into "other"
     let stats = vec![
-        period(2024, 1, 1, 3, 120, 30, 8),
-        period(2024, 1, 2, 5, 180, 40, 11),
-        period(2024, 1, 3, 2, 60, 15, 4),
-        period(2024, 1, 4, 4, 150, 45, 9),
-        period(2024, 1, 5, 6, 220, 70, 13),
-        period(2024, 1, 6, 1, 20, 10, 2),
-        period(2024, 1, 7, 3, 90, 25, 6),
+        with_extensions(
+            period(2024, 1, 1, 3, 120, 30, 8),
+            &[("rs", 80, 20), ("md", 30, 5), ("toml", 10, 5)],
+        ),
+        with_extensions(
+            period(2024, 1, 2, 5, 180, 40, 11),
+            &[
+                ("rs", 100, 20),
+                ("ts", 50, 10),
+                ("json", 20, 5),
+                ("yml", 10, 5),
+            ],
+        ),
+        with_extensions(
+            period(2024, 1, 3, 2, 60, 15, 4),
+            &[("md", 40, 10), ("sh", 20, 5)],
+        ),
+        with_extensions(
+            period(2024, 1, 4, 4, 150, 45, 9),
+            &[("rs", 90, 30), ("ts", 40, 10), ("none", 20, 5)],
+        ),
+        with_extensions(
+            period(2024, 1, 5, 6, 220, 70, 13),
+            &[("ts", 120, 40), ("rs", 60, 20), ("css", 40, 10)],
+        ),
+        with_extensions(period(2024, 1, 6, 1, 20, 10, 2), &[("toml", 20, 10)]),
+        with_extensions(
+            period(2024, 1, 7, 3, 90, 25, 6),
+            &[("rs", 50, 15), ("md", 40, 10)],
+        ),
     ];
 
     AnalysisResult::new(
@@ -23,12 +56,44 @@ pub fn fixed_analysis_result() -> AnalysisResult {
 }
 
 pub fn fixed_activity_stats() -> ActivityStats {
+    let weekday = [3, 5, 2, 4, 6, 1, 3];
+    let hourly = [
+        0, 0, 0, 0, 0, 1, 2, 1, 3, 4, 3, 2, 1, 2, 3, 2, 4, 5, 4, 3, 2, 1, 0, 0,
+    ];
+
+    // Deterministic weekday × hour spread derived from the hourly totals
+    let mut heatmap = [[0; 24]; 7];
+    for (hour, &count) in hourly.iter().enumerate() {
+        for n in 0..count {
+            heatmap[(hour + n as usize) % 7][hour] += 1;
+        }
+    }
+
     ActivityStats {
-        weekday: [3, 5, 2, 4, 6, 1, 3],
-        hourly: [
-            0, 0, 0, 0, 0, 1, 2, 1, 3, 4, 3, 2, 1, 2, 3, 2, 4, 5, 4, 3, 2, 1, 0, 0,
-        ],
+        weekday,
+        hourly,
+        heatmap,
+        // Line weights that rank differently from the commit counts
+        weekday_lines: [120, 340, 4_800, 60, 1_250, 0, 30],
+        hourly_lines: hourly.map(|count| u64::from(count) * u64::from(count) * 150),
+    }
+}
+
+/// Single-mode app with a 3-period moving average over the fixed data
+pub fn make_moving_average_app() -> App {
+    let mut result = fixed_analysis_result();
+    result.moving_average = Some(moving_averages(&result.stats, 3));
+    App::new(result, fixed_activity_stats(), true)
+}
+
+/// Single-mode app over the fixed data with merges collected on three days
+pub fn make_merges_app() -> App {
+    let mut result = fixed_analysis_result();
+    for (day, merges) in [(1, 1), (4, 3), (5, 2)] {
+        result.stats[day].merge_commits = merges;
     }
+    result.total = TotalStats::from_periods(&result.stats);
+    App::new(result, fixed_activity_stats(), true)
 }
 
 pub fn make_app(single_metric: bool) -> App {
@@ -39,6 +104,280 @@ pub fn make_app(single_metric: bool) -> App {
     )
 }
 
+/// App whose running net line total dips below zero and ends negative
+pub fn make_shrinking_app() -> App {
+    let stats = vec![
+        period(2024, 1, 1, 2, 40, 10, 3),
+        period(2024, 1, 2, 3, 10, 120, 6),
+        period(2024, 1, 3, 1, 5, 60, 2),
+        period(2024, 1, 4, 2, 30, 20, 4),
+    ];
+    let result = AnalysisResult::new(
+        "kodo".to_string(),
+        "daily".to_string(),
+        date(2024, 1, 1),
+        date(2024, 1, 4),
+        stats,
+    );
+
+    App::new(result, fixed_activity_stats(), true)
+}
+
+/// Single-mode app whose daily net lines are exactly `net_lines`, from 2024-01-01
+pub fn make_net_lines_app(net_lines: &[i64]) -> App {
+    let stats: Vec<PeriodStats> = (1u32..)
+        .zip(net_lines)
+        .map(|(day, &net)| {
+            let additions = net.max(0).unsigned_abs();
+            let deletions = net.min(0).unsigned_abs();
+            period(2024, 1, day, 1, additions, deletions, 1)
+        })
+        .collect();
+    let to = stats.last().map_or(date(2024, 1, 1), |s| s.date);
+    let result = AnalysisResult::new(
+        "kodo".to_string(),
+        "daily".to_string(),
+        date(2024, 1, 1),
+        to,
+        stats,
+    );
+
+    App::new(result, fixed_activity_stats(), true)
+}
+
+pub fn make_multi_repo_app() -> App {
+    App::new(multi_repo_result(), fixed_activity_stats(), false)
+}
+
+fn multi_repo_result() -> AnalysisResult {
+    let mut result = fixed_analysis_result();
+    result.repository = "2 repos".to_string();
+    result.repositories = vec![
+        RepoStats {
+            name: "kodo".to_string(),
+            total: TotalStats {
+                commits: 14,
+                merge_commits: 0,
+                additions: 500,
+                deletions: 135,
+                net_lines: 365,
+                files_changed: 30,
+                unique_files: 12,
+            },
+        },
+        RepoStats {
+            name: "docs".to_string(),
+            total: TotalStats {
+                commits: 10,
+                merge_commits: 0,
+                additions: 340,
+                deletions: 100,
+                net_lines: 240,
+                files_changed: 23,
+                unique_files: 9,
+            },
+        },
+    ];
+    result
+}
+
+/// Two repositories with a tab each next to the combined view
+pub fn make_repo_tabs_app(single_metric: bool) -> App {
+    let kodo = fixed_analysis_result();
+    let docs = AnalysisResult::new(
+        "docs".to_string(),
+        "daily".to_string(),
+        date(2024, 1, 1),
+        date(2024, 1, 7),
+        vec![
+            period(2024, 1, 1, 0, 0, 0, 0),
+            period(2024, 1, 2, 1, 40, 5, 2),
+            period(2024, 1, 3, 4, 90, 30, 6),
+            period(2024, 1, 4, 0, 0, 0, 0),
+            period(2024, 1, 5, 2, 25, 25, 3),
+            period(2024, 1, 6, 3, 60, 10, 4),
+            period(2024, 1, 7, 0, 0, 0, 0),
+        ],
+    );
+
+    App::new(multi_repo_result(), fixed_activity_stats(), single_metric).with_repo_views(vec![
+        RepoView {
+            result: kodo,
+            activity_stats: fixed_activity_stats(),
+            daily_stats: Vec::new(),
+        },
+        RepoView {
+            result: docs,
+            activity_stats: ActivityStats::default(),
+            daily_stats: Vec::new(),
+        },
+    ])
+}
+
+pub fn make_comparison_app() -> App {
+    let mut result = fixed_analysis_result();
+    let previous = TotalStats {
+        commits: 20,
+        merge_commits: 0,
+        additions: 700,
+        deletions: 320,
+        net_lines: 380,
+        files_changed: 41,
+        unique_files: 15,
+    };
+    result.comparison = Some(ComparisonStats::new(
+        DateRange::new(date(2023, 12, 25), date(2023, 12, 31)),
+        &result.total,
+        &previous,
+    ));
+
+    App::new(result, fixed_activity_stats(), false)
+}
+
+/// Single-mode app with 120 daily periods, more than fit in an 80-column line chart
+///
+/// Keeps the daily stats, so the period can be switched.
+pub fn make_long_app() -> App {
+    let stats = (0..120u32)
+        .map(|i| {
+            let day = date(2024, 1, 1) + chrono::Days::new(u64::from(i));
+            // Deterministic saw-tooth so each window looks different
+            let commits = i % 9 + i / 30;
+            let mut stats = period(2024, 1, 1, commits, u64::from(commits) * 20, 5, commits);
+            stats.label = day.format("%Y-%m-%d").to_string();
+            stats.date = day;
+            stats
+        })
+        .collect();
+    let result = AnalysisResult::new(
+        "kodo".to_string(),
+        "daily".to_string(),
+        date(2024, 1, 1),
+        date(2024, 4, 29),
+        stats,
+    );
+    let daily_stats = result.stats.clone();
+
+    App::new(result, fixed_activity_stats(), true).with_daily_stats(daily_stats)
+}
+
+/// Split-mode app over three weeks from 2024-01-01 without commits on
+/// Jan 4–8 and from Jan 17 to the end
+pub fn make_gap_app() -> App {
+    let stats: Vec<PeriodStats> = (1..=21)
+        .map(|day| {
+            let commits = if (4..=8).contains(&day) || day >= 17 {
+                0
+            } else {
+                day % 3 + 1
+            };
+            let mut stats = period(2024, 1, day, commits, u64::from(commits) * 15, 4, commits);
+            stats.label = stats.date.format("%Y-%m-%d").to_string();
+            stats
+        })
+        .collect();
+    let mut result = AnalysisResult::new(
+        "kodo".to_string(),
+        "daily".to_string(),
+        date(2024, 1, 1),
+        date(2024, 1, 21),
+        stats,
+    );
+    result.gaps = find_gaps(&result.stats, default_min_gap(Period::Daily));
+    let daily_stats = result.stats.clone();
+
+    App::new(result, fixed_activity_stats(), false).with_daily_stats(daily_stats)
+}
+
+pub fn make_churn_app() -> App {
+    let mut result = fixed_analysis_result();
+    result.top_files = Some(vec![
+        churn("src/tui/widgets/horizontal_bar_chart.rs", 310, 95, 4),
+        churn("src/cli/run.rs", 180, 60, 6),
+        churn("README.md", 90, 12, 3),
+        churn("Cargo.toml", 8, 2, 2),
+    ]);
+    result.top_dirs = Some(vec![
+        churn("src/tui", 420, 130, 7),
+        churn("src/cli", 260, 75, 8),
+        churn(".", 98, 14, 4),
+    ]);
+
+    App::new(result, fixed_activity_stats(), true)
+}
+
+/// Single-mode app with Japanese period labels and file paths, one too wide to fit
+pub fn make_wide_label_app() -> App {
+    let mut result = fixed_analysis_result();
+    for stat in &mut result.stats {
+        stat.label = stat.date.format("%-m月%-d日").to_string();
+    }
+    if let Some(last) = result.stats.last_mut() {
+        last.label.push_str("（日本語）");
+    }
+    result.top_files = Some(vec![
+        churn("docs/日本語/はじめに.md", 240, 30, 5),
+        churn("src/日本語.rs", 120, 40, 3),
+        churn("README.md", 90, 12, 3),
+    ]);
+
+    App::new(result, fixed_activity_stats(), true)
+}
+
+/// App still loading: two of three repositories are in, the third never finishes
+///
+/// The loader runs until the app is dropped.
+pub fn make_loading_app() -> App {
+    let mut app = App::new(
+        AnalysisResult::new(
+            "3 repos".to_string(),
+            "daily".to_string(),
+            date(2024, 1, 1),
+            date(2024, 1, 7),
+            Vec::new(),
+        ),
+        ActivityStats::default(),
+        false,
+    )
+    .with_loader(Box::new(|context| {
+        context.send(LoadEvent::Started { total: 3 });
+        context.send(LoadEvent::RepoLoaded {
+            repo: "kodo".to_string(),
+            commits: 42,
+        });
+        context.send(LoadEvent::RepoLoaded {
+            repo: "web".to_string(),
+            commits: 7,
+        });
+        while !context.is_cancelled() {
+            thread::sleep(Duration::from_millis(5));
+        }
+        Err(Error::Cancelled)
+    }));
+
+    app.start_loading();
+    for _ in 0..500 {
+        app.poll_loading();
+        if app
+            .load_progress()
+            .is_some_and(|progress| progress.loaded == 2)
+        {
+            return app;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("loader did not report progress");
+}
+
+fn churn(path: &str, additions: u64, deletions: u64, commits: u32) -> FileChurn {
+    FileChurn {
+        path: path.to_string(),
+        additions,
+        deletions,
+        commits,
+    }
+}
+
 fn period(
     year: i32,
     month: u32,
@@ -57,13 +396,37 @@ fn period(
         label: date.format("%Y-%m-%d").to_string(),
         date,
         commits,
+        merge_commits: 0,
         additions,
         deletions,
         net_lines: additions_i64 - deletions_i64,
         files_changed,
+        unique_files: files_changed,
+        // Distinct per day, repeating from day to day
+        paths: (0..files_changed)
+            .map(|i| format!("src/file{i}.rs"))
+            .collect(),
+        ext_stats: BTreeMap::new(),
+        is_outlier: false,
     }
 }
 
+fn with_extensions(mut stat: PeriodStats, lines: &[(&str, u64, u64)]) -> PeriodStats {
+    stat.ext_stats = lines
+        .iter()
+        .map(|&(extension, additions, deletions)| {
+            (
+                extension.to_string(),
+                ExtensionLines {
+                    additions,
+                    deletions,
+                },
+            )
+        })
+        .collect();
+    stat
+}
+
 fn date(year: i32, month: u32, day: u32) -> NaiveDate {
     NaiveDate::from_ymd_opt(year, month, day).expect("fixed test date must be valid")
 }
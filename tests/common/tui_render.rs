@@ -1,21 +1,49 @@
 use kodo::tui::App;
+use kodo::tui::terminal::ViewportMode;
 use kodo::tui::ui;
-use ratatui::Terminal;
 use ratatui::backend::TestBackend;
+use ratatui::{Terminal, TerminalOptions};
 
 const TERM_WIDTH: u16 = 80;
 const TERM_HEIGHT: u16 = 20;
 
-pub fn make_terminal() -> Terminal<TestBackend> {
-    Terminal::new(TestBackend::new(TERM_WIDTH, TERM_HEIGHT))
-        .expect("test terminal should be created")
+pub fn make_terminal(width: u16, height: u16) -> Terminal<TestBackend> {
+    Terminal::new(TestBackend::new(width, height)).expect("test terminal should be created")
 }
 
 pub fn render_ui(app: &App) -> String {
-    let mut terminal = make_terminal();
+    render_ui_with_size(app, TERM_WIDTH, TERM_HEIGHT)
+}
+
+pub fn render_ui_with_size(app: &App, width: u16, height: u16) -> String {
+    let mut terminal = make_terminal(width, height);
     terminal
         .draw(|frame| ui::render(frame, app))
         .expect("ui rendering should succeed");
 
     format!("{}", terminal.backend())
 }
+
+/// Render into a `height`-row terminal through a `--inline` viewport of `rows`
+pub fn render_ui_inline(app: &App, height: u16, rows: u16) -> String {
+    let options = TerminalOptions {
+        viewport: ViewportMode::Inline(rows).viewport(),
+    };
+    let mut terminal = Terminal::with_options(TestBackend::new(TERM_WIDTH, height), options)
+        .expect("test terminal should be created");
+    terminal
+        .draw(|frame| ui::render(frame, app))
+        .expect("ui rendering should succeed");
+
+    format!("{}", terminal.backend())
+}
+
+/// Render with cell styles included, for snapshots that check colors
+pub fn render_ui_styled(app: &App) -> String {
+    let mut terminal = make_terminal(TERM_WIDTH, TERM_HEIGHT);
+    terminal
+        .draw(|frame| ui::render(frame, app))
+        .expect("ui rendering should succeed");
+
+    format!("{:?}", terminal.backend().buffer())
+}
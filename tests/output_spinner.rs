@@ -51,6 +51,7 @@ fn json_output_does_not_include_spinner_text() {
             dir.path().to_str().expect("repo path"),
             "--output",
             "json",
+            "--no-cache",
         ])
         .output()
         .expect("run kodo");
@@ -80,6 +81,7 @@ fn csv_output_does_not_include_spinner_text() {
             dir.path().to_str().expect("repo path"),
             "--output",
             "csv",
+            "--no-cache",
         ])
         .output()
         .expect("run kodo");
@@ -1,8 +1,27 @@
 mod common;
 
-use common::tui_fixture::make_app;
-use common::tui_render::render_ui;
+use chrono::NaiveDate;
+use common::tui_fixture::{
+    make_app, make_churn_app, make_comparison_app, make_gap_app, make_loading_app, make_long_app,
+    make_merges_app, make_moving_average_app, make_multi_repo_app, make_net_lines_app,
+    make_repo_tabs_app, make_shrinking_app, make_wide_label_app,
+};
+use common::tui_render::{
+    make_terminal, render_ui, render_ui_inline, render_ui_styled, render_ui_with_size,
+};
 use insta::assert_snapshot;
+use kodo::cli::args::{Period, ThemeName};
+use kodo::stats::{WorkPatternBucket, WorkPatternStats};
+use kodo::tui::mvu::action::Action;
+use kodo::tui::mvu::model::StatusLevel;
+use kodo::tui::widgets::{
+    BarDataPoint, render_diverging_bar_chart, render_horizontal_bar_chart,
+    render_line_chart_for_metric, render_vertical_bar_chart,
+};
+use kodo::tui::{FocusedPanel, Keymap, Metric, Theme};
+use ratatui::layout::Size;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::Modifier;
 
 #[test]
 fn test_ui_split_default_snapshot() {
@@ -11,6 +30,20 @@ fn test_ui_split_default_snapshot() {
     assert_snapshot!("ui_split_default", rendered);
 }
 
+#[test]
+fn test_ui_inline_snapshot() {
+    // The viewport takes the rows below the cursor; the rest stays untouched
+    let app = make_app(false);
+    let rendered = render_ui_inline(&app, 30, 16);
+    assert_snapshot!("ui_inline", rendered);
+}
+
+#[test]
+fn test_ui_inline_clamped_to_terminal() {
+    let app = make_app(false);
+    assert_eq!(render_ui_inline(&app, 20, 40), render_ui(&app));
+}
+
 #[test]
 fn test_ui_single_commits_snapshot() {
     let app = make_app(true);
@@ -21,12 +54,31 @@ fn test_ui_single_commits_snapshot() {
 fn assert_single_chart_snapshot(name: &str, next_count: usize) {
     let mut app = make_app(true);
     for _ in 0..next_count {
-        app.next_chart();
+        app.dispatch(Action::NextChart);
     }
     let rendered = render_ui(&app);
     assert_snapshot!(name, rendered);
 }
 
+#[test]
+fn test_ui_single_commits_moving_average_snapshot() {
+    let app = make_moving_average_app();
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_commits_moving_average", rendered);
+}
+
+#[test]
+fn test_ui_single_commits_merges_snapshot() {
+    // Merges are drawn dim under the commits, with their total in the title
+    let app = make_merges_app();
+    let rendered = render_ui(&app);
+    assert!(
+        rendered.contains("Commits (Total: 24, Merges: 6)"),
+        "{rendered}"
+    );
+    assert_snapshot!("ui_single_commits_merges", rendered);
+}
+
 #[test]
 fn test_ui_single_files_changed_snapshot() {
     assert_single_chart_snapshot("ui_single_files_changed", 1);
@@ -37,6 +89,16 @@ fn test_ui_single_add_del_snapshot() {
     assert_single_chart_snapshot("ui_single_add_del", 2);
 }
 
+#[test]
+fn test_ui_single_add_del_narrow_snapshot() {
+    // Too narrow for value annotations; the bars keep the full half width
+    let mut app = make_app(true);
+    app.dispatch(Action::NextChart);
+    app.dispatch(Action::NextChart);
+    let rendered = render_ui_with_size(&app, 30, 20);
+    assert_snapshot!("ui_single_add_del_narrow", rendered);
+}
+
 #[test]
 fn test_ui_single_weekday_snapshot() {
     assert_single_chart_snapshot("ui_single_weekday", 3);
@@ -46,3 +108,617 @@ fn test_ui_single_weekday_snapshot() {
 fn test_ui_single_hour_snapshot() {
     assert_single_chart_snapshot("ui_single_hour", 4);
 }
+
+#[test]
+fn test_ui_single_hour_wide_snapshot() {
+    // Wide enough for one bar per hour
+    let mut app = make_app(true);
+    for _ in 0..4 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui_with_size(&app, 120, 20);
+    assert_snapshot!("ui_single_hour_wide", rendered);
+}
+
+#[test]
+fn test_ui_single_hour_narrow_snapshots() {
+    // Three-hour bars at 50 columns, four-hour rows below that
+    let mut app = make_app(true);
+    for _ in 0..4 {
+        app.dispatch(Action::NextChart);
+    }
+    assert_snapshot!("ui_single_hour_50", render_ui_with_size(&app, 50, 20));
+    assert_snapshot!("ui_single_hour_40", render_ui_with_size(&app, 40, 20));
+}
+
+#[test]
+fn test_ui_single_time_of_day_snapshot() {
+    assert_single_chart_snapshot("ui_single_time_of_day", 5);
+}
+
+#[test]
+fn test_ui_single_heatmap_snapshot() {
+    assert_single_chart_snapshot("ui_single_heatmap", 6);
+}
+
+#[test]
+fn test_ui_single_net_lines_snapshot() {
+    assert_single_chart_snapshot("ui_single_net_lines", 7);
+}
+
+#[test]
+fn test_ui_single_cumulative_snapshot() {
+    assert_single_chart_snapshot("ui_single_cumulative", 8);
+}
+
+#[test]
+fn test_ui_single_top_files_empty_snapshot() {
+    assert_single_chart_snapshot("ui_single_top_files_empty", 9);
+}
+
+#[test]
+fn test_ui_single_top_files_snapshot() {
+    let mut app = make_churn_app();
+    for _ in 0..9 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui_with_size(&app, 120, 24);
+    assert_snapshot!("ui_single_top_files", rendered);
+}
+
+#[test]
+fn test_ui_single_add_del_wide_labels_snapshot() {
+    // Double-width labels stay right-aligned without shifting the bars
+    let mut app = make_wide_label_app();
+    app.dispatch(Action::NextChart);
+    app.dispatch(Action::NextChart);
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_add_del_wide_labels", rendered);
+}
+
+#[test]
+fn test_ui_single_top_files_wide_labels_snapshot() {
+    let mut app = make_wide_label_app();
+    for _ in 0..9 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui_with_size(&app, 60, 16);
+    assert_snapshot!("ui_single_top_files_wide_labels", rendered);
+}
+
+#[test]
+fn test_ui_single_languages_snapshot() {
+    assert_single_chart_snapshot("ui_single_languages", 10);
+}
+
+#[test]
+fn test_ui_single_languages_narrow_snapshot() {
+    // Only the most recent periods that fit keep full-width bars
+    let mut app = make_app(true);
+    for _ in 0..10 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui_with_size(&app, 22, 20);
+    assert_snapshot!("ui_single_languages_narrow", rendered);
+}
+
+#[test]
+fn test_ui_single_languages_without_extension_stats() {
+    let mut app = make_shrinking_app();
+    for _ in 0..10 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui(&app);
+    assert!(rendered.contains("Run with --by-extension"), "{rendered}");
+}
+
+#[test]
+fn test_ui_single_commits_panned_snapshot() {
+    let mut app = make_long_app();
+    app.resize(Size::new(80, 20));
+    for _ in 0..30 {
+        app.dispatch(Action::PanLeft);
+    }
+    assert_eq!(app.model().window_offset, 30);
+
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_commits_panned", rendered);
+}
+
+/// 120-day app on the Add/Del chart at 80x20, with 11 rows per page
+fn make_scrolled_add_del_app(pages_up: usize) -> kodo::tui::App {
+    let mut app = make_long_app();
+    app.dispatch(Action::NextChart);
+    app.dispatch(Action::NextChart);
+    app.resize(Size::new(80, 20));
+    assert_eq!(app.model().page_rows, 11);
+    for _ in 0..pages_up {
+        app.dispatch(Action::PageUp);
+    }
+    app
+}
+
+#[test]
+fn test_ui_single_add_del_scroll_position_snapshots() {
+    // At the newest period the title says END and the footer keeps its hints
+    let rendered = render_ui(&make_scrolled_add_del_app(0));
+    assert!(rendered.contains("rows 110–120/120 END"), "{rendered}");
+    assert_snapshot!("ui_single_add_del_scroll_end", rendered);
+
+    // Between the ends the footer shows where the rows are, without a tag
+    let rendered = render_ui(&make_scrolled_add_del_app(3));
+    assert!(
+        rendered.contains("2024-03-17 – 2024-03-27 of 2024-01-01 – 2024-04-29 (rows 77–87/120)"),
+        "{rendered}"
+    );
+
+    let rendered = render_ui(&make_scrolled_add_del_app(10));
+    assert_snapshot!("ui_single_add_del_scroll_top", rendered);
+}
+
+/// The Jan 1–7 2024 fixture (Monday to Sunday) with today frozen on Wednesday
+fn make_today_app() -> kodo::tui::App {
+    make_app(true).with_today(NaiveDate::from_ymd_opt(2024, 1, 3).unwrap())
+}
+
+#[test]
+fn test_ui_single_commits_today_snapshot() {
+    let app = make_today_app();
+    assert_eq!(app.today_index(), Some(2));
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_commits_today", rendered);
+}
+
+#[test]
+fn test_ui_single_add_del_today_snapshot() {
+    let mut app = make_today_app();
+    app.dispatch(Action::NextChart);
+    app.dispatch(Action::NextChart);
+    assert!(app.is_weekend(5) && app.is_weekend(6) && !app.is_weekend(4));
+
+    let mut terminal = make_terminal(80, 20);
+    terminal
+        .draw(|frame| kodo::tui::ui::render(frame, &app))
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    // Below the header, the chart's third row holds 2024-01-03
+    let today = buffer[(1, 6)].modifier;
+    assert!(
+        today.contains(Modifier::BOLD | Modifier::UNDERLINED),
+        "{today:?}"
+    );
+    assert!(!buffer[(1, 5)].modifier.contains(Modifier::UNDERLINED));
+    assert_snapshot!("ui_single_add_del_today", render_ui(&app));
+}
+
+#[test]
+fn test_ui_weekly_period_drops_day_markers() {
+    let mut app = make_long_app().with_today(NaiveDate::from_ymd_opt(2024, 4, 29).unwrap());
+    assert_eq!(app.today_index(), Some(119));
+    app.dispatch(Action::SetPeriod(Period::Weekly));
+    assert_eq!(app.today_index(), None);
+    assert!(!app.is_weekend(0));
+
+    let rendered = render_ui(&app);
+    assert!(!rendered.contains('•'), "{rendered}");
+    app.dispatch(Action::NextChart);
+    app.dispatch(Action::NextChart);
+    let rendered = render_ui(&app);
+    assert!(!rendered.contains('\u{a6}'), "{rendered}");
+}
+
+#[test]
+fn test_ui_footer_shows_pending_count() {
+    let mut app = make_app(true);
+    app.dispatch(Action::Digit(1));
+    app.dispatch(Action::Digit(2));
+    let rendered = render_ui(&app);
+    assert!(rendered.contains("| Count: 12 |"), "{rendered}");
+
+    app.dispatch(Action::Escape);
+    assert!(!app.model().should_quit);
+    assert!(!render_ui(&app).contains("Count:"));
+}
+
+#[test]
+fn test_ui_single_cumulative_negative_snapshot() {
+    let mut app = make_shrinking_app();
+    for _ in 0..8 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_cumulative_negative", rendered);
+}
+
+#[test]
+fn test_ui_single_net_lines_crossing_zero_snapshot() {
+    let mut app = make_shrinking_app();
+    for _ in 0..7 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_net_lines_crossing_zero", rendered);
+}
+
+#[test]
+fn test_ui_single_net_lines_cleanup_week_snapshot() {
+    // Deletions outweigh additions by 500 lines on one day
+    let mut app = make_net_lines_app(&[120, 80, -500, 40, 60]);
+    for _ in 0..7 {
+        app.dispatch(Action::NextChart);
+    }
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_net_lines_cleanup_week", rendered);
+}
+
+#[test]
+fn test_ui_single_net_lines_all_negative_or_zero() {
+    for net_lines in [&[-1_200, -300, -4_500][..], &[0, 0, 0], &[-7], &[]] {
+        let mut app = make_net_lines_app(net_lines);
+        for _ in 0..7 {
+            app.dispatch(Action::NextChart);
+        }
+        let rendered = render_ui(&app);
+        assert!(rendered.contains("Net Lines"), "{rendered}");
+    }
+}
+
+#[test]
+fn test_ui_split_tall_with_heatmap_snapshot() {
+    let app = make_app(false);
+    let rendered = render_ui_with_size(&app, 80, 40);
+    assert_snapshot!("ui_split_tall_heatmap", rendered);
+}
+
+#[test]
+fn test_ui_split_summary_cards_snapshot() {
+    let app = make_app(false);
+    let rendered = render_ui_with_size(&app, 80, 30);
+    assert_snapshot!("ui_split_summary_cards", rendered);
+}
+
+#[test]
+fn test_ui_split_summary_cards_narrow_snapshot() {
+    // Below 60 columns only Commits, Net, and Files fit
+    let app = make_app(false);
+    let rendered = render_ui_with_size(&app, 50, 30);
+    assert_snapshot!("ui_split_summary_cards_narrow", rendered);
+}
+
+#[test]
+fn test_ui_split_summary_cards_toggle_off() {
+    let mut app = make_app(false);
+    app.dispatch(Action::ToggleSummary);
+    assert!(!app.model().show_summary);
+
+    let rendered = render_ui_with_size(&app, 80, 30);
+    assert!(!rendered.contains("+Lines"), "{rendered}");
+    assert!(!rendered.contains("-Lines"), "{rendered}");
+}
+
+#[test]
+fn test_ui_split_activity_by_lines_snapshot() {
+    let mut app = make_app(false);
+    app.dispatch(Action::ToggleActivityWeight);
+    assert!(app.model().activity_by_lines);
+
+    let rendered = render_ui_with_size(&app, 80, 40);
+    assert_snapshot!("ui_split_activity_by_lines", rendered);
+}
+
+#[test]
+fn test_ui_single_hour_by_lines_snapshot() {
+    // Wide enough for one bar per hour, with K-formatted values
+    let mut app = make_app(true);
+    for _ in 0..4 {
+        app.dispatch(Action::NextChart);
+    }
+    app.dispatch(Action::ToggleActivityWeight);
+    let rendered = render_ui_with_size(&app, 120, 20);
+    assert_snapshot!("ui_single_hour_by_lines", rendered);
+}
+
+#[test]
+fn test_ui_footer_marks_truncated_result() {
+    let mut app = make_app(false);
+    assert!(!render_ui(&app).contains("Truncated"));
+
+    app.result.truncated = true;
+    let rendered = render_ui(&app);
+    assert!(
+        rendered.contains("Truncated by --max-commits"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn test_ui_footer_shows_work_pattern_percentages() {
+    let mut app = make_app(false);
+    assert!(!render_ui(&app).contains("Work hrs"));
+
+    let bucket = |commits| WorkPatternBucket {
+        commits,
+        ..WorkPatternBucket::default()
+    };
+    app.result.work_pattern = Some(WorkPatternStats {
+        business_hours: bucket(6),
+        weekday_evenings: bucket(3),
+        weekends: bucket(1),
+        ..WorkPatternStats::default()
+    });
+    let rendered = render_ui(&app);
+    assert!(
+        rendered.contains("Work hrs 60% / eve 30% / wknd 10%"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn test_ui_split_multi_repo_snapshot() {
+    let app = make_multi_repo_app();
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_split_multi_repo", rendered);
+}
+
+#[test]
+fn test_ui_split_repo_tabs_all_snapshot() {
+    let app = make_repo_tabs_app(false);
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_split_repo_tabs_all", rendered);
+}
+
+#[test]
+fn test_ui_single_repo_tab_snapshot() {
+    let mut app = make_repo_tabs_app(true);
+    app.dispatch(Action::SelectRepo(2));
+    assert_eq!(app.active_result().repository, "docs");
+
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_repo_tab", rendered);
+}
+
+#[test]
+fn test_ui_split_commits_by_repo_snapshot() {
+    let mut app = make_repo_tabs_app(false);
+    app.dispatch(Action::ToggleCommitsByRepo);
+    assert!(app.model().commits_by_repo);
+
+    let rendered = render_ui_with_size(&app, 100, 40);
+    assert!(rendered.contains("Commits by Repository"), "{rendered}");
+    assert_snapshot!("ui_split_commits_by_repo", rendered);
+}
+
+#[test]
+fn test_ui_single_commits_by_repo_snapshot() {
+    let mut app = make_repo_tabs_app(true);
+    app.dispatch(Action::ToggleCommitsByRepo);
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_commits_by_repo", rendered);
+
+    // A repository tab shows its own single line
+    app.dispatch(Action::SelectRepo(1));
+    assert!(!render_ui(&app).contains("Commits by Repository"));
+}
+
+#[test]
+fn test_ui_single_commits_inspect_snapshot() {
+    let mut app = make_app(true);
+    app.dispatch(Action::EnterInspect);
+    app.dispatch(Action::MoveSelection(-2));
+    let index = app.model().selected_period().expect("a period is selected");
+    let commits = app.active_result().stats[index].commits;
+
+    let rendered = render_ui(&app);
+    assert!(
+        rendered.contains(&format!("{commits} commits")),
+        "{rendered}"
+    );
+    assert!(rendered.contains("[Esc] Exit"), "{rendered}");
+    assert_snapshot!("ui_single_commits_inspect", rendered);
+
+    app.dispatch(Action::ExitInspect);
+    assert!(!render_ui(&app).contains("[Esc] Exit"));
+}
+
+#[test]
+fn test_ui_single_status_snapshot() {
+    let mut app = make_app(true);
+    app.show_status("Export failed: permission denied", StatusLevel::Error);
+    app.show_status("Exported to ./kodo-export.json", StatusLevel::Info);
+
+    // Queued messages wait their turn
+    let rendered = render_ui(&app);
+    assert!(rendered.contains("Export failed"), "{rendered}");
+    assert!(!rendered.contains("Exported to"), "{rendered}");
+    assert_snapshot!("ui_single_status", rendered);
+}
+
+#[test]
+fn test_ui_split_comparison_snapshot() {
+    let app = make_comparison_app();
+    let rendered = render_ui_with_size(&app, 120, 30);
+    assert_snapshot!("ui_split_comparison", rendered);
+}
+
+#[test]
+fn test_ui_help_overlay_snapshot() {
+    let mut app = make_app(true);
+    app.dispatch(Action::ToggleHelp);
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_help_overlay", rendered);
+}
+
+#[test]
+fn test_ui_help_overlay_remapped_snapshot() {
+    let bindings = [
+        ("scroll_down", vec!["n".to_string(), "ctrl+n".to_string()]),
+        ("quit", vec!["j".to_string()]),
+    ]
+    .into_iter()
+    .map(|(name, keys)| (name.to_string(), keys))
+    .collect();
+    let mut app = make_app(true).with_keymap(Keymap::from_config(&bindings).unwrap());
+    app.dispatch(Action::ToggleHelp);
+
+    let rendered = render_ui_with_size(&app, 80, 44);
+    assert!(rendered.contains("j, q, Esc"), "{rendered}");
+    assert!(rendered.contains("n, C-n, ↓ "), "{rendered}");
+    assert_snapshot!("ui_help_overlay_remapped", rendered);
+}
+
+#[test]
+fn test_ui_split_monochrome_snapshot() {
+    let app = make_app(false).with_theme(Theme::preset(ThemeName::Monochrome));
+    let rendered = render_ui_styled(&app);
+    assert_snapshot!("ui_split_monochrome", rendered);
+}
+
+#[test]
+fn test_ui_single_commits_monthly_snapshot() {
+    let mut app = make_long_app();
+    app.dispatch(Action::SetPeriod(Period::Weekly));
+    // 2024-01-01 is a Monday, so 120 days span 17 full weeks and one more day
+    assert_eq!(app.active_result().stats.len(), 18);
+
+    app.dispatch(Action::SetPeriod(Period::Monthly));
+    assert_eq!(app.active_result().stats.len(), 4);
+    assert_eq!(app.active_result().stats[1].label, "2024-02");
+
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_single_commits_monthly", rendered);
+}
+
+#[test]
+fn test_ui_split_gap_footer_snapshot() {
+    let mut app = make_gap_app();
+    assert_eq!(app.active_result().gaps.len(), 2);
+    assert!(app.active_result().gaps[1].open_ended);
+    let rendered = render_ui(&app);
+    assert!(
+        rendered.contains("Longest gap: 5 days, Jan 4\u{2013}8"),
+        "{rendered}"
+    );
+    assert_snapshot!("ui_split_gap_footer", rendered);
+
+    // A week is the weekly threshold, so neither gap is reported any more
+    app.dispatch(Action::SetPeriod(Period::Weekly));
+    assert!(app.active_result().gaps.is_empty());
+    assert!(!render_ui(&app).contains("Longest gap"));
+}
+
+#[test]
+fn test_ui_loading_snapshot() {
+    let app = make_loading_app();
+    let rendered = render_ui(&app);
+    assert_snapshot!("ui_loading", rendered);
+}
+
+#[test]
+fn test_ui_split_focused_hour_snapshot() {
+    let mut app = make_app(false);
+    app.dispatch(Action::NextPanel);
+    app.dispatch(Action::NextPanel);
+    assert_eq!(app.model().focused_panel, FocusedPanel::Hour);
+
+    // Styled, so the highlighted border shows up
+    let rendered = render_ui_styled(&app);
+    assert_snapshot!("ui_split_focused_hour", rendered);
+}
+
+#[test]
+fn test_widgets_at_20x5_snapshot() {
+    // Each widget gets 20x5: too small for the line and weekday charts, which
+    // say how much room they need instead of drawing a cut-off chart
+    let app = make_app(false);
+    let theme = Theme::default();
+    let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let rows = [
+        BarDataPoint::new("0-3", 1),
+        BarDataPoint::new("4-7", 15),
+        BarDataPoint::new("8-11", 17),
+    ];
+    let mut terminal = make_terminal(20, 20);
+    terminal
+        .draw(|frame| {
+            let [line, diverging, vertical, horizontal] =
+                Layout::vertical([Constraint::Length(5); 4]).areas(frame.area());
+            render_line_chart_for_metric(frame, line, &app, Metric::Commits, false);
+            render_diverging_bar_chart(frame, diverging, &app, false);
+            render_vertical_bar_chart(
+                frame,
+                vertical,
+                "Weekday",
+                &weekdays,
+                &[3, 5, 2, 4, 6, 1, 3],
+                theme.bar_primary,
+                None,
+                &theme,
+            );
+            render_horizontal_bar_chart(
+                frame,
+                horizontal,
+                "Hour",
+                &rows,
+                theme.bar_secondary,
+                &theme,
+            );
+        })
+        .unwrap();
+    assert_snapshot!("widgets_20x5", format!("{}", terminal.backend()));
+}
+
+/// Net lines line chart of `app` drawn alone at `width`×`height`
+fn render_net_line_chart(app: &kodo::tui::App, width: u16, height: u16) -> String {
+    let mut terminal = make_terminal(width, height);
+    terminal
+        .draw(|frame| {
+            render_line_chart_for_metric(
+                frame,
+                frame.area(),
+                app,
+                Metric::AdditionsAndDeletions,
+                false,
+            );
+        })
+        .unwrap();
+    format!("{}", terminal.backend())
+}
+
+#[test]
+fn test_line_chart_humanized_y_labels_snapshot() {
+    // The top label is the peak itself, humanized like the bar charts
+    let app = make_net_lines_app(&[0, 950, 12_400, 1_200_000]);
+    let rendered = render_net_line_chart(&app, 40, 10);
+    assert!(rendered.contains("1.2M│"), "{rendered}");
+    assert_snapshot!("line_chart_humanized_y_labels", rendered);
+}
+
+#[test]
+fn test_line_chart_zero_label_for_both_signs_snapshot() {
+    let app = make_net_lines_app(&[950, -12_400, 0, 12_400]);
+    let rendered = render_net_line_chart(&app, 40, 12);
+    assert!(rendered.contains("     0│"), "{rendered}");
+    assert_snapshot!("line_chart_zero_label_for_both_signs", rendered);
+}
+
+#[test]
+fn test_line_chart_gutter_is_fixed_while_panning() {
+    // The one wide value scrolls out of view without moving the plot
+    let mut net_lines = vec![1_200_000];
+    net_lines.extend([5, 9, 3].repeat(8));
+    let mut app = make_net_lines_app(&net_lines);
+    app.resize(Size::new(20, 10));
+
+    let axis_column = |rendered: &str| {
+        let row = rendered.lines().nth(1).unwrap();
+        row.chars().position(|c| c == '│').unwrap()
+    };
+    let newest = render_net_line_chart(&app, 20, 10);
+    for _ in 0..net_lines.len() {
+        app.dispatch(Action::PanLeft);
+    }
+    let oldest = render_net_line_chart(&app, 20, 10);
+    assert!(!newest.contains("1.2M"), "{newest}");
+    assert!(oldest.contains("1.2M"), "{oldest}");
+    assert_eq!(axis_column(&newest), axis_column(&oldest));
+}